@@ -0,0 +1,265 @@
+#ifndef NEMO_H
+#define NEMO_H
+
+/* This file is generated by cbindgen from the sources of nemo-capi. Do not edit it manually. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Result of a function that may fail
+typedef enum NemoStatus {
+  // The function succeeded
+  NEMO_STATUS_OK = 0,
+  // The function failed, see [nemo_last_error]
+  NEMO_STATUS_ERROR = 1,
+} NemoStatus;
+
+// Kinds of [NemoValue]s
+typedef enum NemoValueKind {
+  // An IRI or a constant name
+  NEMO_VALUE_KIND_IRI = 0,
+  // A string without language tag
+  NEMO_VALUE_KIND_STRING = 1,
+  // A string with language tag
+  NEMO_VALUE_KIND_LANGUAGE_STRING = 2,
+  // An integer that fits into 64 bits
+  NEMO_VALUE_KIND_INTEGER = 3,
+  // A floating point number
+  NEMO_VALUE_KIND_DOUBLE = 4,
+  // A boolean
+  NEMO_VALUE_KIND_BOOLEAN = 5,
+  // A null, i.e., a value invented by an existential rule
+  NEMO_VALUE_KIND_NULL = 6,
+  // Any other value, which can be read as a string
+  NEMO_VALUE_KIND_OTHER = 7,
+} NemoValueKind;
+
+// A program together with the engine that evaluates it
+typedef struct NemoEngine NemoEngine;
+
+// Iterator over the facts of a predicate, see [nemo_engine_result][crate::engine::nemo_engine_result]
+//
+// Initially, the iterator is positioned before the first fact,
+// so [nemo_results_next] has to be called before the values of the first fact can be read.
+typedef struct NemoResults NemoResults;
+
+// A value of a fact
+//
+// Values that are created with one of the `nemo_value_new_*` functions
+// must be released with [nemo_value_free].
+// Values that are obtained from [NemoResults][crate::NemoResults] are owned by the results.
+typedef struct NemoValue NemoValue;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Parse the given program and create an engine for it.
+//
+// Resources of import directives are resolved relative to the working directory.
+// Returns null if the program cannot be parsed or loaded.
+// The engine must be released with [nemo_engine_free].
+//
+// # Safety
+// `program` must be a nul-terminated string.
+struct NemoEngine *nemo_engine_new(const char *program);
+
+// Release the given engine.
+//
+// # Safety
+// `engine` must have been created by [nemo_engine_new] and must not be used afterwards.
+void nemo_engine_free(struct NemoEngine *engine);
+
+// Add a fact with the given values for a predicate of the program.
+//
+// The values are copied and remain owned by the caller.
+// Facts can only be added before reasoning.
+//
+// # Safety
+// `engine` must point to a valid [NemoEngine], `predicate` must be a nul-terminated string
+// and `values` must point to `count` valid [NemoValue]s.
+enum NemoStatus nemo_engine_add_fact(struct NemoEngine *engine,
+                                     const char *predicate,
+                                     const struct NemoValue *const *values,
+                                     size_t count);
+
+// Compute all facts that follow from the program.
+//
+// # Safety
+// `engine` must point to a valid [NemoEngine].
+enum NemoStatus nemo_engine_reason(struct NemoEngine *engine);
+
+// Return an iterator over the facts of the given predicate.
+//
+// Returns null if the facts cannot be computed.
+// The results must be released with [nemo_results_free][crate::results::nemo_results_free].
+//
+// # Safety
+// `engine` must point to a valid [NemoEngine] and `predicate` must be a nul-terminated string.
+struct NemoResults *nemo_engine_result(struct NemoEngine *engine, const char *predicate);
+
+// Explain how the given fact, written in the syntax of nemo programs, was derived.
+//
+// On success, `trace` is set to a textual derivation tree,
+// which must be released with [nemo_string_free][crate::nemo_string_free],
+// or to null if the fact was not derived.
+//
+// # Safety
+// `engine` must point to a valid [NemoEngine], `fact` must be a nul-terminated string
+// and `trace` must point to writable memory.
+enum NemoStatus nemo_engine_trace(struct NemoEngine *engine, const char *fact, char **trace);
+
+// Return the message of the last error that occurred in the current thread,
+// or null if the last call succeeded.
+//
+// The message stays valid until the next call of a function of this library in the same thread.
+const char *nemo_last_error(void);
+
+// Release a string that was returned by a function of this library.
+//
+// # Safety
+// The string must have been returned by this library and must not be used afterwards.
+void nemo_string_free(char *string);
+
+// Advance to the next fact.
+//
+// Returns false if there are no more facts.
+//
+// # Safety
+// `results` must point to valid [NemoResults].
+bool nemo_results_next(struct NemoResults *results);
+
+// Return the number of values of the current fact.
+//
+// # Safety
+// `results` must point to valid [NemoResults].
+size_t nemo_results_arity(const struct NemoResults *results);
+
+// Return the value at the given position of the current fact,
+// or null if the position is out of range.
+//
+// The value is owned by the results and stays valid until the next call of [nemo_results_next].
+//
+// # Safety
+// `results` must point to valid [NemoResults].
+const struct NemoValue *nemo_results_value(const struct NemoResults *results, size_t index);
+
+// Release the given results.
+//
+// # Safety
+// `results` must have been created by this library and must not be used afterwards.
+void nemo_results_free(struct NemoResults *results);
+
+// Create an IRI or a constant name.
+//
+// Returns null if the given string is not valid UTF-8.
+//
+// # Safety
+// `iri` must be a nul-terminated string.
+struct NemoValue *nemo_value_new_iri(const char *iri);
+
+// Create a string without language tag.
+//
+// Returns null if the given string is not valid UTF-8.
+//
+// # Safety
+// `string` must be a nul-terminated string.
+struct NemoValue *nemo_value_new_string(const char *string);
+
+// Create a string with a language tag.
+//
+// Returns null if one of the given strings is not valid UTF-8.
+//
+// # Safety
+// `string` and `language` must be nul-terminated strings.
+struct NemoValue *nemo_value_new_language_string(const char *string, const char *language);
+
+// Create an integer.
+struct NemoValue *nemo_value_new_integer(int64_t integer);
+
+// Create a floating point number.
+//
+// Returns null if the number is not finite.
+struct NemoValue *nemo_value_new_double(double number);
+
+// Create a boolean.
+struct NemoValue *nemo_value_new_boolean(bool boolean);
+
+// Release a value that was created with one of the `nemo_value_new_*` functions.
+//
+// # Safety
+// The value must have been created by this library and must not be used afterwards.
+void nemo_value_free(struct NemoValue *value);
+
+// Return the [NemoValueKind] of the given value.
+//
+// # Safety
+// `value` must point to a valid [NemoValue].
+enum NemoValueKind nemo_value_kind(const struct NemoValue *value);
+
+// Write the integer represented by the given value into `integer`.
+//
+// Returns false if the value is not of kind [NemoValueKind::Integer].
+//
+// # Safety
+// `value` must point to a valid [NemoValue] and `integer` to writable memory.
+bool nemo_value_as_integer(const struct NemoValue *value, int64_t *integer);
+
+// Write the floating point number represented by the given value into `number`.
+//
+// Returns false if the value is not of kind [NemoValueKind::Double].
+//
+// # Safety
+// `value` must point to a valid [NemoValue] and `number` to writable memory.
+bool nemo_value_as_double(const struct NemoValue *value, double *number);
+
+// Write the boolean represented by the given value into `boolean`.
+//
+// Returns false if the value is not of kind [NemoValueKind::Boolean].
+//
+// # Safety
+// `value` must point to a valid [NemoValue] and `boolean` to writable memory.
+bool nemo_value_as_boolean(const struct NemoValue *value, bool *boolean);
+
+// Return the lexical value of the given value,
+// e.g., the IRI without angle brackets or the contents of a string.
+//
+// The returned string must be released with [nemo_string_free][crate::nemo_string_free].
+//
+// # Safety
+// `value` must point to a valid [NemoValue].
+char *nemo_value_lexical(const struct NemoValue *value);
+
+// Return the language tag of the given value,
+// or null if the value is not of kind [NemoValueKind::LanguageString].
+//
+// The returned string must be released with [nemo_string_free][crate::nemo_string_free].
+//
+// # Safety
+// `value` must point to a valid [NemoValue].
+char *nemo_value_language(const struct NemoValue *value);
+
+// Return the IRI of the datatype of the given value.
+//
+// The returned string must be released with [nemo_string_free][crate::nemo_string_free].
+//
+// # Safety
+// `value` must point to a valid [NemoValue].
+char *nemo_value_datatype(const struct NemoValue *value);
+
+// Return the representation of the given value in the syntax of nemo programs.
+//
+// The returned string must be released with [nemo_string_free][crate::nemo_string_free].
+//
+// # Safety
+// `value` must point to a valid [NemoValue].
+char *nemo_value_to_string(const struct NemoValue *value);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* NEMO_H */
@@ -0,0 +1,172 @@
+//! Loading programs, adding facts and reasoning.
+
+use std::{ffi::c_char, ptr};
+
+use nemo::{
+    execution::{DefaultExecutionEngine, ExecutionEngine},
+    io::{parser::parse_fact, resource_providers::ResourceProviders, ImportManager},
+    model::{Identifier, Program},
+};
+
+use crate::{
+    error::{guard, guard_status, owned_string, str_argument, Error, NemoStatus},
+    results::NemoResults,
+    value::{value_argument, NemoValue},
+};
+
+/// A program together with the engine that evaluates it
+#[derive(Debug)]
+pub struct NemoEngine {
+    /// The loaded program, which is needed for tracing
+    program: Program,
+    /// The engine that evaluates the program
+    engine: DefaultExecutionEngine,
+}
+
+/// Return a mutable reference to the engine behind the given pointer.
+///
+/// # Safety
+/// The pointer must either be null or point to a valid [NemoEngine].
+unsafe fn engine_argument<'a>(engine: *mut NemoEngine) -> Result<&'a mut NemoEngine, Error> {
+    engine
+        .as_mut()
+        .ok_or_else(|| Error::new("argument engine is null"))
+}
+
+/// Parse the given program and create an engine for it.
+///
+/// Resources of import directives are resolved relative to the working directory.
+/// Returns null if the program cannot be parsed or loaded.
+/// The engine must be released with [nemo_engine_free].
+///
+/// # Safety
+/// `program` must be a nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nemo_engine_new(program: *const c_char) -> *mut NemoEngine {
+    guard(ptr::null_mut(), || {
+        let program = nemo::io::parser::parse_program(str_argument(program, "program")?)?;
+
+        let import_manager = ImportManager::new(ResourceProviders::default());
+        let engine = ExecutionEngine::initialize(&program, import_manager)?;
+
+        Ok(Box::into_raw(Box::new(NemoEngine { program, engine })))
+    })
+}
+
+/// Release the given engine.
+///
+/// # Safety
+/// `engine` must have been created by [nemo_engine_new] and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn nemo_engine_free(engine: *mut NemoEngine) {
+    if !engine.is_null() {
+        drop(Box::from_raw(engine));
+    }
+}
+
+/// Add a fact with the given values for a predicate of the program.
+///
+/// The values are copied and remain owned by the caller.
+/// Facts can only be added before reasoning.
+///
+/// # Safety
+/// `engine` must point to a valid [NemoEngine], `predicate` must be a nul-terminated string
+/// and `values` must point to `count` valid [NemoValue]s.
+#[no_mangle]
+pub unsafe extern "C" fn nemo_engine_add_fact(
+    engine: *mut NemoEngine,
+    predicate: *const c_char,
+    values: *const *const NemoValue,
+    count: usize,
+) -> NemoStatus {
+    guard_status(|| {
+        let engine = engine_argument(engine)?;
+        let predicate = Identifier::from(str_argument(predicate, "predicate")?.to_string());
+
+        let values = if count == 0 {
+            &[]
+        } else if values.is_null() {
+            return Err(Error::new("argument values is null"));
+        } else {
+            std::slice::from_raw_parts(values, count)
+        };
+        let row = values
+            .iter()
+            .map(|&value| value_argument(value).map(|value| value.0.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(engine.engine.add_facts(&predicate, [row])?)
+    })
+}
+
+/// Compute all facts that follow from the program.
+///
+/// # Safety
+/// `engine` must point to a valid [NemoEngine].
+#[no_mangle]
+pub unsafe extern "C" fn nemo_engine_reason(engine: *mut NemoEngine) -> NemoStatus {
+    guard_status(|| Ok(engine_argument(engine)?.engine.execute()?))
+}
+
+/// Return an iterator over the facts of the given predicate.
+///
+/// Returns null if the facts cannot be computed.
+/// The results must be released with [nemo_results_free][crate::results::nemo_results_free].
+///
+/// # Safety
+/// `engine` must point to a valid [NemoEngine] and `predicate` must be a nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nemo_engine_result(
+    engine: *mut NemoEngine,
+    predicate: *const c_char,
+) -> *mut NemoResults {
+    guard(ptr::null_mut(), || {
+        let engine = engine_argument(engine)?;
+        let predicate = Identifier::from(str_argument(predicate, "predicate")?.to_string());
+
+        let rows = engine
+            .engine
+            .predicate_rows(&predicate)?
+            .into_iter()
+            .flatten()
+            .collect();
+
+        Ok(Box::into_raw(Box::new(NemoResults::new(rows))))
+    })
+}
+
+/// Explain how the given fact, written in the syntax of nemo programs, was derived.
+///
+/// On success, `trace` is set to a textual derivation tree,
+/// which must be released with [nemo_string_free][crate::nemo_string_free],
+/// or to null if the fact was not derived.
+///
+/// # Safety
+/// `engine` must point to a valid [NemoEngine], `fact` must be a nul-terminated string
+/// and `trace` must point to writable memory.
+#[no_mangle]
+pub unsafe extern "C" fn nemo_engine_trace(
+    engine: *mut NemoEngine,
+    fact: *const c_char,
+    trace: *mut *mut c_char,
+) -> NemoStatus {
+    guard_status(|| {
+        let engine = engine_argument(engine)?;
+        let fact = parse_fact(str_argument(fact, "fact")?.to_string())?;
+        if trace.is_null() {
+            return Err(Error::new("argument trace is null"));
+        }
+
+        let (execution_trace, handles) = engine.engine.trace(engine.program.clone(), vec![fact]);
+        let handle = *handles
+            .first()
+            .expect("trace returns a handle for each input fact");
+
+        *trace = match execution_trace.tree(handle) {
+            Some(tree) => owned_string(tree.to_ascii_art())?,
+            None => ptr::null_mut(),
+        };
+
+        Ok(())
+    })
+}
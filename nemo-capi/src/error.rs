@@ -0,0 +1,131 @@
+//! Reporting errors to C callers.
+//!
+//! The message of the last error is stored per thread and can be read with [nemo_last_error].
+
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CStr, CString},
+    panic::{catch_unwind, AssertUnwindSafe},
+    ptr,
+};
+
+thread_local! {
+    /// Message of the last error that occurred in the current thread
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Result of a function that may fail
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NemoStatus {
+    /// The function succeeded
+    Ok = 0,
+    /// The function failed, see [nemo_last_error]
+    Error = 1,
+}
+
+/// Error that is reported to C callers as a message
+#[derive(Debug)]
+pub(crate) struct Error(String);
+
+impl Error {
+    /// Create a new [Error] with the given message.
+    pub(crate) fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+}
+
+impl From<nemo::error::Error> for Error {
+    fn from(error: nemo::error::Error) -> Self {
+        Self(error.to_string())
+    }
+}
+
+impl From<std::str::Utf8Error> for Error {
+    fn from(error: std::str::Utf8Error) -> Self {
+        Self(format!("invalid UTF-8: {error}"))
+    }
+}
+
+impl From<std::ffi::NulError> for Error {
+    fn from(error: std::ffi::NulError) -> Self {
+        Self(format!("string contains a nul byte: {error}"))
+    }
+}
+
+/// Store the given message as the last error of the current thread.
+fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', "\\0")).expect("nul bytes have been replaced");
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// Run the given function, clearing the last error before.
+///
+/// Errors and panics are stored as the last error, in which case `fallback` is returned.
+pub(crate) fn guard<T>(fallback: T, function: impl FnOnce() -> Result<T, Error>) -> T {
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
+
+    match catch_unwind(AssertUnwindSafe(function)) {
+        Ok(Ok(value)) => value,
+        Ok(Err(Error(message))) => {
+            set_last_error(message);
+            fallback
+        }
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            set_last_error(format!("internal error: {message}"));
+            fallback
+        }
+    }
+}
+
+/// Run the given function and report its result as a [NemoStatus].
+pub(crate) fn guard_status(function: impl FnOnce() -> Result<(), Error>) -> NemoStatus {
+    guard(NemoStatus::Error, || function().map(|_| NemoStatus::Ok))
+}
+
+/// Convert a C string argument into a `&str`.
+///
+/// # Safety
+/// The pointer must either be null or point to a nul-terminated string
+/// that stays valid for the lifetime `'a`.
+pub(crate) unsafe fn str_argument<'a>(string: *const c_char, name: &str) -> Result<&'a str, Error> {
+    if string.is_null() {
+        return Err(Error::new(format!("argument {name} is null")));
+    }
+
+    Ok(CStr::from_ptr(string).to_str()?)
+}
+
+/// Hand out the given string to a C caller, who has to release it with [nemo_string_free].
+pub(crate) fn owned_string(string: String) -> Result<*mut c_char, Error> {
+    Ok(CString::new(string)?.into_raw())
+}
+
+/// Return the message of the last error that occurred in the current thread,
+/// or null if the last call succeeded.
+///
+/// The message stays valid until the next call of a function of this library in the same thread.
+#[no_mangle]
+pub extern "C" fn nemo_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Release a string that was returned by a function of this library.
+///
+/// # Safety
+/// The string must have been returned by this library and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn nemo_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
@@ -0,0 +1,30 @@
+//! C bindings for nemo.
+//!
+//! All functions are prefixed with `nemo_` and declared in `include/nemo.h`.
+//! Objects are handed out as opaque pointers that must be released with the matching `*_free` function.
+//! Functions that may fail either return a [NemoStatus] or a null pointer,
+//! in which case [nemo_last_error] describes the error.
+
+#![deny(
+    missing_debug_implementations,
+    missing_copy_implementations,
+    trivial_casts,
+    trivial_numeric_casts
+)]
+#![warn(
+    missing_docs,
+    unused_import_braces,
+    unused_qualifications,
+    unused_extern_crates,
+    variant_size_differences
+)]
+
+pub mod engine;
+pub mod error;
+pub mod results;
+pub mod value;
+
+pub use engine::NemoEngine;
+pub use error::{nemo_last_error, nemo_string_free, NemoStatus};
+pub use results::NemoResults;
+pub use value::{NemoValue, NemoValueKind};
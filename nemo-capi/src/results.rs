@@ -0,0 +1,93 @@
+//! Iterating over the facts of a predicate.
+
+use std::ptr;
+
+use nemo::datavalues::AnyDataValue;
+
+use crate::{error::guard, value::NemoValue};
+
+/// Iterator over the facts of a predicate, see [nemo_engine_result][crate::engine::nemo_engine_result]
+///
+/// Initially, the iterator is positioned before the first fact,
+/// so [nemo_results_next] has to be called before the values of the first fact can be read.
+#[derive(Debug)]
+pub struct NemoResults {
+    /// Facts that have not been visited yet
+    rows: std::vec::IntoIter<Vec<AnyDataValue>>,
+    /// Values of the current fact
+    current: Vec<NemoValue>,
+}
+
+impl NemoResults {
+    /// Create a new [NemoResults] that iterates over the given rows.
+    pub(crate) fn new(rows: Vec<Vec<AnyDataValue>>) -> Self {
+        Self {
+            rows: rows.into_iter(),
+            current: Vec::new(),
+        }
+    }
+}
+
+/// Advance to the next fact.
+///
+/// Returns false if there are no more facts.
+///
+/// # Safety
+/// `results` must point to valid [NemoResults].
+#[no_mangle]
+pub unsafe extern "C" fn nemo_results_next(results: *mut NemoResults) -> bool {
+    guard(false, || {
+        let Some(results) = results.as_mut() else {
+            return Ok(false);
+        };
+
+        match results.rows.next() {
+            Some(row) => {
+                results.current = row.into_iter().map(NemoValue).collect();
+                Ok(true)
+            }
+            None => {
+                results.current.clear();
+                Ok(false)
+            }
+        }
+    })
+}
+
+/// Return the number of values of the current fact.
+///
+/// # Safety
+/// `results` must point to valid [NemoResults].
+#[no_mangle]
+pub unsafe extern "C" fn nemo_results_arity(results: *const NemoResults) -> usize {
+    results.as_ref().map_or(0, |results| results.current.len())
+}
+
+/// Return the value at the given position of the current fact,
+/// or null if the position is out of range.
+///
+/// The value is owned by the results and stays valid until the next call of [nemo_results_next].
+///
+/// # Safety
+/// `results` must point to valid [NemoResults].
+#[no_mangle]
+pub unsafe extern "C" fn nemo_results_value(
+    results: *const NemoResults,
+    index: usize,
+) -> *const NemoValue {
+    results
+        .as_ref()
+        .and_then(|results| results.current.get(index))
+        .map_or(ptr::null(), ptr::from_ref)
+}
+
+/// Release the given results.
+///
+/// # Safety
+/// `results` must have been created by this library and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn nemo_results_free(results: *mut NemoResults) {
+    if !results.is_null() {
+        drop(Box::from_raw(results));
+    }
+}
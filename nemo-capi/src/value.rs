@@ -0,0 +1,298 @@
+//! Values of facts, which are used to add facts and to read results.
+
+use std::{ffi::c_char, ptr};
+
+use nemo::datavalues::{AnyDataValue, DataValue, ValueDomain};
+
+use crate::error::{guard, owned_string, str_argument, Error};
+
+/// A value of a fact
+///
+/// Values that are created with one of the `nemo_value_new_*` functions
+/// must be released with [nemo_value_free].
+/// Values that are obtained from [NemoResults][crate::NemoResults] are owned by the results.
+#[derive(Debug, Clone)]
+pub struct NemoValue(pub(crate) AnyDataValue);
+
+/// Kinds of [NemoValue]s
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NemoValueKind {
+    /// An IRI or a constant name
+    Iri = 0,
+    /// A string without language tag
+    String = 1,
+    /// A string with language tag
+    LanguageString = 2,
+    /// An integer that fits into 64 bits
+    Integer = 3,
+    /// A floating point number
+    Double = 4,
+    /// A boolean
+    Boolean = 5,
+    /// A null, i.e., a value invented by an existential rule
+    Null = 6,
+    /// Any other value, which can be read as a string
+    Other = 7,
+}
+
+impl NemoValue {
+    /// Return the [NemoValueKind] of this value.
+    fn kind(&self) -> NemoValueKind {
+        match self.0.value_domain() {
+            ValueDomain::Iri => NemoValueKind::Iri,
+            ValueDomain::PlainString => NemoValueKind::String,
+            ValueDomain::LanguageTaggedString => NemoValueKind::LanguageString,
+            ValueDomain::Float | ValueDomain::Double => NemoValueKind::Double,
+            ValueDomain::Boolean => NemoValueKind::Boolean,
+            ValueDomain::Null => NemoValueKind::Null,
+            ValueDomain::UnsignedLong
+            | ValueDomain::NonNegativeLong
+            | ValueDomain::UnsignedInt
+            | ValueDomain::NonNegativeInt
+            | ValueDomain::Long
+            | ValueDomain::Int
+                if self.0.fits_into_i64() =>
+            {
+                NemoValueKind::Integer
+            }
+            _ => NemoValueKind::Other,
+        }
+    }
+
+    /// Return a pointer to a new value that has to be released by the caller.
+    fn into_raw(value: AnyDataValue) -> *mut NemoValue {
+        Box::into_raw(Box::new(NemoValue(value)))
+    }
+}
+
+/// Return a reference to the value behind the given pointer.
+///
+/// # Safety
+/// The pointer must either be null or point to a valid [NemoValue].
+pub(crate) unsafe fn value_argument<'a>(value: *const NemoValue) -> Result<&'a NemoValue, Error> {
+    value
+        .as_ref()
+        .ok_or_else(|| Error::new("argument value is null"))
+}
+
+/// Create an IRI or a constant name.
+///
+/// Returns null if the given string is not valid UTF-8.
+///
+/// # Safety
+/// `iri` must be a nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nemo_value_new_iri(iri: *const c_char) -> *mut NemoValue {
+    guard(ptr::null_mut(), || {
+        let iri = str_argument(iri, "iri")?;
+        Ok(NemoValue::into_raw(AnyDataValue::new_iri(iri.to_string())))
+    })
+}
+
+/// Create a string without language tag.
+///
+/// Returns null if the given string is not valid UTF-8.
+///
+/// # Safety
+/// `string` must be a nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nemo_value_new_string(string: *const c_char) -> *mut NemoValue {
+    guard(ptr::null_mut(), || {
+        let string = str_argument(string, "string")?;
+        Ok(NemoValue::into_raw(AnyDataValue::new_plain_string(
+            string.to_string(),
+        )))
+    })
+}
+
+/// Create a string with a language tag.
+///
+/// Returns null if one of the given strings is not valid UTF-8.
+///
+/// # Safety
+/// `string` and `language` must be nul-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn nemo_value_new_language_string(
+    string: *const c_char,
+    language: *const c_char,
+) -> *mut NemoValue {
+    guard(ptr::null_mut(), || {
+        let string = str_argument(string, "string")?;
+        let language = str_argument(language, "language")?;
+        Ok(NemoValue::into_raw(
+            AnyDataValue::new_language_tagged_string(string.to_string(), language.to_string()),
+        ))
+    })
+}
+
+/// Create an integer.
+#[no_mangle]
+pub extern "C" fn nemo_value_new_integer(integer: i64) -> *mut NemoValue {
+    NemoValue::into_raw(AnyDataValue::new_integer_from_i64(integer))
+}
+
+/// Create a floating point number.
+///
+/// Returns null if the number is not finite.
+#[no_mangle]
+pub extern "C" fn nemo_value_new_double(number: f64) -> *mut NemoValue {
+    guard(ptr::null_mut(), || {
+        let value =
+            AnyDataValue::new_double_from_f64(number).map_err(|err| Error::new(err.to_string()))?;
+        Ok(NemoValue::into_raw(value))
+    })
+}
+
+/// Create a boolean.
+#[no_mangle]
+pub extern "C" fn nemo_value_new_boolean(boolean: bool) -> *mut NemoValue {
+    NemoValue::into_raw(AnyDataValue::new_boolean(boolean))
+}
+
+/// Release a value that was created with one of the `nemo_value_new_*` functions.
+///
+/// # Safety
+/// The value must have been created by this library and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn nemo_value_free(value: *mut NemoValue) {
+    if !value.is_null() {
+        drop(Box::from_raw(value));
+    }
+}
+
+/// Return the [NemoValueKind] of the given value.
+///
+/// # Safety
+/// `value` must point to a valid [NemoValue].
+#[no_mangle]
+pub unsafe extern "C" fn nemo_value_kind(value: *const NemoValue) -> NemoValueKind {
+    guard(NemoValueKind::Other, || Ok(value_argument(value)?.kind()))
+}
+
+/// Write the integer represented by the given value into `integer`.
+///
+/// Returns false if the value is not of kind [NemoValueKind::Integer].
+///
+/// # Safety
+/// `value` must point to a valid [NemoValue] and `integer` to writable memory.
+#[no_mangle]
+pub unsafe extern "C" fn nemo_value_as_integer(value: *const NemoValue, integer: *mut i64) -> bool {
+    guard(false, || {
+        let value = value_argument(value)?;
+        if value.kind() != NemoValueKind::Integer || integer.is_null() {
+            return Ok(false);
+        }
+
+        *integer = value.0.to_i64_unchecked();
+        Ok(true)
+    })
+}
+
+/// Write the floating point number represented by the given value into `number`.
+///
+/// Returns false if the value is not of kind [NemoValueKind::Double].
+///
+/// # Safety
+/// `value` must point to a valid [NemoValue] and `number` to writable memory.
+#[no_mangle]
+pub unsafe extern "C" fn nemo_value_as_double(value: *const NemoValue, number: *mut f64) -> bool {
+    guard(false, || {
+        let value = value_argument(value)?;
+        if value.kind() != NemoValueKind::Double || number.is_null() {
+            return Ok(false);
+        }
+
+        *number = match value.0.value_domain() {
+            ValueDomain::Float => f64::from(value.0.to_f32_unchecked()),
+            _ => value.0.to_f64_unchecked(),
+        };
+        Ok(true)
+    })
+}
+
+/// Write the boolean represented by the given value into `boolean`.
+///
+/// Returns false if the value is not of kind [NemoValueKind::Boolean].
+///
+/// # Safety
+/// `value` must point to a valid [NemoValue] and `boolean` to writable memory.
+#[no_mangle]
+pub unsafe extern "C" fn nemo_value_as_boolean(
+    value: *const NemoValue,
+    boolean: *mut bool,
+) -> bool {
+    guard(false, || {
+        let value = value_argument(value)?;
+        if value.kind() != NemoValueKind::Boolean || boolean.is_null() {
+            return Ok(false);
+        }
+
+        *boolean = value.0.to_boolean_unchecked();
+        Ok(true)
+    })
+}
+
+/// Return the lexical value of the given value,
+/// e.g., the IRI without angle brackets or the contents of a string.
+///
+/// The returned string must be released with [nemo_string_free][crate::nemo_string_free].
+///
+/// # Safety
+/// `value` must point to a valid [NemoValue].
+#[no_mangle]
+pub unsafe extern "C" fn nemo_value_lexical(value: *const NemoValue) -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        let value = value_argument(value)?;
+        let lexical = match value.kind() {
+            NemoValueKind::LanguageString => value.0.to_language_tagged_string_unchecked().0,
+            _ => value.0.lexical_value(),
+        };
+
+        owned_string(lexical)
+    })
+}
+
+/// Return the language tag of the given value,
+/// or null if the value is not of kind [NemoValueKind::LanguageString].
+///
+/// The returned string must be released with [nemo_string_free][crate::nemo_string_free].
+///
+/// # Safety
+/// `value` must point to a valid [NemoValue].
+#[no_mangle]
+pub unsafe extern "C" fn nemo_value_language(value: *const NemoValue) -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        let value = value_argument(value)?;
+        match value.0.to_language_tagged_string() {
+            Some((_, language)) => owned_string(language),
+            None => Ok(ptr::null_mut()),
+        }
+    })
+}
+
+/// Return the IRI of the datatype of the given value.
+///
+/// The returned string must be released with [nemo_string_free][crate::nemo_string_free].
+///
+/// # Safety
+/// `value` must point to a valid [NemoValue].
+#[no_mangle]
+pub unsafe extern "C" fn nemo_value_datatype(value: *const NemoValue) -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        owned_string(value_argument(value)?.0.datatype_iri())
+    })
+}
+
+/// Return the representation of the given value in the syntax of nemo programs.
+///
+/// The returned string must be released with [nemo_string_free][crate::nemo_string_free].
+///
+/// # Safety
+/// `value` must point to a valid [NemoValue].
+#[no_mangle]
+pub unsafe extern "C" fn nemo_value_to_string(value: *const NemoValue) -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        owned_string(value_argument(value)?.0.to_string())
+    })
+}
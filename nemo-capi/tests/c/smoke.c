@@ -0,0 +1,157 @@
+// Exercises the C API of nemo, see tests/c_api.rs for how this is compiled.
+
+#include <stdio.h>
+#include <string.h>
+
+#include "nemo.h"
+
+#define CHECK(condition)                                                       \
+  do {                                                                         \
+    if (!(condition)) {                                                        \
+      const char *error = nemo_last_error();                                   \
+      fprintf(stderr, "%s:%d: check failed: %s (last error: %s)\n", __FILE__,  \
+              __LINE__, #condition, error ? error : "none");                   \
+      return 1;                                                                \
+    }                                                                          \
+  } while (0)
+
+static const char *PROGRAM =
+    "parent(alice, bob) .\n"
+    "ancestor(?x, ?y) :- parent(?x, ?y) .\n"
+    "ancestor(?x, ?z) :- ancestor(?x, ?y), parent(?y, ?z) .\n"
+    "age(?x, ?n) :- years(?x, ?n) .\n";
+
+static int test_values(void) {
+  int64_t integer = 0;
+  double number = 0.0;
+  bool boolean = false;
+
+  NemoValue *value = nemo_value_new_integer(42);
+  CHECK(value != NULL);
+  CHECK(nemo_value_kind(value) == NEMO_VALUE_KIND_INTEGER);
+  CHECK(nemo_value_as_integer(value, &integer) && integer == 42);
+  CHECK(!nemo_value_as_double(value, &number));
+  nemo_value_free(value);
+
+  value = nemo_value_new_double(2.5);
+  CHECK(value != NULL);
+  CHECK(nemo_value_kind(value) == NEMO_VALUE_KIND_DOUBLE);
+  CHECK(nemo_value_as_double(value, &number) && number == 2.5);
+  nemo_value_free(value);
+
+  CHECK(nemo_value_new_double(1.0 / 0.0) == NULL);
+  CHECK(nemo_last_error() != NULL);
+
+  value = nemo_value_new_boolean(true);
+  CHECK(nemo_value_kind(value) == NEMO_VALUE_KIND_BOOLEAN);
+  CHECK(nemo_value_as_boolean(value, &boolean) && boolean);
+  nemo_value_free(value);
+
+  value = nemo_value_new_language_string("Hallo", "de");
+  CHECK(nemo_value_kind(value) == NEMO_VALUE_KIND_LANGUAGE_STRING);
+  char *lexical = nemo_value_lexical(value);
+  char *language = nemo_value_language(value);
+  CHECK(strcmp(lexical, "Hallo") == 0);
+  CHECK(strcmp(language, "de") == 0);
+  nemo_string_free(lexical);
+  nemo_string_free(language);
+  nemo_value_free(value);
+
+  value = nemo_value_new_string("text");
+  CHECK(nemo_value_kind(value) == NEMO_VALUE_KIND_STRING);
+  CHECK(nemo_value_language(value) == NULL);
+  char *datatype = nemo_value_datatype(value);
+  CHECK(strcmp(datatype, "http://www.w3.org/2001/XMLSchema#string") == 0);
+  nemo_string_free(datatype);
+  nemo_value_free(value);
+
+  return 0;
+}
+
+static int test_reasoning(void) {
+  NemoEngine *engine = nemo_engine_new(PROGRAM);
+  CHECK(engine != NULL);
+
+  NemoValue *bob = nemo_value_new_iri("bob");
+  NemoValue *carol = nemo_value_new_iri("carol");
+  const NemoValue *fact[] = {bob, carol};
+  CHECK(nemo_engine_add_fact(engine, "parent", fact, 2) == NEMO_STATUS_OK);
+
+  NemoValue *age = nemo_value_new_integer(7);
+  const NemoValue *years[] = {carol, age};
+  CHECK(nemo_engine_add_fact(engine, "years", years, 2) == NEMO_STATUS_OK);
+
+  // wrong number of values
+  CHECK(nemo_engine_add_fact(engine, "parent", fact, 1) == NEMO_STATUS_ERROR);
+  CHECK(nemo_last_error() != NULL);
+
+  nemo_value_free(bob);
+  nemo_value_free(carol);
+  nemo_value_free(age);
+
+  CHECK(nemo_engine_reason(engine) == NEMO_STATUS_OK);
+  CHECK(nemo_last_error() == NULL);
+
+  NemoResults *results = nemo_engine_result(engine, "ancestor");
+  CHECK(results != NULL);
+  size_t count = 0;
+  while (nemo_results_next(results)) {
+    CHECK(nemo_results_arity(results) == 2);
+    const NemoValue *first = nemo_results_value(results, 0);
+    CHECK(nemo_value_kind(first) == NEMO_VALUE_KIND_IRI);
+    CHECK(nemo_results_value(results, 2) == NULL);
+    count++;
+  }
+  CHECK(count == 3);
+  nemo_results_free(results);
+
+  results = nemo_engine_result(engine, "age");
+  CHECK(results != NULL);
+  CHECK(nemo_results_next(results));
+  int64_t integer = 0;
+  CHECK(nemo_value_as_integer(nemo_results_value(results, 1), &integer));
+  CHECK(integer == 7);
+  char *text = nemo_value_to_string(nemo_results_value(results, 0));
+  CHECK(strcmp(text, "carol") == 0);
+  nemo_string_free(text);
+  CHECK(!nemo_results_next(results));
+  nemo_results_free(results);
+
+  char *trace = NULL;
+  CHECK(nemo_engine_trace(engine, "ancestor(alice, carol)", &trace) ==
+        NEMO_STATUS_OK);
+  CHECK(trace != NULL);
+  CHECK(strstr(trace, "parent(bob, carol)") != NULL);
+  nemo_string_free(trace);
+
+  CHECK(nemo_engine_trace(engine, "ancestor(carol, alice)", &trace) ==
+        NEMO_STATUS_OK);
+  CHECK(trace == NULL);
+
+  CHECK(nemo_engine_trace(engine, "ancestor(", &trace) == NEMO_STATUS_ERROR);
+  CHECK(nemo_last_error() != NULL);
+
+  nemo_engine_free(engine);
+  return 0;
+}
+
+static int test_errors(void) {
+  CHECK(nemo_engine_new("p(?x) :- q(?x") == NULL);
+  CHECK(nemo_last_error() != NULL);
+
+  CHECK(nemo_engine_new(NULL) == NULL);
+  CHECK(strstr(nemo_last_error(), "program") != NULL);
+
+  CHECK(nemo_engine_reason(NULL) == NEMO_STATUS_ERROR);
+
+  return 0;
+}
+
+int main(void) {
+  if (test_values() != 0 || test_reasoning() != 0 || test_errors() != 0) {
+    return 1;
+  }
+
+  printf("ok\n");
+  return 0;
+}
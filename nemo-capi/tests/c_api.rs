@@ -0,0 +1,72 @@
+//! Compiles the C programs in `tests/c` against the shared library and runs them.
+
+#![cfg(unix)]
+
+use std::{
+    env,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Directory containing the shared library built for the tests
+fn library_dir() -> PathBuf {
+    let library = format!(
+        "{}nemo_capi{}",
+        env::consts::DLL_PREFIX,
+        env::consts::DLL_SUFFIX
+    );
+
+    // the library is placed in `target/<profile>`, which contains the test executable
+    env::current_exe()
+        .expect("path of the test executable is known")
+        .ancestors()
+        .find(|dir| dir.join(&library).exists())
+        .expect("shared library is built before the tests")
+        .to_path_buf()
+}
+
+/// Compile the given C source file and run the resulting program.
+fn compile_and_run(source: &str) {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let library_dir = library_dir();
+    let executable = Path::new(env!("CARGO_TARGET_TMPDIR")).join(source.trim_end_matches(".c"));
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+
+    let output = Command::new(compiler)
+        .arg("-std=c99")
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg(manifest_dir.join("tests/c").join(source))
+        .arg("-o")
+        .arg(&executable)
+        .arg("-L")
+        .arg(&library_dir)
+        .arg("-lnemo_capi")
+        .arg(format!("-Wl,-rpath,{}", library_dir.display()))
+        .output()
+        .expect("C compiler can be executed");
+    assert!(
+        output.status.success(),
+        "compiling {source} failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = Command::new(&executable)
+        .current_dir(manifest_dir)
+        .output()
+        .expect("compiled test can be executed");
+    assert!(
+        output.status.success(),
+        "running {source} failed:\n{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn smoke() {
+    compile_and_run("smoke.c");
+}
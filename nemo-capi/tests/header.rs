@@ -0,0 +1,25 @@
+//! Checks that the committed header `include/nemo.h` agrees with the output of cbindgen.
+
+use std::{fs, path::Path};
+
+#[test]
+fn header_is_up_to_date() {
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml"))
+        .expect("configuration of cbindgen is valid");
+
+    let mut generated = Vec::new();
+    cbindgen::Builder::new()
+        .with_crate(crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("bindings can be generated")
+        .write(&mut generated);
+
+    let committed = fs::read(crate_dir.join("include/nemo.h")).expect("header can be read");
+    assert!(
+        generated == committed,
+        "include/nemo.h is outdated, regenerate it in nemo-capi with \
+         `cbindgen --config cbindgen.toml --output include/nemo.h`"
+    );
+}
@@ -38,6 +38,18 @@ pub(crate) enum Reporting {
     Mem,
     /// Print short report and all details on timing and memory usage.
     All,
+    /// Print short report and statistics about the applications of each rule, sorted by time.
+    Rules,
+}
+
+/// Possible settings for handling violations of integrity constraints.
+#[derive(clap::ValueEnum, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub(crate) enum OnViolation {
+    /// Stop reasoning at the first violation.
+    #[default]
+    Abort,
+    /// Complete reasoning and report all violations.
+    Report,
 }
 
 /// Cli Arguments related to logging
@@ -130,11 +142,57 @@ pub(crate) struct TracingArgs {
     pub(crate) output_file: Option<PathBuf>,
 }
 
+/// Cli arguments of the check subcommand
+#[derive(Debug, clap::Args)]
+pub(crate) struct CheckArgs {
+    /// One or more rule program files
+    #[arg(value_parser, required = true)]
+    pub(crate) rules: Vec<PathBuf>,
+    /// Exit with a non-zero status if any warnings are reported
+    #[arg(long = "deny-warnings", default_value = "false")]
+    pub(crate) deny_warnings: bool,
+}
+
+/// Cli arguments of the fmt subcommand
+#[derive(Debug, clap::Args)]
+pub(crate) struct FmtArgs {
+    /// One or more rule program files
+    #[arg(value_parser, required = true)]
+    pub(crate) rules: Vec<PathBuf>,
+    /// Overwrite the given files with their formatted version instead of printing it
+    #[arg(short = 'w', long = "write", default_value = "false")]
+    pub(crate) write: bool,
+    /// Only check whether the given files are formatted, exiting with a non-zero status if not
+    #[arg(long = "check", default_value = "false", conflicts_with = "write")]
+    pub(crate) check: bool,
+}
+
+/// Subcommands of the CLI
+#[derive(Debug, clap::Subcommand)]
+pub(crate) enum Command {
+    /// Check rule programs for likely mistakes without reasoning
+    Check(CheckArgs),
+    /// Format rule programs in a canonical layout
+    Fmt(FmtArgs),
+}
+
 /// Nemo CLI
 #[derive(clap::Parser, Debug)]
-#[command(author, version, about)]
+#[command(
+    author,
+    version,
+    about,
+    subcommand_negates_reqs = true,
+    args_conflicts_with_subcommands = true
+)]
 pub(crate) struct CliApp {
+    /// Subcommand to run instead of reasoning
+    #[command(subcommand)]
+    pub(crate) command: Option<Command>,
     /// One or more rule program files
+    ///
+    /// A file whose name is also the name of a subcommand, such as `check`,
+    /// is read as that subcommand and has to be given as a path instead, e.g., `./check`.
     #[arg(value_parser, required = true)]
     pub(crate) rules: Vec<PathBuf>,
     /// Arguments related to output
@@ -143,6 +201,24 @@ pub(crate) struct CliApp {
     /// Base directory for importing files (default is working directory)
     #[arg(short = 'I', long = "import-dir")]
     pub(crate) import_directory: Option<PathBuf>,
+    /// Only derive facts that are relevant for the output and export predicates
+    #[arg(long = "magic-sets", default_value = "false")]
+    pub(crate) magic_sets: bool,
+    /// Whether to stop reasoning at the first violation of an integrity constraint
+    /// or to report all violations, together with their traces
+    #[arg(long = "on-violation", value_enum, default_value_t)]
+    pub(crate) on_violation: OnViolation,
+    /// Export every fact together with the number of its derivations
+    /// and the inputs and rules that contribute to it, as two additional columns
+    #[arg(long = "provenance", default_value = "false")]
+    pub(crate) provenance: bool,
+    /// Reduce the result of reasoning to its core by removing facts with redundant nulls
+    #[arg(long = "core", default_value = "false")]
+    pub(crate) core: bool,
+    /// Print the execution plans of the first and the most recent application of each rule,
+    /// together with the number of rows of each computed table
+    #[arg(long = "explain", default_value = "false")]
+    pub(crate) explain: bool,
     /// Arguments related to tracing
     #[command(flatten)]
     pub(crate) tracing: TracingArgs,
@@ -20,21 +20,29 @@
 
 pub mod cli;
 
-use std::fs::{read_to_string, File};
+use std::{
+    fs::{read_to_string, File},
+    time::Duration,
+};
 
 use clap::Parser;
-use cli::{CliApp, Exporting, Reporting};
+use cli::{CheckArgs, CliApp, Command, Exporting, FmtArgs, OnViolation, Reporting};
 use colored::Colorize;
 use nemo::{
     error::{Error, ReadingError},
-    execution::{DefaultExecutionEngine, ExecutionEngine},
+    execution::{
+        ConstraintViolation, DefaultExecutionEngine, ExecutionEngine, ExecutionParameters,
+        ViolationHandling,
+    },
     io::{
+        formatter::format_program,
         parser::{parse_fact, parse_program},
         resource_providers::ResourceProviders,
         ImportManager,
     },
     meta::timing::{TimedCode, TimedDisplay},
     model::{ExportDirective, Program},
+    program_analysis::lint::{check_program, LintSeverity},
 };
 
 /// Set exports according to command-line parameter.
@@ -69,28 +77,37 @@ fn override_exports(program: &mut Program, value: Exporting) {
     program.add_exports(additional_exports);
 }
 
+/// Prints the given violations of integrity constraints together with their traces.
+fn print_violations(engine: &mut DefaultExecutionEngine, violations: &[ConstraintViolation]) {
+    let (trace, handles) = engine.trace_violations(violations);
+
+    for (violation, handle) in violations.iter().zip(handles) {
+        println!("\nIntegrity constraint {violation}");
+        if let Some(tree) = trace.tree(handle) {
+            print!("{}", tree.to_ascii_art());
+        }
+    }
+}
+
 /// Prints short summary message.
-fn print_finished_message(new_facts: usize, saving: bool) {
-    let overall_time = TimedCode::instance().total_system_time().as_millis();
-    let reading_time = TimedCode::instance()
+fn print_finished_message(timing: &mut TimedCode, new_facts: usize, saving: bool) {
+    let overall_time = timing.total_system_time().as_millis();
+    let reading_time = timing
         .sub("Reading & Preprocessing")
         .total_system_time()
         .as_millis();
-    let loading_time = TimedCode::instance()
+    let loading_time = timing
         .sub("Reasoning/Execution/Load Table")
         .total_system_time()
         .as_millis();
-    let execution_time = TimedCode::instance()
-        .sub("Reasoning")
-        .total_system_time()
-        .as_millis();
+    let execution_time = timing.sub("Reasoning").total_system_time().as_millis();
 
     // NOTE: for some reason the subtraction produced an overflow for me once when running the tests; so better safe than sorry now :)
     let loading_preprocessing = reading_time.saturating_add(loading_time);
     let reasoning_time = execution_time.saturating_sub(loading_time);
 
     let writing_time = if saving {
-        TimedCode::instance()
+        timing
             .sub("Output & Final Materialization")
             .total_system_time()
             .as_millis()
@@ -130,10 +147,10 @@ fn print_finished_message(new_facts: usize, saving: bool) {
 }
 
 /// Prints detailed timing information.
-fn print_timing_details() {
+fn print_timing_details(timing: &TimedCode) {
     println!(
         "\nTiming report:\n\n{}",
-        TimedCode::instance().create_tree_string(
+        timing.create_tree_string(
             "nemo",
             &[
                 TimedDisplay::default(),
@@ -144,14 +161,110 @@ fn print_timing_details() {
     );
 }
 
+/// Prints the position and the text of the rule with the given index.
+fn print_rule_heading(engine: &DefaultExecutionEngine, program: &Program, rule: usize) {
+    let position = engine.instrumentation().rules()[rule].position;
+    let source = position.and_then(|position| {
+        program
+            .rules()
+            .iter()
+            .find(|rule| rule.position() == Some(position))
+            .map(|rule| (position, rule))
+    });
+
+    match source {
+        Some((position, source_rule)) => {
+            println!(
+                "\n{} {source_rule}",
+                format!("line {}:", position.line).bold()
+            )
+        }
+        None => println!("\n{}", format!("rule {rule} (generated):").bold()),
+    }
+}
+
+/// Prints statistics about the applications of each rule,
+/// starting with the rule that took the longest.
+fn print_rule_details(engine: &DefaultExecutionEngine, program: &Program) {
+    let millis = |duration: Duration| format!("{}ms", duration.as_millis());
+
+    println!("\nRule report:");
+
+    let instrumentation = engine.instrumentation();
+    for rule in instrumentation.rules_by_cost() {
+        let statistics = &instrumentation.rules()[rule];
+
+        print_rule_heading(engine, program, rule);
+
+        println!(
+            "   {0: <14} {1} (body {2}, head {3})",
+            "Time:",
+            millis(statistics.time),
+            millis(statistics.body_time),
+            millis(statistics.head_time)
+        );
+        println!(
+            "   {0: <14} {1} ({2} productive)",
+            "Applications:", statistics.applications, statistics.productive_applications
+        );
+
+        let mut derived_facts = statistics.derived_facts.iter().collect::<Vec<_>>();
+        derived_facts.sort();
+        let derived_facts = derived_facts
+            .into_iter()
+            .map(|(predicate, facts)| format!("{predicate}: {facts}"))
+            .collect::<Vec<_>>();
+        println!(
+            "   {0: <14} {1} ({2})",
+            "Derived:",
+            statistics.total_derived_facts(),
+            derived_facts.join(", ")
+        );
+
+        if let Some(duplicates) = statistics.duplicates {
+            println!("   {0: <14} {1}", "Duplicates:", duplicates);
+        }
+        if statistics.nulls > 0 {
+            println!("   {0: <14} {1}", "Nulls:", statistics.nulls);
+        }
+    }
+}
+
+/// Prints the execution plans of the first and the most recent application of each rule.
+fn print_rule_explanations(engine: &DefaultExecutionEngine, program: &Program) {
+    println!("\nExecution plans:");
+
+    let instrumentation = engine.instrumentation();
+    for rule in 0..instrumentation.rules().len() {
+        let explanations = instrumentation.explanations(rule);
+        if explanations.is_empty() {
+            continue;
+        }
+
+        print_rule_heading(engine, program, rule);
+        let omitted = instrumentation.rules()[rule].applications - explanations.len();
+        for (index, explanation) in explanations.iter().enumerate() {
+            if index > 0 && omitted > 0 {
+                println!("({omitted} further applications omitted)");
+            }
+            println!("{explanation}");
+        }
+    }
+}
+
 /// Prints detailed memory information.
 fn print_memory_details(engine: &DefaultExecutionEngine) {
     println!("\nMemory report:\n\n{}", engine.memory_usage());
+    println!(
+        "Peak memory: {}",
+        engine.instrumentation().peak_memory().to_string().bold()
+    );
 }
 
 fn run(mut cli: CliApp) -> Result<(), Error> {
-    TimedCode::instance().start();
-    TimedCode::instance().sub("Reading & Preprocessing").start();
+    let mut timing = TimedCode::new();
+    timing.start();
+    timing.sub("Reading & Preprocessing").start();
 
     log::info!("Parsing rules ...");
 
@@ -217,43 +330,71 @@ fn run(mut cli: CliApp) -> Result<(), Error> {
     let import_manager =
         ImportManager::new(ResourceProviders::with_base_path(cli.import_directory));
 
-    let mut engine: DefaultExecutionEngine = ExecutionEngine::initialize(&program, import_manager)?;
+    let parameters = ExecutionParameters {
+        magic_sets: cli.magic_sets,
+        constraint_violations: match cli.on_violation {
+            OnViolation::Abort => ViolationHandling::Abort,
+            OnViolation::Report => ViolationHandling::Report,
+        },
+        provenance: cli.provenance,
+        count_duplicates: cli.reporting == Reporting::Rules,
+        explain: cli.explain,
+        ..Default::default()
+    };
+    let mut engine: DefaultExecutionEngine =
+        ExecutionEngine::initialize_with_parameters(&program, import_manager, parameters)?;
 
-    TimedCode::instance().sub("Reading & Preprocessing").stop();
+    timing.sub("Reading & Preprocessing").stop();
 
-    TimedCode::instance().sub("Reasoning").start();
+    timing.sub("Reasoning").start();
     log::info!("Reasoning ... ");
-    engine.execute()?;
+    let result = engine.execute();
+    if let Err(Error::ConstraintViolation(violation)) = &result {
+        print_violations(&mut engine, std::slice::from_ref(violation));
+    }
+    result?;
+    let core_statistics = if cli.core {
+        Some(engine.compute_core()?)
+    } else {
+        None
+    };
     log::info!("Reasoning done");
-    TimedCode::instance().sub("Reasoning").stop();
+    timing.sub("Reasoning").stop();
 
     let mut stdout_used = false;
     if !export_manager.write_disabled() {
-        TimedCode::instance()
-            .sub("Output & Final Materialization")
-            .start();
+        timing.sub("Output & Final Materialization").start();
         log::info!("writing output");
 
         for export_directive in program.exports() {
-            if let Some(arity) = engine.predicate_arity(export_directive.predicate()) {
-                stdout_used |= export_manager.export_table(
+            let Some(arity) = engine.predicate_arity(export_directive.predicate()) else {
+                continue;
+            };
+
+            stdout_used |= if cli.provenance {
+                export_manager.export_table(
+                    export_directive,
+                    engine.predicate_rows_with_provenance(export_directive.predicate())?,
+                    arity + 2,
+                )?
+            } else {
+                export_manager.export_table(
                     export_directive,
                     engine.predicate_rows(export_directive.predicate())?,
                     arity,
-                )?;
-            }
+                )?
+            };
         }
 
-        TimedCode::instance()
-            .sub("Output & Final Materialization")
-            .stop();
+        timing.sub("Output & Final Materialization").stop();
     }
 
-    TimedCode::instance().stop();
+    timing.stop();
+    timing.merge(engine.timing());
 
     let (print_summary, print_times, print_memory) = match cli.reporting {
         Reporting::All => (true, true, true),
-        Reporting::Short => (true, false, false),
+        Reporting::Short | Reporting::Rules => (true, false, false),
         Reporting::Time => (true, true, false),
         Reporting::Mem => (true, false, true),
         Reporting::None => (false, false, false),
@@ -262,16 +403,36 @@ fn run(mut cli: CliApp) -> Result<(), Error> {
 
     if print_summary {
         print_finished_message(
+            &mut timing,
             engine.count_facts_of_derived_predicates(),
             !export_manager.write_disabled(),
         );
+
+        if let Some(statistics) = core_statistics {
+            println!("Core computation {statistics}.");
+        }
     }
     if print_times {
-        print_timing_details();
+        print_timing_details(&timing);
     }
     if print_memory {
         print_memory_details(&engine);
     }
+    if cli.reporting == Reporting::Rules {
+        print_rule_details(&engine, &program);
+    }
+    if cli.explain {
+        print_rule_explanations(&engine, &program);
+    }
+
+    let violations = engine.constraint_violations()?;
+    if !violations.is_empty() {
+        println!(
+            "\n{} integrity constraint violations",
+            violations.len().to_string().red().bold()
+        );
+        print_violations(&mut engine, &violations);
+    }
 
     if let Some(facts) = facts_to_be_traced {
         let (trace, handles) = engine.trace(program.clone(), facts.clone());
@@ -301,15 +462,115 @@ fn run(mut cli: CliApp) -> Result<(), Error> {
     Ok(())
 }
 
+/// Checks the given rule files for likely mistakes and prints the results.
+///
+/// Returns `false` if the check failed, i.e. if there were errors
+/// or if there were warnings and those are not allowed.
+fn check(args: CheckArgs) -> Result<bool, Error> {
+    let mut num_errors = 0;
+    let mut num_warnings = 0;
+
+    for rules in args.rules {
+        let filename = rules.to_string_lossy().to_string();
+        let rules_content = read_to_string(rules).map_err(|err| ReadingError::IoReading {
+            error: err,
+            filename: filename.clone(),
+        })?;
+
+        let program = match parse_program(rules_content) {
+            Ok(program) => program,
+            Err(err) => {
+                println!("{filename}: {} {err}", "error:".red().bold());
+                num_errors += 1;
+                continue;
+            }
+        };
+
+        for lint in check_program(&program) {
+            let location = match lint.position {
+                Some(position) => format!("{filename}:{position}"),
+                None => filename.clone(),
+            };
+            let severity = match lint.severity() {
+                LintSeverity::Warning => {
+                    num_warnings += 1;
+                    format!("warning[{}]:", lint.kind.code()).yellow().bold()
+                }
+                LintSeverity::Error => {
+                    num_errors += 1;
+                    format!("error[{}]:", lint.kind.code()).red().bold()
+                }
+            };
+
+            println!("{location}: {severity} {}", lint.kind);
+        }
+    }
+
+    println!("Check finished with {num_errors} error(s) and {num_warnings} warning(s).");
+
+    Ok(num_errors == 0 && (num_warnings == 0 || !args.deny_warnings))
+}
+
+/// Format the given rule files.
+///
+/// Returns `false` if `--check` is given and some file is not formatted.
+fn fmt(args: FmtArgs) -> Result<bool, Error> {
+    let mut all_formatted = true;
+
+    for rules in args.rules {
+        let filename = rules.to_string_lossy().to_string();
+        let rules_content = read_to_string(&rules).map_err(|err| ReadingError::IoReading {
+            error: err,
+            filename: filename.clone(),
+        })?;
+
+        let formatted = format_program(&rules_content)?;
+
+        if args.check {
+            if formatted != rules_content {
+                println!("{filename} is not formatted");
+                all_formatted = false;
+            }
+        } else if args.write {
+            if formatted != rules_content {
+                std::fs::write(&rules, formatted)
+                    .map_err(|error| Error::IOWriting { error, filename })?;
+            }
+        } else {
+            print!("{formatted}");
+        }
+    }
+
+    Ok(all_formatted)
+}
+
 fn main() {
     let cli = CliApp::parse();
 
     cli.logging.initialize_logging();
     log::info!("Version: {}", clap::crate_version!());
-    log::debug!("Rule files: {:?}", cli.rules);
 
-    run(cli).unwrap_or_else(|err| {
-        log::error!("{} {err}", "error:".red().bold());
-        std::process::exit(1)
-    })
+    let result = match cli.command {
+        Some(Command::Check(args)) => {
+            log::debug!("Rule files: {:?}", args.rules);
+            check(args)
+        }
+        Some(Command::Fmt(args)) => {
+            log::debug!("Rule files: {:?}", args.rules);
+            fmt(args)
+        }
+        None => {
+            log::debug!("Rule files: {:?}", cli.rules);
+            run(cli).map(|_| true)
+        }
+    };
+
+    match result {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(1),
+        Err(err) => {
+            log::error!("{} {err}", "error:".red().bold());
+            std::process::exit(1)
+        }
+    }
 }
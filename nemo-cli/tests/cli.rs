@@ -50,3 +50,268 @@ fn cli_argument_parsing() -> Result<(), Box<dyn std::error::Error>> {
         .stderr(predicate::str::contains("'--log <LOG_LEVEL>'"));
     Ok(())
 }
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn cli_check() -> Result<(), Box<dyn std::error::Error>> {
+    use assert_fs::prelude::*;
+
+    let bin = "nmo";
+    let dir = assert_fs::TempDir::new()?;
+
+    let clean = dir.child("clean.rls");
+    clean.write_str("a(1).\np(?x) :- a(?x).\n")?;
+    let sloppy = dir.child("sloppy.rls");
+    sloppy.write_str("a(1).\np(?x) :- a(?x), q(?x, ?y).\n")?;
+    let invalid = dir.child("invalid.rls");
+    invalid.write_str("a(1).\na(1, 2).\n")?;
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("check").arg(clean.path());
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Check finished with 0 error(s) and 0 warning(s).",
+    ));
+
+    cmd = Command::cargo_bin(bin)?;
+    cmd.arg("check").arg(sloppy.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("sloppy.rls:2:1"))
+        .stdout(predicate::str::contains("undefined-predicate"))
+        .stdout(predicate::str::contains("singleton-variable"));
+
+    cmd = Command::cargo_bin(bin)?;
+    cmd.arg("check").arg("--deny-warnings").arg(sloppy.path());
+    cmd.assert().failure();
+
+    cmd = Command::cargo_bin(bin)?;
+    cmd.arg("check").arg(invalid.path());
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("invalid-program"));
+
+    // A rule file named like the subcommand can be given as a path
+    let named_check = dir.child("check");
+    named_check.write_str("a(1).\n")?;
+    cmd = Command::cargo_bin(bin)?;
+    cmd.current_dir(dir.path())
+        .arg("./check")
+        .arg("--export=none");
+    cmd.assert().success();
+
+    dir.close()?;
+    Ok(())
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn cli_fmt() -> Result<(), Box<dyn std::error::Error>> {
+    use assert_fs::prelude::*;
+
+    let bin = "nmo";
+    let dir = assert_fs::TempDir::new()?;
+
+    let formatted = "% facts\na(1).\n\np(?x) :- a(?x). % rule\n";
+    let rules = dir.child("rules.rls");
+    rules.write_str("% facts\na( 1 ) .\n\n\n p(?x):-a(?x) . % rule\n")?;
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("fmt").arg(rules.path());
+    cmd.assert().success().stdout(formatted);
+
+    cmd = Command::cargo_bin(bin)?;
+    cmd.arg("fmt").arg("--check").arg(rules.path());
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("is not formatted"));
+
+    cmd = Command::cargo_bin(bin)?;
+    cmd.arg("fmt").arg("--write").arg(rules.path());
+    cmd.assert().success();
+    rules.assert(formatted);
+
+    cmd = Command::cargo_bin(bin)?;
+    cmd.arg("fmt").arg("--check").arg(rules.path());
+    cmd.assert().success();
+
+    dir.close()?;
+    Ok(())
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn cli_declared_types() -> Result<(), Box<dyn std::error::Error>> {
+    use assert_fs::prelude::*;
+
+    let bin = "nmo";
+    let dir = assert_fs::TempDir::new()?;
+
+    dir.child("data.csv").write_str("1,a\nx,b\n")?;
+    let import = dir.child("import.rls");
+    import.write_str(
+        "@declare data(int, string).\n\
+        @import data :- csv{resource=\"data.csv\"}.\n\
+        result(?x) :- data(?x, ?y).\n",
+    )?;
+    let rule = dir.child("rule.rls");
+    rule.write_str("@declare result(int).\na(\"x\").\nresult(?x) :- a(?x).\n")?;
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("-I")
+        .arg(dir.path())
+        .arg("-D")
+        .arg(dir.child("results").path())
+        .arg(import.path());
+    cmd.assert().failure().stderr(predicate::str::contains(
+        r#"import of "data" at 2:1: value x in position 1 of predicate "data" is not of declared type int"#,
+    ));
+
+    cmd = Command::cargo_bin(bin)?;
+    cmd.arg("-D")
+        .arg(dir.child("results").path())
+        .arg(rule.path());
+    cmd.assert().failure().stderr(predicate::str::contains(
+        r#"rule at 3:1: value "x" in position 1 of predicate "result" is not of declared type int"#,
+    ));
+
+    dir.close()?;
+    Ok(())
+}
+
+#[test]
+fn cli_magic_sets() -> Result<(), Box<dyn std::error::Error>> {
+    use assert_fs::prelude::*;
+
+    let bin = "nmo";
+    let dir = assert_fs::TempDir::new()?;
+
+    let program = dir.child("program.rls");
+    program.write_str(
+        "edge(1, 2). edge(2, 3). edge(4, 5).\n\
+        path(?x, ?y) :- edge(?x, ?y).\n\
+        path(?x, ?z) :- edge(?x, ?y), path(?y, ?z).\n\
+        reach(?y) :- path(1, ?y).\n\
+        @export reach :- csv{}.\n",
+    )?;
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("--magic-sets")
+        .arg("-D")
+        .arg(dir.child("results").path())
+        .arg(program.path());
+    cmd.assert().success();
+
+    let mut rows = std::fs::read_to_string(dir.child("results/reach.csv").path())?
+        .lines()
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    rows.sort();
+    assert_eq!(rows, ["2", "3"]);
+
+    dir.close()?;
+    Ok(())
+}
+
+#[cfg_attr(miri, ignore)]
+#[test]
+fn cli_integrity_constraints() -> Result<(), Box<dyn std::error::Error>> {
+    use assert_fs::prelude::*;
+
+    let bin = "nmo";
+    let dir = assert_fs::TempDir::new()?;
+
+    let program = dir.child("program.rls");
+    program.write_str(
+        "age(alice, 30). age(alice, 31). edge(1, 2). edge(2, 1).\n\
+        path(?x, ?y) :- edge(?x, ?y).\n\
+        path(?x, ?z) :- path(?x, ?y), edge(?y, ?z).\n\
+        @key age(?person, _).\n\
+        ⊥ :- path(?x, ?x).\n",
+    )?;
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg(program.path());
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("integrity constraint"))
+        .stdout(predicate::str::contains("age(alice, 30)"));
+
+    cmd = Command::cargo_bin(bin)?;
+    cmd.arg("--on-violation").arg("report").arg(program.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "4 integrity constraint violations",
+        ))
+        .stdout(predicate::str::contains(
+            "⊥ :- path(?x, ?x) . is violated for ?x = 1",
+        ))
+        .stdout(predicate::str::contains("edge(2, 1)"));
+
+    dir.close()?;
+    Ok(())
+}
+
+#[test]
+fn cli_provenance() -> Result<(), Box<dyn std::error::Error>> {
+    use assert_fs::prelude::*;
+
+    let bin = "nmo";
+    let dir = assert_fs::TempDir::new()?;
+
+    let program = dir.child("program.rls");
+    program.write_str(
+        "edge(a, b). edge(b, c). edge(a, c).\n\
+        reach(?x, ?y) :- edge(?x, ?y).\n\
+        reach(?x, ?z) :- reach(?x, ?y), edge(?y, ?z).\n\
+        @export reach :- csv {}.\n",
+    )?;
+    let output = dir.child("output");
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("--provenance")
+        .arg("-D")
+        .arg(output.path())
+        .arg(program.path());
+    cmd.assert().success();
+
+    let reach = std::fs::read_to_string(output.child("reach.csv").path())?;
+    assert!(reach.contains("a,c,2,\"\"\"input edge, rule 0, rule 1\"\"\""));
+    assert!(reach.contains("b,c,1,\"\"\"input edge, rule 0\"\"\""));
+
+    dir.close()?;
+    Ok(())
+}
+
+#[test]
+fn cli_core() -> Result<(), Box<dyn std::error::Error>> {
+    use assert_fs::prelude::*;
+
+    let bin = "nmo";
+    let dir = assert_fs::TempDir::new()?;
+
+    let program = dir.child("program.rls");
+    program.write_str(
+        "person(alice). person(bob). mother(alice, carol).\n\
+        parent(?x, !p) :- person(?x).\n\
+        parent(?x, ?m) :- mother(?x, ?m).\n\
+        @export parent :- csv {}.\n",
+    )?;
+    let output = dir.child("output");
+
+    let mut cmd = Command::cargo_bin(bin)?;
+    cmd.arg("--core")
+        .arg("-D")
+        .arg(output.path())
+        .arg(program.path());
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Core computation removed 1 facts and 1 nulls.",
+    ));
+
+    let parent = std::fs::read_to_string(output.child("parent.csv").path())?;
+    assert_eq!(parent.lines().count(), 2);
+    assert!(parent.contains("alice,carol"));
+
+    dir.close()?;
+    Ok(())
+}
@@ -73,7 +73,8 @@ impl DictEnum {
 
 fn main() {
     //env_logger::init();
-    TimedCode::instance().start();
+    let mut timing = TimedCode::new();
+    timing.start();
 
     let args: Vec<_> = env::args().collect();
     if args.len() < 3 {
@@ -114,7 +115,7 @@ fn main() {
         string_lengths = vec![];
     }
 
-    TimedCode::instance().sub("Dictionary filling").start();
+    timing.sub("Dictionary filling").start();
 
     println!("Starting to fill dictionary ...");
 
@@ -192,7 +193,7 @@ fn main() {
         count_lines += 1;
     }
 
-    TimedCode::instance().sub("Dictionary filling").stop();
+    timing.sub("Dictionary filling").stop();
 
     println!("Processed {} strings.", count_lines);
     println!(
@@ -204,11 +205,11 @@ fn main() {
         count_rejected, bytes_rejected
     );
 
-    TimedCode::instance().stop();
+    timing.stop();
 
     println!(
         "\n{}",
-        TimedCode::instance().create_tree_string(
+        timing.create_tree_string(
             "dict-bench",
             &[
                 TimedDisplay::default(),
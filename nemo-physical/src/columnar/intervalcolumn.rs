@@ -5,7 +5,7 @@
 
 pub(crate) mod interval_lookup;
 
-use std::ops::Range;
+use std::{collections::HashSet, ops::Range};
 
 use bytesize::ByteSize;
 use delegate::delegate;
@@ -70,6 +70,14 @@ where
 
         Some(interval_start..interval_end)
     }
+
+    /// Return the number of distinct values in this column.
+    pub(crate) fn num_distinct(&self) -> usize {
+        (0..self.data.len())
+            .map(|index| self.data.get(index))
+            .collect::<HashSet<_>>()
+            .len()
+    }
 }
 
 impl<'a, T, LookupMethod> Column<'a, T> for IntervalColumn<T, LookupMethod>
@@ -193,6 +201,15 @@ where
             + self.column_float.data.len()
             + self.column_double.data.len()
     }
+
+    /// Return the number of distinct values in this column
+    pub(crate) fn num_distinct(&self) -> usize {
+        self.column_id32.num_distinct()
+            + self.column_id64.num_distinct()
+            + self.column_int64.num_distinct()
+            + self.column_float.num_distinct()
+            + self.column_double.num_distinct()
+    }
 }
 
 impl<LookupMethod> ByteSized for IntervalColumnT<LookupMethod>
@@ -2,3 +2,4 @@
 
 pub mod table_providers;
 pub mod tuple_writer;
+pub mod value_validator;
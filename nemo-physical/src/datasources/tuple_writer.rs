@@ -1,7 +1,7 @@
 //! Module that allows callers to write tuples of data values into a buffer, which
 //! can later be turned into a database table.
 
-use std::cell::RefCell;
+use std::{cell::RefCell, error::Error};
 
 use crate::{
     datavalues::{AnyDataValue, DataValue, NullDataValue},
@@ -12,6 +12,8 @@ use crate::{
 
 use delegate::delegate;
 
+use super::value_validator::ValueValidator;
+
 /// The [TupleWriter] is used to send the tuples of [AnyDataValue]s to the database, so that they
 /// can be turned into a table. The interface allows values to be added one by one, and also provides
 /// some roll-back functionality for dropping a previously started tuple in case of errors.
@@ -21,6 +23,10 @@ pub struct TupleWriter<'a> {
     dictionary: &'a RefCell<Dict>,
     /// [TupleBuffer] for storing the provided tuples
     tuple_buffer: TupleBuffer,
+    /// [ValueValidator] that checks every provided value before it is written
+    validator: Option<Box<dyn ValueValidator>>,
+    /// First error reported by the validator, after which all further values are ignored
+    validation_error: Option<Box<dyn Error>>,
 }
 
 impl<'a> TupleWriter<'a> {
@@ -31,17 +37,45 @@ impl<'a> TupleWriter<'a> {
         Self {
             dictionary,
             tuple_buffer: TupleBuffer::new(column_count),
+            validator: None,
+            validation_error: None,
         }
     }
 
     /// Provide the next value for the current tuple. Values are added in in order.
     /// When the value for the last column was provided, the tuple is committed to the buffer.
     /// Alternatively, a partially built tuple can be abandonded by calling `drop_current_tuple`.
+    ///
+    /// If a [ValueValidator] is set and rejects the value, the current tuple is dropped
+    /// and all further values are ignored.
     pub fn add_tuple_value(&mut self, value: AnyDataValue) {
+        if self.validation_error.is_some() {
+            return;
+        }
+
+        if let Some(validator) = &self.validator {
+            if let Err(error) = validator.validate(self.tuple_buffer.current_column(), &value) {
+                self.validation_error = Some(error);
+                self.tuple_buffer.drop_current_tuple();
+                return;
+            }
+        }
+
         self.tuple_buffer
             .add_tuple_value(value.to_storage_value_t_dict(&mut self.dictionary.borrow_mut()));
     }
 
+    /// Set the [ValueValidator] that checks all values added from now on.
+    pub(crate) fn set_validator(&mut self, validator: Option<Box<dyn ValueValidator>>) {
+        self.validator = validator;
+    }
+
+    /// Return the error reported by the [ValueValidator], if some value was rejected,
+    /// and resume accepting values.
+    pub(crate) fn take_validation_error(&mut self) -> Option<Box<dyn Error>> {
+        self.validation_error.take()
+    }
+
     /// Create a fresh null value. This is the correct (and only) way to create nulls that
     /// should be used in the written tuples. Each null will be unequal to any other null
     /// created before, so nulls that are to be used in several places must be stored for later.
@@ -73,12 +107,12 @@ impl<'a> TupleWriter<'a> {
 
 #[cfg(test)]
 pub mod test {
-    use std::cell::RefCell;
+    use std::{cell::RefCell, error::Error};
 
     use crate::{
-        datasources::tuple_writer::TupleWriter,
+        datasources::{tuple_writer::TupleWriter, value_validator::ValueValidator},
         datatypes::{Float, StorageValueT},
-        datavalues::AnyDataValue,
+        datavalues::{AnyDataValue, DataValue},
         dictionary::{meta_dv_dict::MetaDvDictionary, DvDict},
         management::database::Dict,
     };
@@ -174,4 +208,42 @@ pub mod test {
         );
         assert_eq!(second_column.next(), None);
     }
+
+    /// Validator that only accepts integers in the first column
+    #[derive(Debug)]
+    struct IntegerKeys;
+
+    impl ValueValidator for IntegerKeys {
+        fn validate(&self, column: usize, value: &AnyDataValue) -> Result<(), Box<dyn Error>> {
+            if column == 0 && value.to_i64().is_none() {
+                return Err(format!("{value} is not an integer").into());
+            }
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn tuple_writer_validation() {
+        let dictionary = RefCell::new(Dict::new());
+        let mut writer = TupleWriter::new(&dictionary, 2);
+        writer.set_validator(Some(Box::new(IntegerKeys)));
+
+        writer.add_tuple_value(AnyDataValue::new_integer_from_i64(1));
+        writer.add_tuple_value(AnyDataValue::new_plain_string(String::from("1")));
+        assert!(writer.take_validation_error().is_none());
+
+        writer.add_tuple_value(AnyDataValue::new_integer_from_i64(2));
+        writer.add_tuple_value(AnyDataValue::new_plain_string(String::from("2")));
+        writer.add_tuple_value(AnyDataValue::new_plain_string(String::from("3")));
+        writer.add_tuple_value(AnyDataValue::new_plain_string(String::from("3")));
+        writer.add_tuple_value(AnyDataValue::new_integer_from_i64(4));
+        writer.add_tuple_value(AnyDataValue::new_plain_string(String::from("4")));
+
+        let error = writer
+            .take_validation_error()
+            .expect("value should be rejected");
+        assert_eq!(error.to_string(), r#""3" is not an integer"#);
+        assert_eq!(writer.size(), 2);
+    }
 }
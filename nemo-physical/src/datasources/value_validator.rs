@@ -0,0 +1,14 @@
+//! Module for defining a trait that can be implemented by code that restricts
+//! the values that may be written into a table.
+
+use std::error::Error;
+
+use crate::datavalues::AnyDataValue;
+
+/// This trait is implemented by code that checks the values provided to a
+/// [TupleWriter][super::tuple_writer::TupleWriter], e.g., to enforce declared types of columns.
+pub trait ValueValidator: std::fmt::Debug + Send {
+    /// Check whether the given value may be written into the column with the given index.
+    /// Returns an error describing the violation otherwise.
+    fn validate(&self, column: usize, value: &AnyDataValue) -> Result<(), Box<dyn Error>>;
+}
@@ -1,4 +1,4 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, hash::Hash};
 
 use num::{Bounded, CheckedMul};
 
@@ -14,6 +14,7 @@ pub(crate) trait ColumnDataType:
     Debug
     + Copy
     + Ord
+    + Hash
     + TryFrom<usize>
     + FloorToUsize
     + Field
@@ -29,6 +30,7 @@ impl<T> ColumnDataType for T where
     T: Debug
         + Copy
         + Ord
+        + Hash
         + TryFrom<usize>
         + FloorToUsize
         + Field
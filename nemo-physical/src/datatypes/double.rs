@@ -6,6 +6,7 @@ use num::traits::CheckedNeg;
 use num::{Bounded, CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, FromPrimitive, One, Zero};
 use std::cmp::Ordering;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::iter::{Product, Sum};
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, Sub, SubAssign};
 
@@ -86,6 +87,14 @@ impl Double {
 
 impl Eq for Double {}
 
+impl Hash for Double {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Zero and negative zero are equal and must therefore have the same hash
+        let value = if self.0 == 0.0 { 0.0 } else { self.0 };
+        value.to_bits().hash(state);
+    }
+}
+
 impl PartialOrd for Double {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -18,6 +18,13 @@ use super::{
     OtherDataValue, StringDataValue, TupleDataValue, UnsignedLongDataValue, ValueDomain,
 };
 
+/// Datatype that is reported for the values that represent the label of a blank node,
+/// which are replaced by nulls in the dictionary
+///
+/// Values of this datatype that are constructed with [AnyDataValue::new_other]
+/// are ordinary values in [ValueDomain::Other] and do not refer to blank nodes.
+const BLANK_NODE_LABEL_DATATYPE: &str = "nemo:blank-node-label";
+
 /// Supported kinds of arbitrary size numbers.
 /// The variants we consider are taken from the XML Schema
 /// datatypes. Fixed-size integer types, such as long, are
@@ -75,6 +82,10 @@ enum AnyDataValueEnum {
     Map(MapDataValue),
     /// Variant for representing [DataValue]s in [ValueDomain::Other].
     Other(OtherDataValue),
+    /// Variant for representing the label of a blank node,
+    /// which is only created by [AnyDataValue::new_blank_node_label]
+    /// and behaves like a value in [ValueDomain::Other] otherwise.
+    BlankNodeLabel(OtherDataValue),
 }
 
 /// Type that can represent arbitrary [DataValue]s.
@@ -139,6 +150,26 @@ impl AnyDataValue {
         )))
     }
 
+    /// Construct a datavalue that refers to the blank node with the given label.
+    ///
+    /// The value is replaced by a null when it is added to the dictionary,
+    /// where equal labels are always replaced by the same null.
+    pub fn new_blank_node_label(label: String) -> Self {
+        AnyDataValue(AnyDataValueEnum::BlankNodeLabel(OtherDataValue::new(
+            label,
+            BLANK_NODE_LABEL_DATATYPE.to_string(),
+        )))
+    }
+
+    /// Return the label of a datavalue that was constructed with [AnyDataValue::new_blank_node_label],
+    /// or `None` if it was constructed otherwise.
+    pub(crate) fn to_blank_node_label(&self) -> Option<String> {
+        match &self.0 {
+            AnyDataValueEnum::BlankNodeLabel(value) => Some(value.lexical_value()),
+            _ => None,
+        }
+    }
+
     /// Construct a datavalue from its physical representation as a [StorageValueT], using
     /// the given dictionary to resolve IDs.
     pub(crate) fn new_from_storage_value(
@@ -580,6 +611,7 @@ impl DataValue for AnyDataValue {
             AnyDataValueEnum::Tuple(value) => value,
             AnyDataValueEnum::Map(value) => value,
             AnyDataValueEnum::Other(value) => value,
+            AnyDataValueEnum::BlankNodeLabel(value) => value,
         } {
             fn datatype_iri(&self) -> String;
             fn lexical_value(&self) -> String;
@@ -615,6 +647,10 @@ impl DataValue for AnyDataValue {
             fn length(&self) -> Option<usize>;
             fn len_unchecked(&self) -> usize;
             fn tuple_element_unchecked(&self, _index: usize) -> &AnyDataValue;
+            fn map_keys(&self) -> Option<Box<dyn Iterator<Item = &AnyDataValue> + '_>>;
+            fn contains(&self, _key: &AnyDataValue) -> bool;
+            fn map_element(&self, _key: &AnyDataValue) -> Option<&AnyDataValue>;
+            fn map_element_unchecked(&self, _key: &AnyDataValue) -> &AnyDataValue;
         }
     }
 }
@@ -634,6 +670,7 @@ impl std::hash::Hash for AnyDataValue {
             AnyDataValueEnum::Tuple(value) => value,
             AnyDataValueEnum::Map(value) => value,
             AnyDataValueEnum::Other(value) => value,
+            AnyDataValueEnum::BlankNodeLabel(value) => value,
         } {
             /// The hash function for [AnyDataValue] is delegated to the contained value
             /// implementations, without adding variant-specific information as the
@@ -660,6 +697,7 @@ impl std::fmt::Display for AnyDataValue {
             AnyDataValueEnum::Tuple(value) => value,
             AnyDataValueEnum::Map(value) => value,
             AnyDataValueEnum::Other(value) => value,
+            AnyDataValueEnum::BlankNodeLabel(value) => value,
         } {
             /// The fmt function for [AnyDataValue] is delegated to the contained value
             /// implementations, without adding variant-specific information as the
@@ -703,6 +741,9 @@ impl PartialEq for AnyDataValue {
             (AnyDataValueEnum::Tuple(dv), AnyDataValueEnum::Tuple(dv_other)) => dv == dv_other,
             (AnyDataValueEnum::Map(dv), AnyDataValueEnum::Map(dv_other)) => dv == dv_other,
             (AnyDataValueEnum::Other(dv), AnyDataValueEnum::Other(dv_other)) => dv == dv_other,
+            (AnyDataValueEnum::BlankNodeLabel(dv), AnyDataValueEnum::BlankNodeLabel(dv_other)) => {
+                dv == dv_other
+            }
             _ => false,
         }
     }
@@ -748,6 +789,17 @@ impl Ord for AnyDataValue {
                 (AnyDataValueEnum::Other(dv), AnyDataValueEnum::Other(dv_other)) => {
                     dv.cmp(dv_other)
                 }
+                (
+                    AnyDataValueEnum::BlankNodeLabel(dv),
+                    AnyDataValueEnum::BlankNodeLabel(dv_other),
+                ) => dv.cmp(dv_other),
+                // Labels of blank nodes are ordered after all other values in [ValueDomain::Other]
+                (AnyDataValueEnum::Other(_), AnyDataValueEnum::BlankNodeLabel(_)) => {
+                    std::cmp::Ordering::Less
+                }
+                (AnyDataValueEnum::BlankNodeLabel(_), AnyDataValueEnum::Other(_)) => {
+                    std::cmp::Ordering::Greater
+                }
                 _ => unreachable!("no other combination of values can have equal domains"),
             }
         } else {
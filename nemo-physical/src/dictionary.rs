@@ -26,6 +26,8 @@ pub(crate) use string_dv_dict::StringDvDictionary;
 
 pub(crate) mod null_dv_dict;
 pub(crate) use null_dv_dict::NullDvDictionary;
+pub(crate) mod complex_dv_dict;
+pub(crate) use complex_dv_dict::ComplexDvDictionary;
 
 pub(crate) mod dictionary_string;
 
@@ -0,0 +1,132 @@
+//! A [DvDict] implementation for complex values, i.e., tuples and maps.
+
+use std::collections::HashMap;
+
+use crate::datavalues::{AnyDataValue, DataValue, ValueDomain};
+
+use super::{AddResult, DvDict};
+
+/// A [DvDict] dictionary for datavalues in the domains [ValueDomain::Tuple] and [ValueDomain::Map].
+///
+/// Complex values are stored as a whole, including all nested values.
+/// The dictionary does not support marking of values.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ComplexDvDictionary {
+    /// Map from stored values to their ids
+    ids: HashMap<AnyDataValue, usize>,
+    /// Stored values, indexed by their id
+    values: Vec<AnyDataValue>,
+}
+
+impl ComplexDvDictionary {
+    /// Construct a new and empty dictionary.
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true if the given value can be stored in this dictionary.
+    fn supports(dv: &AnyDataValue) -> bool {
+        matches!(dv.value_domain(), ValueDomain::Tuple | ValueDomain::Map)
+    }
+}
+
+impl DvDict for ComplexDvDictionary {
+    fn add_datavalue(&mut self, dv: AnyDataValue) -> AddResult {
+        if !Self::supports(&dv) {
+            return AddResult::Rejected;
+        }
+
+        if let Some(id) = self.ids.get(&dv) {
+            return AddResult::Known(*id);
+        }
+
+        let id = self.values.len();
+        self.values.push(dv.clone());
+        self.ids.insert(dv, id);
+
+        AddResult::Fresh(id)
+    }
+
+    fn fresh_null(&mut self) -> (AnyDataValue, usize) {
+        panic!("complex value dictionaries cannot make fresh nulls");
+    }
+
+    fn fresh_null_id(&mut self) -> usize {
+        panic!("complex value dictionaries cannot make fresh nulls");
+    }
+
+    fn datavalue_to_id(&self, dv: &AnyDataValue) -> Option<usize> {
+        self.ids.get(dv).copied()
+    }
+
+    fn id_to_datavalue(&self, id: usize) -> Option<AnyDataValue> {
+        self.values.get(id).cloned()
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn is_iri(&self, _id: usize) -> bool {
+        false
+    }
+
+    fn is_plain_string(&self, _id: usize) -> bool {
+        false
+    }
+
+    fn is_lang_string(&self, _id: usize) -> bool {
+        false
+    }
+
+    fn is_null(&self, _id: usize) -> bool {
+        false
+    }
+
+    fn mark_dv(&mut self, _dv: AnyDataValue) -> AddResult {
+        AddResult::Rejected
+    }
+
+    fn has_marked(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        datavalues::{AnyDataValue, MapDataValue, TupleDataValue},
+        dictionary::{AddResult, DvDict},
+    };
+
+    use super::ComplexDvDictionary;
+
+    #[test]
+    fn add_and_get_complex() {
+        let mut dict = ComplexDvDictionary::new();
+
+        let tuple: AnyDataValue = TupleDataValue::from_iter([
+            AnyDataValue::new_integer_from_i64(1),
+            AnyDataValue::new_plain_string("a".to_string()),
+        ])
+        .into();
+        let map: AnyDataValue = MapDataValue::from_iter([(
+            AnyDataValue::new_plain_string("key".to_string()),
+            tuple.clone(),
+        )])
+        .into();
+        let string = AnyDataValue::new_plain_string("a".to_string());
+
+        assert_eq!(dict.add_datavalue(tuple.clone()), AddResult::Fresh(0));
+        assert_eq!(dict.add_datavalue(map.clone()), AddResult::Fresh(1));
+        assert_eq!(dict.add_datavalue(tuple.clone()), AddResult::Known(0));
+        assert_eq!(dict.add_datavalue(string.clone()), AddResult::Rejected);
+
+        assert_eq!(dict.datavalue_to_id(&map), Some(1));
+        assert_eq!(dict.datavalue_to_id(&string), None);
+        assert_eq!(dict.id_to_datavalue(0), Some(tuple));
+        assert_eq!(dict.id_to_datavalue(2), None);
+
+        assert_eq!(dict.len(), 2);
+    }
+}
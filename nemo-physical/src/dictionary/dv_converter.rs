@@ -8,7 +8,7 @@ use std::fmt::Debug;
 /// Trait to encapsulate (static) functions for converting datavalues to strings
 /// and vice versa. The mapping must therefore be invertible, but otherwise it
 /// can be arbitrary. Implementations may choose which datavalues to support.
-pub(crate) trait DvConverter: Debug {
+pub(crate) trait DvConverter: Debug + Send {
     /// Converts a datavalue to a string, if supported.
     fn dict_string(dv: &AnyDataValue) -> Option<String>;
     /// Converts a string to a datavalue, if supported.
@@ -1,9 +1,12 @@
 //! This module defines [MetaDvDictionary].
 
+use std::collections::HashMap;
+
 use crate::datavalues::ValueDomain;
 use crate::datavalues::{AnyDataValue, DataValue};
 use crate::dictionary::NONEXISTING_ID_MARK;
 
+use super::ComplexDvDictionary;
 use super::DvDict;
 use super::IriDvDictionary;
 use super::LangStringDvDictionary;
@@ -33,6 +36,8 @@ enum DictionaryType {
     Other,
     /// Dictionary for null datavalues
     Null,
+    /// Dictionary for tuple and map datavalues
+    Complex,
     // /// Dictionary for long strings (blobs)
     // Blob,
     // /// Dictionary for strings with a fixed prefix and suffix
@@ -56,6 +61,8 @@ impl DictionaryType {
                 )
                 | (DictionaryType::Other, ValueDomain::Other)
                 | (DictionaryType::Null, ValueDomain::Null)
+                | (DictionaryType::Complex, ValueDomain::Tuple)
+                | (DictionaryType::Complex, ValueDomain::Map)
         )
     }
 }
@@ -119,6 +126,7 @@ impl DictIterator {
                 ValueDomain::Iri => return md.iri_dict,
                 ValueDomain::Other => return md.other_dict,
                 ValueDomain::Null => return md.null_dict,
+                ValueDomain::Tuple | ValueDomain::Map => return md.complex_dict,
                 ValueDomain::Boolean => return md.other_dict, // TODO: maybe not the best place, using a whole page for two values if there is not much "other"
                 ValueDomain::UnsignedLong => return md.other_dict, // TODO: maybe not the best place either
                 _ => {}
@@ -165,6 +173,8 @@ pub struct MetaDvDictionary {
     other_dict: DictId,
     /// Id of the null dictionary, if any (otherwise [NO_DICT])
     null_dict: DictId,
+    /// Id of the tuple and map datavalue dictionary, if any (otherwise [NO_DICT])
+    complex_dict: DictId,
     /// Ids of further general-purpose dictionaries,
     /// which might be used for any kind of datavalue.
     generic_dicts: Vec<DictId>,
@@ -176,6 +186,9 @@ pub struct MetaDvDictionary {
     // dict_candidates: LruCache<StringPair, u32>,
     /// Keep track of total number of entries for faster checks
     size: usize,
+    /// Ids of the nulls that replace blank node labels,
+    /// see [AnyDataValue::new_blank_node_label]
+    labelled_nulls: HashMap<String, usize>,
 }
 
 impl Default for MetaDvDictionary {
@@ -190,10 +203,12 @@ impl Default for MetaDvDictionary {
             iri_dict: NO_DICT,
             other_dict: NO_DICT,
             null_dict: NO_DICT,
+            complex_dict: NO_DICT,
             // dict_candidates: LruCache::new(NonZeroUsize::new(150).unwrap()),
             //infix_dicts: HashMap::new(),
             generic_dicts: Vec::new(),
             size: 0,
+            labelled_nulls: HashMap::new(),
         };
 
         result.add_dictionary(DictionaryType::Iri);
@@ -201,6 +216,7 @@ impl Default for MetaDvDictionary {
         result.add_dictionary(DictionaryType::LangString);
         result.add_dictionary(DictionaryType::Other);
         result.add_dictionary(DictionaryType::Null);
+        result.add_dictionary(DictionaryType::Complex);
 
         result
     }
@@ -212,6 +228,15 @@ impl MetaDvDictionary {
         Self::default()
     }
 
+    /// Return the number of nulls that have been created so far.
+    pub fn num_nulls(&self) -> usize {
+        if self.null_dict == NO_DICT {
+            0
+        } else {
+            self.dicts[self.null_dict].dict.len()
+        }
+    }
+
     /// Convert the local ID of a given dictionary to a global ID.
     /// The function assumes that the given local id exists, and will crash
     /// otherwise. It can safely be used for conversion of previously stored data.
@@ -325,6 +350,13 @@ impl MetaDvDictionary {
                 }
                 dict = Box::new(NullDvDictionary::new());
                 self.null_dict = self.dicts.len();
+            }
+            DictionaryType::Complex => {
+                if self.complex_dict != NO_DICT {
+                    return;
+                }
+                dict = Box::new(ComplexDvDictionary::new());
+                self.complex_dict = self.dicts.len();
             } // DictionaryType::Infix {
               //     ref prefix,
               //     ref suffix,
@@ -442,6 +474,16 @@ impl MetaDvDictionary {
 
 impl DvDict for MetaDvDictionary {
     fn add_datavalue(&mut self, dv: AnyDataValue) -> AddResult {
+        if let Some(label) = dv.to_blank_node_label() {
+            if let Some(&id) = self.labelled_nulls.get(&label) {
+                return AddResult::Known(id);
+            }
+
+            let id = self.fresh_null_id();
+            self.labelled_nulls.insert(label, id);
+            return AddResult::Fresh(id);
+        }
+
         self.add_datavalue_inline(dv)
     }
 
@@ -456,6 +498,10 @@ impl DvDict for MetaDvDictionary {
     }
 
     fn datavalue_to_id(&self, dv: &AnyDataValue) -> Option<usize> {
+        if let Some(label) = dv.to_blank_node_label() {
+            return self.labelled_nulls.get(&label).copied();
+        }
+
         // Look up new entry in all applicable dictionaries.
         let mut d_it = DictIterator::new();
         let mut dict_idx: usize;
@@ -539,7 +585,7 @@ impl DvDict for MetaDvDictionary {
 #[cfg(test)]
 mod test {
     use crate::{
-        datavalues::{syntax::XSD_PREFIX, AnyDataValue, NullDataValue},
+        datavalues::{syntax::XSD_PREFIX, AnyDataValue, DataValue, NullDataValue},
         dictionary::{AddResult, DvDict},
     };
 
@@ -628,6 +674,37 @@ mod test {
         assert_eq!(dict.len(), 2);
     }
 
+    #[test]
+    fn add_blank_node_labels() {
+        let mut dict = MetaDvDictionary::new();
+
+        let label_a = AnyDataValue::new_blank_node_label("a".to_string());
+        let label_b = AnyDataValue::new_blank_node_label("b".to_string());
+
+        assert_eq!(dict.datavalue_to_id(&label_a), None);
+
+        let AddResult::Fresh(a_id) = dict.add_datavalue(label_a.clone()) else {
+            panic!("add failed")
+        };
+        let AddResult::Fresh(b_id) = dict.add_datavalue(label_b) else {
+            panic!("add failed")
+        };
+
+        assert_ne!(a_id, b_id);
+        assert!(dict.is_null(a_id));
+        assert_eq!(dict.datavalue_to_id(&label_a), Some(a_id));
+        assert_eq!(dict.add_datavalue(label_a.clone()), AddResult::Known(a_id));
+        assert_ne!(dict.fresh_null_id(), a_id);
+
+        // A literal with the same datatype does not refer to a blank node
+        let literal = AnyDataValue::new_other(label_a.lexical_value(), label_a.datatype_iri());
+        assert_ne!(literal, label_a);
+        let AddResult::Fresh(literal_id) = dict.add_datavalue(literal) else {
+            panic!("add failed")
+        };
+        assert!(!dict.is_null(literal_id));
+    }
+
     #[test]
     fn add_unsupported_dv() {
         let mut dict = MetaDvDictionary::new();
@@ -5,7 +5,7 @@ use std::fmt::Debug;
 /// Trait to encapsulate (static) functions for converting datavalues to strings
 /// and vice versa. The mapping must therefore be invertible, but otherwise it
 /// can be arbitrary. Implementations may choose which datavalues to support.
-pub(crate) trait PairDvConverter: Debug {
+pub(crate) trait PairDvConverter: Debug + Send {
     /// Converts a datavalue to a string pair, when supported.
     fn dict_string_pair(dv: &AnyDataValue) -> Option<[String; 2]>;
     /// Converts a string pair to a datavalue, if supported.
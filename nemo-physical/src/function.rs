@@ -1,6 +1,7 @@
 //! This module contains data structures and implementations
 //! for realizing the evaluation of functions on columnar data.
 
+pub mod external;
 pub mod tree;
 
 pub(crate) mod definitions;
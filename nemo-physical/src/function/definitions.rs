@@ -3,9 +3,13 @@
 pub(crate) mod boolean;
 pub(crate) mod casting;
 pub(crate) mod checktype;
+pub(crate) mod collection;
 pub(crate) mod generic;
+pub(crate) mod hash;
 pub(crate) mod language;
 pub(crate) mod numeric;
+pub(crate) mod rdf;
+pub(crate) mod regex;
 pub(crate) mod string;
 
 use delegate::delegate;
@@ -19,10 +23,14 @@ use self::{
     boolean::{BooleanConjunction, BooleanDisjunction, BooleanNegation},
     casting::{CastingIntoDouble, CastingIntoFloat, CastingIntoInteger64},
     checktype::{
-        CheckIsDouble, CheckIsFloat, CheckIsInteger, CheckIsIri, CheckIsNull, CheckIsNumeric,
-        CheckIsString,
+        CheckIsDouble, CheckIsFloat, CheckIsInteger, CheckIsIri, CheckIsLiteral, CheckIsNull,
+        CheckIsNumeric, CheckIsString,
+    },
+    collection::{
+        CollectionLength, MapConstruction, MapElement, MapKeys, TupleConstruction, TupleElement,
     },
     generic::{CanonicalString, Datatype, Equals, LexicalValue, Unequals},
+    hash::{HashMd5, HashSha1, HashSha256},
     language::LanguageTag,
     numeric::{
         BitAnd, BitOr, BitXor, NumericAbsolute, NumericAddition, NumericCeil, NumericCosine,
@@ -32,10 +40,12 @@ use self::{
         NumericRound, NumericSine, NumericSquareroot, NumericSubtraction, NumericSum,
         NumericTangent,
     },
+    rdf::{RdfBlankNode, RdfIri, RdfLanguageMatches, RdfLanguageString, RdfTypedLiteral},
+    regex::{PatternFunction, StringRegex, StringRegexGroup, StringReplace},
     string::{
-        StringAfter, StringBefore, StringCompare, StringConcatenation, StringContains, StringEnds,
-        StringLength, StringLowercase, StringReverse, StringStarts, StringSubstring,
-        StringSubstringLength, StringUppercase,
+        StringAfter, StringBefore, StringCompare, StringConcatenation, StringContains,
+        StringEncodeForUri, StringEnds, StringLength, StringLowercase, StringReverse, StringStarts,
+        StringSubstring, StringSubstringLength, StringUppercase,
     },
 };
 
@@ -49,7 +59,7 @@ pub(crate) enum FunctionTypePropagation {
     /// If input types are numeric, cast them to the maximum type
     NumericUpcast,
     /// Nothing is known about the the type propagation
-    _Unknown,
+    Unknown,
 }
 
 impl FunctionTypePropagation {
@@ -64,7 +74,7 @@ impl FunctionTypePropagation {
 
                 result_type
             }
-            FunctionTypePropagation::_Unknown => StorageTypeBitSet::full(),
+            FunctionTypePropagation::Unknown => StorageTypeBitSet::full(),
             FunctionTypePropagation::NumericUpcast => {
                 if input.is_empty() {
                     return StorageTypeBitSet::empty();
@@ -136,12 +146,18 @@ pub enum UnaryFunctionEnum {
     CheckIsFloat(CheckIsFloat),
     CheckIsInteger(CheckIsInteger),
     CheckIsIri(CheckIsIri),
+    CheckIsLiteral(CheckIsLiteral),
     CheckIsNull(CheckIsNull),
     CheckIsNumeric(CheckIsNumeric),
     CheckIsString(CheckIsString),
+    CollectionLength(CollectionLength),
     Datatype(Datatype),
+    HashMd5(HashMd5),
+    HashSha1(HashSha1),
+    HashSha256(HashSha256),
     LanguageTag(LanguageTag),
     LexicalValue(LexicalValue),
+    MapKeys(MapKeys),
     NumericAbsolute(NumericAbsolute),
     NumericCeil(NumericCeil),
     NumericCosine(NumericCosine),
@@ -151,6 +167,9 @@ pub enum UnaryFunctionEnum {
     NumericSine(NumericSine),
     NumericSquareroot(NumericSquareroot),
     NumericTangent(NumericTangent),
+    RdfBlankNode(RdfBlankNode),
+    RdfIri(RdfIri),
+    StringEncodeForUri(StringEncodeForUri),
     StringLength(StringLength),
     StringReverse(StringReverse),
     StringLowercase(StringLowercase),
@@ -169,12 +188,18 @@ impl UnaryFunction for UnaryFunctionEnum {
             Self::CheckIsFloat(function) => function,
             Self::CheckIsInteger(function) => function,
             Self::CheckIsIri(function) => function,
+            Self::CheckIsLiteral(function) => function,
             Self::CheckIsNull(function) => function,
             Self::CheckIsNumeric(function) => function,
             Self::CheckIsString(function) => function,
+            Self::CollectionLength(function) => function,
             Self::Datatype(function) => function,
+            Self::HashMd5(function) => function,
+            Self::HashSha1(function) => function,
+            Self::HashSha256(function) => function,
             Self::LanguageTag(function) => function,
             Self::LexicalValue(function) => function,
+            Self::MapKeys(function) => function,
             Self::NumericAbsolute(function) => function,
             Self::NumericCeil(function) => function,
             Self::NumericCosine(function) => function,
@@ -184,6 +209,9 @@ impl UnaryFunction for UnaryFunctionEnum {
             Self::NumericSine(function) => function,
             Self::NumericSquareroot(function) => function,
             Self::NumericTangent(function) => function,
+            Self::RdfBlankNode(function) => function,
+            Self::RdfIri(function) => function,
+            Self::StringEncodeForUri(function) => function,
             Self::StringLength(function) => function,
             Self::StringReverse(function) => function,
             Self::StringLowercase(function) => function,
@@ -234,6 +262,11 @@ pub enum BinaryFunctionEnum {
     StringEnds(StringEnds),
     StringStarts(StringStarts),
     StringSubstring(StringSubstring),
+    RdfLanguageMatches(RdfLanguageMatches),
+    RdfLanguageString(RdfLanguageString),
+    RdfTypedLiteral(RdfTypedLiteral),
+    TupleElement(TupleElement),
+    MapElement(MapElement),
 }
 
 impl BinaryFunction for BinaryFunctionEnum {
@@ -259,6 +292,11 @@ impl BinaryFunction for BinaryFunctionEnum {
             Self::StringEnds(function) => function,
             Self::StringStarts(function) => function,
             Self::StringSubstring(function) => function,
+            Self::RdfLanguageMatches(function) => function,
+            Self::RdfLanguageString(function) => function,
+            Self::RdfTypedLiteral(function) => function,
+            Self::TupleElement(function) => function,
+            Self::MapElement(function) => function,
         } {
             fn evaluate(&self, first_parameter: AnyDataValue, second_parameter: AnyDataValue) -> Option<AnyDataValue>;
             fn type_propagation(&self) -> FunctionTypePropagation;
@@ -326,6 +364,11 @@ pub enum NaryFunctionEnum {
     NumericSum(NumericSum),
     NumericProduct(NumericProduct),
     StringConcatenation(StringConcatenation),
+    StringRegex(StringRegex),
+    StringReplace(StringReplace),
+    StringRegexGroup(StringRegexGroup),
+    TupleConstruction(TupleConstruction),
+    MapConstruction(MapConstruction),
 }
 
 impl NaryFunction for NaryFunctionEnum {
@@ -342,9 +385,27 @@ impl NaryFunction for NaryFunctionEnum {
             Self::NumericSum(function) => function,
             Self::NumericProduct(function) => function,
             Self::StringConcatenation(function) => function,
+            Self::StringRegex(function) => function,
+            Self::StringReplace(function) => function,
+            Self::StringRegexGroup(function) => function,
+            Self::TupleConstruction(function) => function,
+            Self::MapConstruction(function) => function,
         } {
             fn evaluate(&self, parameters: &[AnyDataValue]) -> Option<AnyDataValue>;
             fn type_propagation(&self) -> FunctionTypePropagation;
         }
     }
 }
+
+impl NaryFunctionEnum {
+    /// Return this function as a [PatternFunction]
+    /// if its second parameter is a regular expression.
+    pub(crate) fn as_pattern_function(&self) -> Option<&dyn PatternFunction> {
+        match self {
+            Self::StringRegex(function) => Some(function),
+            Self::StringReplace(function) => Some(function),
+            Self::StringRegexGroup(function) => Some(function),
+            _ => None,
+        }
+    }
+}
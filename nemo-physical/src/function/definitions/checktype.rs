@@ -2,7 +2,7 @@
 
 use crate::{
     datatypes::StorageTypeName,
-    datavalues::{AnyDataValue, DataValue},
+    datavalues::{AnyDataValue, DataValue, ValueDomain},
 };
 
 use super::{FunctionTypePropagation, UnaryFunction};
@@ -108,12 +108,13 @@ impl UnaryFunction for CheckIsNumeric {
 
 /// Check if value is a null
 ///
-/// Returns "true" from the boolean value space if value is a null and "false" otherwise.
+/// Returns "true" from the boolean value space if value is a null,
+/// including nulls that are created for blank node labels, and "false" otherwise.
 #[derive(Debug, Copy, Clone)]
 pub struct CheckIsNull;
 impl UnaryFunction for CheckIsNull {
     fn evaluate(&self, parameter: AnyDataValue) -> Option<AnyDataValue> {
-        if parameter.to_null().is_some() {
+        if parameter.to_null().is_some() || parameter.to_blank_node_label().is_some() {
             Some(AnyDataValue::new_boolean(true))
         } else {
             Some(AnyDataValue::new_boolean(false))
@@ -154,6 +155,32 @@ impl UnaryFunction for CheckIsIri {
     }
 }
 
+/// Check if value is a literal
+///
+/// Returns "true" from the boolean value space if value is a literal,
+/// i.e., neither an iri nor a null nor a tuple or map, and "false" otherwise.
+#[derive(Debug, Copy, Clone)]
+pub struct CheckIsLiteral;
+impl UnaryFunction for CheckIsLiteral {
+    fn evaluate(&self, parameter: AnyDataValue) -> Option<AnyDataValue> {
+        let is_literal = !matches!(
+            parameter.value_domain(),
+            ValueDomain::Iri | ValueDomain::Null | ValueDomain::Tuple | ValueDomain::Map
+        ) && parameter.to_blank_node_label().is_none();
+
+        Some(AnyDataValue::new_boolean(is_literal))
+    }
+
+    fn type_propagation(&self) -> FunctionTypePropagation {
+        // TODO: This is playing it save, one should probably give booleans a special status
+        FunctionTypePropagation::KnownOutput(
+            StorageTypeName::Id32
+                .bitset()
+                .union(StorageTypeName::Id64.bitset()),
+        )
+    }
+}
+
 /// Check if value is a string
 ///
 /// Returns "true" from the boolean value space if value is a string and "false" otherwise.
@@ -0,0 +1,226 @@
+//! This module defines functions on collections, i.e., tuples and maps.
+
+use crate::{
+    datatypes::StorageTypeName,
+    datavalues::{AnyDataValue, DataValue, MapDataValue, TupleDataValue},
+};
+
+use super::{BinaryFunction, FunctionTypePropagation, NaryFunction, UnaryFunction};
+
+/// Type propagation of functions that return collections,
+/// which are always stored in the dictionary
+fn collection_type_propagation() -> FunctionTypePropagation {
+    FunctionTypePropagation::KnownOutput(
+        StorageTypeName::Id32
+            .bitset()
+            .union(StorageTypeName::Id64.bitset()),
+    )
+}
+
+/// Construction of a tuple
+///
+/// Returns a tuple containing the given parameters in the given order.
+#[derive(Debug, Copy, Clone)]
+pub struct TupleConstruction;
+impl NaryFunction for TupleConstruction {
+    fn evaluate(&self, parameters: &[AnyDataValue]) -> Option<AnyDataValue> {
+        Some(TupleDataValue::from_iter(parameters.iter().cloned()).into())
+    }
+
+    fn type_propagation(&self) -> FunctionTypePropagation {
+        collection_type_propagation()
+    }
+}
+
+/// Construction of a map
+///
+/// Expects an even number of parameters,
+/// which are interpreted as alternating keys and values.
+///
+/// Returns a map that assigns each key to the value following it.
+/// If a key occurs multiple times, the last value is used.
+///
+/// Returns `None` if the number of parameters is odd.
+#[derive(Debug, Copy, Clone)]
+pub struct MapConstruction;
+impl NaryFunction for MapConstruction {
+    fn evaluate(&self, parameters: &[AnyDataValue]) -> Option<AnyDataValue> {
+        if parameters.len() % 2 != 0 {
+            return None;
+        }
+
+        Some(
+            MapDataValue::from_iter(
+                parameters
+                    .iter()
+                    .step_by(2)
+                    .cloned()
+                    .zip(parameters.iter().skip(1).step_by(2).cloned()),
+            )
+            .into(),
+        )
+    }
+
+    fn type_propagation(&self) -> FunctionTypePropagation {
+        collection_type_propagation()
+    }
+}
+
+/// Element of a tuple
+///
+/// Expects a tuple as the first parameter and an integer value as the second.
+///
+/// Returns the element of the tuple at the position given by the second parameter,
+/// where the first element is at position 1.
+///
+/// Returns `None` if the type requirements from above are not met
+/// or if the position is out of bounds.
+#[derive(Debug, Copy, Clone)]
+pub struct TupleElement;
+impl BinaryFunction for TupleElement {
+    fn evaluate(
+        &self,
+        parameter_first: AnyDataValue,
+        parameter_second: AnyDataValue,
+    ) -> Option<AnyDataValue> {
+        let position = usize::try_from(parameter_second.to_u64()?).ok()?;
+
+        parameter_first
+            .tuple_element(position.checked_sub(1)?)
+            .cloned()
+    }
+
+    fn type_propagation(&self) -> FunctionTypePropagation {
+        FunctionTypePropagation::Unknown
+    }
+}
+
+/// Value of a map
+///
+/// Expects a map as the first parameter and an arbitrary value as the second.
+///
+/// Returns the value that the map assigns to the second parameter.
+///
+/// Returns `None` if the first parameter is not a map
+/// or if it does not contain the second parameter as a key.
+#[derive(Debug, Copy, Clone)]
+pub struct MapElement;
+impl BinaryFunction for MapElement {
+    fn evaluate(
+        &self,
+        parameter_first: AnyDataValue,
+        parameter_second: AnyDataValue,
+    ) -> Option<AnyDataValue> {
+        parameter_first.map_element(&parameter_second).cloned()
+    }
+
+    fn type_propagation(&self) -> FunctionTypePropagation {
+        FunctionTypePropagation::Unknown
+    }
+}
+
+/// Length of a collection
+///
+/// Returns the number of elements of a tuple or the number of keys of a map
+/// as a number from the integer value space.
+///
+/// Returns `None` if the provided argument is neither a tuple nor a map.
+#[derive(Debug, Copy, Clone)]
+pub struct CollectionLength;
+impl UnaryFunction for CollectionLength {
+    fn evaluate(&self, parameter: AnyDataValue) -> Option<AnyDataValue> {
+        parameter
+            .length()
+            .map(|length| AnyDataValue::new_integer_from_u64(length as u64))
+    }
+
+    fn type_propagation(&self) -> FunctionTypePropagation {
+        FunctionTypePropagation::KnownOutput(StorageTypeName::Int64.bitset())
+    }
+}
+
+/// Keys of a map
+///
+/// Returns a tuple containing the keys of the given map in their natural order.
+///
+/// Returns `None` if the provided argument is not a map.
+#[derive(Debug, Copy, Clone)]
+pub struct MapKeys;
+impl UnaryFunction for MapKeys {
+    fn evaluate(&self, parameter: AnyDataValue) -> Option<AnyDataValue> {
+        let keys = parameter.map_keys()?.cloned().collect::<Vec<_>>();
+
+        Some(TupleDataValue::from_iter(keys).into())
+    }
+
+    fn type_propagation(&self) -> FunctionTypePropagation {
+        collection_type_propagation()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        datavalues::AnyDataValue,
+        function::definitions::{BinaryFunction, NaryFunction, UnaryFunction},
+    };
+
+    use super::{
+        CollectionLength, MapConstruction, MapElement, MapKeys, TupleConstruction, TupleElement,
+    };
+
+    fn string(value: &str) -> AnyDataValue {
+        AnyDataValue::new_plain_string(value.to_string())
+    }
+
+    fn integer(value: i64) -> AnyDataValue {
+        AnyDataValue::new_integer_from_i64(value)
+    }
+
+    #[test]
+    fn test_tuple() {
+        let tuple = TupleConstruction
+            .evaluate(&[string("a"), integer(2), string("c")])
+            .unwrap();
+
+        assert_eq!(
+            TupleElement.evaluate(tuple.clone(), integer(1)),
+            Some(string("a"))
+        );
+        assert_eq!(
+            TupleElement.evaluate(tuple.clone(), integer(3)),
+            Some(string("c"))
+        );
+        assert_eq!(TupleElement.evaluate(tuple.clone(), integer(0)), None);
+        assert_eq!(TupleElement.evaluate(tuple.clone(), integer(4)), None);
+        assert_eq!(TupleElement.evaluate(string("abc"), integer(1)), None);
+
+        assert_eq!(CollectionLength.evaluate(tuple), Some(integer(3)));
+        assert_eq!(
+            CollectionLength.evaluate(TupleConstruction.evaluate(&[]).unwrap()),
+            Some(integer(0))
+        );
+        assert_eq!(CollectionLength.evaluate(string("abc")), None);
+    }
+
+    #[test]
+    fn test_map() {
+        let map = MapConstruction
+            .evaluate(&[string("b"), integer(2), string("a"), integer(1)])
+            .unwrap();
+
+        assert_eq!(
+            MapElement.evaluate(map.clone(), string("a")),
+            Some(integer(1))
+        );
+        assert_eq!(MapElement.evaluate(map.clone(), string("c")), None);
+        assert_eq!(CollectionLength.evaluate(map.clone()), Some(integer(2)));
+        assert_eq!(
+            MapKeys.evaluate(map),
+            TupleConstruction.evaluate(&[string("a"), string("b")])
+        );
+
+        assert_eq!(MapConstruction.evaluate(&[string("a")]), None);
+        assert_eq!(MapKeys.evaluate(string("a")), None);
+    }
+}
@@ -0,0 +1,123 @@
+//! This module defines functions that compute hash values of strings.
+
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    datatypes::StorageTypeName,
+    datavalues::{AnyDataValue, DataValue},
+};
+
+use super::{FunctionTypePropagation, UnaryFunction};
+
+/// Compute the hash value of the given string with the given hash function
+/// and return it as a plain string in lowercase hexadecimal notation.
+///
+/// Returns `None` if the given value is not a string.
+fn hex_digest<Hash: Digest>(parameter: AnyDataValue) -> Option<AnyDataValue> {
+    let string = parameter.to_plain_string()?;
+
+    let digest = Hash::digest(string.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+
+    Some(AnyDataValue::new_plain_string(digest))
+}
+
+/// Type propagation of functions that return hash values as strings
+fn hash_type_propagation() -> FunctionTypePropagation {
+    FunctionTypePropagation::KnownOutput(
+        StorageTypeName::Id32
+            .bitset()
+            .union(StorageTypeName::Id64.bitset()),
+    )
+}
+
+/// MD5 hash, corresponding to SPARQL function `MD5`
+///
+/// Returns the MD5 hash of the UTF-8 representation of the given string
+/// as a string in lowercase hexadecimal notation.
+///
+/// Returns `None` if the provided argument is not a string.
+#[derive(Debug, Copy, Clone)]
+pub struct HashMd5;
+impl UnaryFunction for HashMd5 {
+    fn evaluate(&self, parameter: AnyDataValue) -> Option<AnyDataValue> {
+        hex_digest::<Md5>(parameter)
+    }
+
+    fn type_propagation(&self) -> FunctionTypePropagation {
+        hash_type_propagation()
+    }
+}
+
+/// SHA-1 hash, corresponding to SPARQL function `SHA1`
+///
+/// Returns the SHA-1 hash of the UTF-8 representation of the given string
+/// as a string in lowercase hexadecimal notation.
+///
+/// Returns `None` if the provided argument is not a string.
+#[derive(Debug, Copy, Clone)]
+pub struct HashSha1;
+impl UnaryFunction for HashSha1 {
+    fn evaluate(&self, parameter: AnyDataValue) -> Option<AnyDataValue> {
+        hex_digest::<Sha1>(parameter)
+    }
+
+    fn type_propagation(&self) -> FunctionTypePropagation {
+        hash_type_propagation()
+    }
+}
+
+/// SHA-256 hash, corresponding to SPARQL function `SHA256`
+///
+/// Returns the SHA-256 hash of the UTF-8 representation of the given string
+/// as a string in lowercase hexadecimal notation.
+///
+/// Returns `None` if the provided argument is not a string.
+#[derive(Debug, Copy, Clone)]
+pub struct HashSha256;
+impl UnaryFunction for HashSha256 {
+    fn evaluate(&self, parameter: AnyDataValue) -> Option<AnyDataValue> {
+        hex_digest::<Sha256>(parameter)
+    }
+
+    fn type_propagation(&self) -> FunctionTypePropagation {
+        hash_type_propagation()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{datavalues::AnyDataValue, function::definitions::UnaryFunction};
+
+    use super::{HashMd5, HashSha1, HashSha256};
+
+    fn string(value: &str) -> AnyDataValue {
+        AnyDataValue::new_plain_string(value.to_string())
+    }
+
+    #[test]
+    fn test_hashes() {
+        assert_eq!(
+            HashMd5.evaluate(string("abc")),
+            Some(string("900150983cd24fb0d6963f7d28e17f72"))
+        );
+        assert_eq!(
+            HashSha1.evaluate(string("abc")),
+            Some(string("a9993e364706816aba3e25717850c26c9cd0d89d"))
+        );
+        assert_eq!(
+            HashSha256.evaluate(string("abc")),
+            Some(string(
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+            ))
+        );
+        assert_eq!(
+            HashMd5.evaluate(AnyDataValue::new_iri("abc".to_string())),
+            None
+        );
+    }
+}
@@ -0,0 +1,320 @@
+//! This module defines functions that construct and inspect RDF terms,
+//! i.e., IRIs, literals and blank nodes, following the corresponding functions of SPARQL.
+
+use crate::{
+    datatypes::StorageTypeName,
+    datavalues::{AnyDataValue, DataValue, ValueDomain},
+};
+
+use super::{BinaryFunction, FunctionTypePropagation, UnaryFunction};
+
+/// Type propagation of functions whose results are stored in the dictionary
+fn dictionary_type_propagation() -> FunctionTypePropagation {
+    FunctionTypePropagation::KnownOutput(
+        StorageTypeName::Id32
+            .bitset()
+            .union(StorageTypeName::Id64.bitset()),
+    )
+}
+
+/// Check if the given string is a well-formed language tag,
+/// i.e., consists of alphanumeric subtags of length 1 to 8 that are separated by hyphens,
+/// where the first subtag only contains letters.
+fn is_language_tag(tag: &str) -> bool {
+    tag.split('-').enumerate().all(|(index, subtag)| {
+        (1..=8).contains(&subtag.len())
+            && subtag.chars().all(|character| {
+                if index == 0 {
+                    character.is_ascii_alphabetic()
+                } else {
+                    character.is_ascii_alphanumeric()
+                }
+            })
+    })
+}
+
+/// Construction of an IRI, corresponding to SPARQL function `IRI`
+///
+/// Returns an IRI whose content is given by the provided string
+/// or the provided IRI itself.
+/// Relative IRIs are not resolved against a base.
+///
+/// Returns `None` if the provided argument is neither a string nor an IRI.
+#[derive(Debug, Copy, Clone)]
+pub struct RdfIri;
+impl UnaryFunction for RdfIri {
+    fn evaluate(&self, parameter: AnyDataValue) -> Option<AnyDataValue> {
+        match parameter.value_domain() {
+            ValueDomain::Iri => Some(parameter),
+            ValueDomain::PlainString => Some(AnyDataValue::new_iri(parameter.to_plain_string()?)),
+            _ => None,
+        }
+    }
+
+    fn type_propagation(&self) -> FunctionTypePropagation {
+        dictionary_type_propagation()
+    }
+}
+
+/// Construction of a typed literal, corresponding to SPARQL function `STRDT`
+///
+/// Expects a string as the first parameter and an IRI as the second.
+///
+/// Returns the literal with the lexical value given by the first parameter
+/// and the datatype given by the second parameter.
+///
+/// Returns `None` if the type requirements from above are not met
+/// or if the lexical value is not valid for a known datatype.
+#[derive(Debug, Copy, Clone)]
+pub struct RdfTypedLiteral;
+impl BinaryFunction for RdfTypedLiteral {
+    fn evaluate(
+        &self,
+        parameter_first: AnyDataValue,
+        parameter_second: AnyDataValue,
+    ) -> Option<AnyDataValue> {
+        let lexical_value = parameter_first.to_plain_string()?;
+        let datatype = parameter_second.to_iri()?;
+
+        AnyDataValue::new_from_typed_literal(lexical_value, datatype).ok()
+    }
+
+    fn type_propagation(&self) -> FunctionTypePropagation {
+        FunctionTypePropagation::Unknown
+    }
+}
+
+/// Construction of a language tagged string, corresponding to SPARQL function `STRLANG`
+///
+/// Expects a string as the first parameter and a language tag as the second.
+///
+/// Returns the language tagged string with the lexical value given by the first parameter
+/// and the language tag given by the second parameter.
+///
+/// Returns `None` if the type requirements from above are not met
+/// or if the language tag is not well-formed.
+#[derive(Debug, Copy, Clone)]
+pub struct RdfLanguageString;
+impl BinaryFunction for RdfLanguageString {
+    fn evaluate(
+        &self,
+        parameter_first: AnyDataValue,
+        parameter_second: AnyDataValue,
+    ) -> Option<AnyDataValue> {
+        let lexical_value = parameter_first.to_plain_string()?;
+        let tag = parameter_second.to_plain_string()?;
+
+        is_language_tag(&tag).then(|| AnyDataValue::new_language_tagged_string(lexical_value, tag))
+    }
+
+    fn type_propagation(&self) -> FunctionTypePropagation {
+        dictionary_type_propagation()
+    }
+}
+
+/// Matching of language tags, corresponding to SPARQL function `LANGMATCHES`
+///
+/// Expects a language tag as the first parameter and a language range as the second.
+///
+/// Returns `true` from the boolean value space if the language tag matches the language range
+/// according to the basic filtering scheme of RFC 4647 and `false` otherwise.
+/// The range `*` matches every non-empty language tag.
+///
+/// Returns `None` if either parameter is not a string.
+#[derive(Debug, Copy, Clone)]
+pub struct RdfLanguageMatches;
+impl BinaryFunction for RdfLanguageMatches {
+    fn evaluate(
+        &self,
+        parameter_first: AnyDataValue,
+        parameter_second: AnyDataValue,
+    ) -> Option<AnyDataValue> {
+        let tag = parameter_first.to_plain_string()?.to_ascii_lowercase();
+        let range = parameter_second.to_plain_string()?.to_ascii_lowercase();
+
+        let matches = if range == "*" {
+            !tag.is_empty()
+        } else {
+            !range.is_empty()
+                && tag
+                    .strip_prefix(&range)
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('-'))
+        };
+
+        Some(AnyDataValue::new_boolean(matches))
+    }
+
+    fn type_propagation(&self) -> FunctionTypePropagation {
+        // TODO: This is playing it save, one should probably give booleans a special status
+        dictionary_type_propagation()
+    }
+}
+
+/// Construction of a blank node, corresponding to SPARQL function `BNODE` with an argument
+///
+/// Returns a null that is determined by the provided string,
+/// i.e., the same null is returned for equal strings
+/// and this null is different from all other nulls.
+///
+/// Returns `None` if the provided argument is not a string.
+#[derive(Debug, Copy, Clone)]
+pub struct RdfBlankNode;
+impl UnaryFunction for RdfBlankNode {
+    fn evaluate(&self, parameter: AnyDataValue) -> Option<AnyDataValue> {
+        parameter
+            .to_plain_string()
+            .map(AnyDataValue::new_blank_node_label)
+    }
+
+    fn type_propagation(&self) -> FunctionTypePropagation {
+        dictionary_type_propagation()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        datavalues::{syntax::XSD_PREFIX, AnyDataValue, DataValue},
+        function::definitions::{
+            checktype::{CheckIsLiteral, CheckIsNull},
+            BinaryFunction, UnaryFunction,
+        },
+    };
+
+    use super::{RdfBlankNode, RdfIri, RdfLanguageMatches, RdfLanguageString, RdfTypedLiteral};
+
+    fn string(value: &str) -> AnyDataValue {
+        AnyDataValue::new_plain_string(value.to_string())
+    }
+
+    fn iri(value: &str) -> AnyDataValue {
+        AnyDataValue::new_iri(value.to_string())
+    }
+
+    #[test]
+    fn test_iri() {
+        let example = "http://example.org/myobject";
+
+        assert_eq!(RdfIri.evaluate(string(example)), Some(iri(example)));
+        assert_eq!(RdfIri.evaluate(iri(example)), Some(iri(example)));
+        assert_eq!(RdfIri.evaluate(AnyDataValue::new_integer_from_i64(1)), None);
+    }
+
+    #[test]
+    fn test_typed_literal() {
+        assert_eq!(
+            RdfTypedLiteral.evaluate(string("123"), iri(&format!("{XSD_PREFIX}integer"))),
+            Some(AnyDataValue::new_integer_from_i64(123))
+        );
+        assert_eq!(
+            RdfTypedLiteral.evaluate(string("iiii"), iri("http://example/romanNumeral")),
+            Some(AnyDataValue::new_other(
+                "iiii".to_string(),
+                "http://example/romanNumeral".to_string()
+            ))
+        );
+        assert_eq!(
+            RdfTypedLiteral.evaluate(string("abc"), iri(&format!("{XSD_PREFIX}integer"))),
+            None
+        );
+        assert_eq!(
+            RdfTypedLiteral.evaluate(string("123"), string("http://example/romanNumeral")),
+            None
+        );
+
+        // Lexical forms that are invalid for their datatype
+        let invalid = [
+            ("1.5", "integer"),
+            ("", "integer"),
+            ("-1", "nonNegativeInteger"),
+            ("maybe", "boolean"),
+            ("1,5", "double"),
+        ];
+        for (lexical_value, datatype) in invalid {
+            assert_eq!(
+                RdfTypedLiteral.evaluate(
+                    string(lexical_value),
+                    iri(&format!("{XSD_PREFIX}{datatype}"))
+                ),
+                None,
+                "{lexical_value} is not a valid {datatype}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_language_string() {
+        assert_eq!(
+            RdfLanguageString.evaluate(string("chat"), string("fr")),
+            Some(AnyDataValue::new_language_tagged_string(
+                "chat".to_string(),
+                "fr".to_string()
+            ))
+        );
+        assert!(RdfLanguageString
+            .evaluate(string("color"), string("en-US"))
+            .is_some());
+        assert_eq!(RdfLanguageString.evaluate(string("chat"), string("")), None);
+        assert_eq!(
+            RdfLanguageString.evaluate(string("chat"), string("f r")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_language_matches() {
+        let matches = |tag: &str, range: &str| {
+            RdfLanguageMatches
+                .evaluate(string(tag), string(range))
+                .and_then(|result| result.to_boolean())
+        };
+
+        assert_eq!(matches("fr", "FR"), Some(true));
+        assert_eq!(matches("fr-BE", "FR"), Some(true));
+        assert_eq!(matches("fr", "fr-BE"), Some(false));
+        assert_eq!(matches("frr", "fr"), Some(false));
+        assert_eq!(matches("fr", "*"), Some(true));
+        assert_eq!(matches("en-US", "en-us"), Some(true));
+        assert_eq!(matches("de-Latn-DE", "de-DE"), Some(false));
+
+        // Untagged literals have the empty language tag, which matches no range
+        assert_eq!(matches("", "*"), Some(false));
+        assert_eq!(matches("", "fr"), Some(false));
+        assert_eq!(matches("", ""), Some(false));
+        assert_eq!(matches("fr", ""), Some(false));
+
+        assert_eq!(
+            RdfLanguageMatches.evaluate(AnyDataValue::new_integer_from_i64(1), string("*")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_blank_node() {
+        let node = RdfBlankNode.evaluate(string("abc")).unwrap();
+        assert_eq!(RdfBlankNode.evaluate(string("abc")), Some(node.clone()));
+        assert_ne!(RdfBlankNode.evaluate(string("xyz")), Some(node.clone()));
+        assert_eq!(RdfBlankNode.evaluate(iri("abc")), None);
+
+        assert_eq!(
+            CheckIsNull.evaluate(node.clone()).unwrap().to_boolean(),
+            Some(true)
+        );
+        assert_eq!(
+            CheckIsLiteral.evaluate(node.clone()).unwrap().to_boolean(),
+            Some(false)
+        );
+
+        // A literal with the datatype that is reported for blank nodes is not a blank node
+        let literal = AnyDataValue::new_other(node.lexical_value(), node.datatype_iri());
+        assert_ne!(literal, node);
+        assert_eq!(
+            CheckIsNull.evaluate(literal.clone()).unwrap().to_boolean(),
+            Some(false)
+        );
+        assert_eq!(
+            CheckIsLiteral.evaluate(literal).unwrap().to_boolean(),
+            Some(true)
+        );
+    }
+}
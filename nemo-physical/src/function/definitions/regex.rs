@@ -0,0 +1,402 @@
+//! This module defines functions on strings that are based on regular expressions.
+
+use regex::{Regex, RegexBuilder};
+
+use crate::{
+    datatypes::StorageTypeName,
+    datavalues::{AnyDataValue, DataValue},
+};
+
+use super::{FunctionTypePropagation, NaryFunction};
+
+/// Index of the parameter that contains the regular expression
+/// in all functions that implement [PatternFunction]
+pub(crate) const PATTERN_INDEX: usize = 1;
+
+/// Compile a regular expression from the given pattern and optional flags.
+///
+/// The flags follow the SPARQL function `REGEX`:
+/// `i` (case-insensitive), `s` (dot matches newlines), `m` (multi-line mode),
+/// `x` (ignore whitespace) and `q` (the pattern is matched literally).
+///
+/// Returns `None` if the pattern or flags are not strings,
+/// if a flag is unknown, or if the pattern is not a valid regular expression.
+pub(crate) fn compile_pattern(
+    pattern: &AnyDataValue,
+    flags: Option<&AnyDataValue>,
+) -> Option<Regex> {
+    let pattern = pattern.to_plain_string()?;
+    let flags = match flags {
+        Some(flags) => flags.to_plain_string()?,
+        None => String::new(),
+    };
+
+    let pattern = if flags.contains('q') {
+        regex::escape(&pattern)
+    } else {
+        pattern
+    };
+    let mut builder = RegexBuilder::new(&pattern);
+
+    for flag in flags.chars() {
+        match flag {
+            'i' => builder.case_insensitive(true),
+            's' => builder.dot_matches_new_line(true),
+            'm' => builder.multi_line(true),
+            'x' => builder.ignore_whitespace(true),
+            'q' => &mut builder,
+            _ => return None,
+        };
+    }
+
+    builder.build().ok()
+}
+
+/// Defines an n-ary function whose second parameter is a regular expression
+/// and that may take an additional parameter with flags for that expression.
+///
+/// The regular expression may be compiled in advance if it is constant.
+pub(crate) trait PatternFunction {
+    /// Return the index of the parameter that contains the flags of the regular expression,
+    /// if the function is called with the given number of parameters,
+    /// or `None` if no flags are given.
+    fn flags_index(&self, parameter_count: usize) -> Option<usize>;
+
+    /// Evaluate this function on the given parameters
+    /// using the given compiled regular expression.
+    ///
+    /// Returns `None` if the result of the operation is undefined.
+    fn evaluate_pattern(
+        &self,
+        pattern: &Regex,
+        parameters: &[AnyDataValue],
+    ) -> Option<AnyDataValue>;
+}
+
+/// Evaluate a [PatternFunction] by compiling its regular expression first.
+fn evaluate_uncompiled<Function: PatternFunction>(
+    function: &Function,
+    parameters: &[AnyDataValue],
+) -> Option<AnyDataValue> {
+    let flags = function
+        .flags_index(parameters.len())
+        .and_then(|index| parameters.get(index));
+    let pattern = compile_pattern(parameters.get(PATTERN_INDEX)?, flags)?;
+
+    function.evaluate_pattern(&pattern, parameters)
+}
+
+/// Type propagation of functions that return strings or booleans
+fn string_type_propagation() -> FunctionTypePropagation {
+    FunctionTypePropagation::KnownOutput(
+        StorageTypeName::Id32
+            .bitset()
+            .union(StorageTypeName::Id64.bitset()),
+    )
+}
+
+/// Matching of regular expressions, corresponding to SPARQL function `REGEX`
+///
+/// Expects a string value as the first parameter, a regular expression as the second parameter
+/// and optionally a string of flags as the third parameter.
+///
+/// Returns `true` from the boolean value space if the regular expression matches
+/// some part of the string and `false` otherwise.
+///
+/// Returns `None` if the type requirements from above are not met
+/// or the regular expression is invalid.
+#[derive(Debug, Copy, Clone)]
+pub struct StringRegex;
+impl PatternFunction for StringRegex {
+    fn flags_index(&self, parameter_count: usize) -> Option<usize> {
+        (parameter_count == 3).then_some(2)
+    }
+
+    fn evaluate_pattern(
+        &self,
+        pattern: &Regex,
+        parameters: &[AnyDataValue],
+    ) -> Option<AnyDataValue> {
+        if !(2..=3).contains(&parameters.len()) {
+            return None;
+        }
+
+        let string = parameters[0].to_plain_string()?;
+        Some(AnyDataValue::new_boolean(pattern.is_match(&string)))
+    }
+}
+impl NaryFunction for StringRegex {
+    fn evaluate(&self, parameters: &[AnyDataValue]) -> Option<AnyDataValue> {
+        evaluate_uncompiled(self, parameters)
+    }
+
+    fn type_propagation(&self) -> FunctionTypePropagation {
+        // TODO: This is playing it save, one should probably give booleans a special status
+        string_type_propagation()
+    }
+}
+
+/// Replacement of the matches of a regular expression, corresponding to SPARQL function `REPLACE`
+///
+/// Expects a string value as the first parameter, a regular expression as the second parameter,
+/// a replacement string as the third parameter and optionally a string of flags as the fourth parameter.
+///
+/// Returns a string in which each non-overlapping match of the regular expression
+/// is replaced by the replacement string,
+/// where `$n` refers to the text matched by the n-th capture group,
+/// and `\$` and `\\` denote the characters `$` and `\`, respectively.
+/// The digits following `$` are read as long as they form the number of a capture group.
+/// With the flag `q`, the replacement string is used literally.
+///
+/// Returns `None` if the type requirements from above are not met,
+/// the regular expression is invalid or matches the empty string,
+/// or the replacement string contains a `$` or `\` that does not follow these rules.
+#[derive(Debug, Copy, Clone)]
+pub struct StringReplace;
+impl PatternFunction for StringReplace {
+    fn flags_index(&self, parameter_count: usize) -> Option<usize> {
+        (parameter_count == 4).then_some(3)
+    }
+
+    fn evaluate_pattern(
+        &self,
+        pattern: &Regex,
+        parameters: &[AnyDataValue],
+    ) -> Option<AnyDataValue> {
+        if !(3..=4).contains(&parameters.len()) || pattern.is_match("") {
+            return None;
+        }
+
+        let string = parameters[0].to_plain_string()?;
+        let replacement = parameters[2].to_plain_string()?;
+        let literal = match parameters.get(3) {
+            Some(flags) => flags.to_plain_string()?.contains('q'),
+            None => false,
+        };
+        let replacement = translate_replacement(&replacement, literal, pattern.captures_len() - 1)?;
+
+        Some(AnyDataValue::new_plain_string(
+            pattern
+                .replace_all(&string, replacement.as_str())
+                .into_owned(),
+        ))
+    }
+}
+
+/// Translate a replacement string of the SPARQL function `REPLACE`
+/// into the syntax of [Regex::replace_all],
+/// given the number of capture groups of the regular expression.
+///
+/// Returns `None` if the replacement string is invalid.
+fn translate_replacement(replacement: &str, literal: bool, groups: usize) -> Option<String> {
+    if literal {
+        return Some(replacement.replace('$', "$$"));
+    }
+
+    let mut result = String::with_capacity(replacement.len());
+    let mut characters = replacement.chars().peekable();
+
+    while let Some(character) = characters.next() {
+        match character {
+            '\\' => match characters.next()? {
+                '$' => result.push_str("$$"),
+                '\\' => result.push('\\'),
+                _ => return None,
+            },
+            '$' => {
+                let mut group = characters.next()?.to_digit(10)? as usize;
+                while let Some(digit) = characters.peek().and_then(|next| next.to_digit(10)) {
+                    let extended = group * 10 + digit as usize;
+                    if extended > groups {
+                        break;
+                    }
+
+                    group = extended;
+                    characters.next();
+                }
+
+                result.push_str(&format!("${{{group}}}"));
+            }
+            _ => result.push(character),
+        }
+    }
+
+    Some(result)
+}
+impl NaryFunction for StringReplace {
+    fn evaluate(&self, parameters: &[AnyDataValue]) -> Option<AnyDataValue> {
+        evaluate_uncompiled(self, parameters)
+    }
+
+    fn type_propagation(&self) -> FunctionTypePropagation {
+        string_type_propagation()
+    }
+}
+
+/// Extraction of a capture group of a regular expression
+///
+/// Expects a string value as the first parameter, a regular expression as the second parameter,
+/// an integer as the third parameter and optionally a string of flags as the fourth parameter.
+///
+/// Returns a string containing the text matched by the capture group
+/// with the index given by the third parameter in the first match of the regular expression,
+/// where the index 0 refers to the whole match.
+///
+/// Returns `None` if the type requirements from above are not met,
+/// the regular expression is invalid or does not match,
+/// or the capture group does not participate in the match.
+#[derive(Debug, Copy, Clone)]
+pub struct StringRegexGroup;
+impl PatternFunction for StringRegexGroup {
+    fn flags_index(&self, parameter_count: usize) -> Option<usize> {
+        (parameter_count == 4).then_some(3)
+    }
+
+    fn evaluate_pattern(
+        &self,
+        pattern: &Regex,
+        parameters: &[AnyDataValue],
+    ) -> Option<AnyDataValue> {
+        if !(3..=4).contains(&parameters.len()) {
+            return None;
+        }
+
+        let string = parameters[0].to_plain_string()?;
+        let group = usize::try_from(parameters[2].to_u64()?).ok()?;
+
+        let captures = pattern.captures(&string)?;
+        Some(AnyDataValue::new_plain_string(
+            captures.get(group)?.as_str().to_string(),
+        ))
+    }
+}
+impl NaryFunction for StringRegexGroup {
+    fn evaluate(&self, parameters: &[AnyDataValue]) -> Option<AnyDataValue> {
+        evaluate_uncompiled(self, parameters)
+    }
+
+    fn type_propagation(&self) -> FunctionTypePropagation {
+        string_type_propagation()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        datavalues::AnyDataValue,
+        function::definitions::{
+            regex::{compile_pattern, PatternFunction},
+            NaryFunction,
+        },
+    };
+
+    use super::{StringRegex, StringRegexGroup, StringReplace};
+
+    fn string(value: &str) -> AnyDataValue {
+        AnyDataValue::new_plain_string(value.to_string())
+    }
+
+    #[test]
+    fn test_regex() {
+        let parameters = [string("Alice Smith"), string("^ali")];
+        assert_eq!(
+            StringRegex.evaluate(&parameters),
+            Some(AnyDataValue::new_boolean(false))
+        );
+
+        let parameters = [string("Alice Smith"), string("^ali"), string("i")];
+        assert_eq!(
+            StringRegex.evaluate(&parameters),
+            Some(AnyDataValue::new_boolean(true))
+        );
+
+        let parameters = [string("abc"), string("."), string("q")];
+        assert_eq!(
+            StringRegex.evaluate(&parameters),
+            Some(AnyDataValue::new_boolean(false))
+        );
+        let parameters = [string("a.c"), string("."), string("q")];
+        assert_eq!(
+            StringRegex.evaluate(&parameters),
+            Some(AnyDataValue::new_boolean(true))
+        );
+
+        let invalid_flag = [string("abc"), string("b"), string("u")];
+        assert_eq!(StringRegex.evaluate(&invalid_flag), None);
+        let invalid_pattern = [string("abc"), string("(b")];
+        assert_eq!(StringRegex.evaluate(&invalid_pattern), None);
+        let not_string = [AnyDataValue::new_integer_from_i64(1), string("1")];
+        assert_eq!(StringRegex.evaluate(&not_string), None);
+    }
+
+    #[test]
+    fn test_replace() {
+        let parameters = [string("abcd"), string("b"), string("Z")];
+        assert_eq!(StringReplace.evaluate(&parameters), Some(string("aZcd")));
+
+        let parameters = [
+            string("Smith, Alice"),
+            string("(\\w+), (\\w+)"),
+            string("$2 $1"),
+        ];
+        assert_eq!(
+            StringReplace.evaluate(&parameters),
+            Some(string("Alice Smith"))
+        );
+
+        let parameters = [string("AbAB"), string("b"), string("-"), string("i")];
+        assert_eq!(StringReplace.evaluate(&parameters), Some(string("A-A-")));
+
+        let empty_match = [string("abc"), string("x*"), string("-")];
+        assert_eq!(StringReplace.evaluate(&empty_match), None);
+        // A group reference is followed by a digit that does not belong to it
+        let parameters = [string("abc"), string("(b)"), string("$1a")];
+        assert_eq!(StringReplace.evaluate(&parameters), Some(string("abac")));
+        let parameters = [string("abc"), string("(b)"), string("$12")];
+        assert_eq!(StringReplace.evaluate(&parameters), Some(string("ab2c")));
+        let parameters = [string("abc"), string("(b)"), string("[$0]")];
+        assert_eq!(StringReplace.evaluate(&parameters), Some(string("a[b]c")));
+
+        let parameters = [string("abc"), string("b"), string("\\$1\\\\")];
+        assert_eq!(StringReplace.evaluate(&parameters), Some(string("a$1\\c")));
+        let invalid_dollar = [string("abc"), string("b"), string("$x")];
+        assert_eq!(StringReplace.evaluate(&invalid_dollar), None);
+        let invalid_backslash = [string("abc"), string("b"), string("\\n")];
+        assert_eq!(StringReplace.evaluate(&invalid_backslash), None);
+
+        let parameters = [string("a.c"), string("."), string("$1\\"), string("q")];
+        assert_eq!(StringReplace.evaluate(&parameters), Some(string("a$1\\c")));
+    }
+
+    #[test]
+    fn test_regex_group() {
+        let date = string("born 1815-12-10 in London");
+        let pattern = string("(\\d{4})-(\\d{2})-(\\d{2})");
+
+        let group = |index: i64| {
+            StringRegexGroup.evaluate(&[
+                date.clone(),
+                pattern.clone(),
+                AnyDataValue::new_integer_from_i64(index),
+            ])
+        };
+
+        assert_eq!(group(0), Some(string("1815-12-10")));
+        assert_eq!(group(1), Some(string("1815")));
+        assert_eq!(group(3), Some(string("10")));
+        assert_eq!(group(4), None);
+        assert_eq!(group(-1), None);
+    }
+
+    #[test]
+    fn test_compiled_pattern() {
+        let pattern = compile_pattern(&string("B+"), Some(&string("i"))).unwrap();
+        let parameters = [string("abbc"), string("B+"), string("-"), string("i")];
+
+        assert_eq!(
+            StringReplace.evaluate_pattern(&pattern, &parameters),
+            StringReplace.evaluate(&parameters)
+        );
+        assert!(compile_pattern(&string("a"), Some(&string("g"))).is_none());
+    }
+}
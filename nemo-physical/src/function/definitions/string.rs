@@ -415,6 +415,44 @@ impl UnaryFunction for StringLowercase {
     }
 }
 
+/// Percent-encoding of a string, corresponding to SPARQL function `ENCODE_FOR_URI`
+///
+/// Returns a string in which each character other than a letter, a digit, `-`, `.`, `_` and `~`
+/// is replaced by the percent-encoding of its UTF-8 bytes.
+/// The language tag of a language tagged string is dropped.
+///
+/// Returns `None` if the provided argument is not a string.
+#[derive(Debug, Copy, Clone)]
+pub struct StringEncodeForUri;
+impl UnaryFunction for StringEncodeForUri {
+    fn evaluate(&self, parameter: AnyDataValue) -> Option<AnyDataValue> {
+        let string = parameter.to_plain_string().or_else(|| {
+            parameter
+                .to_language_tagged_string()
+                .map(|(string, _)| string)
+        })?;
+
+        let mut result = String::with_capacity(string.len());
+        for byte in string.bytes() {
+            if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+                result.push(char::from(byte));
+            } else {
+                result.push_str(&format!("%{byte:02X}"));
+            }
+        }
+
+        Some(AnyDataValue::new_plain_string(result))
+    }
+
+    fn type_propagation(&self) -> FunctionTypePropagation {
+        FunctionTypePropagation::KnownOutput(
+            StorageTypeName::Id32
+                .bitset()
+                .union(StorageTypeName::Id64.bitset()),
+        )
+    }
+}
+
 /// Substring with Length
 ///
 /// Expects a string value as the first parameter
@@ -469,7 +507,7 @@ mod test {
     use crate::{
         datavalues::AnyDataValue,
         function::definitions::{
-            string::{StringContains, StringLowercase, StringUppercase},
+            string::{StringContains, StringEncodeForUri, StringLowercase, StringUppercase},
             BinaryFunction, TernaryFunction, UnaryFunction,
         },
     };
@@ -746,4 +784,50 @@ mod test {
             super::StringAfter.evaluate(string_notstring, start_notstring);
         assert!(actual_result_notstring.is_none());
     }
+
+    #[test]
+    fn test_string_encode_for_uri() {
+        let string = AnyDataValue::new_plain_string("Los Angeles".to_string());
+        let result = AnyDataValue::new_plain_string("Los%20Angeles".to_string());
+        assert_eq!(StringEncodeForUri.evaluate(string), Some(result.clone()));
+
+        let string_language =
+            AnyDataValue::new_language_tagged_string("Los Angeles".to_string(), "en".to_string());
+        assert_eq!(StringEncodeForUri.evaluate(string_language), Some(result));
+
+        let string_unicode = AnyDataValue::new_plain_string("a/ẅ~".to_string());
+        let result_unicode = AnyDataValue::new_plain_string("a%2F%E1%BA%85~".to_string());
+        assert_eq!(
+            StringEncodeForUri.evaluate(string_unicode),
+            Some(result_unicode)
+        );
+
+        // Examples of the function fn:encode-for-uri in XPath and XQuery Functions and Operators
+        let examples = [
+            (
+                "http://www.example.com/00/Weather/CA/Los%20Angeles#ocean",
+                "http%3A%2F%2Fwww.example.com%2F00%2FWeather%2FCA%2FLos%2520Angeles%23ocean",
+            ),
+            ("~bébé", "~b%C3%A9b%C3%A9"),
+            ("100% organic", "100%25%20organic"),
+        ];
+        for (string, result) in examples {
+            assert_eq!(
+                StringEncodeForUri.evaluate(AnyDataValue::new_plain_string(string.to_string())),
+                Some(AnyDataValue::new_plain_string(result.to_string()))
+            );
+        }
+
+        let string_reserved = AnyDataValue::new_plain_string("!$&'()*+,;=:@?/[]".to_string());
+        let result_reserved = AnyDataValue::new_plain_string(
+            "%21%24%26%27%28%29%2A%2B%2C%3B%3D%3A%40%3F%2F%5B%5D".to_string(),
+        );
+        assert_eq!(
+            StringEncodeForUri.evaluate(string_reserved),
+            Some(result_reserved)
+        );
+
+        let string_notstring = AnyDataValue::new_integer_from_i64(1);
+        assert!(StringEncodeForUri.evaluate(string_notstring).is_none());
+    }
 }
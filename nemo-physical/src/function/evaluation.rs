@@ -1,6 +1,8 @@
 //! This module defines the structures for evaluating functions on column data.
 
-use std::{collections::HashMap, fmt::Debug, hash::Hash};
+use std::{collections::HashMap, fmt::Debug, hash::Hash, sync::Arc};
+
+use regex::Regex;
 
 use crate::{
     datatypes::storage_type_name::StorageTypeBitSet,
@@ -11,9 +13,11 @@ use crate::{
 
 use super::{
     definitions::{
+        regex::{compile_pattern, PATTERN_INDEX},
         BinaryFunction, BinaryFunctionEnum, FunctionTypePropagation, NaryFunction,
         NaryFunctionEnum, TernaryFunction, TernaryFunctionEnum, UnaryFunction, UnaryFunctionEnum,
     },
+    external::{external_type_propagation, ExternalFunction},
     tree::FunctionTree,
 };
 
@@ -48,6 +52,14 @@ pub(crate) enum StackOperation {
     TernaryFunction(TernaryFunctionEnum),
     /// Evaluate the given n-ary function on the top n elements in the stack.
     NaryFunction(NaryFunctionEnum, usize),
+    /// Evaluate the given n-ary function on the top n elements in the stack,
+    /// using the given compiled regular expression instead of the constant pattern parameter.
+    ///
+    /// The function must return a [PatternFunction][super::definitions::regex::PatternFunction]
+    /// from [NaryFunctionEnum::as_pattern_function].
+    PatternFunction(NaryFunctionEnum, usize, Regex),
+    /// Evaluate the given external function on the top n elements in the stack.
+    ExternalFunction(Arc<dyn ExternalFunction>, usize),
 }
 
 /// Representation of a [FunctionTree] as a stack program
@@ -92,12 +104,14 @@ impl StackProgram {
 
                     current_height -= 2;
                 }
-                StackOperation::NaryFunction(_, parameter_count) => {
+                StackOperation::NaryFunction(_, parameter_count)
+                | StackOperation::PatternFunction(_, parameter_count, _)
+                | StackOperation::ExternalFunction(_, parameter_count) => {
                     if current_height < *parameter_count {
                         return Err(Error::MalformedStackProgram);
                     }
 
-                    current_height -= parameter_count - 1;
+                    current_height = current_height - parameter_count + 1;
                 }
             }
 
@@ -176,11 +190,53 @@ impl StackProgram {
                         build_operations(parameter, this, reference_map, operations);
                     }
 
-                    operations.push(StackOperation::NaryFunction(*function, parameters.len()))
+                    match constant_pattern(function, parameters) {
+                        Some(pattern) => operations.push(StackOperation::PatternFunction(
+                            *function,
+                            parameters.len(),
+                            pattern,
+                        )),
+                        None => operations
+                            .push(StackOperation::NaryFunction(*function, parameters.len())),
+                    }
+                }
+                FunctionTree::External {
+                    function,
+                    parameters,
+                } => {
+                    for parameter in parameters {
+                        build_operations(parameter, this, reference_map, operations);
+                    }
+
+                    operations.push(StackOperation::ExternalFunction(
+                        function.clone(),
+                        parameters.len(),
+                    ));
                 }
             }
         }
 
+        /// Compile the regular expression of a [PatternFunction][super::definitions::regex::PatternFunction]
+        /// if its pattern and flags are constant.
+        fn constant_pattern<ReferenceType: Debug + Clone>(
+            function: &NaryFunctionEnum,
+            parameters: &[FunctionTree<ReferenceType>],
+        ) -> Option<Regex> {
+            let pattern_function = function.as_pattern_function()?;
+
+            let constant = |index: usize| match parameters.get(index)? {
+                FunctionTree::Leaf(FunctionLeaf::Constant(constant)) => Some(constant),
+                _ => None,
+            };
+
+            let flags = match pattern_function.flags_index(parameters.len()) {
+                Some(index) => Some(constant(index)?),
+                None => None,
+            };
+
+            compile_pattern(constant(PATTERN_INDEX)?, flags)
+        }
+
         let mut term_operations = Vec::new();
         build_operations(tree, &this, reference_map, &mut term_operations);
         Self::new(term_operations).expect("Compilation produces only valid stack programs.")
@@ -245,6 +301,21 @@ impl StackProgram {
                     }
                     inputs.reverse();
 
+                    stack.push(function.evaluate(&inputs)?);
+                }
+                StackOperation::PatternFunction(function, parameter_count, pattern) => {
+                    let inputs = stack.split_off(stack.len() - parameter_count);
+
+                    stack.push(
+                        function
+                            .as_pattern_function()
+                            .expect("Only pattern functions are compiled into this operation.")
+                            .evaluate_pattern(pattern, &inputs)?,
+                    );
+                }
+                StackOperation::ExternalFunction(function, parameter_count) => {
+                    let inputs = stack.split_off(stack.len() - parameter_count);
+
                     stack.push(function.evaluate(&inputs)?);
                 }
             }
@@ -308,7 +379,9 @@ impl StackProgram {
                 },
                 StackOperation::BinaryFunction(function) => (2, function.type_propagation()),
                 StackOperation::TernaryFunction(function) => (3, function.type_propagation()),
-                StackOperation::NaryFunction(function, num_arguments) => (*num_arguments, function.type_propagation()),
+                StackOperation::NaryFunction(function, num_arguments)
+                | StackOperation::PatternFunction(function, num_arguments, _) => (*num_arguments, function.type_propagation()),
+                StackOperation::ExternalFunction(function, num_arguments) => (*num_arguments, external_type_propagation(function.as_ref())),
             };
 
             for _ in 0..pop {
@@ -330,10 +403,11 @@ impl StackProgram {
 
 #[cfg(test)]
 mod test {
-    use std::collections::HashMap;
+    use std::{collections::HashMap, sync::Arc};
 
     use crate::{
-        datavalues::AnyDataValue, function::tree::FunctionTree,
+        datavalues::{AnyDataValue, DataValue, ValueDomain},
+        function::{external::ExternalFunction, tree::FunctionTree},
         tabular::operations::OperationColumnMarker,
     };
 
@@ -466,6 +540,120 @@ mod test {
         evaluate_expect(&tree_after, Some(any_string("World")));
     }
 
+    #[test]
+    fn evaluate_regex() {
+        let tree_regex = Function::string_regex(vec![
+            Function::constant(any_string("Hello World")),
+            Function::constant(any_string("^hello")),
+            Function::constant(any_string("i")),
+        ]);
+        evaluate_expect(&tree_regex, Some(any_bool(true)));
+
+        let tree_regex_computed = Function::string_regex(vec![
+            Function::constant(any_string("Hello World")),
+            Function::string_concatenation(vec![
+                Function::constant(any_string("^")),
+                Function::constant(any_string("hello")),
+            ]),
+        ]);
+        evaluate_expect(&tree_regex_computed, Some(any_bool(false)));
+
+        let tree_replace = Function::string_replace(vec![
+            Function::constant(any_string("Hello World")),
+            Function::constant(any_string("(\\w+) (\\w+)")),
+            Function::constant(any_string("$2 $1")),
+        ]);
+        evaluate_expect(&tree_replace, Some(any_string("World Hello")));
+
+        let tree_regex_group = Function::string_regex_group(vec![
+            Function::constant(any_string("Hello World")),
+            Function::constant(any_string("o (w)")),
+            Function::constant(any_int(1)),
+            Function::constant(any_string("i")),
+        ]);
+        evaluate_expect(&tree_regex_group, Some(any_string("W")));
+
+        let tree_invalid = Function::string_regex(vec![
+            Function::constant(any_string("Hello World")),
+            Function::constant(any_string("(")),
+        ]);
+        evaluate_expect(&tree_invalid, None);
+    }
+
+    #[test]
+    fn evaluate_collection() {
+        let tree_tuple = Function::tuple_construction(vec![
+            Function::constant(any_string("a")),
+            Function::constant(any_int(2)),
+        ]);
+        let tree_map = Function::map_construction(vec![
+            Function::constant(any_string("key")),
+            tree_tuple.clone(),
+        ]);
+
+        let tree_tuple_element = Function::tuple_element(
+            Function::map_element(tree_map.clone(), Function::constant(any_string("key"))),
+            Function::constant(any_int(2)),
+        );
+        evaluate_expect(&tree_tuple_element, Some(any_int(2)));
+
+        let tree_length = Function::collection_length(tree_tuple.clone());
+        evaluate_expect(&tree_length, Some(any_int(2)));
+
+        let tree_keys_length = Function::collection_length(Function::map_keys(tree_map.clone()));
+        evaluate_expect(&tree_keys_length, Some(any_int(1)));
+
+        let tree_missing_key =
+            Function::map_element(tree_map, Function::constant(any_string("other")));
+        evaluate_expect(&tree_missing_key, None);
+
+        let tree_not_tuple = Function::tuple_element(
+            Function::constant(any_string("a")),
+            Function::constant(any_int(1)),
+        );
+        evaluate_expect(&tree_not_tuple, None);
+    }
+
+    /// External function that counts the characters of all its string parameters
+    #[derive(Debug)]
+    struct TotalLength;
+    impl ExternalFunction for TotalLength {
+        fn evaluate(&self, parameters: &[AnyDataValue]) -> Option<AnyDataValue> {
+            let mut length = 0;
+            for parameter in parameters {
+                length += parameter.to_plain_string()?.chars().count();
+            }
+
+            Some(AnyDataValue::new_integer_from_u64(length as u64))
+        }
+
+        fn output_domain(&self) -> Option<ValueDomain> {
+            Some(ValueDomain::Long)
+        }
+    }
+
+    #[test]
+    fn evaluate_external() {
+        let tree_external = Function::external(
+            Arc::new(TotalLength),
+            vec![
+                Function::constant(any_string("abc")),
+                Function::string_uppercase(Function::constant(any_string("de"))),
+            ],
+        );
+        evaluate_expect(&tree_external, Some(any_int(5)));
+
+        let tree_nested = Function::numeric_addition(
+            Function::external(Arc::new(TotalLength), vec![]),
+            Function::constant(any_int(1)),
+        );
+        evaluate_expect(&tree_nested, Some(any_int(1)));
+
+        let tree_undefined =
+            Function::external(Arc::new(TotalLength), vec![Function::constant(any_int(1))]);
+        evaluate_expect(&tree_undefined, None);
+    }
+
     #[test]
     fn evaluate_numeric_integer() {
         // log_2(((|sqrt(64) - 11| * -2 + 26) / 5) ^ 3) = 6
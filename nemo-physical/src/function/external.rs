@@ -0,0 +1,34 @@
+//! This module defines the interface for functions that are not built into the engine,
+//! but are supplied by an application.
+
+use std::fmt::Debug;
+
+use crate::datavalues::{AnyDataValue, ValueDomain};
+
+use super::definitions::FunctionTypePropagation;
+
+/// A function on [AnyDataValue] that is defined outside of this crate
+///
+/// The function is assumed to be deterministic,
+/// i.e., it returns the same result whenever it is called with the same parameters.
+pub trait ExternalFunction: Debug + Send + Sync {
+    /// Evaluate this function on the given parameters.
+    ///
+    /// Returns `None` if the result of the function is undefined.
+    fn evaluate(&self, parameters: &[AnyDataValue]) -> Option<AnyDataValue>;
+
+    /// Return the [ValueDomain] that contains every result of this function
+    /// or `None` if results may belong to arbitrary domains.
+    fn output_domain(&self) -> Option<ValueDomain>;
+}
+
+/// Return a [FunctionTypePropagation] indicating how storage types are propagated
+/// when applying the given [ExternalFunction].
+pub(crate) fn external_type_propagation(
+    function: &dyn ExternalFunction,
+) -> FunctionTypePropagation {
+    match function.output_domain() {
+        Some(domain) => FunctionTypePropagation::KnownOutput(domain.storage_type()),
+        None => FunctionTypePropagation::Unknown,
+    }
+}
@@ -1,6 +1,6 @@
 //! This module defines a tree representation [FunctionTree].
 
-use std::{collections::HashMap, fmt::Debug, hash::Hash};
+use std::{collections::HashMap, fmt::Debug, hash::Hash, sync::Arc};
 
 use crate::datavalues::AnyDataValue;
 
@@ -9,10 +9,14 @@ use super::{
         boolean::{BooleanConjunction, BooleanDisjunction, BooleanNegation},
         casting::{CastingIntoDouble, CastingIntoFloat, CastingIntoInteger64},
         checktype::{
-            CheckIsDouble, CheckIsFloat, CheckIsInteger, CheckIsIri, CheckIsNull, CheckIsNumeric,
-            CheckIsString,
+            CheckIsDouble, CheckIsFloat, CheckIsInteger, CheckIsIri, CheckIsLiteral, CheckIsNull,
+            CheckIsNumeric, CheckIsString,
+        },
+        collection::{
+            CollectionLength, MapConstruction, MapElement, MapKeys, TupleConstruction, TupleElement,
         },
         generic::{CanonicalString, Datatype, Equals, LexicalValue, Unequals},
+        hash::{HashMd5, HashSha1, HashSha256},
         language::LanguageTag,
         numeric::{
             BitAnd, BitOr, BitXor, NumericAbsolute, NumericAddition, NumericCeil, NumericCosine,
@@ -22,14 +26,17 @@ use super::{
             NumericProduct, NumericRemainder, NumericRound, NumericSine, NumericSquareroot,
             NumericSubtraction, NumericSum, NumericTangent,
         },
+        rdf::{RdfBlankNode, RdfIri, RdfLanguageMatches, RdfLanguageString, RdfTypedLiteral},
+        regex::{StringRegex, StringRegexGroup, StringReplace},
         string::{
             StringAfter, StringBefore, StringCompare, StringConcatenation, StringContains,
-            StringEnds, StringLength, StringLowercase, StringReverse, StringStarts,
-            StringSubstring, StringSubstringLength, StringUppercase,
+            StringEncodeForUri, StringEnds, StringLength, StringLowercase, StringReverse,
+            StringStarts, StringSubstring, StringSubstringLength, StringUppercase,
         },
         BinaryFunctionEnum, NaryFunctionEnum, TernaryFunctionEnum, UnaryFunctionEnum,
     },
     evaluation::StackProgram,
+    external::ExternalFunction,
 };
 
 /// Leaf node of a [FunctionTree]
@@ -81,6 +88,13 @@ where
         /// Parameters of the function
         parameters: Vec<FunctionTree<ReferenceType>>,
     },
+    /// Application of an [ExternalFunction]
+    External {
+        /// External function
+        function: Arc<dyn ExternalFunction>,
+        /// Parameters of the function
+        parameters: Vec<FunctionTree<ReferenceType>>,
+    },
 }
 
 /// Enumeration of special cases for [FunctionTree]s
@@ -253,6 +267,50 @@ where
         Self::Unary(UnaryFunctionEnum::LanguageTag(LanguageTag), Box::new(sub))
     }
 
+    /// Create a tree node that evaluates to an iri
+    /// whose content is given by the string of the sub node.
+    pub fn rdf_iri(sub: Self) -> Self {
+        Self::Unary(UnaryFunctionEnum::RdfIri(RdfIri), Box::new(sub))
+    }
+
+    /// Create a tree node that evaluates to a literal
+    /// with the lexical value resulting from `lexical_value`
+    /// and the datatype iri resulting from `datatype`.
+    pub fn rdf_typed_literal(lexical_value: Self, datatype: Self) -> Self {
+        Self::Binary {
+            function: BinaryFunctionEnum::RdfTypedLiteral(RdfTypedLiteral),
+            left: Box::new(lexical_value),
+            right: Box::new(datatype),
+        }
+    }
+
+    /// Create a tree node that evaluates to a language tagged string
+    /// with the lexical value resulting from `lexical_value`
+    /// and the language tag resulting from `tag`.
+    pub fn rdf_language_string(lexical_value: Self, tag: Self) -> Self {
+        Self::Binary {
+            function: BinaryFunctionEnum::RdfLanguageString(RdfLanguageString),
+            left: Box::new(lexical_value),
+            right: Box::new(tag),
+        }
+    }
+
+    /// Create a tree node that checks whether the language tag resulting from `tag`
+    /// matches the language range resulting from `range`.
+    pub fn rdf_language_matches(tag: Self, range: Self) -> Self {
+        Self::Binary {
+            function: BinaryFunctionEnum::RdfLanguageMatches(RdfLanguageMatches),
+            left: Box::new(tag),
+            right: Box::new(range),
+        }
+    }
+
+    /// Create a tree node that evaluates to a null
+    /// that is determined by the string of the sub node.
+    pub fn rdf_blank_node(sub: Self) -> Self {
+        Self::Unary(UnaryFunctionEnum::RdfBlankNode(RdfBlankNode), Box::new(sub))
+    }
+
     /// Create a tree node the checks whether the sub node is an integer.
     pub fn check_is_integer(sub: Self) -> Self {
         Self::Unary(
@@ -300,6 +358,14 @@ where
         )
     }
 
+    /// Create a tree node the checks whether the sub node is a literal.
+    pub fn check_is_literal(sub: Self) -> Self {
+        Self::Unary(
+            UnaryFunctionEnum::CheckIsLiteral(CheckIsLiteral),
+            Box::new(sub),
+        )
+    }
+
     /// Create a tree node representing the conjunction of boolean values.
     ///
     /// This evaluates to `true` if all its subnodes evaluate to `true`
@@ -611,6 +677,110 @@ where
         }
     }
 
+    /// Create a tree node representing the matching of a regular expression.
+    ///
+    /// This evaluates to a boolean indicating whether the regular expression
+    /// resulting from the second subnode matches some part of the string
+    /// resulting from the first subnode.
+    /// An optional third subnode provides the flags of the regular expression.
+    pub fn string_regex(parameters: Vec<Self>) -> Self {
+        Self::Nary {
+            function: NaryFunctionEnum::StringRegex(StringRegex),
+            parameters,
+        }
+    }
+
+    /// Create a tree node representing the replacement of the matches of a regular expression.
+    ///
+    /// This evaluates to the string resulting from the first subnode,
+    /// where every match of the regular expression resulting from the second subnode
+    /// is replaced by the string resulting from the third subnode.
+    /// An optional fourth subnode provides the flags of the regular expression.
+    pub fn string_replace(parameters: Vec<Self>) -> Self {
+        Self::Nary {
+            function: NaryFunctionEnum::StringReplace(StringReplace),
+            parameters,
+        }
+    }
+
+    /// Create a tree node representing the extraction of a capture group of a regular expression.
+    ///
+    /// This evaluates to the part of the string resulting from the first subnode
+    /// that is matched by a capture group of the regular expression resulting from the second subnode,
+    /// where the index of the group results from the third subnode.
+    /// An optional fourth subnode provides the flags of the regular expression.
+    pub fn string_regex_group(parameters: Vec<Self>) -> Self {
+        Self::Nary {
+            function: NaryFunctionEnum::StringRegexGroup(StringRegexGroup),
+            parameters,
+        }
+    }
+
+    /// Create a tree node representing the construction of a tuple.
+    ///
+    /// This evaluates to a tuple containing the values
+    /// resulting from each of the subnodes.
+    pub fn tuple_construction(parameters: Vec<Self>) -> Self {
+        Self::Nary {
+            function: NaryFunctionEnum::TupleConstruction(TupleConstruction),
+            parameters,
+        }
+    }
+
+    /// Create a tree node representing the construction of a map.
+    ///
+    /// This evaluates to a map that assigns the value of each odd subnode
+    /// to the value of the subnode following it.
+    pub fn map_construction(parameters: Vec<Self>) -> Self {
+        Self::Nary {
+            function: NaryFunctionEnum::MapConstruction(MapConstruction),
+            parameters,
+        }
+    }
+
+    /// Create a tree node representing the element of a tuple at some position.
+    ///
+    /// This evaluates to the element of the tuple resulting from `tuple`
+    /// at the position resulting from `position`, where positions start at 1.
+    pub fn tuple_element(tuple: Self, position: Self) -> Self {
+        Self::Binary {
+            function: BinaryFunctionEnum::TupleElement(TupleElement),
+            left: Box::new(tuple),
+            right: Box::new(position),
+        }
+    }
+
+    /// Create a tree node representing the value of a map for some key.
+    ///
+    /// This evaluates to the value that the map resulting from `map`
+    /// assigns to the value resulting from `key`.
+    pub fn map_element(map: Self, key: Self) -> Self {
+        Self::Binary {
+            function: BinaryFunctionEnum::MapElement(MapElement),
+            left: Box::new(map),
+            right: Box::new(key),
+        }
+    }
+
+    /// Create a tree node representing the length of a tuple or map.
+    ///
+    /// This evaluates to a number from the integer value space
+    /// that is the number of elements of the collection that results from evaluating `sub`.
+    pub fn collection_length(sub: Self) -> Self {
+        Self::Unary(
+            UnaryFunctionEnum::CollectionLength(CollectionLength),
+            Box::new(sub),
+        )
+    }
+
+    /// Create a tree node representing the keys of a map.
+    ///
+    /// This evaluates to a tuple containing the keys of the map
+    /// that results from evaluating `sub`.
+    pub fn map_keys(sub: Self) -> Self {
+        Self::Unary(UnaryFunctionEnum::MapKeys(MapKeys), Box::new(sub))
+    }
+
     /// Create a tree node representing the comparison of the beginning of a string.
     ///
     /// This evaluates to a boolean indicating whether
@@ -716,6 +886,41 @@ where
         )
     }
 
+    /// Create a tree node representing the percent-encoding of a string.
+    ///
+    /// This evaluates to a version of the string that results from evaluating `sub`,
+    /// which can be used as part of an iri.
+    pub fn string_encode_for_uri(sub: Self) -> Self {
+        Self::Unary(
+            UnaryFunctionEnum::StringEncodeForUri(StringEncodeForUri),
+            Box::new(sub),
+        )
+    }
+
+    /// Create a tree node representing the MD5 hash of a string.
+    ///
+    /// This evaluates to the hexadecimal representation of the hash
+    /// of the string that results from evaluating `sub`.
+    pub fn hash_md5(sub: Self) -> Self {
+        Self::Unary(UnaryFunctionEnum::HashMd5(HashMd5), Box::new(sub))
+    }
+
+    /// Create a tree node representing the SHA-1 hash of a string.
+    ///
+    /// This evaluates to the hexadecimal representation of the hash
+    /// of the string that results from evaluating `sub`.
+    pub fn hash_sha1(sub: Self) -> Self {
+        Self::Unary(UnaryFunctionEnum::HashSha1(HashSha1), Box::new(sub))
+    }
+
+    /// Create a tree node representing the SHA-256 hash of a string.
+    ///
+    /// This evaluates to the hexadecimal representation of the hash
+    /// of the string that results from evaluating `sub`.
+    pub fn hash_sha256(sub: Self) -> Self {
+        Self::Unary(UnaryFunctionEnum::HashSha256(HashSha256), Box::new(sub))
+    }
+
     /// Create a tree node representing a substring operation.
     ///
     /// This evaluates to a string containing the
@@ -856,6 +1061,17 @@ where
             parameters.remove(0)
         }
     }
+
+    /// Create a tree node representing the application of an [ExternalFunction].
+    ///
+    /// This evaluates to the result of the external function
+    /// on the values resulting from each of the subnodes.
+    pub fn external(function: Arc<dyn ExternalFunction>, parameters: Vec<Self>) -> Self {
+        Self::External {
+            function,
+            parameters,
+        }
+    }
 }
 
 impl<ReferenceType> FunctionTree<ReferenceType>
@@ -895,6 +1111,10 @@ where
             FunctionTree::Nary {
                 function: _,
                 parameters,
+            }
+            | FunctionTree::External {
+                function: _,
+                parameters,
             } => {
                 let mut result = Vec::new();
 
@@ -6,3 +6,4 @@ pub(crate) mod util;
 pub mod bytesized;
 pub mod database;
 pub mod execution_plan;
+pub mod plan_explanation;
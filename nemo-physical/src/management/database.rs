@@ -14,6 +14,7 @@ use std::{
     cell::{Ref, RefCell, RefMut},
     collections::HashMap,
     fmt::Debug,
+    time::Duration,
 };
 
 use bytesize::ByteSize;
@@ -27,6 +28,7 @@ use crate::{
     tabular::{
         operations::{projectreorder::ProjectReordering, OperationGenerator},
         rowscan::RowScan,
+        statistics::TableStatistics,
         trie::Trie,
         triescan::TrieScanEnum,
     },
@@ -45,6 +47,21 @@ use super::execution_plan::{ColumnOrder, ExecutionPlan, ExecutionResult};
 /// Dictionary Implementation used in the current configuration
 pub type Dict = crate::dictionary::meta_dv_dict::MetaDvDictionary;
 
+/// Number of rows and computation time of a table
+/// that was computed during the execution of an [ExecutionPlan]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutionStatistics {
+    /// Number of rows of the computed table
+    ///
+    /// This is `None` for tables that are only stored in other orders.
+    pub rows: Option<usize>,
+    /// Time spent on computing the table
+    ///
+    /// This is zero if the crate is compiled without the `timing` feature
+    /// and for tables that are only reorderings of other computed tables.
+    pub time: Duration,
+}
+
 /// Struct that contains useful information about a trie
 /// as well as the actual owner of the trie.
 #[derive(Debug)]
@@ -74,10 +91,16 @@ pub struct DatabaseInstance {
     /// used in all tables of this database
     dictionary: RefCell<Dict>,
 
+    /// Cached [TableStatistics] for each table for which they were requested
+    statistics: HashMap<PermanentTableId, TableStatistics>,
+
     /// The lowest unused [PermanentTableId]
     ///
     /// This will be incremented for each new table.
     current_id: PermanentTableId,
+
+    /// Time spent on operations of this database
+    timing: TimedCode,
 }
 
 // Return basic information about tables managed by the database
@@ -120,6 +143,18 @@ impl DatabaseInstance {
         self.dictionary.borrow_mut()
     }
 
+    /// Return the time spent on the operations of this database.
+    ///
+    /// Callers may record additional timings, e.g., of the operations that use this database.
+    pub fn timing(&self) -> &TimedCode {
+        &self.timing
+    }
+
+    /// Return a mutable reference to the time spent on the operations of this database.
+    pub fn timing_mut(&mut self) -> &mut TimedCode {
+        &mut self.timing
+    }
+
     /// Return the amount of memory consumed by the table under the given [PermanentTableId].
     /// This also includes additional index structures but excludes tables that are currently stored on disk.
     ///
@@ -136,6 +171,44 @@ impl DatabaseInstance {
         self.reference_manager.count_rows(id)
     }
 
+    /// Return [TableStatistics] about the contents of the table with the given [PermanentTableId].
+    ///
+    /// Statistics are computed the first time they are requested,
+    /// which requires loading the table if it is not yet available in memory.
+    /// They are computed from any [ColumnOrder] in which the table is stored,
+    /// so the table is never reordered.
+    ///
+    /// # Panics
+    /// Panics if the given id does not exist.
+    pub fn table_statistics(&mut self, id: PermanentTableId) -> Result<&TableStatistics, Error> {
+        if !self.statistics.contains_key(&id) {
+            let order = self.reference_manager.stored_order(id);
+            let storage_id = self.reference_manager.trie_id(
+                &self.dictionary,
+                &mut self.timing,
+                id,
+                order.clone(),
+            )?;
+            let statistics = self.reference_manager.trie(storage_id).statistics(&order);
+
+            self.statistics.insert(id, statistics);
+        }
+
+        Ok(self
+            .statistics
+            .get(&id)
+            .expect("Statistics have been computed above"))
+    }
+
+    /// Return all [ColumnOrder]s in which the table with the given [PermanentTableId]
+    /// is available without reordering it.
+    ///
+    /// # Panics
+    /// Panics if the given id does not exist.
+    pub fn table_orders(&self, id: PermanentTableId) -> Vec<ColumnOrder> {
+        self.reference_manager.stored_column_orders(id)
+    }
+
     /// Provide an iterator over the rows of the table with the given [PermanentTableId].
     ///
     /// # Panics
@@ -147,7 +220,12 @@ impl DatabaseInstance {
         // Make sure trie is loaded
         let storage_id = self
             .reference_manager
-            .trie_id(&self.dictionary, id, ColumnOrder::default())
+            .trie_id(
+                &self.dictionary,
+                &mut self.timing,
+                id,
+                ColumnOrder::default(),
+            )
             .expect("No table with id {id} exists.");
         let trie = self.reference_manager.trie(storage_id);
 
@@ -162,6 +240,40 @@ impl DatabaseInstance {
         }))
     }
 
+    /// Convert the table with the given [PermanentTableId] into a [RecordBatch][arrow_array::RecordBatch]
+    /// with the given column names.
+    ///
+    /// Columns that only contain numbers of one storage type are converted into numeric columns.
+    /// Values of all other columns are represented as strings by the given function.
+    ///
+    /// # Panics
+    /// Panics if the given id does not exist.
+    #[cfg(feature = "arrow")]
+    pub fn table_record_batch<Serializer>(
+        &mut self,
+        id: PermanentTableId,
+        column_names: &[String],
+        serialize: Serializer,
+    ) -> Result<arrow_array::RecordBatch, Error>
+    where
+        Serializer: Fn(&AnyDataValue) -> String,
+    {
+        let storage_id = self.reference_manager.trie_id(
+            &self.dictionary,
+            &mut self.timing,
+            id,
+            ColumnOrder::default(),
+        )?;
+        let trie = self.reference_manager.trie(storage_id);
+
+        Ok(crate::tabular::arrow::trie_record_batch(
+            trie,
+            &self.dictionary(),
+            column_names,
+            serialize,
+        ))
+    }
+
     /// Return whether a table of the given [PermanentTableId] contains a given row.
     ///
     /// # Panics
@@ -169,7 +281,12 @@ impl DatabaseInstance {
     pub fn table_contains_row(&mut self, id: PermanentTableId, row: &[AnyDataValue]) -> bool {
         let storage_id = self
             .reference_manager
-            .trie_id(&self.dictionary, id, ColumnOrder::default())
+            .trie_id(
+                &self.dictionary,
+                &mut self.timing,
+                id,
+                ColumnOrder::default(),
+            )
             .expect("No table with id {id} exists.");
         let trie = self.reference_manager.trie(storage_id);
 
@@ -203,6 +320,7 @@ impl DatabaseInstance {
 
     /// Add a new trie.
     fn add_trie(&mut self, id: PermanentTableId, order: ColumnOrder, trie: Trie) {
+        self.statistics.remove(&id);
         self.reference_manager.add_trie(id, order, trie);
     }
 
@@ -227,6 +345,7 @@ impl DatabaseInstance {
         order: ColumnOrder,
         sources: Vec<TableSource>,
     ) {
+        self.statistics.remove(&id);
         self.reference_manager.add_sources(id, order, sources);
     }
 
@@ -239,10 +358,17 @@ impl DatabaseInstance {
     ) {
         let arity = self.table_arity(id);
 
+        self.statistics.remove(&id);
         self.reference_manager
             .add_source(id, order, TableSource::new(provider, arity));
     }
 
+    /// Add a [TableSource] to an existing table.
+    pub fn add_source(&mut self, id: PermanentTableId, order: ColumnOrder, source: TableSource) {
+        self.statistics.remove(&id);
+        self.reference_manager.add_source(id, order, source);
+    }
+
     /// Add a table given as [SimpleTable].
     pub fn add_source_table(
         &mut self,
@@ -252,6 +378,7 @@ impl DatabaseInstance {
     ) {
         let arity = table.arity();
 
+        self.statistics.remove(&id);
         self.reference_manager
             .add_source(id, order, TableSource::new(Box::new(table), arity));
     }
@@ -292,10 +419,12 @@ impl DatabaseInstance {
         let mut result = Vec::new();
 
         for (id, order) in tables.iter().cloned() {
-            result.push(
-                self.reference_manager
-                    .trie_id(&self.dictionary, id, order)?,
-            );
+            result.push(self.reference_manager.trie_id(
+                &self.dictionary,
+                &mut self.timing,
+                id,
+                order,
+            )?);
         }
 
         Ok(result)
@@ -431,20 +560,34 @@ impl DatabaseInstance {
         &mut self,
         plan: ExecutionPlan,
     ) -> Result<HashMap<ExecutionId, PermanentTableId>, Error> {
+        self.execute_plan_with_statistics(plan)
+            .map(|(result, _statistics)| result)
+    }
+
+    /// Evaluate the given [ExecutionPlan]
+    /// and additionally return the [ExecutionStatistics] of every computed table.
+    #[allow(clippy::type_complexity)]
+    pub fn execute_plan_with_statistics(
+        &mut self,
+        plan: ExecutionPlan,
+    ) -> Result<
+        (
+            HashMap<ExecutionId, PermanentTableId>,
+            HashMap<ExecutionId, ExecutionStatistics>,
+        ),
+        Error,
+    > {
         let execution_series = plan.finalize();
+        let mut statistics = HashMap::new();
 
-        TimedCode::instance()
-            .sub("Reasoning/Execution/Load Table")
-            .start();
+        self.timing.sub("Reasoning/Execution/Load Table").start();
 
         let mut temporary_storage = TemporaryStorage {
             loaded_tables: self.collect_requiured_tries(&execution_series.loaded_tries)?,
             computed_tables: vec![None; execution_series.trees.len()],
         };
 
-        TimedCode::instance()
-            .sub("Reasoning/Execution/Load Table")
-            .stop();
+        self.timing.sub("Reasoning/Execution/Load Table").stop();
 
         for (tree_index, tree) in execution_series.trees.iter().enumerate() {
             if temporary_storage.computed_tables[tree_index].is_some() {
@@ -460,20 +603,37 @@ impl DatabaseInstance {
                 .collect::<Vec<_>>();
 
             let timed_string = format!("Reasoning/Execution/{}", tree.operation_name);
-            TimedCode::instance().sub(&timed_string).start();
+            self.timing.sub(&timed_string).start();
             let (result_tree, results_dependent) =
                 self.execute_tree(&temporary_storage, tree, dependent_reorderings);
-            TimedCode::instance().sub(&timed_string).stop();
+            let time = self.timing.sub(&timed_string).stop();
 
             if tree.used > 0 {
                 Self::log_new_trie(tree, &result_tree);
             }
 
+            statistics.insert(
+                tree.id,
+                ExecutionStatistics {
+                    rows: (tree.used > 0 || tree.dependents.is_empty())
+                        .then(|| result_tree.num_rows()),
+                    time,
+                },
+            );
+
             temporary_storage.computed_tables[tree_index] = Some(result_tree);
             for ((computed_id, _), result_dependent) in
                 tree.dependents.iter().zip(results_dependent)
             {
                 Self::log_new_trie(tree, &result_dependent);
+
+                statistics
+                    .entry(execution_series.trees[*computed_id].id)
+                    .or_insert(ExecutionStatistics {
+                        rows: Some(result_dependent.num_rows()),
+                        time: Duration::ZERO,
+                    });
+
                 temporary_storage.computed_tables[*computed_id] = Some(result_dependent);
             }
         }
@@ -498,7 +658,7 @@ impl DatabaseInstance {
             }
         }
 
-        Ok(result)
+        Ok((result, statistics))
     }
 
     /// Evaluate a given [ExecutionPlan] until the first row is found and return it.
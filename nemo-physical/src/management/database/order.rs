@@ -102,6 +102,64 @@ impl OrderedReferenceManager {
         }
     }
 
+    /// Return all [ColumnOrder]s in which the given table is stored,
+    /// i.e., in which it can be obtained as a [Trie] without reordering,
+    /// together with the [StorageId] of the stored table.
+    ///
+    /// # Panics
+    /// Panics if the given id does not exist.
+    fn stored_orders(&self, id: PermanentTableId) -> Vec<(ColumnOrder, StorageId)> {
+        let (id_stored, permutation) = match self.reference_map.get(&id) {
+            Some(reference) => (reference.id, Some(reference.permutation.invert())),
+            None => (id, None),
+        };
+
+        self.storage_map
+            .get(&id_stored)
+            .expect("No table with the id {id} exists.")
+            .iter()
+            .map(|(order, &storage_id)| {
+                // Invert the translation performed in `resolve_reference`
+                let order = match &permutation {
+                    Some(permutation) => order.chain_permutation(permutation),
+                    None => order.clone(),
+                };
+
+                (order, storage_id)
+            })
+            .collect()
+    }
+
+    /// Return all [ColumnOrder]s in which the given table
+    /// can be obtained as a [Trie] without reordering.
+    ///
+    /// # Panics
+    /// Panics if the given id does not exist.
+    pub(crate) fn stored_column_orders(&self, id: PermanentTableId) -> Vec<ColumnOrder> {
+        self.stored_orders(id)
+            .into_iter()
+            .map(|(order, _)| order)
+            .collect()
+    }
+
+    /// Return a [ColumnOrder] in which the given table
+    /// can be obtained as a [Trie] without reordering.
+    ///
+    /// Orders in which the table is already loaded into memory are preferred.
+    ///
+    /// # Panics
+    /// Panics if the given id does not exist.
+    pub(crate) fn stored_order(&self, id: PermanentTableId) -> ColumnOrder {
+        let orders = self.stored_orders(id);
+
+        orders
+            .iter()
+            .find(|(_, storage_id)| self.stored_tables[*storage_id].trie_in_memory().is_some())
+            .or(orders.first())
+            .map(|(order, _)| order.clone())
+            .expect("Trie should exist at least in one order.")
+    }
+
     /// Return the number of rows contained in this table.
     ///
     /// TODO: Currently only counting of in-memory facts is supported, see <https://github.com/knowsys/nemo/issues/335>
@@ -266,6 +324,7 @@ impl OrderedReferenceManager {
     pub(crate) fn trie_id(
         &mut self,
         dictionary: &RefCell<Dict>,
+        timing: &mut TimedCode,
         id: PermanentTableId,
         column_order: ColumnOrder,
     ) -> Result<StorageId, Error> {
@@ -290,17 +349,13 @@ impl OrderedReferenceManager {
                 );
 
                 if !generator.is_noop() {
-                    TimedCode::instance()
-                        .sub("Reasoning/Execution/Required Reorder")
-                        .start();
+                    timing.sub("Reasoning/Execution/Required Reorder").start();
 
                     let closest_trie = self.stored_tables[closest_storage_id].trie(dictionary)?;
                     let trie_reordered = generator.apply_operation(closest_trie.partial_iterator());
                     let result_storage_id = self.add_trie(id, column_order, trie_reordered);
 
-                    TimedCode::instance()
-                        .sub("Reasoning/Execution/Required Reorder")
-                        .stop();
+                    timing.sub("Reasoning/Execution/Required Reorder").stop();
 
                     return Ok(result_storage_id);
                 } else {
@@ -405,5 +460,10 @@ mod test {
         for order in available_orders {
             assert!(reference_available_orders.iter().any(|o| o == &order));
         }
+
+        for id in [id_present, id_reference, id_second_reference] {
+            let (id_stored, order_stored) = manager.resolve_reference(id, manager.stored_order(id));
+            assert!(manager.storage_map[&id_stored].contains_key(&order_stored));
+        }
     }
 }
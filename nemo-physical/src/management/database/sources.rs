@@ -5,7 +5,9 @@ use std::{error::Error, fmt::Display, mem::size_of};
 use bytesize::ByteSize;
 
 use crate::{
-    datasources::{table_providers::TableProvider, tuple_writer::TupleWriter},
+    datasources::{
+        table_providers::TableProvider, tuple_writer::TupleWriter, value_validator::ValueValidator,
+    },
     datavalues::AnyDataValue,
     management::bytesized::ByteSized,
 };
@@ -73,12 +75,25 @@ pub struct TableSource {
     provider: Box<dyn TableProvider>,
     /// Number of columns in the table
     arity: usize,
+    /// [ValueValidator] that checks the values provided by the source
+    validator: Option<Box<dyn ValueValidator>>,
 }
 
 impl TableSource {
     /// Construct a new [TableSource].
     pub fn new(provider: Box<dyn TableProvider>, arity: usize) -> Self {
-        Self { provider, arity }
+        Self {
+            provider,
+            arity,
+            validator: None,
+        }
+    }
+
+    /// Check every value provided by this source with the given [ValueValidator].
+    /// Loading the source fails if a value is rejected.
+    pub fn with_validator(mut self, validator: Box<dyn ValueValidator>) -> Self {
+        self.validator = Some(validator);
+        self
     }
 
     /// Construct a new [TableSource] from a [SimpleTable].
@@ -96,7 +111,15 @@ impl TableSource {
     /// Load the data represented by this [TableSource]
     /// and write it into the given [TupleWriter]
     pub fn provide_table_data(self, tuple_writer: &mut TupleWriter) -> Result<(), Box<dyn Error>> {
-        self.provider.provide_table_data(tuple_writer)
+        tuple_writer.set_validator(self.validator);
+        let result = self.provider.provide_table_data(tuple_writer);
+        tuple_writer.set_validator(None);
+        result?;
+
+        match tuple_writer.take_validation_error() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
     }
 }
 
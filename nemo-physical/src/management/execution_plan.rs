@@ -3,7 +3,7 @@
 
 use std::{
     cell::RefCell,
-    collections::{hash_map::Entry, HashMap},
+    collections::{hash_map::Entry, HashMap, HashSet},
     fmt::Debug,
     rc::{Rc, Weak},
 };
@@ -21,7 +21,7 @@ use crate::{
         projectreorder::GeneratorProjectReorder,
         subtract::GeneratorSubtract,
         union::GeneratorUnion,
-        OperationGeneratorEnum, OperationTable,
+        OperationColumnMarker, OperationGeneratorEnum, OperationTable,
     },
     util::mapping::{permutation::Permutation, traits::NatMapping},
 };
@@ -31,6 +31,7 @@ use super::{
         execution_series::{ComputedTableId, ExecutionSeries, ExecutionTree, LoadedTableId},
         id::{ExecutionId, PermanentTableId, TableId},
     },
+    plan_explanation::{explain_function, OperationExplanation, PlanExplanation, TableExplanation},
     util::closest_order,
 };
 
@@ -90,6 +91,12 @@ impl ExecutionNodeRef {
 
         node_borrow.marked_columns.clone()
     }
+
+    /// Attach a description to the referenced node,
+    /// which is shown when explaining the [ExecutionPlan].
+    pub fn annotate(&self, annotation: &str) {
+        self.get_rc().borrow_mut().annotation = Some(String::from(annotation));
+    }
 }
 
 impl ExecutionNodeRef {
@@ -116,7 +123,6 @@ impl ExecutionNodeRef {
     }
 
     /// Return the list of subnodes.
-    #[allow(dead_code)]
     pub(crate) fn subnodes(&self) -> Vec<ExecutionNodeRef> {
         let node_rc = self.get_rc();
         let node_operation = &node_rc.borrow().operation;
@@ -150,6 +156,8 @@ pub(crate) struct ExecutionNode {
     pub operation: ExecutionOperation,
     /// Representation of the output table represented by this node
     pub marked_columns: OperationTable,
+    /// Description of the node, see [ExecutionNodeRef::annotate]
+    pub annotation: Option<String>,
 }
 
 #[derive(Debug)]
@@ -227,6 +235,7 @@ impl ExecutionPlan {
             id,
             operation,
             marked_columns,
+            annotation: None,
         };
 
         self.nodes.push(ExecutionNodeOwned::new(node));
@@ -844,3 +853,177 @@ impl ExecutionPlan {
         }
     }
 }
+
+impl ExecutionPlan {
+    /// Return the "output" node that materializes the node with the given [ExecutionId], if it exists.
+    fn out_node(&self, id: ExecutionId) -> Option<&ExecutionOutNode> {
+        self.out_nodes
+            .iter()
+            .find(|out_node| out_node.node.id() == id)
+    }
+
+    /// Collect the ids of all "output" nodes that need to be computed for the given node.
+    fn required_tables(&self, node: &ExecutionNodeRef, required: &mut HashSet<ExecutionId>) {
+        if self.out_node(node.id()).is_some() && !required.insert(node.id()) {
+            return;
+        }
+
+        for subnode in node.subnodes() {
+            self.required_tables(&subnode, required);
+        }
+    }
+
+    /// Describe the operations computing the table represented by the given node.
+    fn explain_node<TableName, MarkerName>(
+        &self,
+        node: &ExecutionNodeRef,
+        is_root: bool,
+        table_name: &TableName,
+        marker_name: &MarkerName,
+    ) -> OperationExplanation
+    where
+        TableName: Fn(PermanentTableId) -> String,
+        MarkerName: Fn(&OperationColumnMarker) -> String,
+    {
+        let node_rc = node.get_rc();
+        let node_borrow = node_rc.borrow();
+        let columns = node_borrow.marked_columns.iter().map(marker_name).collect();
+
+        if !is_root {
+            if let Some(out_node) = self.out_node(node_borrow.id) {
+                return OperationExplanation {
+                    operation: format!("Table {}: {}", node_borrow.id, out_node.operation_name),
+                    annotation: node_borrow.annotation.clone(),
+                    columns,
+                    subnodes: Vec::new(),
+                };
+            }
+        }
+
+        let operation = match &node_borrow.operation {
+            ExecutionOperation::FetchTable(id, order) => {
+                if order.is_identity() {
+                    format!("Fetch {}", table_name(*id))
+                } else {
+                    format!("Fetch {} reordered by {order}", table_name(*id))
+                }
+            }
+            ExecutionOperation::Join(_) => String::from("Join"),
+            ExecutionOperation::Union(_) => String::from("Union"),
+            ExecutionOperation::Subtract(_, _) => String::from("Subtract"),
+            ExecutionOperation::ProjectReorder(_) => String::from("Project/Reorder"),
+            ExecutionOperation::Filter(_, filters) => {
+                let mut operation = String::from("Filter");
+                for (index, filter) in filters.iter().enumerate() {
+                    operation.push_str(if index == 0 { " " } else { ", " });
+                    operation.push_str(&explain_function(filter, marker_name));
+                }
+
+                operation
+            }
+            ExecutionOperation::Function(_, assignment) => {
+                let mut functions = assignment.iter().collect::<Vec<_>>();
+                functions.sort_by_key(|(marker, _)| **marker);
+
+                let mut operation = String::from("Function");
+                for (index, (marker, function)) in functions.into_iter().enumerate() {
+                    operation.push_str(if index == 0 { " " } else { ", " });
+                    operation.push_str(&format!(
+                        "{} = {}",
+                        marker_name(marker),
+                        explain_function(function, marker_name)
+                    ));
+                }
+
+                operation
+            }
+            ExecutionOperation::Null(subnode) => {
+                let input = subnode.markers_cloned();
+
+                format!(
+                    "Null {}",
+                    node_borrow
+                        .marked_columns
+                        .iter()
+                        .filter(|marker| !input.contains(marker))
+                        .map(marker_name)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            }
+            ExecutionOperation::Aggregate(_, assignment) => {
+                let mut operation = format!(
+                    "Aggregate {:?}({})",
+                    assignment.aggregate_operation,
+                    marker_name(&assignment.aggregated_column)
+                );
+                if !assignment.group_by_columns.is_empty() {
+                    operation.push_str(&format!(
+                        " grouped by {}",
+                        assignment
+                            .group_by_columns
+                            .iter()
+                            .map(marker_name)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ));
+                }
+
+                operation
+            }
+        };
+
+        OperationExplanation {
+            operation,
+            annotation: node_borrow.annotation.clone(),
+            columns,
+            subnodes: node
+                .subnodes()
+                .iter()
+                .map(|subnode| self.explain_node(subnode, false, table_name, marker_name))
+                .collect(),
+        }
+    }
+
+    /// Return a [PlanExplanation] describing the tables
+    /// that are materialized when executing this plan.
+    ///
+    /// Tables loaded from the database are named using `table_name`
+    /// and columns are named using `marker_name`.
+    pub fn explain<TableName, MarkerName>(
+        &self,
+        table_name: TableName,
+        marker_name: MarkerName,
+    ) -> PlanExplanation
+    where
+        TableName: Fn(PermanentTableId) -> String,
+        MarkerName: Fn(&OperationColumnMarker) -> String,
+    {
+        // As in [ExecutionPlan::finalize], only tables required for permanent results are computed
+        let mut required = HashSet::new();
+        for out_node in &self.out_nodes {
+            if out_node.result.is_permanent() {
+                self.required_tables(&out_node.node, &mut required);
+            }
+        }
+
+        let tables = self
+            .out_nodes
+            .iter()
+            .filter(|out_node| required.contains(&out_node.node.id()))
+            .map(|out_node| TableExplanation {
+                id: out_node.node.id(),
+                operation_name: out_node.operation_name.clone(),
+                permanent_name: match &out_node.result {
+                    ExecutionResult::Temporary => None,
+                    ExecutionResult::Permanent(_, name) => Some(name.clone()),
+                },
+                root: self.explain_node(&out_node.node, true, &table_name, &marker_name),
+                rows: None,
+                time: None,
+            })
+            .collect();
+
+        PlanExplanation { tables }
+    }
+}
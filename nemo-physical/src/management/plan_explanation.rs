@@ -0,0 +1,270 @@
+//! This module defines [PlanExplanation],
+//! a human-readable description of an [ExecutionPlan][super::execution_plan::ExecutionPlan].
+
+use std::{collections::HashMap, fmt::Display, time::Duration};
+
+use crate::function::tree::{FunctionLeaf, FunctionTree};
+
+use super::database::{id::ExecutionId, ExecutionStatistics};
+
+/// Description of an operation within an [ExecutionPlan][super::execution_plan::ExecutionPlan]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationExplanation {
+    /// Description of the operation, e.g., `Join` or `Fetch p (step 2)`
+    pub operation: String,
+    /// Description attached to the node while building the plan,
+    /// e.g., the range of subtables that is fetched
+    pub annotation: Option<String>,
+    /// Names of the columns of the table represented by this node
+    pub columns: Vec<String>,
+    /// Inputs of the operation
+    pub subnodes: Vec<OperationExplanation>,
+}
+
+impl OperationExplanation {
+    fn ascii_tree(&self) -> ascii_tree::Tree {
+        let mut label = self.operation.clone();
+        if let Some(annotation) = &self.annotation {
+            label.push_str(&format!(" ({annotation})"));
+        }
+        if !self.columns.is_empty() {
+            label.push_str(&format!(" [{}]", self.columns.join(", ")));
+        }
+
+        if self.subnodes.is_empty() {
+            ascii_tree::Tree::Leaf(vec![label])
+        } else {
+            ascii_tree::Tree::Node(label, self.subnodes.iter().map(Self::ascii_tree).collect())
+        }
+    }
+}
+
+/// Description of a table that is materialized
+/// while executing an [ExecutionPlan][super::execution_plan::ExecutionPlan]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableExplanation {
+    /// Identifies the table within its plan
+    pub id: ExecutionId,
+    /// Name of the operation computing the table
+    pub operation_name: String,
+    /// Name of the table if it is stored permanently
+    pub permanent_name: Option<String>,
+    /// Operations computing the table
+    ///
+    /// Inputs that are materialized into their own tables
+    /// are referred to by their [ExecutionId] and not expanded again.
+    pub root: OperationExplanation,
+    /// Number of rows of the computed table, if the plan has been executed
+    pub rows: Option<usize>,
+    /// Time spent on computing the table, if the plan has been executed
+    pub time: Option<Duration>,
+}
+
+impl TableExplanation {
+    fn ascii_tree(&self) -> ascii_tree::Tree {
+        let mut label = format!("Table {}: {}", self.id, self.operation_name);
+        match &self.permanent_name {
+            Some(name) => label.push_str(&format!(" -> {name}")),
+            None => label.push_str(" (temporary)"),
+        }
+        if let Some(rows) = self.rows {
+            label.push_str(&format!(", rows: {rows}"));
+        }
+        if let Some(time) = self.time {
+            label.push_str(&format!(", time: {}ms", time.as_millis()));
+        }
+
+        ascii_tree::Tree::Node(label, vec![self.root.ascii_tree()])
+    }
+}
+
+/// Description of the tables computed by an [ExecutionPlan][super::execution_plan::ExecutionPlan],
+/// in the order in which they are listed in the plan
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlanExplanation {
+    /// Materialized tables
+    pub tables: Vec<TableExplanation>,
+}
+
+impl PlanExplanation {
+    /// Add the number of rows and the computation time of each table,
+    /// as obtained from [DatabaseInstance::execute_plan_with_statistics][super::database::DatabaseInstance::execute_plan_with_statistics].
+    pub fn add_statistics(&mut self, statistics: &HashMap<ExecutionId, ExecutionStatistics>) {
+        for table in &mut self.tables {
+            if let Some(statistics) = statistics.get(&table.id) {
+                table.rows = statistics.rows;
+                table.time = Some(statistics.time);
+            }
+        }
+    }
+}
+
+impl Display for PlanExplanation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for table in &self.tables {
+            ascii_tree::write_tree(f, &table.ascii_tree())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Return the name of a function from its debug representation,
+/// which starts with the name of the enum variant.
+fn function_name<Function: std::fmt::Debug>(function: &Function) -> String {
+    let debug = format!("{function:?}");
+
+    match debug.find(['(', ' ', '{']) {
+        Some(end) => debug[..end].to_string(),
+        None => debug,
+    }
+}
+
+/// Return a compact representation of a [FunctionTree],
+/// where references are displayed using the given function.
+pub(crate) fn explain_function<ReferenceType, ReferenceName>(
+    tree: &FunctionTree<ReferenceType>,
+    reference_name: &ReferenceName,
+) -> String
+where
+    ReferenceType: std::fmt::Debug + Clone,
+    ReferenceName: Fn(&ReferenceType) -> String,
+{
+    let parameters = |parameters: &[&FunctionTree<ReferenceType>]| {
+        parameters
+            .iter()
+            .map(|parameter| explain_function(parameter, reference_name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    match tree {
+        FunctionTree::Leaf(FunctionLeaf::Constant(constant)) => constant.to_string(),
+        FunctionTree::Leaf(FunctionLeaf::Reference(reference)) => reference_name(reference),
+        FunctionTree::Unary(function, sub) => {
+            format!("{}({})", function_name(function), parameters(&[sub]))
+        }
+        FunctionTree::Binary {
+            function,
+            left,
+            right,
+        } => format!(
+            "{}({})",
+            function_name(function),
+            parameters(&[left, right])
+        ),
+        FunctionTree::Ternary {
+            function,
+            first,
+            second,
+            third,
+        } => format!(
+            "{}({})",
+            function_name(function),
+            parameters(&[first, second, third])
+        ),
+        FunctionTree::Nary {
+            function,
+            parameters: nary_parameters,
+        } => format!(
+            "{}({})",
+            function_name(function),
+            parameters(&nary_parameters.iter().collect::<Vec<_>>())
+        ),
+        FunctionTree::External {
+            function,
+            parameters: external_parameters,
+        } => format!(
+            "{}({})",
+            function_name(function),
+            parameters(&external_parameters.iter().collect::<Vec<_>>())
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        datavalues::AnyDataValue,
+        function::tree::FunctionTree,
+        management::database::{id::ExecutionId, ExecutionStatistics},
+    };
+
+    use super::{explain_function, OperationExplanation, PlanExplanation, TableExplanation};
+
+    #[test]
+    fn explain_functions() {
+        let tree = FunctionTree::equals(
+            FunctionTree::numeric_addition(
+                FunctionTree::reference(0),
+                FunctionTree::constant(AnyDataValue::new_integer_from_i64(1)),
+            ),
+            FunctionTree::reference(1),
+        );
+
+        assert_eq!(
+            explain_function(&tree, &|reference: &usize| format!("?v{reference}")),
+            "Equals(NumericAddition(?v0, 1), ?v1)"
+        );
+    }
+
+    #[test]
+    fn display_explanation() {
+        let fetch = |name: &str| OperationExplanation {
+            operation: format!("Fetch {name}"),
+            annotation: None,
+            columns: vec![],
+            subnodes: vec![],
+        };
+
+        let mut explanation = PlanExplanation {
+            tables: vec![TableExplanation {
+                id: ExecutionId::default(),
+                operation_name: String::from("Head"),
+                permanent_name: Some(String::from("q (step 2)")),
+                root: OperationExplanation {
+                    operation: String::from("Union"),
+                    annotation: Some(String::from("p steps 0..2")),
+                    columns: vec![String::from("?x")],
+                    subnodes: vec![fetch("p (step 0)"), fetch("p (step 1)")],
+                },
+                rows: None,
+                time: None,
+            }],
+        };
+
+        explanation.add_statistics(
+            &[(
+                ExecutionId::default(),
+                ExecutionStatistics {
+                    rows: Some(3),
+                    ..Default::default()
+                },
+            )]
+            .into_iter()
+            .collect(),
+        );
+
+        let lines = explanation
+            .to_string()
+            .lines()
+            .map(|line| {
+                line.trim_start_matches(|c: char| !c.is_alphabetic())
+                    .to_string()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            lines,
+            vec![
+                format!(
+                    "Table {}: Head -> q (step 2), rows: 3, time: 0ms",
+                    ExecutionId::default()
+                ),
+                String::from("Union (p steps 0..2) [?x]"),
+                String::from("Fetch p (step 0)"),
+                String::from("Fetch p (step 1)"),
+            ]
+        );
+    }
+}
@@ -4,20 +4,9 @@ use ascii_tree::{write_tree, Tree};
 #[cfg(feature = "timing")]
 use howlong::*;
 use linked_hash_map::LinkedHashMap;
-use once_cell::sync::Lazy;
 #[cfg(not(feature = "timing"))]
 use std::time::Duration;
-use std::{
-    fmt,
-    str::FromStr,
-    sync::{Mutex, MutexGuard},
-};
-
-/// Global instance of the [TimedCode]
-static TIMECODE_INSTANCE: Lazy<Mutex<TimedCode>> = Lazy::new(|| {
-    let instance = TimedCode::new();
-    Mutex::new(instance)
-});
+use std::{fmt, str::FromStr};
 
 /// Represents a block of code that is timed
 #[derive(Default, Copy, Clone)]
@@ -135,11 +124,6 @@ impl TimedCode {
         }
     }
 
-    /// Return the global instance
-    pub fn instance() -> MutexGuard<'static, TimedCode> {
-        TIMECODE_INSTANCE.lock().unwrap()
-    }
-
     /// Return an iterator through the sub-nodes
     pub fn sub_nodes(&self) -> impl Iterator<Item = (&str, &TimedCode)> {
         self.subblocks.iter().map(|(k, v)| (k.as_str(), v))
@@ -156,6 +140,19 @@ impl TimedCode {
         self.subblocks.clear();
     }
 
+    /// Add the recorded timings of another [TimedCode] to this one,
+    /// including the timings of all subblocks
+    pub fn merge(&mut self, other: &TimedCode) {
+        self.info.total_system_time += other.info.total_system_time;
+        self.info.total_process_time += other.info.total_process_time;
+        self.info.total_thread_time += other.info.total_thread_time;
+        self.info.runs += other.info.runs;
+
+        for (name, block) in &other.subblocks {
+            self.subblocks.entry(name.clone()).or_default().merge(block);
+        }
+    }
+
     /// Navigate to a subblock (use forward slash to go multiple layers at once)
     pub fn sub(&mut self, name: &str) -> &mut TimedCode {
         if cfg!(test) || cfg!(not(feature = "timing")) {
@@ -1,8 +1,12 @@
 //! This module collects data structures and operations for tables.
 
 pub mod operations;
+pub mod statistics;
 pub mod trie;
 pub(crate) mod triescan;
 
 pub(crate) mod buffer;
 pub(crate) mod rowscan;
+
+#[cfg(feature = "arrow")]
+pub(crate) mod arrow;
@@ -0,0 +1,177 @@
+//! This module implements the conversion of a [Trie] into a [RecordBatch] of the Arrow format.
+
+use std::sync::Arc;
+
+use arrow_array::{
+    builder::{Float32Builder, Float64Builder, Int64Builder, StringBuilder},
+    ArrayRef, RecordBatch, RecordBatchOptions,
+};
+use arrow_schema::{DataType, Field, Schema};
+
+use crate::{
+    datatypes::{storage_type_name::STORAFE_TYPES, StorageTypeName, StorageValueT},
+    datavalues::AnyDataValue,
+    management::database::Dict,
+};
+
+use super::trie::Trie;
+
+/// Builder for a column of a [RecordBatch]
+enum ColumnBuilder {
+    /// Column that only contains integers
+    Integer(Int64Builder),
+    /// Column that only contains 32-bit floating point numbers
+    Float(Float32Builder),
+    /// Column that only contains 64-bit floating point numbers
+    Double(Float64Builder),
+    /// Column that contains values from the dictionary or values of different storage types,
+    /// which are all represented as strings
+    String(StringBuilder),
+}
+
+impl ColumnBuilder {
+    /// Create a [ColumnBuilder] for the given layer of a [Trie],
+    /// based on the storage types of the values in that layer.
+    fn new(trie: &Trie, layer: usize) -> Self {
+        let capacity = trie.num_rows();
+        let storage_types = STORAFE_TYPES
+            .iter()
+            .copied()
+            .filter(|&storage_type| !trie.is_empty_layer(layer, storage_type))
+            .collect::<Vec<_>>();
+
+        match storage_types.as_slice() {
+            [StorageTypeName::Int64] => Self::Integer(Int64Builder::with_capacity(capacity)),
+            [StorageTypeName::Float] => Self::Float(Float32Builder::with_capacity(capacity)),
+            [StorageTypeName::Double] => Self::Double(Float64Builder::with_capacity(capacity)),
+            _ => Self::String(StringBuilder::with_capacity(capacity, 0)),
+        }
+    }
+
+    /// Return the [DataType] of the built column.
+    fn data_type(&self) -> DataType {
+        match self {
+            Self::Integer(_) => DataType::Int64,
+            Self::Float(_) => DataType::Float32,
+            Self::Double(_) => DataType::Float64,
+            Self::String(_) => DataType::Utf8,
+        }
+    }
+
+    /// Finish building the column.
+    fn finish(self) -> ArrayRef {
+        match self {
+            Self::Integer(mut builder) => Arc::new(builder.finish()),
+            Self::Float(mut builder) => Arc::new(builder.finish()),
+            Self::Double(mut builder) => Arc::new(builder.finish()),
+            Self::String(mut builder) => Arc::new(builder.finish()),
+        }
+    }
+}
+
+/// Convert the given [Trie] into a [RecordBatch] with the given column names.
+///
+/// Columns that only contain integers or floating point numbers of one storage type
+/// are converted into columns of the corresponding numeric type.
+/// All other columns are converted into string columns,
+/// where the given function is used to represent each value as a string.
+pub(crate) fn trie_record_batch<Serializer>(
+    trie: &Trie,
+    dictionary: &Dict,
+    column_names: &[String],
+    serialize: Serializer,
+) -> RecordBatch
+where
+    Serializer: Fn(&AnyDataValue) -> String,
+{
+    debug_assert_eq!(trie.arity(), column_names.len());
+
+    let mut builders = (0..trie.arity())
+        .map(|layer| ColumnBuilder::new(trie, layer))
+        .collect::<Vec<_>>();
+
+    for row in trie.row_iterator() {
+        for (builder, value) in builders.iter_mut().zip(row) {
+            match (builder, value) {
+                (ColumnBuilder::Integer(builder), StorageValueT::Int64(value)) => {
+                    builder.append_value(value)
+                }
+                (ColumnBuilder::Float(builder), StorageValueT::Float(value)) => {
+                    builder.append_value(value.into())
+                }
+                (ColumnBuilder::Double(builder), StorageValueT::Double(value)) => {
+                    builder.append_value(value.into())
+                }
+                (ColumnBuilder::String(builder), value) => {
+                    let value = AnyDataValue::new_from_storage_value(value, dictionary)
+                        .expect("Values from tries should be sound.");
+                    builder.append_value(serialize(&value))
+                }
+                _ => unreachable!("column types are derived from the storage types of the layer"),
+            }
+        }
+    }
+
+    let fields = column_names
+        .iter()
+        .zip(builders.iter())
+        .map(|(name, builder)| Field::new(name, builder.data_type(), false))
+        .collect::<Vec<_>>();
+    let columns = builders.into_iter().map(ColumnBuilder::finish).collect();
+
+    RecordBatch::try_new_with_options(
+        Arc::new(Schema::new(fields)),
+        columns,
+        &RecordBatchOptions::new().with_row_count(Some(trie.num_rows())),
+    )
+    .expect("columns match the schema")
+}
+
+#[cfg(test)]
+mod test {
+    use arrow_array::{cast::AsArray, types::Int64Type};
+    use arrow_schema::DataType;
+
+    use crate::{
+        datatypes::{Double, StorageValueT},
+        datavalues::DataValue,
+        management::database::Dict,
+        tabular::trie::Trie,
+    };
+
+    use super::trie_record_batch;
+
+    #[test]
+    fn typed_columns() {
+        let double = |value| StorageValueT::Double(Double::new(value).unwrap());
+        let trie = Trie::from_rows(vec![
+            vec![
+                StorageValueT::Int64(1),
+                double(0.5),
+                StorageValueT::Int64(7),
+            ],
+            vec![StorageValueT::Int64(2), double(1.5), double(2.5)],
+        ]);
+        let names = ["a", "b", "c"].map(String::from);
+
+        let batch = trie_record_batch(&trie, &Dict::default(), &names, |value| {
+            value.lexical_value()
+        });
+
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.schema().field(0).name(), "a");
+        assert_eq!(batch.column(0).data_type(), &DataType::Int64);
+        assert_eq!(batch.column(1).data_type(), &DataType::Float64);
+        assert_eq!(batch.column(2).data_type(), &DataType::Utf8);
+
+        assert_eq!(
+            batch
+                .column(0)
+                .as_primitive::<Int64Type>()
+                .values()
+                .to_vec(),
+            vec![1, 2]
+        );
+        assert_eq!(batch.column(2).as_string::<i32>().value(0), "7");
+    }
+}
@@ -317,6 +317,11 @@ impl TupleBuffer {
         self.current_tuple.len()
     }
 
+    /// Returns the index of the column the next value will be written to.
+    pub(crate) fn current_column(&self) -> usize {
+        self.current_tuple_index
+    }
+
     /// Returns the number of rows in the [TupleBuffer]
     pub(crate) fn size(&self) -> usize {
         self.typed_subtables
@@ -246,6 +246,14 @@ where
     pub fn get<'a>(&'a self, marker: &ExternalMarker) -> Option<&'a OperationColumnMarker> {
         self.map.get(marker)
     }
+
+    /// Returns the external marker associated with the given [OperationColumnMarker].
+    pub fn find_external(&self, marker: &OperationColumnMarker) -> Option<&ExternalMarker> {
+        self.map
+            .iter()
+            .find(|(_, operation_marker)| *operation_marker == marker)
+            .map(|(external, _)| external)
+    }
 }
 
 /// Trait for objects that are able to generate [TrieScanEnum],
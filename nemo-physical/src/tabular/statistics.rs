@@ -0,0 +1,51 @@
+//! This module defines statistics about the contents of tables,
+//! which can be used to estimate the cost of operations on them.
+
+/// Statistics about one column of a table
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ColumnStatistics {
+    /// Number of distinct values in this column
+    pub num_distinct: usize,
+}
+
+/// Statistics about the contents of a table
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableStatistics {
+    /// Number of rows in the table
+    pub num_rows: usize,
+    /// Statistics for each column of the table
+    pub columns: Vec<ColumnStatistics>,
+}
+
+impl TableStatistics {
+    /// Create [TableStatistics] for an empty table with the given arity.
+    pub fn empty(arity: usize) -> Self {
+        Self {
+            num_rows: 0,
+            columns: vec![ColumnStatistics::default(); arity],
+        }
+    }
+
+    /// Return the arity of the table.
+    pub fn arity(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Combine the statistics of two tables with the same arity
+    /// into (an upper bound for) the statistics of their union.
+    pub fn union(&self, other: &Self) -> Self {
+        debug_assert!(self.arity() == other.arity());
+
+        let num_rows = self.num_rows + other.num_rows;
+        let columns = self
+            .columns
+            .iter()
+            .zip(other.columns.iter())
+            .map(|(left, right)| ColumnStatistics {
+                num_distinct: (left.num_distinct + right.num_distinct).min(num_rows),
+            })
+            .collect();
+
+        Self { num_rows, columns }
+    }
+}
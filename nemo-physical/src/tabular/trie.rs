@@ -18,7 +18,10 @@ use crate::{
         storage_type_name::{StorageTypeBitSet, STORAFE_TYPES},
         StorageTypeName, StorageValueT,
     },
-    management::bytesized::{sum_bytes, ByteSized},
+    management::{
+        bytesized::{sum_bytes, ByteSized},
+        execution_plan::ColumnOrder,
+    },
     tabular::{buffer::tuple_buffer::TupleBuffer, rowscan::RowScan},
     util::bitset::BitSet,
 };
@@ -27,6 +30,7 @@ use super::{
     buffer::sorted_tuple_buffer::SortedTupleBuffer,
     operations::{projectreorder::ProjectReordering, trim::TrieScanTrim},
     rowscan::Row,
+    statistics::{ColumnStatistics, TableStatistics},
     triescan::{PartialTrieScan, TrieScan, TrieScanEnum},
 };
 
@@ -69,6 +73,28 @@ impl Trie {
         self.num_rows() == 0
     }
 
+    /// Compute [TableStatistics] about the contents of this trie,
+    /// where the given [ColumnOrder] maps each column of the table to its layer in the trie.
+    pub fn statistics(&self, order: &ColumnOrder) -> TableStatistics {
+        TableStatistics {
+            num_rows: self.num_rows(),
+            columns: (0..self.arity())
+                .map(|column| {
+                    let layer = order.get(column);
+
+                    // Values of the first layer are already sorted and distinct
+                    let num_distinct = if layer == 0 {
+                        self.columns[layer].num_data()
+                    } else {
+                        self.columns[layer].num_distinct()
+                    };
+
+                    ColumnStatistics { num_distinct }
+                })
+                .collect(),
+        }
+    }
+
     /// Returns whether a column of a particular [StorageTypeName] contains no data values.
     pub(crate) fn is_empty_layer(&self, layer: usize, storage_type: StorageTypeName) -> bool {
         self.columns[layer].is_empty_typed(storage_type)
@@ -616,7 +642,11 @@ impl<'a> PartialTrieScan<'a> for TrieScanGeneric<'a> {
 mod test {
     use crate::{
         datatypes::{Float, StorageTypeName, StorageValueT},
-        tabular::triescan::{PartialTrieScan, TrieScanEnum},
+        management::execution_plan::ColumnOrder,
+        tabular::{
+            statistics::{ColumnStatistics, TableStatistics},
+            triescan::{PartialTrieScan, TrieScanEnum},
+        },
     };
 
     use super::{Trie, TrieScanGeneric};
@@ -896,4 +926,37 @@ mod test {
         ]));
         assert!(!trie.contains_row(&[StorageValueT::Id32(1)]));
     }
+
+    #[test]
+    fn trie_statistics() {
+        let trie = Trie::from_rows(vec![
+            vec![StorageValueT::Id32(1), StorageValueT::Id32(3)],
+            vec![StorageValueT::Id32(1), StorageValueT::Id32(4)],
+            vec![StorageValueT::Id32(2), StorageValueT::Id32(3)],
+            vec![StorageValueT::Int64(2), StorageValueT::Id32(3)],
+        ]);
+
+        assert_eq!(
+            trie.statistics(&ColumnOrder::default()),
+            TableStatistics {
+                num_rows: 4,
+                columns: vec![
+                    ColumnStatistics { num_distinct: 3 },
+                    ColumnStatistics { num_distinct: 2 },
+                ],
+            }
+        );
+
+        // Statistics are reported for the columns of the table, not for the layers of the trie
+        assert_eq!(
+            trie.statistics(&ColumnOrder::from_vector(vec![1, 0])),
+            TableStatistics {
+                num_rows: 4,
+                columns: vec![
+                    ColumnStatistics { num_distinct: 2 },
+                    ColumnStatistics { num_distinct: 3 },
+                ],
+            }
+        );
+    }
 }
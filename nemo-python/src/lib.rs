@@ -3,24 +3,43 @@
 
 use std::{
     collections::{HashMap, HashSet},
+    ffi::CString,
     fs::read_to_string,
+    sync::{Arc, Mutex},
     time::Duration,
 };
 
+use arrow_array::{
+    ffi_stream::{ArrowArrayStreamReader, FFI_ArrowArrayStream},
+    RecordBatch, RecordBatchIterator,
+};
+use arrow_schema::{DataType, Field, Schema};
+
 use nemo::{
     datavalues::{AnyDataValue, DataValue},
-    execution::{tracing::trace::ExecutionTraceTree, ExecutionEngine},
+    execution::{
+        external_predicates::ExternalEvaluationError, tracing::trace::ExecutionTraceTree,
+        BindingPattern, CancellationToken, ExecutionEngine, ExecutionParameters, ExecutionProgress,
+        ExternalPredicate, ExternalPredicateError, ExternalPredicates, ProgressCallback,
+    },
     io::{resource_providers::ResourceProviders, ExportManager, ImportManager},
     meta::timing::TimedCode,
     model::{
         chase_model::{ChaseAtom, ChaseFact},
-        ExportDirective, Identifier, Variable,
+        Atom, ExportDirective, Fact, Identifier, Literal, PrimitiveTerm, ProgramBuilder, Rule,
+        Term, Variable,
     },
 };
 
-use pyo3::{create_exception, exceptions::PyNotImplementedError, prelude::*, types::PyDict};
+use pyo3::{
+    create_exception,
+    exceptions::PyNotImplementedError,
+    prelude::*,
+    types::{PyBool, PyCapsule, PyDict, PyFloat, PyLong, PyString, PyTuple},
+};
 
 create_exception!(module, NemoError, pyo3::exceptions::PyException);
+create_exception!(module, NemoCancelled, NemoError);
 
 pub const RDF_LANG_STRING: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#langString";
 pub const XSD_STRING: &str = "http://www.w3.org/2001/XMLSchema#string";
@@ -69,6 +88,148 @@ impl NemoProgram {
             .map(|id| id.name())
             .collect()
     }
+
+    fn __repr__(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+#[pyclass]
+#[derive(Clone)]
+struct NemoTerm(Term);
+
+#[pymethods]
+impl NemoTerm {
+    #[staticmethod]
+    fn variable(name: String) -> Self {
+        NemoTerm(Term::Primitive(PrimitiveTerm::Variable(
+            Variable::Universal(name),
+        )))
+    }
+
+    #[staticmethod]
+    fn existential(name: String) -> Self {
+        NemoTerm(Term::Primitive(PrimitiveTerm::Variable(
+            Variable::Existential(name),
+        )))
+    }
+
+    #[staticmethod]
+    fn constant(value: &Bound<PyAny>) -> PyResult<Self> {
+        Ok(NemoTerm(Term::Primitive(PrimitiveTerm::GroundTerm(
+            python_to_datavalue(value)?,
+        ))))
+    }
+
+    fn __repr__(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+#[pyclass]
+#[derive(Clone)]
+struct NemoAtom(Atom);
+
+#[pymethods]
+impl NemoAtom {
+    /// Terms are either [NemoTerm]s or python values, which are used as constants.
+    #[new]
+    fn py_new(predicate: String, terms: Vec<Bound<PyAny>>) -> PyResult<Self> {
+        let terms = terms
+            .iter()
+            .map(|term| match term.downcast::<NemoTerm>() {
+                Ok(term) => Ok(term.borrow().0.clone()),
+                Err(_) => NemoTerm::constant(term).map(|term| term.0),
+            })
+            .collect::<PyResult<_>>()?;
+
+        Ok(NemoAtom(Atom::new(Identifier::from(predicate), terms)))
+    }
+
+    fn predicate(&self) -> String {
+        self.0.predicate().name()
+    }
+
+    fn terms(&self) -> Vec<NemoTerm> {
+        self.0.terms().iter().cloned().map(NemoTerm).collect()
+    }
+
+    fn __repr__(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+#[pyclass]
+#[derive(Clone)]
+struct NemoRule(Rule);
+
+#[pymethods]
+impl NemoRule {
+    #[new]
+    #[pyo3(signature=(head, body, negative=Vec::new()))]
+    fn py_new(head: Vec<NemoAtom>, body: Vec<NemoAtom>, negative: Vec<NemoAtom>) -> PyResult<Self> {
+        let head = head.into_iter().map(|atom| atom.0).collect();
+        let body = body
+            .into_iter()
+            .map(|atom| Literal::Positive(atom.0))
+            .chain(negative.into_iter().map(|atom| Literal::Negative(atom.0)))
+            .collect();
+
+        let rule = Rule::new_validated(head, body, Vec::new())
+            .map_err(|err| NemoError::new_err(err.to_string()))?;
+        Ok(NemoRule(rule))
+    }
+
+    fn __repr__(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+#[pyclass]
+struct NemoProgramBuilder(ProgramBuilder);
+
+#[pymethods]
+impl NemoProgramBuilder {
+    #[new]
+    fn py_new() -> Self {
+        NemoProgramBuilder(ProgramBuilder::new())
+    }
+
+    fn prefix(mut slf: PyRefMut<'_, Self>, prefix: String, iri: String) -> PyRefMut<'_, Self> {
+        slf.0 = std::mem::take(&mut slf.0).prefix(prefix, iri);
+        slf
+    }
+
+    fn rule(mut slf: PyRefMut<'_, Self>, rule: NemoRule) -> PyRefMut<'_, Self> {
+        slf.0 = std::mem::take(&mut slf.0).rule(rule.0);
+        slf
+    }
+
+    fn fact(mut slf: PyRefMut<'_, Self>, atom: NemoAtom) -> PyResult<PyRefMut<'_, Self>> {
+        let is_ground = atom
+            .0
+            .terms()
+            .iter()
+            .all(|term| matches!(term, Term::Primitive(term) if term.is_ground()));
+        if !is_ground {
+            return Err(NemoError::new_err(format!(
+                "fact {} must only contain constants",
+                atom.0
+            )));
+        }
+
+        slf.0 = std::mem::take(&mut slf.0).fact(Fact(atom.0));
+        Ok(slf)
+    }
+
+    fn output_predicate(mut slf: PyRefMut<'_, Self>, predicate: String) -> PyRefMut<'_, Self> {
+        slf.0 = std::mem::take(&mut slf.0).output_predicate(Identifier::from(predicate));
+        slf
+    }
+
+    fn build(&mut self) -> NemoProgram {
+        NemoProgram(std::mem::take(&mut self.0).build())
+    }
 }
 
 #[pyclass]
@@ -142,6 +303,130 @@ impl NemoLiteral {
     }
 }
 
+/// Convert a python value into a value of nemo.
+///
+/// Strings enclosed in `<` and `>` are IRIs and strings enclosed in double quotes
+/// are string literals in the format that is returned by [datavalue_to_python].
+/// All other strings are plain strings.
+fn python_to_datavalue(value: &Bound<PyAny>) -> PyResult<AnyDataValue> {
+    if value.is_instance_of::<PyBool>() {
+        return Ok(AnyDataValue::new_boolean(value.extract()?));
+    }
+    if value.is_instance_of::<PyLong>() {
+        return Ok(AnyDataValue::new_integer_from_i64(value.extract()?));
+    }
+    if value.is_instance_of::<PyFloat>() {
+        return AnyDataValue::new_double_from_f64(value.extract()?)
+            .map_err(|err| NemoError::new_err(err.to_string()));
+    }
+    if value.is_instance_of::<PyString>() {
+        let string: String = value.extract()?;
+
+        if let Some(iri) = string
+            .strip_prefix('<')
+            .and_then(|rest| rest.strip_suffix('>'))
+        {
+            return Ok(AnyDataValue::new_iri(iri.to_string()));
+        }
+        if let Some(quoted) = string
+            .strip_prefix('"')
+            .and_then(|rest| rest.strip_suffix('"'))
+        {
+            return Ok(AnyDataValue::new_plain_string(unescape_string(quoted)));
+        }
+
+        return Ok(AnyDataValue::new_plain_string(string));
+    }
+    if let Ok(literal) = value.downcast::<NemoLiteral>() {
+        let literal = literal.borrow();
+        return match &literal.language {
+            Some(language) => Ok(AnyDataValue::new_language_tagged_string(
+                literal.value.clone(),
+                language.clone(),
+            )),
+            None => AnyDataValue::new_from_typed_literal(
+                literal.value.clone(),
+                literal.datatype.clone(),
+            )
+            .map_err(|err| NemoError::new_err(err.to_string())),
+        };
+    }
+
+    Err(NemoError::new_err(format!(
+        "values of type {} are not supported",
+        value.get_type().name()?
+    )))
+}
+
+/// Undo the escaping of quotes, line breaks and backslashes in string literals.
+fn unescape_string(quoted: &str) -> String {
+    let mut result = String::with_capacity(quoted.len());
+    let mut characters = quoted.chars();
+
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            result.push(character);
+            continue;
+        }
+
+        match characters.next() {
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+
+    result
+}
+
+/// External predicate that is implemented by a python callable
+///
+/// The callable is called with the values of the input positions as arguments
+/// and returns an iterable of sequences of values for the output positions.
+struct PythonPredicate {
+    pattern: BindingPattern,
+    function: PyObject,
+}
+
+impl std::fmt::Debug for PythonPredicate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PythonPredicate")
+            .field("pattern", &self.pattern)
+            .finish()
+    }
+}
+
+impl ExternalPredicate for PythonPredicate {
+    fn binding_pattern(&self) -> BindingPattern {
+        self.pattern.clone()
+    }
+
+    fn evaluate(
+        &self,
+        inputs: &[AnyDataValue],
+    ) -> Result<Vec<Vec<AnyDataValue>>, ExternalEvaluationError> {
+        Python::with_gil(|py| {
+            let arguments = inputs
+                .iter()
+                .map(|value| datavalue_to_python(py, value.clone()))
+                .collect::<PyResult<Vec<_>>>()?;
+            let result = self.function.call1(py, PyTuple::new_bound(py, arguments))?;
+
+            result
+                .bind(py)
+                .iter()?
+                .map(|row| {
+                    row?.iter()?
+                        .map(|value| python_to_datavalue(&value?))
+                        .collect::<PyResult<Vec<_>>>()
+                })
+                .collect::<PyResult<Vec<_>>>()
+        })
+        .map_err(Into::into)
+    }
+}
+
 #[pyclass]
 struct NemoResults(Box<dyn Iterator<Item = Vec<AnyDataValue>> + Send>);
 
@@ -307,6 +592,134 @@ impl NemoResults {
     }
 }
 
+/// Name of capsules that contain Arrow streams, as required by the Arrow PyCapsule interface
+const ARROW_STREAM_CAPSULE: &str = "arrow_array_stream";
+
+/// Facts of a predicate in the Arrow format
+///
+/// Implements the Arrow PyCapsule interface for streams,
+/// so that the facts can be read by pyarrow, polars and other libraries without copying.
+#[pyclass]
+struct NemoArrowTable(RecordBatch);
+
+#[pymethods]
+impl NemoArrowTable {
+    #[getter]
+    fn num_rows(&self) -> usize {
+        self.0.num_rows()
+    }
+
+    #[getter]
+    fn column_names(&self) -> Vec<String> {
+        self.0
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| field.name().clone())
+            .collect()
+    }
+
+    #[pyo3(signature=(requested_schema=None))]
+    fn __arrow_c_stream__<'py>(
+        &self,
+        py: Python<'py>,
+        requested_schema: Option<PyObject>,
+    ) -> PyResult<Bound<'py, PyCapsule>> {
+        // The schema of the table is fixed, so requested schemas are ignored
+        let _ = requested_schema;
+
+        let reader = RecordBatchIterator::new(vec![Ok(self.0.clone())], self.0.schema());
+        let stream = FFI_ArrowArrayStream::new(Box::new(reader));
+
+        PyCapsule::new_bound(py, stream, Some(CString::new(ARROW_STREAM_CAPSULE)?))
+    }
+
+    /// Convert the table into a pandas `DataFrame`, which requires pyarrow.
+    fn to_pandas(slf: &Bound<Self>) -> PyResult<PyObject> {
+        let table = slf
+            .py()
+            .import_bound("pyarrow")?
+            .call_method1("table", (slf,))?;
+        Ok(table.call_method0("to_pandas")?.unbind())
+    }
+}
+
+/// Read all batches from an object that implements the Arrow PyCapsule interface for streams.
+fn arrow_batches(data: &Bound<PyAny>) -> PyResult<Vec<RecordBatch>> {
+    let capsule = data.call_method0("__arrow_c_stream__")?;
+    let capsule = capsule.downcast::<PyCapsule>()?;
+
+    let name = capsule.name()?.map(|name| name.to_string_lossy());
+    if name.as_deref() != Some(ARROW_STREAM_CAPSULE) {
+        return Err(NemoError::new_err(format!(
+            "expected a capsule named {ARROW_STREAM_CAPSULE}"
+        )));
+    }
+
+    // The stream is moved out of the capsule, which is left with a released stream
+    let stream =
+        unsafe { FFI_ArrowArrayStream::from_raw(capsule.pointer() as *mut FFI_ArrowArrayStream) };
+    let reader = ArrowArrayStreamReader::try_new(stream)
+        .map_err(|err| NemoError::new_err(err.to_string()))?;
+
+    reader
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| NemoError::new_err(err.to_string()))
+}
+
+/// Flag that cancels the reasoning of a [NemoEngine]
+///
+/// The token can be cancelled from another thread while the engine is reasoning.
+#[pyclass]
+#[derive(Clone)]
+struct NemoCancellationToken(CancellationToken);
+
+#[pymethods]
+impl NemoCancellationToken {
+    fn cancel(&self) {
+        self.0.cancel()
+    }
+
+    #[getter]
+    fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled()
+    }
+}
+
+/// Progress of reasoning that is passed to the progress callback of [NemoEngine::reason]
+#[pyclass]
+#[derive(Debug, Clone)]
+struct NemoProgress {
+    #[pyo3(get)]
+    step: usize,
+    #[pyo3(get)]
+    rule: usize,
+    #[pyo3(get)]
+    derived: bool,
+}
+
+#[pymethods]
+impl NemoProgress {
+    fn __repr__(&self) -> String {
+        format!(
+            "NemoProgress(step = {}, rule = {}, derived = {})",
+            self.step,
+            self.rule,
+            if self.derived { "True" } else { "False" }
+        )
+    }
+}
+
+impl From<&ExecutionProgress> for NemoProgress {
+    fn from(progress: &ExecutionProgress) -> Self {
+        NemoProgress {
+            step: progress.step,
+            rule: progress.rule,
+            derived: progress.derived,
+        }
+    }
+}
+
 #[pyclass(unsendable)]
 struct NemoEngine {
     program: NemoProgram,
@@ -321,6 +734,32 @@ struct NemoTiming {
     inner: TimedCode,
 }
 
+#[pyclass]
+#[derive(Debug, Clone)]
+struct NemoRuleStatistics {
+    #[pyo3(get)]
+    rule: usize,
+    #[pyo3(get)]
+    applications: usize,
+    #[pyo3(get)]
+    productive_applications: usize,
+    #[pyo3(get)]
+    time: Duration,
+}
+
+#[pymethods]
+impl NemoRuleStatistics {
+    fn __repr__(&self) -> String {
+        format!(
+            "NemoRuleStatistics(rule = {}, applications = {}, productive_applications = {}, time = {}ms)",
+            self.rule,
+            self.applications,
+            self.productive_applications,
+            self.time.as_millis()
+        )
+    }
+}
+
 #[pymethods]
 impl NemoTiming {
     #[getter]
@@ -380,22 +819,128 @@ impl NemoTiming {
 #[pymethods]
 impl NemoEngine {
     #[new]
-    fn py_new(program: NemoProgram) -> PyResult<Self> {
-        TimedCode::instance().reset();
+    #[pyo3(signature=(program, external=None))]
+    fn py_new(
+        program: NemoProgram,
+        external: Option<HashMap<String, (String, PyObject)>>,
+    ) -> PyResult<Self> {
+        let mut external_predicates = ExternalPredicates::new();
+        for (name, (pattern, function)) in external.unwrap_or_default() {
+            let pattern = pattern
+                .parse()
+                .map_err(|err: ExternalPredicateError| NemoError::new_err(err.to_string()))?;
+            external_predicates
+                .register(name, PythonPredicate { pattern, function })
+                .map_err(|err| NemoError::new_err(err.to_string()))?;
+        }
+        let parameters = ExecutionParameters {
+            external_predicates,
+            ..Default::default()
+        };
+
         let import_manager = ImportManager::new(ResourceProviders::default());
-        let engine = ExecutionEngine::initialize(&program.0, import_manager).py_res()?;
+        let engine =
+            ExecutionEngine::initialize_with_parameters(&program.0, import_manager, parameters)
+                .py_res()?;
         Ok(NemoEngine { program, engine })
     }
 
-    fn reason(&mut self) -> PyResult<()> {
-        TimedCode::instance().start();
-        TimedCode::instance().sub("Reasoning").start();
+    /// Add facts for a predicate of the program from an iterable of sequences of values.
+    ///
+    /// Facts can only be added before reasoning.
+    fn add_facts(&mut self, predicate: String, facts: &Bound<PyAny>) -> PyResult<()> {
+        let rows = facts
+            .iter()?
+            .map(|row| {
+                row?.iter()?
+                    .map(|value| python_to_datavalue(&value?))
+                    .collect::<PyResult<Vec<_>>>()
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+
+        self.engine
+            .add_facts(&Identifier::from(predicate), rows)
+            .py_res()
+    }
+
+    /// Add facts for a predicate of the program from an object that implements
+    /// the Arrow PyCapsule interface for streams, such as tables of pyarrow or polars.
+    ///
+    /// Facts can only be added before reasoning.
+    fn add_arrow_facts(&mut self, predicate: String, data: &Bound<PyAny>) -> PyResult<()> {
+        let batches = arrow_batches(data)?;
 
-        self.engine.execute().py_res()?;
+        self.engine
+            .add_record_batches(&Identifier::from(predicate), batches)
+            .py_res()
+    }
 
-        TimedCode::instance().sub("Reasoning").stop();
-        TimedCode::instance().stop();
-        Ok(())
+    /// Return a token that cancels reasoning from another thread.
+    fn cancellation_token(&self) -> NemoCancellationToken {
+        NemoCancellationToken(self.engine.cancellation_token())
+    }
+
+    /// Run the program without holding the GIL.
+    ///
+    /// Between rule applications, pending signals are handled
+    /// and the optional `progress` callback is called with a [NemoProgress].
+    /// If either raises an exception, reasoning stops and the exception is raised again.
+    #[pyo3(signature=(progress=None))]
+    fn reason(&mut self, py: Python<'_>, progress: Option<PyObject>) -> PyResult<()> {
+        let token = self.engine.cancellation_token();
+        let interruption = Arc::new(Mutex::new(None::<PyErr>));
+
+        let callback = {
+            let token = token.clone();
+            let interruption = interruption.clone();
+
+            ProgressCallback::new(move |state| {
+                let result = Python::with_gil(|py| {
+                    py.check_signals()?;
+                    if let Some(progress) = &progress {
+                        progress.call1(py, (NemoProgress::from(state),))?;
+                    }
+                    Ok(())
+                });
+
+                if let Err(err) = result {
+                    *interruption.lock().expect("lock is not poisoned") = Some(err);
+                    token.cancel();
+                }
+            })
+        };
+        self.engine.set_progress_callback(Some(callback));
+
+        self.engine.timing_mut().start();
+        self.engine.timing_mut().sub("Reasoning").start();
+
+        // Errors of the engine are not `Send`, so they are converted to messages before the GIL is acquired again
+        let engine = &mut self.engine;
+        let result = py.allow_threads(move || {
+            engine.execute().map_err(|err| match err {
+                nemo::error::Error::Cancelled => None,
+                err => Some(err.to_string()),
+            })
+        });
+
+        self.engine.timing_mut().sub("Reasoning").stop();
+        self.engine.timing_mut().stop();
+
+        self.engine.set_progress_callback(None);
+
+        if let Some(err) = interruption.lock().expect("lock is not poisoned").take() {
+            token.reset();
+            return Err(err);
+        }
+
+        match result {
+            Ok(()) => Ok(()),
+            Err(None) => {
+                token.reset();
+                Err(NemoCancelled::new_err("reasoning was cancelled"))
+            }
+            Err(Some(message)) => Err(NemoError::new_err(message)),
+        }
     }
 
     fn trace(&mut self, fact: String) -> Option<NemoTrace> {
@@ -411,10 +956,39 @@ impl NemoEngine {
     fn timing(&self) -> NemoTiming {
         NemoTiming {
             name: "root".into(),
-            inner: TimedCode::instance().clone(),
+            inner: self.engine.timing().clone(),
         }
     }
 
+    /// Return statistics about the applications of each rule.
+    fn rule_statistics(&self) -> Vec<NemoRuleStatistics> {
+        self.engine
+            .instrumentation()
+            .rules()
+            .iter()
+            .enumerate()
+            .map(|(rule, statistics)| NemoRuleStatistics {
+                rule,
+                applications: statistics.applications,
+                productive_applications: statistics.productive_applications,
+                time: statistics.time,
+            })
+            .collect()
+    }
+
+    /// Return pairs of execution steps and the number of bytes
+    /// consumed by all tables after that step.
+    ///
+    /// Memory is only measured every few steps.
+    fn memory_snapshots(&self) -> Vec<(usize, u64)> {
+        self.engine
+            .instrumentation()
+            .memory_snapshots()
+            .iter()
+            .map(|snapshot| (snapshot.step, snapshot.memory.as_u64()))
+            .collect()
+    }
+
     fn write_result(
         &mut self,
         predicate: String,
@@ -439,6 +1013,23 @@ impl NemoEngine {
         Ok(())
     }
 
+    fn arrow_result(&mut self, predicate: String) -> PyResult<NemoArrowTable> {
+        let identifier = Identifier::from(predicate);
+
+        if let Some(batch) = self.engine.predicate_record_batch(&identifier).py_res()? {
+            return Ok(NemoArrowTable(batch));
+        }
+
+        let arity = self.engine.predicate_arity(&identifier).unwrap_or_default();
+        let fields = (0..arity)
+            .map(|column| Field::new(column.to_string(), DataType::Utf8, false))
+            .collect::<Vec<_>>();
+
+        Ok(NemoArrowTable(RecordBatch::new_empty(Arc::new(
+            Schema::new(fields),
+        ))))
+    }
+
     fn result(mut slf: PyRefMut<'_, Self>, predicate: String) -> PyResult<Py<NemoResults>> {
         let iter = slf
             .engine
@@ -460,6 +1051,16 @@ fn nmo_python(_py: Python, m: &Bound<PyModule>) -> PyResult<()> {
     m.add_class::<NemoResults>()?;
     m.add_class::<NemoOutputManager>()?;
     m.add_class::<NemoLiteral>()?;
+    m.add_class::<NemoTerm>()?;
+    m.add_class::<NemoAtom>()?;
+    m.add_class::<NemoRule>()?;
+    m.add_class::<NemoProgramBuilder>()?;
+    m.add_class::<NemoArrowTable>()?;
+    m.add_class::<NemoCancellationToken>()?;
+    m.add_class::<NemoProgress>()?;
+    m.add_class::<NemoRuleStatistics>()?;
+    m.add("NemoError", m.py().get_type_bound::<NemoError>())?;
+    m.add("NemoCancelled", m.py().get_type_bound::<NemoCancelled>())?;
     m.add_function(wrap_pyfunction!(load_file, m)?)?;
     m.add_function(wrap_pyfunction!(load_string, m)?)?;
     Ok(())
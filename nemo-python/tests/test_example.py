@@ -9,6 +9,11 @@ from nmo_python import (
     NemoEngine,
     NemoOutputManager,
     NemoLiteral,
+    NemoTerm,
+    NemoAtom,
+    NemoRule,
+    NemoProgramBuilder,
+    NemoCancelled,
 )
 
 
@@ -115,6 +120,186 @@ class TestExample(unittest.TestCase):
 
         self.assertEqual(names, expected_names)
 
+    def test_timing_per_engine(self):
+        other = NemoEngine(load_string("copy(?x) :- data(?x) . data(1) ."))
+        other.reason()
+
+        names = [node.name for node in self.engine.timing().subnode("Reasoning/Rules").subnodes]
+        self.assertEqual(names, ["Rule 0", "Rule 1", "Rule 2"])
+
+    def test_rule_statistics(self):
+        statistics = self.engine.rule_statistics()
+
+        self.assertEqual([rule.rule for rule in statistics], [0, 1, 2])
+        self.assertTrue(all(rule.applications >= rule.productive_applications for rule in statistics))
+        self.assertTrue(any(rule.productive_applications > 0 for rule in statistics))
+        self.assertTrue(self.engine.memory_snapshots())
+
+
+class TestExternalPredicates(unittest.TestCase):
+    def test_external_predicate(self):
+        rules = """
+        number(12) .
+        divisor(?x, ?y) :- number(?x), divides(?x, ?y) .
+        """
+
+        def divides(number):
+            return [(divisor,) for divisor in range(1, number + 1) if number % divisor == 0]
+
+        engine = NemoEngine(load_string(rules), external={"divides": ("bf", divides)})
+        engine.reason()
+
+        result = sorted(engine.result("divisor"))
+        self.assertEqual(result, [[12, 1], [12, 2], [12, 3], [12, 4], [12, 6], [12, 12]])
+
+    def test_failing_external_predicate(self):
+        rules = """
+        number(12) .
+        divisor(?x, ?y) :- number(?x), divides(?x, ?y) .
+        """
+
+        def divides(number):
+            raise ValueError("no divisors")
+
+        engine = NemoEngine(load_string(rules), external={"divides": ("bf", divides)})
+        with self.assertRaises(Exception):
+            engine.reason()
+
+
+class TestProgramBuilder(unittest.TestCase):
+    def setUp(self):
+        x, y, z = NemoTerm.variable("x"), NemoTerm.variable("y"), NemoTerm.variable("z")
+
+        self.program = (
+            NemoProgramBuilder()
+            .fact(NemoAtom("edge", ["<a>", "<b>"]))
+            .rule(NemoRule([NemoAtom("path", [x, y])], [NemoAtom("edge", [x, y])]))
+            .rule(
+                NemoRule(
+                    [NemoAtom("path", [x, z])],
+                    [NemoAtom("path", [x, y]), NemoAtom("edge", [y, z])],
+                )
+            )
+            .output_predicate("path")
+            .build()
+        )
+
+    def test_build_program(self):
+        self.assertEqual(self.program.output_predicates(), ["path"])
+
+        engine = NemoEngine(self.program)
+        engine.reason()
+
+        self.assertEqual(list(engine.result("path")), [["<a>", "<b>"]])
+
+    def test_add_facts(self):
+        engine = NemoEngine(self.program)
+        engine.add_facts("edge", (("<b>", "<c>"), ["<c>", "<d>"]))
+        engine.reason()
+
+        result = sorted(engine.result("path"))
+        expected = [
+            ["<a>", "<b>"],
+            ["<a>", "<c>"],
+            ["<a>", "<d>"],
+            ["<b>", "<c>"],
+            ["<b>", "<d>"],
+            ["<c>", "<d>"],
+        ]
+        self.assertEqual(result, expected)
+
+    def test_add_facts_of_values(self):
+        program = load_string("copy(?x, ?y) :- data(?x, ?y) .")
+
+        engine = NemoEngine(program)
+        engine.add_facts("data", [(1, 2.5), ("hi", NemoLiteral("hello", lang="en"))])
+        engine.reason()
+
+        result = list(engine.result("copy"))
+        self.assertIn([1, 2.5], result)
+        self.assertIn(['"hi"', NemoLiteral("hello", lang="en")], result)
+
+    def test_invalid_facts(self):
+        engine = NemoEngine(self.program)
+
+        with self.assertRaises(Exception):
+            engine.add_facts("edge", [("<a>",)])
+        with self.assertRaises(Exception):
+            engine.add_facts("unknown", [("<a>",)])
+        with self.assertRaises(Exception):
+            NemoProgramBuilder().fact(NemoAtom("edge", [NemoTerm.variable("x")]))
+
+
+class TestArrow(unittest.TestCase):
+    def setUp(self):
+        rules = """
+        data(1, 2.5) .
+        data(2, 3.5) .
+        copy(?x, ?y) :- data(?x, ?y) .
+        """
+
+        self.engine = NemoEngine(load_string(rules))
+        self.engine.reason()
+
+    def test_arrow_result(self):
+        table = self.engine.arrow_result("copy")
+
+        self.assertEqual(table.num_rows, 2)
+        self.assertEqual(table.column_names, ["0", "1"])
+
+    def test_arrow_round_trip(self):
+        table = self.engine.arrow_result("copy")
+
+        engine = NemoEngine(load_string("result(?y, ?x) :- input(?x, ?y) ."))
+        engine.add_arrow_facts("input", table)
+        engine.reason()
+
+        self.assertEqual(sorted(engine.result("result")), [[2.5, 1], [3.5, 2]])
+
+    def test_empty_arrow_result(self):
+        engine = NemoEngine(load_string("copy(?x, ?y) :- data(?x, ?y) ."))
+        engine.reason()
+
+        table = engine.arrow_result("copy")
+        self.assertEqual(table.num_rows, 0)
+        self.assertEqual(table.column_names, ["0", "1"])
+
+
+class TestReasoningControl(unittest.TestCase):
+    def setUp(self):
+        rules = """
+        number(0) .
+        next(?x, !y) :- number(?x) .
+        number(?y) :- next(?x, ?y) .
+        """
+
+        self.engine = NemoEngine(load_string(rules))
+
+    def test_progress(self):
+        engine = NemoEngine(load_string("data(1). copy(?x) :- data(?x) ."))
+        steps = []
+        engine.reason(progress=lambda progress: steps.append((progress.rule, progress.derived)))
+
+        self.assertIn((0, True), steps)
+
+    def test_cancel(self):
+        token = self.engine.cancellation_token()
+
+        def progress(progress):
+            if progress.step >= 10:
+                token.cancel()
+
+        with self.assertRaises(NemoCancelled):
+            self.engine.reason(progress=progress)
+        self.assertFalse(token.is_cancelled)
+
+    def test_failing_progress(self):
+        def progress(progress):
+            raise ValueError("stop")
+
+        with self.assertRaises(ValueError):
+            self.engine.reason(progress=progress)
+
 
 if __name__ == "__main__":
     unittest.main(verbosity=2)
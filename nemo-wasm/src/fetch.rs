@@ -0,0 +1,223 @@
+//! Resolving `http:` and `https:` resources with the Fetch API.
+//!
+//! Reasoning runs synchronously, whereas the Fetch API is asynchronous.
+//! Resources are therefore fetched in a separate phase before the engine is created,
+//! see [fetch_resources], and are then served from memory by a [FetchedResourceProvider].
+
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use js_sys::{ArrayBuffer, Function, Object, Promise, Reflect, Uint8Array};
+use nemo::io::compression_format::CompressionFormat;
+use nemo::io::resource_providers::ResourceProvider;
+use nemo_physical::error::ReadingError;
+use nemo_physical::resource::Resource;
+use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::Response;
+
+use crate::{NemoError, WasmOrInternalNemoError};
+
+/// Resolves resources from their contents, which have been fetched beforehand.
+#[derive(Debug, Default)]
+pub struct FetchedResourceProvider {
+    resources: HashMap<Resource, Vec<u8>>,
+}
+
+impl FetchedResourceProvider {
+    pub fn new(resources: HashMap<Resource, Vec<u8>>) -> Self {
+        Self { resources }
+    }
+}
+
+impl ResourceProvider for FetchedResourceProvider {
+    fn open_resource(
+        &self,
+        resource: &Resource,
+        compression: CompressionFormat,
+    ) -> Result<Option<Box<dyn std::io::BufRead + Send>>, ReadingError> {
+        match self.resources.get(resource) {
+            Some(content) => decompress(resource, compression, content.clone()).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Decompress the content of a resource that has been transferred to the WebAssembly.
+///
+/// We currently do this in Rust rather than in JavaScript to reuse Nemo's compression logic.
+/// We could also to this on the JavaScript side, see https://developer.mozilla.org/en-US/docs/Web/API/Compression_Streams_API .
+pub(crate) fn decompress(
+    resource: &Resource,
+    compression: CompressionFormat,
+    content: Vec<u8>,
+) -> Result<Box<dyn std::io::BufRead + Send>, ReadingError> {
+    compression
+        .try_decompression(Cursor::new(content))
+        .ok_or_else(|| ReadingError::Decompression {
+            resource: resource.to_owned(),
+            decompression_format: compression.to_string(),
+        })
+}
+
+/// Return whether the given resource should be fetched with the Fetch API.
+fn is_fetchable(resource: &str) -> bool {
+    resource.starts_with("http:") || resource.starts_with("https:")
+}
+
+/// Describe a value that a promise was rejected with.
+fn rejection_reason(value: JsValue) -> String {
+    match value.dyn_ref::<js_sys::Error>() {
+        Some(error) => String::from(error.message()),
+        None => format!("{value:?}"),
+    }
+}
+
+fn fetch_error(resource: &str, reason: String) -> NemoError {
+    NemoError(WasmOrInternalNemoError::FetchFailure {
+        resource: resource.to_string(),
+        reason,
+    })
+}
+
+/// Fetch the contents of all `http:` and `https:` resources among the given ones,
+/// e.g., the result of `NemoProgram.getResourcesUsedInImports()`.
+///
+/// Other resources are skipped.
+/// Returns an object that maps the fetched resources to their contents as `Uint8Array`s,
+/// which can be passed to the `NemoEngine` constructor, possibly together with `Blob`s for the remaining resources.
+///
+/// `fetchFunction` is used instead of the global `fetch` if given.
+/// It is called with the resource and must return a (promise of a) `Response`.
+#[wasm_bindgen(js_name = "fetchResources")]
+pub async fn fetch_resources(
+    resources: JsValue,
+    fetch_function: Option<Function>,
+) -> Result<Object, NemoError> {
+    let fetch_function = match fetch_function {
+        Some(function) => function,
+        None => Reflect::get(&js_sys::global(), &JsValue::from("fetch"))
+            .and_then(JsCast::dyn_into)
+            .map_err(WasmOrInternalNemoError::ReflectionError)
+            .map_err(NemoError)?,
+    };
+
+    let resources = js_sys::try_iter(&resources)
+        .map_err(WasmOrInternalNemoError::ReflectionError)
+        .map_err(NemoError)?
+        .ok_or_else(|| {
+            NemoError(WasmOrInternalNemoError::ReflectionError(JsValue::from(
+                "resources are not iterable",
+            )))
+        })?;
+
+    // Start all requests before waiting for any of them
+    let mut requests = Vec::new();
+    for resource in resources {
+        let resource = resource
+            .map_err(WasmOrInternalNemoError::ReflectionError)
+            .map_err(NemoError)?;
+        let Some(resource) = resource.as_string() else {
+            continue;
+        };
+        if !is_fetchable(&resource) {
+            continue;
+        }
+
+        let response = fetch_function
+            .call1(&JsValue::undefined(), &JsValue::from(&resource))
+            .map_err(|value| fetch_error(&resource, rejection_reason(value)))?;
+        requests.push((resource, JsFuture::from(Promise::resolve(&response))));
+    }
+
+    let contents = Object::new();
+    for (resource, request) in requests {
+        let response: Response = request
+            .await
+            .and_then(JsCast::dyn_into)
+            .map_err(|value| fetch_error(&resource, rejection_reason(value)))?;
+
+        if !response.ok() {
+            return Err(fetch_error(
+                &resource,
+                format!("server responded with status {}", response.status()),
+            ));
+        }
+
+        let buffer: ArrayBuffer = match response.array_buffer() {
+            Ok(promise) => JsFuture::from(promise).await.and_then(JsCast::dyn_into),
+            Err(value) => Err(value),
+        }
+        .map_err(|value| fetch_error(&resource, rejection_reason(value)))?;
+
+        Reflect::set(
+            &contents,
+            &JsValue::from(&resource),
+            &Uint8Array::new(&buffer),
+        )
+        .map_err(WasmOrInternalNemoError::ReflectionError)
+        .map_err(NemoError)?;
+    }
+
+    Ok(contents)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use std::io::Read;
+
+    use nemo::io::compression_format::CompressionFormat;
+    use nemo::io::resource_providers::ResourceProvider;
+    use nemo_physical::error::ReadingError;
+
+    use super::FetchedResourceProvider;
+
+    const RESOURCE: &str = "https://example.org/data.csv";
+
+    /// `a,b\n` compressed with gzip
+    const GZIPPED: [u8; 24] = [
+        0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0x4b, 0xd4, 0x49, 0xe2, 0x02,
+        0x00, 0xc5, 0x10, 0x97, 0x24, 0x04, 0x00, 0x00, 0x00,
+    ];
+
+    fn provider(content: &[u8]) -> FetchedResourceProvider {
+        FetchedResourceProvider::new(HashMap::from([(RESOURCE.to_string(), content.to_vec())]))
+    }
+
+    fn read(provider: &FetchedResourceProvider, compression: CompressionFormat) -> String {
+        let mut content = String::new();
+        provider
+            .open_resource(&RESOURCE.to_string(), compression)
+            .expect("resource can be opened")
+            .expect("resource is known")
+            .read_to_string(&mut content)
+            .expect("content is valid");
+        content
+    }
+
+    #[test]
+    fn serves_fetched_resources() {
+        let provider = provider(b"a,b\n");
+
+        assert_eq!(read(&provider, CompressionFormat::None), "a,b\n");
+        assert!(provider
+            .open_resource(
+                &"https://example.org/other.csv".to_string(),
+                CompressionFormat::None
+            )
+            .expect("unknown resources are no error")
+            .is_none());
+    }
+
+    #[test]
+    fn decompresses_fetched_resources() {
+        assert_eq!(read(&provider(&GZIPPED), CompressionFormat::Gzip), "a,b\n");
+
+        assert!(matches!(
+            provider(b"a,b\n").open_resource(&RESOURCE.to_string(), CompressionFormat::Gzip),
+            Err(ReadingError::Decompression { .. })
+        ));
+    }
+}
@@ -3,14 +3,16 @@
 use std::alloc::Layout;
 use std::collections::HashMap;
 use std::fmt::Formatter;
-use std::io::Cursor;
 
 use js_sys::Array;
+use js_sys::ArrayBuffer;
 use js_sys::Reflect;
 use js_sys::Set;
 use js_sys::Uint8Array;
 use nemo::execution::tracing::trace::ExecutionTraceTree;
 use nemo::execution::ExecutionEngine;
+use nemo::execution::ExecutionLimits;
+use nemo::execution::ExecutionStatus;
 
 use nemo::io::compression_format::CompressionFormat;
 use nemo::io::parser::parse_fact;
@@ -34,6 +36,10 @@ use wasm_bindgen::JsValue;
 use web_sys::Blob;
 use web_sys::FileReaderSync;
 
+mod fetch;
+
+pub use fetch::{fetch_resources, FetchedResourceProvider};
+
 #[wasm_bindgen]
 #[derive(Clone)]
 pub struct NemoProgram(nemo::model::Program);
@@ -45,6 +51,8 @@ enum WasmOrInternalNemoError {
     NemoError(#[from] nemo::error::Error),
     #[error("Internal reflection error: {0:#?}")]
     ReflectionError(JsValue),
+    #[error("Failed to fetch {resource}: {reason}")]
+    FetchFailure { resource: String, reason: String },
 }
 
 #[wasm_bindgen]
@@ -155,7 +163,7 @@ impl ResourceProvider for BlobResourceProvider {
         &self,
         resource: &Resource,
         compression: CompressionFormat,
-    ) -> Result<Option<Box<dyn std::io::BufRead>>, nemo_physical::error::ReadingError> {
+    ) -> Result<Option<Box<dyn std::io::BufRead + Send>>, nemo_physical::error::ReadingError> {
         if let Some(blob) = self.blobs.get(resource) {
             let array_buffer: js_sys::ArrayBuffer = self
                 .file_reader_sync
@@ -166,25 +174,48 @@ impl ResourceProvider for BlobResourceProvider {
 
             let data = Uint8Array::new(&array_buffer).to_vec();
 
-            let cursor = Cursor::new(data);
-
-            // Decompress blob
-            // We currently do this in Rust after the Blob has been transferred to the WebAssembly, to reuse Nemo's compression logic.
-            // We could also to this on the JavaScript side, see https://developer.mozilla.org/en-US/docs/Web/API/Compression_Streams_API .
-            if let Some(reader) = compression.try_decompression(cursor) {
-                Ok(Some(reader))
-            } else {
-                Err(ReadingError::Decompression {
-                    resource: resource.to_owned(),
-                    decompression_format: compression.to_string(),
-                })
-            }
+            fetch::decompress(resource, compression, data).map(Some)
         } else {
             Ok(None)
         }
     }
 }
 
+/// Statistics about the applications of a rule
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy)]
+pub struct NemoRuleStatistics {
+    pub rule: usize,
+    pub applications: usize,
+    #[wasm_bindgen(js_name = "productiveApplications")]
+    pub productive_applications: usize,
+    #[wasm_bindgen(js_name = "timeMs")]
+    pub time_ms: f64,
+}
+
+/// Memory consumed by all tables after an execution step
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy)]
+pub struct NemoMemorySnapshot {
+    pub step: usize,
+    pub bytes: f64,
+}
+
+/// State of reasoning after a call of `reasonFor`
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy)]
+pub struct NemoExecutionProgress {
+    /// Whether all facts that follow from the program have been derived
+    pub finished: bool,
+    /// Number of rule applications so far
+    pub step: usize,
+    /// Index of the rule that was applied last
+    pub rule: Option<usize>,
+    /// Number of facts of the predicates that are derived by rules
+    #[wasm_bindgen(js_name = "derivedFacts")]
+    pub derived_facts: usize,
+}
+
 #[wasm_bindgen]
 pub struct NemoEngine {
     program: NemoProgram,
@@ -242,8 +273,9 @@ impl NemoEngine {
         program: &NemoProgram,
         resource_blobs_js_value: JsValue,
     ) -> Result<NemoEngine, NemoError> {
-        // Parse JavaScript object into `HashMap`
+        // Parse JavaScript object into `HashMap`s of blobs and of already fetched contents
         let mut resource_blobs = HashMap::new();
+        let mut resource_contents = HashMap::new();
         for key in Reflect::own_keys(&resource_blobs_js_value)
             .map_err(WasmOrInternalNemoError::ReflectionError)
             .map_err(NemoError)?
@@ -252,21 +284,29 @@ impl NemoEngine {
                 let value = Reflect::get(&resource_blobs_js_value, &key)
                     .map_err(WasmOrInternalNemoError::ReflectionError)
                     .map_err(NemoError)?;
-                let blob: Blob = JsCast::dyn_into(value).unwrap();
 
-                resource_blobs.insert(resource, blob);
+                if value.is_instance_of::<Uint8Array>() || value.is_instance_of::<ArrayBuffer>() {
+                    resource_contents.insert(resource, Uint8Array::new(&value).to_vec());
+                } else {
+                    let blob: Blob = JsCast::dyn_into(value).unwrap();
+
+                    resource_blobs.insert(resource, blob);
+                }
             }
         }
 
-        let resource_providers = if resource_blobs.is_empty() {
-            ResourceProviders::empty()
-        } else {
-            ResourceProviders::from(vec![Box::new(
+        let mut resource_providers: Vec<Box<dyn ResourceProvider>> = Vec::new();
+        if !resource_blobs.is_empty() {
+            resource_providers.push(Box::new(
                 BlobResourceProvider::new(resource_blobs)
                     .map_err(WasmOrInternalNemoError::ReflectionError)
                     .map_err(NemoError)?,
-            )])
-        };
+            ));
+        }
+        if !resource_contents.is_empty() {
+            resource_providers.push(Box::new(FetchedResourceProvider::new(resource_contents)));
+        }
+        let resource_providers = ResourceProviders::from(resource_providers);
         let import_manager = ImportManager::new(resource_providers);
 
         let engine = ExecutionEngine::initialize(&program.0, import_manager)
@@ -287,6 +327,103 @@ impl NemoEngine {
             .map_err(NemoError)
     }
 
+    /// Continue reasoning for at most `maxSteps` rule applications
+    /// and at most `maxMillis` milliseconds, then return to the caller.
+    ///
+    /// At least one rule is applied in each call unless reasoning has finished,
+    /// so that repeated calls always make progress.
+    #[wasm_bindgen(js_name = "reasonFor")]
+    pub fn reason_for(
+        &mut self,
+        max_steps: Option<usize>,
+        max_millis: Option<f64>,
+    ) -> Result<NemoExecutionProgress, NemoError> {
+        let max_steps = max_steps.map(|steps| steps.max(1));
+
+        let Some(max_millis) = max_millis else {
+            self.execute_bounded(max_steps)?;
+            return Ok(self.progress());
+        };
+
+        // Time is measured with the clock of JavaScript, which is available in all environments
+        let start = js_sys::Date::now();
+        let mut steps = 0;
+        while self.execute_bounded(Some(1))? == ExecutionStatus::Paused {
+            steps += 1;
+
+            if max_steps.is_some_and(|max_steps| steps >= max_steps)
+                || js_sys::Date::now() - start >= max_millis
+            {
+                break;
+            }
+        }
+
+        Ok(self.progress())
+    }
+
+    /// Continue reasoning for at most the given number of rule applications.
+    fn execute_bounded(&mut self, steps: Option<usize>) -> Result<ExecutionStatus, NemoError> {
+        self.engine
+            .execute_bounded(ExecutionLimits { steps, time: None })
+            .map_err(WasmOrInternalNemoError::NemoError)
+            .map_err(NemoError)
+    }
+
+    /// Return the current state of reasoning.
+    #[wasm_bindgen(js_name = "getProgress")]
+    pub fn progress(&self) -> NemoExecutionProgress {
+        NemoExecutionProgress {
+            finished: self.engine.is_finished(),
+            step: self.engine.steps(),
+            rule: self.engine.last_applied_rule(),
+            derived_facts: self.engine.count_facts_of_derived_predicates(),
+        }
+    }
+
+    /// Request that reasoning stops,
+    /// so that the next call of `reason` or `reasonFor` throws an error.
+    ///
+    /// This allows event handlers to abort reasoning that is driven by repeated calls of `reasonFor`.
+    #[wasm_bindgen]
+    pub fn cancel(&self) {
+        self.engine.cancellation_token().cancel();
+    }
+
+    /// Withdraw a request to stop reasoning, so that it can be continued.
+    #[wasm_bindgen(js_name = "resetCancellation")]
+    pub fn reset_cancellation(&self) {
+        self.engine.cancellation_token().reset();
+    }
+
+    #[wasm_bindgen(js_name = "getRuleStatistics")]
+    pub fn rule_statistics(&self) -> Vec<NemoRuleStatistics> {
+        self.engine
+            .instrumentation()
+            .rules()
+            .iter()
+            .enumerate()
+            .map(|(rule, statistics)| NemoRuleStatistics {
+                rule,
+                applications: statistics.applications,
+                productive_applications: statistics.productive_applications,
+                time_ms: statistics.time.as_secs_f64() * 1000.0,
+            })
+            .collect()
+    }
+
+    #[wasm_bindgen(js_name = "getMemorySnapshots")]
+    pub fn memory_snapshots(&self) -> Vec<NemoMemorySnapshot> {
+        self.engine
+            .instrumentation()
+            .memory_snapshots()
+            .iter()
+            .map(|snapshot| NemoMemorySnapshot {
+                step: snapshot.step,
+                bytes: snapshot.memory.as_u64() as f64,
+            })
+            .collect()
+    }
+
     #[wasm_bindgen(js_name = "countFactsOfDerivedPredicates")]
     pub fn count_facts_of_derived_predicates(&mut self) -> usize {
         self.engine.count_facts_of_derived_predicates()
@@ -0,0 +1,84 @@
+//! Tests for fetching resources, using a mocked fetch function.
+//!
+//! Run with `wasm-pack test --node`.
+
+#![cfg(target_arch = "wasm32")]
+
+use js_sys::{Array, Function, Reflect, Uint8Array};
+use nemo_wasm::{fetch_resources, NemoEngine, NemoProgram};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+/// A fetch function that serves `a,b\nc,d\n` for resources ending in `.csv`,
+/// and responds with status 404 otherwise.
+fn mocked_fetch() -> Function {
+    Function::new_with_args(
+        "resource",
+        "return resource.endsWith('.csv')
+            ? Promise.resolve(new Response('a,b\\nc,d\\n'))
+            : Promise.resolve(new Response('', { status: 404 }));",
+    )
+}
+
+fn resources(resources: &[&str]) -> JsValue {
+    resources
+        .iter()
+        .map(|resource| JsValue::from(*resource))
+        .collect::<Array>()
+        .into()
+}
+
+#[wasm_bindgen_test]
+async fn fetches_http_resources_only() {
+    let contents = fetch_resources(
+        resources(&["https://example.org/data.csv", "local.csv"]),
+        Some(mocked_fetch()),
+    )
+    .await
+    .expect("resources can be fetched");
+
+    let content: Uint8Array = Reflect::get(&contents, &"https://example.org/data.csv".into())
+        .expect("property can be read")
+        .into();
+    assert_eq!(content.to_vec(), b"a,b\nc,d\n");
+    assert!(!Reflect::has(&contents, &"local.csv".into()).expect("property can be checked"));
+}
+
+#[wasm_bindgen_test]
+async fn reports_failed_requests() {
+    let error = fetch_resources(
+        resources(&["https://example.org/missing.nt"]),
+        Some(mocked_fetch()),
+    )
+    .await
+    .expect_err("missing resources cannot be fetched");
+
+    assert!(error.to_string().contains("status 404"));
+}
+
+#[wasm_bindgen_test]
+async fn reasons_over_fetched_resources() {
+    let program = NemoProgram::new(
+        r#"@import data :- csv{resource="https://example.org/data.csv"} .
+        result(?x) :- data(?x, ?y) ."#,
+    )
+    .expect("program is valid");
+
+    let contents = fetch_resources(
+        program
+            .resources_used_in_imports()
+            .expect("imports are valid")
+            .into(),
+        Some(mocked_fetch()),
+    )
+    .await
+    .expect("resources can be fetched");
+
+    let mut engine = NemoEngine::new(&program, contents.into()).expect("engine can be created");
+    engine.reason().expect("reasoning succeeds");
+
+    assert_eq!(
+        engine.count_facts_of_predicate("result".to_string()),
+        Some(2)
+    );
+}
@@ -1,5 +1,10 @@
 //! Nemo API to call the reasoning engine and compute results
 //!
+//! A [Loader] collects the program from files and strings together with the settings for reasoning
+//! and creates an [Engine], which evaluates the program and gives access to its results.
+//! The functions [load], [load_string], [reason], [output_predicates] and [write]
+//! cover the most common case of a single program with default settings.
+//!
 //! # Examples
 //! ```
 //! # use std::fs::read_to_string;
@@ -8,7 +13,7 @@
 //! # fn main() {}
 //! # #[cfg(not(miri))]
 //! # fn main() {
-//! use nemo::api::{load, reason, output_predicates};
+//! use nemo::api::{load, reason, output_predicates, write};
 //! std::env::set_current_dir("../resources/testcases/lcs-diff-computation/").unwrap();
 //! let mut engine = load("run-lcs-10.rls".into()).unwrap();
 //! // reasoning on the rule file
@@ -16,36 +21,462 @@
 //! // write the results to a temporary directory
 //! let temp_dir = TempDir::new().unwrap();
 //! let predicates = output_predicates(&engine);
-//! // TODO: Write API disabled due to refactoring.
-//! // write(temp_dir.to_str().unwrap().to_string(), &mut engine, predicates).unwrap();
+//! write(temp_dir.to_str().unwrap().to_string(), &mut engine, predicates).unwrap();
 //! # }
 //! ```
+//!
+//! Programs can also be assembled from several sources and evaluated with custom settings:
+//! ```
+//! use nemo::{
+//!     api::Loader,
+//!     execution::ExecutionLimits,
+//!     model::{ExportDirective, FileFormat, Identifier},
+//! };
+//!
+//! let mut engine = Loader::new()
+//!     .string("edge(a, b) . edge(b, c) .")
+//!     .string("path(?x, ?y) :- edge(?x, ?y) . path(?x, ?z) :- path(?x, ?y), edge(?y, ?z) .")
+//!     .default_resource_providers(false)
+//!     .limits(ExecutionLimits {
+//!         steps: Some(1000),
+//!         time: None,
+//!     })
+//!     .load()
+//!     .unwrap();
+//! engine.reason().unwrap();
+//!
+//! let path = Identifier::from("path".to_string());
+//! assert_eq!(engine.query(&path).unwrap().len(), 3);
+//!
+//! let tsv = engine
+//!     .export_to_bytes(&ExportDirective::new(path, FileFormat::TSV, []))
+//!     .unwrap();
+//! assert_eq!(String::from_utf8(tsv).unwrap().lines().count(), 3);
+//! ```
 
-use std::{fs::read_to_string, path::PathBuf};
+use std::{
+    cell::RefCell,
+    fs::read_to_string,
+    io::Write,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use bytesize::ByteSize;
+use nemo_physical::{datavalues::AnyDataValue, meta::timing::TimedCode};
 
 use crate::{
     error::{Error, ReadingError},
-    execution::{DefaultExecutionEngine, ExecutionEngine},
+    execution::{
+        tracing::trace::{ExecutionTrace, TraceFactHandle},
+        CancellationToken, DefaultExecutionEngine, ExecutionEngine, ExecutionLimits,
+        ExecutionParameters, MemorySnapshot, RuleStatistics,
+    },
     io::{
-        parser::{all_input_consumed, RuleParser},
-        resource_providers::ResourceProviders,
-        ImportManager,
+        parser::{all_input_consumed, parse_fact, RuleParser},
+        resource_providers::{
+            file::FileResourceProvider, http::HttpResourceProvider, ResourceProvider,
+            ResourceProviders,
+        },
+        ExportManager, ImportManager,
     },
-    model::Identifier,
+    model::{ExportDirective, FunctionRegistry, Identifier, Program},
 };
 
-/// Reasoning Engine exposed by the API
-pub type Engine = DefaultExecutionEngine;
+/// Origin of a part of the program
+#[derive(Debug, Clone)]
+enum ProgramSource {
+    /// A file that contains rules
+    File(PathBuf),
+    /// Rules given as text
+    String(String),
+}
+
+/// Builder for an [Engine]
+///
+/// The program of the engine combines the rules, facts and directives of all sources,
+/// which are added with [Loader::file] and [Loader::string].
+///
+/// If some source declares output predicates or exports,
+/// only the facts of these predicates are derived in a program without further sources.
+/// Sources that declare neither keep deriving the facts of all predicates in the heads of their rules,
+/// which become output predicates of the combined program.
+#[derive(Debug, Default)]
+pub struct Loader {
+    /// Sources of the program, in the order in which they were added
+    sources: Vec<ProgramSource>,
+    /// Functions that may be used in the program in addition to the builtin functions
+    functions: FunctionRegistry,
+    /// Directory against which relative paths of imported files are resolved
+    import_base_path: Option<PathBuf>,
+    /// Resource providers that are asked for resources before the default ones
+    resource_providers: Vec<Box<dyn ResourceProvider>>,
+    /// Whether resources are not resolved to files and HTTP(S) downloads by default
+    disable_default_resource_providers: bool,
+    /// Parameters that control reasoning
+    parameters: ExecutionParameters,
+}
+
+impl Loader {
+    /// Create a new [Loader] without any sources.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the program in the given file.
+    pub fn file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.sources.push(ProgramSource::File(path.into()));
+        self
+    }
+
+    /// Add the program given as a string.
+    pub fn string(mut self, program: impl Into<String>) -> Self {
+        self.sources.push(ProgramSource::String(program.into()));
+        self
+    }
+
+    /// Allow the functions of the given [FunctionRegistry] to be used in the program
+    /// in addition to the builtin functions.
+    pub fn functions(mut self, functions: FunctionRegistry) -> Self {
+        self.functions = functions;
+        self
+    }
+
+    /// Resolve relative paths of imported files against the given directory
+    /// instead of the working directory.
+    ///
+    /// Loading fails if resolving resources to files is disabled
+    /// with [Loader::default_resource_providers].
+    pub fn import_base_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.import_base_path = Some(path.into());
+        self
+    }
+
+    /// Add a [ResourceProvider] that is asked for resources of imports.
+    ///
+    /// Providers are asked in the order in which they were added,
+    /// and before the default providers for files and HTTP(S) downloads.
+    pub fn resource_provider(mut self, provider: impl ResourceProvider + 'static) -> Self {
+        self.resource_providers.push(Box::new(provider));
+        self
+    }
+
+    /// Define whether resources of imports may be resolved to files and HTTP(S) downloads,
+    /// which is the default.
+    pub fn default_resource_providers(mut self, enabled: bool) -> Self {
+        self.disable_default_resource_providers = !enabled;
+        self
+    }
+
+    /// Set the [ExecutionLimits] of reasoning.
+    pub fn limits(mut self, limits: ExecutionLimits) -> Self {
+        self.parameters.limits = limits;
+        self
+    }
+
+    /// Set the [ExecutionParameters] that control reasoning.
+    ///
+    /// This replaces limits that were set before with [Loader::limits].
+    pub fn parameters(mut self, parameters: ExecutionParameters) -> Self {
+        self.parameters = parameters;
+        self
+    }
+
+    /// Parse the program of all sources and return an [Engine] for it.
+    ///
+    /// # Error
+    /// Returns an appropriate [Error] variant if no source was added,
+    /// an import base path was given without the default resource providers,
+    /// a file cannot be read, or on parsing and feature check issues.
+    pub fn load(self) -> Result<Engine, Error> {
+        if self.sources.is_empty() {
+            return Err(Error::NoInput);
+        }
+        if self.disable_default_resource_providers && self.import_base_path.is_some() {
+            return Err(Error::ImportBasePathWithoutFiles);
+        }
+
+        let programs = self
+            .sources
+            .iter()
+            .map(|source| {
+                let input = match source {
+                    ProgramSource::File(path) => read_file(path)?,
+                    ProgramSource::String(input) => input.clone(),
+                };
+
+                let program = all_input_consumed(
+                    RuleParser::with_functions(&self.functions).parse_program(),
+                )(&input)?;
+                Ok(program)
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let program = merge_programs(programs);
+
+        let mut resource_providers = self.resource_providers;
+        if !self.disable_default_resource_providers {
+            resource_providers.push(Box::<HttpResourceProvider>::default());
+            resource_providers.push(Box::new(FileResourceProvider::new(self.import_base_path)));
+        }
+        let import_manager = ImportManager::new(ResourceProviders::from(resource_providers));
+
+        let engine =
+            ExecutionEngine::initialize_with_parameters(&program, import_manager, self.parameters)?;
+
+        Ok(Engine { program, engine })
+    }
+}
+
+/// Read the contents of the given file.
+fn read_file(path: &Path) -> Result<String, Error> {
+    read_to_string(path).map_err(|err| {
+        ReadingError::IoReading {
+            error: err,
+            filename: path.to_string_lossy().to_string(),
+        }
+        .into()
+    })
+}
+
+/// Combine the given programs into one,
+/// whose base is the first base declared by any of the programs.
+///
+/// If some of the programs restrict their output predicates,
+/// the predicates derived by the other programs are added to the output predicates.
+fn merge_programs(mut programs: Vec<Program>) -> Program {
+    if programs.len() == 1 {
+        return programs.pop().expect("there is exactly one program");
+    }
+
+    let restricts_outputs = |program: &Program| {
+        program.output_predicates().next().is_some() || program.exports().next().is_some()
+    };
+    let restricted = programs.iter().any(restricts_outputs);
+
+    let mut builder = Program::builder();
+    if let Some(base) = programs.iter().find_map(Program::base) {
+        builder = builder.base(base);
+    }
+
+    for program in programs {
+        builder = builder
+            .prefixes(program.prefixes().clone())
+            .declarations(program.declarations().iter().cloned())
+            .imports(program.imports().cloned())
+            .exports(program.exports().cloned())
+            .rules(program.rules().iter().cloned())
+            .facts(program.facts().iter().cloned())
+            .constraints(program.constraints().iter().cloned())
+            .output_predicates(program.output_predicates().cloned());
+
+        if restricted && !restricts_outputs(&program) {
+            let mut predicates = program.idb_predicates().into_iter().collect::<Vec<_>>();
+            predicates.sort();
+            builder = builder.output_predicates(predicates);
+        }
+    }
+
+    builder.build()
+}
+
+/// Writer that appends to a buffer, which is shared with its clones
+#[derive(Debug, Clone, Default)]
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Reasoning engine exposed by the API
+#[derive(Debug)]
+pub struct Engine {
+    /// The program as it was loaded
+    program: Program,
+    /// The engine that evaluates the program
+    engine: DefaultExecutionEngine,
+}
+
+impl Engine {
+    /// Return the program of this engine.
+    pub fn program(&self) -> &Program {
+        &self.program
+    }
+
+    /// Add the given rows as facts of the given predicate,
+    /// which must occur in the program.
+    ///
+    /// Facts can only be added before reasoning.
+    pub fn add_facts<Rows>(&mut self, predicate: &Identifier, rows: Rows) -> Result<(), Error>
+    where
+        Rows: IntoIterator<Item = Vec<AnyDataValue>>,
+    {
+        self.engine.add_facts(predicate, rows)
+    }
+
+    /// Compute all facts that follow from the program.
+    ///
+    /// # Note
+    /// If there are `@source` or `@import` directives in the
+    /// parsed rules, all relative paths are resolved with the
+    /// base path given to the [Loader] or the current working directory
+    pub fn reason(&mut self) -> Result<(), Error> {
+        self.engine.execute()
+    }
+
+    /// Return the [CancellationToken] that aborts reasoning once it is cancelled.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.engine.cancellation_token()
+    }
+
+    /// Set the [ExecutionLimits] of reasoning.
+    pub fn set_limits(&mut self, limits: ExecutionLimits) {
+        self.engine.set_limits(limits);
+    }
+
+    /// Get a [Vec] of all predicates that are exported by the program.
+    pub fn output_predicates(&self) -> Vec<Identifier> {
+        self.program
+            .exports()
+            .map(|export| export.predicate().clone())
+            .collect()
+    }
+
+    /// Return the arity of the given predicate, or `None` if the predicate is not known.
+    pub fn predicate_arity(&self, predicate: &Identifier) -> Option<usize> {
+        self.engine.predicate_arity(predicate)
+    }
+
+    /// Return the number of facts of the given predicate, or `None` if the predicate is not known.
+    pub fn count_facts(&self, predicate: &Identifier) -> Option<usize> {
+        self.engine.count_facts_of_predicate(predicate)
+    }
+
+    /// Return all facts of the given predicate.
+    pub fn query(&mut self, predicate: &Identifier) -> Result<Vec<Vec<AnyDataValue>>, Error> {
+        Ok(self
+            .engine
+            .predicate_rows(predicate)?
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+
+    /// Write the facts of all predicates that are exported by the program
+    /// according to their [ExportDirective]s.
+    pub fn write(&mut self, export_manager: &ExportManager) -> Result<(), Error> {
+        let exports = self.program.exports().cloned().collect::<Vec<_>>();
+
+        for export in &exports {
+            self.export(export, export_manager)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the facts of a predicate according to the given [ExportDirective],
+    /// which need not be part of the program.
+    ///
+    /// Nothing is written for predicates that are not known.
+    pub fn export(
+        &mut self,
+        export: &ExportDirective,
+        export_manager: &ExportManager,
+    ) -> Result<(), Error> {
+        let Some(arity) = self.predicate_arity(export.predicate()) else {
+            return Ok(());
+        };
+
+        export_manager.export_table(
+            export,
+            self.engine.predicate_rows(export.predicate())?,
+            arity,
+        )?;
+        Ok(())
+    }
+
+    /// Write the facts of a predicate into the given writer,
+    /// in the format of the given [ExportDirective], ignoring its resource.
+    ///
+    /// Nothing is written for predicates that are not known.
+    pub fn export_to_writer(
+        &mut self,
+        export: &ExportDirective,
+        writer: Box<dyn Write>,
+    ) -> Result<(), Error> {
+        let Some(arity) = self.predicate_arity(export.predicate()) else {
+            return Ok(());
+        };
+
+        ExportManager::new().export_table_with_writer(
+            export,
+            writer,
+            self.engine.predicate_rows(export.predicate())?,
+            arity,
+        )
+    }
+
+    /// Return the facts of a predicate
+    /// in the format of the given [ExportDirective], ignoring its resource.
+    pub fn export_to_bytes(&mut self, export: &ExportDirective) -> Result<Vec<u8>, Error> {
+        let buffer = SharedBuffer::default();
+        self.export_to_writer(export, Box::new(buffer.clone()))?;
+
+        Ok(buffer.0.take())
+    }
+
+    /// Explain how the given facts, written in the syntax of nemo programs, were derived.
+    ///
+    /// Returns an [ExecutionTrace] together with a [TraceFactHandle] for each fact.
+    pub fn trace(
+        &mut self,
+        facts: &[&str],
+    ) -> Result<(ExecutionTrace, Vec<TraceFactHandle>), Error> {
+        let facts = facts
+            .iter()
+            .map(|fact| parse_fact(fact.to_string()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(self.engine.trace(self.program.clone(), facts))
+    }
+
+    /// Return the time spent on the different phases of reasoning.
+    pub fn timing(&self) -> &TimedCode {
+        self.engine.timing()
+    }
+
+    /// Return the [RuleStatistics] of every rule, indexed like the rules of the program.
+    pub fn rule_statistics(&self) -> &[RuleStatistics] {
+        self.engine.instrumentation().rules()
+    }
+
+    /// Return the [MemorySnapshot]s taken during reasoning.
+    pub fn memory_snapshots(&self) -> &[MemorySnapshot] {
+        self.engine.instrumentation().memory_snapshots()
+    }
+
+    /// Return the largest amount of memory consumed by the tables of the engine
+    /// in any of its [MemorySnapshot]s.
+    pub fn peak_memory(&self) -> ByteSize {
+        self.engine.instrumentation().peak_memory()
+    }
+
+    /// Return the number of facts of all predicates that were derived by rules.
+    pub fn count_derived_facts(&self) -> usize {
+        self.engine.count_facts_of_derived_predicates()
+    }
+}
 
 /// Load the given `file` and load the program from the file.
 ///
 /// For details see [load_string]
 pub fn load(file: PathBuf) -> Result<Engine, Error> {
-    let input = read_to_string(file.clone()).map_err(|err| ReadingError::IoReading {
-        error: err,
-        filename: file.to_string_lossy().to_string(),
-    })?;
-    load_string(input)
+    Loader::new().file(file).load()
 }
 
 /// Parse a program in the given `input`-String and return an [Engine].
@@ -55,8 +486,21 @@ pub fn load(file: PathBuf) -> Result<Engine, Error> {
 /// # Error
 /// Returns an appropriate [Error] variant on parsing and feature check issues.
 pub fn load_string(input: String) -> Result<Engine, Error> {
-    let program = all_input_consumed(RuleParser::new().parse_program())(&input)?;
-    ExecutionEngine::initialize(&program, ImportManager::new(ResourceProviders::default()))
+    Loader::new().string(input).load()
+}
+
+/// Parse a program in the given `input`-String and return an [Engine],
+/// where the functions of the given [FunctionRegistry] may be used in addition to the builtin functions.
+///
+/// For details see [load_string]
+pub fn load_string_with_functions(
+    input: String,
+    functions: &FunctionRegistry,
+) -> Result<Engine, Error> {
+    Loader::new()
+        .string(input)
+        .functions(functions.clone())
+        .load()
 }
 
 /// Executes the reasoning process of the [Engine].
@@ -66,55 +510,45 @@ pub fn load_string(input: String) -> Result<Engine, Error> {
 /// parsed rules, all relative paths are resolved with the current
 /// working directory
 pub fn reason(engine: &mut Engine) -> Result<(), Error> {
-    engine.execute()
+    engine.reason()
 }
 
 /// Get a [Vec] of all output predicates that are computed by the engine.
 pub fn output_predicates(engine: &Engine) -> Vec<Identifier> {
-    engine
-        .program()
-        .exports()
-        .map(|(id, _)| id)
-        .cloned()
-        .collect()
+    engine.output_predicates()
 }
 
-// TODO: Disabled write API. This API is designed in a way that does not fit how Nemo controls exporting.
-// One could take a list of export directives instead of a list of predicate names, and one could also
-// have a method that works with the directives from the program.
-//
-// /// Writes all result [predicates][Identifier] in the vector `predicates` into the directory specified in `path`.
-// pub fn write(path: String, engine: &mut Engine, predicates: Vec<Identifier>) -> Result<(), Error> {
-//     let output_dir = PathBuf::from(path);
-//     let export_manager = ExportManager::new()
-//         .set_base_path(output_dir.clone())
-//         .overwrite(true)
-//         .compress(false);
-
-//     let output_predicates = engine
-//         .output_predicates()
-//         .map(|export_spec| (export_spec.predicate().clone(), export_spec))
-//         .collect::<HashMap<_, _>>();
-
-//     for predicate in &predicates {
-//         if let Some(arity) = engine.predicate_arity(predicate) {
-//             export_manager.export_table(
-//                 output_predicates
-//                     .get(predicate)
-//                     .expect("export spec should be present"),
-//                 engine.predicate_rows(predicate)?,
-//                 arity,
-//             )?;
-//         }
-//     }
-
-//     Ok(())
-// }
+/// Writes all result [predicates][Identifier] in the vector `predicates` into the directory specified in `path`.
+///
+/// Predicates are written according to their export directive in the program,
+/// or as uncompressed CSV files if the program does not export them.
+/// Existing files are overwritten.
+pub fn write(path: String, engine: &mut Engine, predicates: Vec<Identifier>) -> Result<(), Error> {
+    let export_manager = ExportManager::new()
+        .set_base_path(PathBuf::from(path))
+        .overwrite(true)
+        .compress(false);
+
+    for predicate in predicates {
+        let export = engine
+            .program
+            .exports()
+            .find(|export| *export.predicate() == predicate)
+            .cloned()
+            .unwrap_or_else(|| ExportDirective::default(predicate));
+
+        engine.export(&export, &export_manager)?;
+    }
+
+    Ok(())
+}
 
 #[cfg(test)]
 mod test {
     use assert_fs::TempDir;
 
+    use crate::{error::Error, execution::ExceededLimit, model::FileFormat};
+
     use super::*;
 
     #[cfg_attr(miri, ignore)]
@@ -131,8 +565,182 @@ mod test {
             .collect::<Vec<_>>();
 
         assert_eq!(results.len(), 5);
-        let _temp_dir = TempDir::new().unwrap();
-        // Disabled:
-        // write(temp_dir.to_str().unwrap().to_string(), &mut engine, results).unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        write(temp_dir.to_str().unwrap().to_string(), &mut engine, results).unwrap();
+
+        let written = std::fs::read_dir(temp_dir.path()).unwrap().count();
+        assert_eq!(written, 5);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn reason_with_functions() {
+        use nemo_physical::datavalues::{AnyDataValue, DataValue};
+
+        use crate::model::{FunctionSignature, ValueType};
+
+        let mut functions = FunctionRegistry::new();
+        functions
+            .register_fn(
+                "vowels",
+                FunctionSignature::new(vec![ValueType::String], ValueType::Int),
+                |parameters| {
+                    let count = parameters[0]
+                        .to_plain_string()?
+                        .chars()
+                        .filter(|c| "aeiou".contains(*c))
+                        .count();
+                    Some(AnyDataValue::new_integer_from_i64(count as i64))
+                },
+            )
+            .unwrap();
+
+        let mut engine = load_string_with_functions(
+            r#"
+            word("banana"). word("sky"). word(42).
+            vowels(?w, vowels(?w)) :- word(?w).
+            many(?w) :- word(?w), vowels(?w) > 1.
+            "#
+            .to_string(),
+            &functions,
+        )
+        .unwrap();
+        super::reason(&mut engine).unwrap();
+
+        let mut rows = |predicate: &str| {
+            let mut rows = engine
+                .query(&Identifier(predicate.to_string()))
+                .unwrap()
+                .into_iter()
+                .map(|row| row.iter().map(ToString::to_string).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            rows.sort();
+            rows
+        };
+
+        assert_eq!(
+            rows("vowels"),
+            vec![
+                vec![r#""banana""#.to_string(), "3".to_string()],
+                vec![r#""sky""#.to_string(), "0".to_string()]
+            ]
+        );
+        assert_eq!(rows("many"), vec![vec![r#""banana""#.to_string()]]);
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn load_multiple_sources() {
+        let mut engine = Loader::new()
+            .string("@prefix ex: <https://example.org/> . ex:edge(a, b) . ex:edge(b, c) .")
+            .string(
+                r#"
+                @prefix ex: <https://example.org/> .
+                ex:path(?x, ?y) :- ex:edge(?x, ?y) .
+                ex:path(?x, ?z) :- ex:path(?x, ?y), ex:edge(?y, ?z) .
+                @export ex:path :- csv {} .
+                "#,
+            )
+            .default_resource_providers(false)
+            .load()
+            .unwrap();
+        engine.reason().unwrap();
+
+        let path = Identifier("https://example.org/path".to_string());
+        assert_eq!(engine.output_predicates(), vec![path.clone()]);
+        assert_eq!(engine.count_facts(&path), Some(3));
+        assert_eq!(engine.count_derived_facts(), 3);
+        assert_eq!(engine.rule_statistics().len(), 2);
+        assert!(!engine.memory_snapshots().is_empty());
+
+        let dsv = engine
+            .export_to_bytes(&ExportDirective::new(
+                path.clone(),
+                FileFormat::DSV,
+                [(
+                    "delimiter".to_string(),
+                    AnyDataValue::new_plain_string(";".to_string()),
+                )],
+            ))
+            .unwrap();
+        let mut lines = String::from_utf8(dsv)
+            .unwrap()
+            .lines()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        lines.sort();
+        assert_eq!(lines, vec!["a;b", "a;c", "b;c"]);
+
+        let (trace, handles) = engine.trace(&["<https://example.org/path>(a, c)"]).unwrap();
+        assert!(trace.tree(handles[0]).is_some());
+
+        assert!(matches!(Loader::new().load(), Err(Error::NoInput)));
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn load_sources_with_and_without_outputs() {
+        let mut engine = Loader::new()
+            .string("edge(a, b) . edge(b, c) . start(?y) :- edge(a, ?y) . @output start .")
+            .string("path(?x, ?y) :- edge(?x, ?y) . path(?x, ?z) :- path(?x, ?y), edge(?y, ?z) .")
+            .default_resource_providers(false)
+            .load()
+            .unwrap();
+        engine.reason().unwrap();
+
+        // The predicates of the second source are derived even though the first declares outputs
+        let start = Identifier("start".to_string());
+        let path = Identifier("path".to_string());
+        assert_eq!(
+            engine.program().output_predicates().collect::<Vec<_>>(),
+            vec![&start, &path]
+        );
+        assert_eq!(engine.count_facts(&start), Some(1));
+        assert_eq!(engine.count_facts(&path), Some(3));
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn import_base_path_and_limits() {
+        let directory = TempDir::new().unwrap();
+        std::fs::write(directory.path().join("edges.csv"), "a,b\nb,c\n").unwrap();
+
+        let program = r#"
+            @import edge :- csv { resource = "edges.csv" } .
+            path(?x, ?y) :- edge(?x, ?y) .
+            path(?x, ?z) :- path(?x, ?y), edge(?y, ?z) .
+        "#;
+
+        let mut engine = Loader::new()
+            .string(program)
+            .import_base_path(directory.path())
+            .load()
+            .unwrap();
+        engine.reason().unwrap();
+        assert_eq!(
+            engine.query(&Identifier("path".to_string())).unwrap().len(),
+            3
+        );
+
+        let mut engine = Loader::new()
+            .string(program)
+            .import_base_path(directory.path())
+            .limits(ExecutionLimits {
+                steps: Some(1),
+                time: None,
+            })
+            .load()
+            .unwrap();
+        assert!(matches!(
+            engine.reason(),
+            Err(Error::LimitExceeded(ExceededLimit::Steps(1)))
+        ));
+
+        let result = Loader::new()
+            .string(program)
+            .import_base_path(directory.path())
+            .default_resource_providers(false)
+            .load();
+        assert!(matches!(result, Err(Error::ImportBasePathWithoutFiles)));
     }
 }
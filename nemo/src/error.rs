@@ -6,9 +6,13 @@ use nemo_physical::datavalues::DataValueCreationError;
 use thiserror::Error;
 
 use crate::{
-    execution::selection_strategy::strategy::SelectionStrategyError,
+    execution::{
+        selection_strategy::strategy::SelectionStrategyError, ConstraintViolation, ExceededLimit,
+        ExternalPredicateError,
+    },
     io::{formats::import_export::ImportExportError, parser::LocatedParseError},
-    program_analysis::analysis::RuleAnalysisError,
+    model::{FunctionRegistrationError, Identifier},
+    program_analysis::{analysis::RuleAnalysisError, type_checking::TypeError},
 };
 
 pub use nemo_physical::error::ReadingError;
@@ -29,15 +33,67 @@ pub enum Error {
     /// Error if no input rule files are specified
     #[error("No inputs were specified")]
     NoInput,
+    /// Error if a base path for imports is given, but imports may not be resolved to files
+    #[error("an import base path was given, but imports cannot be read from files")]
+    ImportBasePathWithoutFiles,
     /// Error if the user asked for an unimplemented feature
     #[error("Multiple file support is not yet implemented")]
     MultipleFilesNotImplemented,
     /// Rule analysis errors
     #[error(transparent)]
     RuleAnalysisError(#[from] RuleAnalysisError),
+    /// Values that do not match the declared types of predicates
+    #[error(transparent)]
+    TypeError(#[from] TypeError),
+    /// An integrity constraint is violated
+    #[error("integrity constraint {0}")]
+    ConstraintViolation(Box<ConstraintViolation>),
+    /// An equality in the head of a rule equates two distinct constants
+    #[error("{rule} equates the distinct constants {left} and {right}")]
+    EqualityOfConstants {
+        /// Description of the rule by its position in the source text or its index
+        rule: String,
+        /// The first constant
+        left: String,
+        /// The second constant
+        right: String,
+    },
+    /// The magic sets transformation cannot compute the requested values of a predicate
+    #[error(r#"magic sets cannot compute the requested values of predicate "{0}""#)]
+    MagicSets(Identifier),
+    /// Facts are added for a predicate that does not occur in the program
+    #[error(r#"facts cannot be added for predicate "{0}", which does not occur in the program"#)]
+    UnknownPredicate(Identifier),
+    /// Facts are added after the program has been executed
+    #[error(r#"facts of predicate "{0}" cannot be added after reasoning has started"#)]
+    FactsAfterReasoning(Identifier),
+    /// An added fact has a different number of values than the arity of its predicate
+    #[error(
+        r#"predicate "{predicate}" has arity {expected}, but a fact with {found} values was given"#
+    )]
+    FactArity {
+        /// The predicate of the fact
+        predicate: Identifier,
+        /// Arity of the predicate
+        expected: usize,
+        /// Number of values of the fact
+        found: usize,
+    },
+    /// Reasoning was cancelled by a [CancellationToken][crate::execution::CancellationToken]
+    #[error("reasoning was cancelled")]
+    Cancelled,
+    /// Reasoning exceeded one of the [ExecutionLimits][crate::execution::ExecutionLimits]
+    #[error("reasoning exceeded the {0}")]
+    LimitExceeded(#[from] ExceededLimit),
     /// Parse errors
     #[error(transparent)]
     ParseError(#[from] LocatedParseError),
+    /// Error when registering a user-defined function
+    #[error(transparent)]
+    FunctionRegistrationError(#[from] FunctionRegistrationError),
+    /// Error related to external predicates
+    #[error(transparent)]
+    ExternalPredicateError(#[from] ExternalPredicateError),
     /// IO Error
     #[error(transparent)]
     IO(#[from] std::io::Error),
@@ -1,7 +1,35 @@
 //! Functionality for evaluating an existential rule program.
 
 pub mod execution_engine;
-pub use execution_engine::ExecutionEngine;
+pub use execution_engine::{ExecutionEngine, ExecutionParameters};
+
+pub mod integrity_constraints;
+pub use integrity_constraints::{ConstraintViolation, ViolationHandling};
+
+pub mod core_computation;
+pub use core_computation::CoreStatistics;
+
+pub mod provenance;
+pub use provenance::{Provenance, ProvenanceSource};
+
+pub(crate) mod unnesting;
+
+pub mod external_predicates;
+pub use external_predicates::{
+    Binding, BindingPattern, ExternalPredicate, ExternalPredicateError, ExternalPredicates,
+};
+
+pub mod instrumentation;
+pub use instrumentation::{Instrumentation, MemorySnapshot, RuleStatistics};
+
+pub mod explanation;
+pub use explanation::RuleExplanation;
+
+pub mod progress;
+pub use progress::{
+    CancellationToken, ExceededLimit, ExecutionLimits, ExecutionProgress, ExecutionStatus,
+    ProgressCallback,
+};
 
 use self::selection_strategy::{
     dependency_graph::graph_positive::GraphConstructorPositive,
@@ -0,0 +1,426 @@
+//! Computation of the core of the result of the chase.
+//!
+//! The facts that contain nulls are partitioned into blocks,
+//! such that two facts belong to the same block if they are connected by shared nulls.
+//! A block can be removed (up to its image) if there is a homomorphism
+//! that maps it into the remaining facts while leaving out at least one of its facts.
+//! Since such homomorphisms can be found for each block separately,
+//! the result is a core once no block can be reduced any further.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use nemo_physical::datavalues::{AnyDataValue, DataValue, ValueDomain};
+
+use crate::{model::Identifier, util::union_find::UnionFind};
+
+/// Statistics about the computation of a core
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CoreStatistics {
+    /// Number of facts that were removed
+    pub removed_facts: usize,
+    /// Number of nulls that no longer occur in any fact
+    pub removed_nulls: usize,
+}
+
+impl std::fmt::Display for CoreStatistics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "removed {} facts and {} nulls",
+            self.removed_facts, self.removed_nulls
+        )
+    }
+}
+
+/// Return whether the given value is a null.
+fn is_null(value: &AnyDataValue) -> bool {
+    value.value_domain() == ValueDomain::Null
+}
+
+/// Assignment of nulls to values
+type NullAssignment = HashMap<AnyDataValue, AnyDataValue>;
+
+/// A set of facts whose core is computed
+#[derive(Debug, Default)]
+pub(crate) struct CoreInstance {
+    /// All predicates, referenced by their index
+    predicates: Vec<Identifier>,
+    /// Index of each predicate in the list of predicates
+    predicate_indices: HashMap<Identifier, usize>,
+    /// Predicate index and values of each fact
+    facts: Vec<(usize, Vec<AnyDataValue>)>,
+    /// Index of each fact in the list of facts
+    fact_indices: HashMap<(usize, Vec<AnyDataValue>), usize>,
+    /// Whether each fact has been removed
+    removed: Vec<bool>,
+    /// Facts of each predicate
+    by_predicate: HashMap<usize, Vec<usize>>,
+    /// Facts with a given value at a given position of a given predicate
+    by_value: HashMap<(usize, usize, AnyDataValue), Vec<usize>>,
+}
+
+impl CoreInstance {
+    /// Add a fact to the instance.
+    pub(crate) fn add_fact(&mut self, predicate: &Identifier, row: Vec<AnyDataValue>) {
+        let predicate = match self.predicate_indices.get(predicate) {
+            Some(&index) => index,
+            None => {
+                self.predicates.push(predicate.clone());
+                self.predicate_indices
+                    .insert(predicate.clone(), self.predicates.len() - 1);
+                self.predicates.len() - 1
+            }
+        };
+
+        if self.fact_indices.contains_key(&(predicate, row.clone())) {
+            return;
+        }
+        let fact = self.facts.len();
+
+        for (position, value) in row.iter().enumerate() {
+            self.by_value
+                .entry((predicate, position, value.clone()))
+                .or_default()
+                .push(fact);
+        }
+        self.by_predicate.entry(predicate).or_default().push(fact);
+        self.fact_indices.insert((predicate, row.clone()), fact);
+        self.facts.push((predicate, row));
+        self.removed.push(false);
+    }
+
+    /// Return the number of distinct nulls in the facts that have not been removed.
+    fn count_nulls(&self) -> usize {
+        self.facts
+            .iter()
+            .zip(&self.removed)
+            .filter(|(_, &removed)| !removed)
+            .flat_map(|((_, row), _)| row.iter().filter(|value| is_null(value)))
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    /// Partition the given facts into blocks of facts that are connected by shared nulls.
+    ///
+    /// Facts without nulls are ignored.
+    fn blocks(&self, facts: impl Iterator<Item = usize>) -> Vec<Vec<usize>> {
+        let mut nulls = UnionFind::<AnyDataValue>::default();
+        let mut null_facts = Vec::new();
+
+        for fact in facts {
+            let mut row_nulls = self.facts[fact].1.iter().filter(|value| is_null(value));
+            let Some(first) = row_nulls.next() else {
+                continue;
+            };
+
+            for null in row_nulls {
+                nulls.union(first, null, |_, _| false);
+            }
+            null_facts.push((fact, first.clone()));
+        }
+
+        let mut blocks = HashMap::<AnyDataValue, Vec<usize>>::new();
+        for (fact, null) in null_facts {
+            blocks.entry(nulls.find(&null)).or_default().push(fact);
+        }
+
+        blocks.into_values().collect()
+    }
+
+    /// Order the facts of a block such that each fact shares nulls with a previous fact if possible,
+    /// starting with the fact that contains the most constants.
+    fn search_order(&self, block: &[usize]) -> Vec<usize> {
+        let constants = |fact: usize| {
+            self.facts[fact]
+                .1
+                .iter()
+                .filter(|value| !is_null(value))
+                .count()
+        };
+
+        let mut remaining = block.to_vec();
+        let mut order = Vec::with_capacity(block.len());
+        let mut nulls = HashSet::new();
+
+        while !remaining.is_empty() {
+            let start = remaining
+                .iter()
+                .enumerate()
+                .max_by_key(|(_, &fact)| constants(fact))
+                .map(|(index, _)| index)
+                .expect("remaining facts are not empty");
+            let mut queue = VecDeque::from([remaining.swap_remove(start)]);
+
+            while let Some(fact) = queue.pop_front() {
+                order.push(fact);
+                nulls.extend(self.facts[fact].1.iter().filter(|value| is_null(value)));
+
+                let (connected, other): (Vec<_>, Vec<_>) = remaining.iter().partition(|&&other| {
+                    self.facts[other]
+                        .1
+                        .iter()
+                        .any(|value| nulls.contains(value))
+                });
+                queue.extend(connected);
+                remaining = other;
+            }
+        }
+
+        order
+    }
+
+    /// Return the facts that may be the image of a fact with the given predicate,
+    /// where each position is either bound to a value or unbound.
+    fn candidates(&self, predicate: usize, pattern: &[Option<&AnyDataValue>]) -> &[usize] {
+        let mut result = self
+            .by_predicate
+            .get(&predicate)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        for (position, value) in pattern.iter().enumerate() {
+            let Some(value) = value else {
+                continue;
+            };
+
+            let facts = self
+                .by_value
+                .get(&(predicate, position, (*value).clone()))
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            if facts.len() < result.len() {
+                result = facts;
+            }
+        }
+
+        result
+    }
+
+    /// Extend the given assignment to a homomorphism
+    /// that maps the given facts to facts of the instance other than `excluded`.
+    fn extend_homomorphism(
+        &self,
+        facts: &[usize],
+        excluded: usize,
+        assignment: &mut NullAssignment,
+    ) -> bool {
+        let Some((&fact, rest)) = facts.split_first() else {
+            return true;
+        };
+        let (predicate, row) = &self.facts[fact];
+
+        let pattern = row
+            .iter()
+            .map(|value| {
+                if is_null(value) {
+                    assignment.get(value)
+                } else {
+                    Some(value)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        for &candidate in self.candidates(*predicate, &pattern) {
+            if candidate == excluded || self.removed[candidate] {
+                continue;
+            }
+            let target = &self.facts[candidate].1;
+
+            let mut bound = Vec::new();
+            let mut consistent = true;
+            for (value, target_value) in row.iter().zip(target) {
+                let image = if is_null(value) {
+                    assignment.get(value)
+                } else {
+                    Some(value)
+                };
+
+                match image {
+                    Some(image) => {
+                        if image != target_value {
+                            consistent = false;
+                            break;
+                        }
+                    }
+                    None => {
+                        assignment.insert(value.clone(), target_value.clone());
+                        bound.push(value.clone());
+                    }
+                }
+            }
+
+            if consistent && self.extend_homomorphism(rest, excluded, assignment) {
+                return true;
+            }
+            for null in bound {
+                assignment.remove(&null);
+            }
+        }
+
+        false
+    }
+
+    /// Return the image of the given fact under the given assignment.
+    fn image(&self, fact: usize, assignment: &NullAssignment) -> usize {
+        let (predicate, row) = &self.facts[fact];
+        let row = row
+            .iter()
+            .map(|value| assignment.get(value).unwrap_or(value).clone())
+            .collect::<Vec<_>>();
+
+        self.fact_indices[&(*predicate, row)]
+    }
+
+    /// Remove facts until the remaining facts form a core.
+    pub(crate) fn compute_core(&mut self) -> CoreStatistics {
+        let nulls_before = self.count_nulls();
+        let mut removed_facts = 0;
+
+        let mut blocks = self.blocks(0..self.facts.len());
+
+        'blocks: while let Some(block) = blocks.pop() {
+            let order = self.search_order(&block);
+
+            for &excluded in &block {
+                let mut assignment = NullAssignment::new();
+                if !self.extend_homomorphism(&order, excluded, &mut assignment) {
+                    continue;
+                }
+
+                let image = block
+                    .iter()
+                    .map(|&fact| self.image(fact, &assignment))
+                    .collect::<HashSet<_>>();
+                let remaining = block
+                    .iter()
+                    .copied()
+                    .filter(|fact| image.contains(fact))
+                    .collect::<Vec<_>>();
+
+                for &fact in &block {
+                    if !image.contains(&fact) {
+                        self.removed[fact] = true;
+                        removed_facts += 1;
+                    }
+                }
+
+                // Removing facts cannot enable homomorphisms from other blocks,
+                // so only the remainder of this block must be checked again
+                blocks.extend(self.blocks(remaining.into_iter()));
+                continue 'blocks;
+            }
+        }
+
+        CoreStatistics {
+            removed_facts,
+            removed_nulls: nulls_before - self.count_nulls(),
+        }
+    }
+
+    /// Return the remaining facts of each predicate from which facts have been removed.
+    pub(crate) fn reduced_predicates(&self) -> Vec<(Identifier, Vec<Vec<AnyDataValue>>)> {
+        let mut result = HashMap::<usize, Vec<Vec<AnyDataValue>>>::new();
+        let reduced = self
+            .facts
+            .iter()
+            .zip(&self.removed)
+            .filter(|(_, &removed)| removed)
+            .map(|((predicate, _), _)| *predicate)
+            .collect::<HashSet<_>>();
+
+        for ((predicate, row), &removed) in self.facts.iter().zip(&self.removed) {
+            if !removed && reduced.contains(predicate) {
+                result.entry(*predicate).or_default().push(row.clone());
+            }
+        }
+
+        let mut result = result
+            .into_iter()
+            .map(|(predicate, rows)| (self.predicates[predicate].clone(), rows))
+            .collect::<Vec<_>>();
+        result.sort_by(|left, right| left.0.cmp(&right.0));
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use nemo_physical::{
+        datavalues::AnyDataValue,
+        dictionary::{meta_dv_dict::MetaDvDictionary, DvDict},
+    };
+
+    use crate::{
+        execution::DefaultExecutionEngine,
+        io::{parser::parse_program, resource_providers::ResourceProviders, ImportManager},
+        model::Identifier,
+    };
+
+    use super::{CoreInstance, CoreStatistics};
+
+    #[test]
+    fn core_of_instance() {
+        let edge = Identifier("edge".to_string());
+        let constant = |name: &str| AnyDataValue::new_iri(name.to_string());
+        let mut dictionary = MetaDvDictionary::default();
+        let nulls = (0..5)
+            .map(|_| dictionary.fresh_null().0)
+            .collect::<Vec<_>>();
+        let null = |index: usize| nulls[index].clone();
+
+        let mut instance = CoreInstance::default();
+        // A cycle of length two and a path of nulls that maps into it
+        instance.add_fact(&edge, vec![constant("a"), constant("b")]);
+        instance.add_fact(&edge, vec![constant("b"), constant("a")]);
+        instance.add_fact(&edge, vec![constant("a"), null(0)]);
+        instance.add_fact(&edge, vec![null(0), null(1)]);
+        instance.add_fact(&edge, vec![null(1), null(2)]);
+        // A loop on a null, which cannot be mapped into the cycle
+        instance.add_fact(&edge, vec![null(3), null(3)]);
+        instance.add_fact(&edge, vec![null(4), null(3)]);
+
+        assert_eq!(
+            instance.compute_core(),
+            CoreStatistics {
+                removed_facts: 4,
+                removed_nulls: 4,
+            }
+        );
+
+        let reduced = instance.reduced_predicates();
+        assert_eq!(reduced.len(), 1);
+        assert_eq!(reduced[0].1.len(), 3);
+        assert!(reduced[0].1.contains(&vec![null(3), null(3)]));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn core_of_chase_result() {
+        let program = parse_program(
+            r#"
+            person(alice). person(bob). mother(alice, carol). named(carol).
+            parent(?x, !p), named(!p) :- person(?x) .
+            parent(?x, ?m) :- mother(?x, ?m) .
+            "#,
+        )
+        .unwrap();
+        let mut engine = DefaultExecutionEngine::initialize(
+            &program,
+            ImportManager::new(ResourceProviders::empty()),
+        )
+        .unwrap();
+        engine.execute().unwrap();
+
+        let parent = Identifier("parent".to_string());
+        let facts_before = engine.count_facts_of_predicate(&parent);
+
+        let statistics = engine.compute_core().unwrap();
+        assert_eq!(statistics.removed_facts, 2);
+        assert_eq!(statistics.removed_nulls, 1);
+        assert_eq!(
+            engine.count_facts_of_predicate(&parent),
+            facts_before.map(|count| count - 1)
+        );
+    }
+}
@@ -1,30 +1,67 @@
 //! Functionality which handles the execution of a program
 
-use std::collections::{hash_map::Entry, HashMap};
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    ops::Range,
+    time::{Duration, Instant},
+};
 
 use nemo_physical::{
-    datavalues::AnyDataValue,
+    datavalues::{AnyDataValue, DataValue, ValueDomain},
     dictionary::DvDict,
-    management::database::sources::{SimpleTable, TableSource},
+    management::database::{
+        id::PermanentTableId,
+        sources::{SimpleTable, TableSource},
+    },
     meta::timing::TimedCode,
 };
 
 use crate::{
     error::Error,
-    execution::{planning::plan_tracing::TracingStrategy, tracing::trace::TraceDerivation},
+    execution::{
+        planning::{cost_model::JoinCostModel, plan_tracing::TracingStrategy},
+        tracing::trace::TraceDerivation,
+    },
     io::import_manager::ImportManager,
     model::{
         chase_model::{ChaseAtom, ChaseFact, ChaseProgram},
-        Fact, Identifier, PrimitiveTerm, Program, Variable,
+        Atom, Fact, Identifier, PrimitiveTerm, Program, ValueType, Variable,
     },
-    program_analysis::analysis::ProgramAnalysis,
-    table_manager::{MemoryUsage, SubtableExecutionPlan, TableManager},
+    program_analysis::{
+        analysis::ProgramAnalysis,
+        magic_sets::apply_magic_sets,
+        monotone_aggregates::rewrite_recursive_aggregates,
+        type_checking::{
+            check_declared_types, check_value, declared_types, DeclaredTypeValidator,
+            TypeErrorLocation,
+        },
+    },
+    table_manager::{MemoryUsage, SubtableExecutionPlan, SubtableIdentifier, TableManager},
+    util::union_find::UnionFind,
 };
 
 use super::{
+    core_computation::{CoreInstance, CoreStatistics},
+    explanation::RuleExplanation,
+    external_predicates::{
+        add_external_input_rules, external_input_predicate, external_rows,
+        is_external_input_predicate, ExternalPredicates,
+    },
+    instrumentation::Instrumentation,
+    integrity_constraints::{
+        add_violation_rules, ConstraintViolation, ViolationHandling, ViolationPredicate,
+    },
+    progress::{
+        CancellationToken, ExecutionLimits, ExecutionProgress, ExecutionStatus, ProgressCallback,
+    },
+    provenance::{add_provenance_rules, Provenance, ProvenanceBuilder, ProvenanceRule},
     rule_execution::RuleExecution,
     selection_strategy::strategy::RuleSelectionStrategy,
     tracing::trace::{ExecutionTrace, TraceFactHandle, TraceRuleApplication, TraceStatus},
+    unnesting::{
+        add_unnesting_rules, is_unnest_predicate, unnest_input_predicate, unnest_predicate,
+        unnest_rows,
+    },
 };
 
 // Number of tables that are periodically combined into one.
@@ -51,11 +88,61 @@ impl RuleInfo {
 pub struct ExecutionEngine<RuleSelectionStrategy> {
     program: ChaseProgram,
     analysis: ProgramAnalysis,
+    /// Declared types of all predicates that are restricted to some type in at least one position
+    declared_types: HashMap<Identifier, Vec<ValueType>>,
+    /// The loaded program including the rules that derive violations of integrity constraints
+    constraint_program: Program,
+    /// Integrity constraints of the program and the predicates that collect their violations
+    violation_predicates: Vec<ViolationPredicate>,
+    /// What happens if an integrity constraint is violated
+    violation_handling: ViolationHandling,
+    /// Equivalence classes of the values that are equated by rules with equality heads
+    equalities: UnionFind<AnyDataValue>,
+    /// Predicates whose tables may contain nulls that are merged by rules with equality heads
+    null_predicates: Vec<Identifier>,
+    /// Whether provenance annotations are computed
+    compute_provenance: bool,
+    /// Rules of the program and the predicates that collect their instantiations,
+    /// if provenance annotations are computed
+    provenance_rules: Vec<ProvenanceRule>,
+    /// Tables that contain the input facts of each predicate,
+    /// if provenance annotations are computed
+    input_tables: HashMap<Identifier, PermanentTableId>,
+    /// Ids of the nulls created by each application of a rule of the program
+    /// and the index of that rule, if provenance annotations are computed
+    null_origins: Vec<(Range<usize>, usize)>,
+    /// Provenance annotations of all facts, once they have been computed
+    provenance: Option<Provenance>,
+    /// Predicates whose facts are computed by external code
+    external_predicates: ExternalPredicates,
+    /// Flag that is checked before every rule application to abort reasoning
+    cancellation: CancellationToken,
+    /// Bounds that are checked before every rule application to abort reasoning
+    limits: ExecutionLimits,
+    /// Time spent on reasoning in all finished calls of [ExecutionEngine::execute_bounded],
+    /// which is only measured while time is limited
+    reasoning_time: Duration,
+    /// Function that is informed after every rule application
+    progress: Option<ProgressCallback>,
+    /// Counters and memory snapshots collected during reasoning
+    instrumentation: Instrumentation,
+    /// Whether facts that are derived again are counted
+    count_duplicates: bool,
+    /// Whether the execution plans of rule applications are recorded
+    explain: bool,
 
     rule_strategy: RuleSelectionStrategy,
+    /// Execution plans of the rules of the program
+    rule_executions: Vec<RuleExecution>,
+    /// Rule that was selected by the strategy but has not been applied yet,
+    /// because reasoning was interrupted
+    pending_rule: Option<usize>,
+    /// Whether the last rule application derived new facts,
+    /// or `None` if the strategy has to start over
+    new_derivations: Option<bool>,
+    /// Whether the strategy has no further rules to apply
+    finished: bool,
 
-    #[allow(dead_code)]
-    input_manager: ImportManager,
     table_manager: TableManager,
 
     predicate_fragmentation: HashMap<Identifier, usize>,
@@ -66,17 +153,140 @@ pub struct ExecutionEngine<RuleSelectionStrategy> {
     current_step: usize,
 }
 
+/// Parameters that control how an [ExecutionEngine] evaluates a program
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionParameters {
+    /// Only derive facts that are relevant for the output and export predicates,
+    /// using the magic sets transformation
+    pub magic_sets: bool,
+    /// Whether reasoning is aborted at the first violation of an integrity constraint
+    /// or all violations are reported afterwards
+    pub constraint_violations: ViolationHandling,
+    /// Collect the instantiations of all rules during reasoning,
+    /// so that every fact can be annotated with its provenance afterwards,
+    /// see [Provenance] for how rules with existential variables and aggregates are handled
+    ///
+    /// This needs memory for the values of all body variables of every instantiation.
+    pub provenance: bool,
+    /// Predicates whose facts are computed by external code
+    /// whenever the bodies of rules bind their inputs
+    pub external_predicates: ExternalPredicates,
+    /// Flag that aborts reasoning with [Error::Cancelled] once it is set
+    pub cancellation: CancellationToken,
+    /// Bounds that abort reasoning with [Error::LimitExceeded] once they are reached
+    pub limits: ExecutionLimits,
+    /// Function that is called after every rule application
+    pub progress: Option<ProgressCallback>,
+    /// Count the facts that rules derive again and discard as duplicates,
+    /// see [RuleStatistics::duplicates][super::RuleStatistics::duplicates]
+    ///
+    /// This slows down reasoning, since facts have to be stored before duplicates are eliminated.
+    pub count_duplicates: bool,
+    /// Record a description of the execution plan of the first and the most recent
+    /// application of every rule, including the number of rows of each computed table,
+    /// see [Instrumentation::explanations]
+    pub explain: bool,
+}
+
+/// A program together with the rules and analysis that are needed to reason over it
+pub(crate) struct PreparedProgram {
+    /// The program including the rules that derive violations of integrity constraints,
+    /// unnest tuples, and collect the inputs of external predicates
+    pub(crate) constraint_program: Program,
+    /// Integrity constraints of the program and the predicates that collect their violations
+    pub(crate) violation_predicates: Vec<ViolationPredicate>,
+    /// Rules of the program and the predicates that collect their instantiations,
+    /// if provenance annotations are computed
+    pub(crate) provenance_rules: Vec<ProvenanceRule>,
+    /// The program that is evaluated by the chase
+    pub(crate) chase_program: ChaseProgram,
+    /// Analysis of the chase program
+    pub(crate) analysis: ProgramAnalysis,
+}
+
+/// Check the given program and apply all rewritings
+/// that precede its execution with the given [ExecutionParameters].
+pub(crate) fn prepare_program(
+    program: &Program,
+    parameters: &ExecutionParameters,
+) -> Result<PreparedProgram, Error> {
+    check_declared_types(program)?;
+
+    let (program, provenance_rules) = if parameters.provenance {
+        add_provenance_rules(program)
+    } else {
+        (program.clone(), Vec::new())
+    };
+    let (constraint_program, violation_predicates) = add_violation_rules(&program);
+    let constraint_program = add_unnesting_rules(&constraint_program);
+    let constraint_program =
+        add_external_input_rules(&constraint_program, &parameters.external_predicates)?;
+
+    let mut chase_program: ChaseProgram = constraint_program.clone().try_into()?;
+    chase_program = rewrite_recursive_aggregates(chase_program)?;
+    if parameters.magic_sets {
+        if chase_program.has_equality_rules() {
+            log::warn!("magic sets are not applied to programs with equality rules");
+        } else {
+            chase_program = apply_magic_sets(chase_program)?;
+        }
+    }
+
+    let analysis = chase_program.analyze()?;
+
+    Ok(PreparedProgram {
+        constraint_program,
+        violation_predicates,
+        provenance_rules,
+        chase_program,
+        analysis,
+    })
+}
+
 impl<Strategy: RuleSelectionStrategy> ExecutionEngine<Strategy> {
     /// Initialize [ExecutionEngine].
     pub fn initialize(program: &Program, input_manager: ImportManager) -> Result<Self, Error> {
-        let chase_program: ChaseProgram = program.clone().try_into()?;
+        Self::initialize_with_parameters(program, input_manager, ExecutionParameters::default())
+    }
 
-        let analysis = chase_program.analyze()?;
+    /// Initialize [ExecutionEngine] with the given [ExecutionParameters].
+    pub fn initialize_with_parameters(
+        program: &Program,
+        input_manager: ImportManager,
+        parameters: ExecutionParameters,
+    ) -> Result<Self, Error> {
+        let PreparedProgram {
+            constraint_program,
+            violation_predicates,
+            provenance_rules,
+            chase_program,
+            analysis,
+        } = prepare_program(program, &parameters)?;
+
+        let mut declared_types = declared_types(chase_program.declarations())?;
+        declared_types
+            .retain(|_, types| types.iter().any(|&value_type| value_type != ValueType::Any));
 
         let mut table_manager = TableManager::new();
         Self::register_all_predicates(&mut table_manager, &analysis);
         Self::add_all_constants(&mut table_manager, &chase_program);
-        Self::add_imports(&mut table_manager, &input_manager, &chase_program)?;
+        Self::add_imports(
+            &mut table_manager,
+            &input_manager,
+            &chase_program,
+            &declared_types,
+        )?;
+
+        let mut input_tables = HashMap::new();
+        if parameters.provenance {
+            for predicate in analysis.all_predicates.keys() {
+                if let Some(table_id) =
+                    table_manager.table_id(&SubtableIdentifier::new(predicate.clone(), 0))
+                {
+                    input_tables.insert(predicate.clone(), table_id);
+                }
+            }
+        }
 
         let mut rule_infos = Vec::<RuleInfo>::new();
         chase_program
@@ -84,16 +294,53 @@ impl<Strategy: RuleSelectionStrategy> ExecutionEngine<Strategy> {
             .iter()
             .for_each(|_| rule_infos.push(RuleInfo::new()));
 
+        let mut null_predicates = if chase_program.has_equality_rules() {
+            chase_program.null_predicates().into_iter().collect()
+        } else {
+            Vec::new()
+        };
+        null_predicates.sort();
+
         let rule_strategy = Strategy::new(
             chase_program.rules().iter().collect(),
             analysis.rule_analysis.iter().collect(),
         )?;
+        let rule_executions = chase_program
+            .rules()
+            .iter()
+            .zip(analysis.rule_analysis.iter())
+            .map(|(rule, analysis)| RuleExecution::initialize(rule, analysis))
+            .collect();
+        let instrumentation =
+            Instrumentation::new(chase_program.rules().iter().map(|rule| rule.position()));
 
         Ok(Self {
             program: chase_program,
             analysis,
+            declared_types,
+            constraint_program,
+            violation_predicates,
+            violation_handling: parameters.constraint_violations,
+            equalities: UnionFind::default(),
+            null_predicates,
+            compute_provenance: parameters.provenance,
+            provenance_rules,
+            input_tables,
+            null_origins: Vec::new(),
+            provenance: None,
+            external_predicates: parameters.external_predicates,
+            cancellation: parameters.cancellation,
+            limits: parameters.limits,
+            reasoning_time: Duration::ZERO,
+            progress: parameters.progress,
+            instrumentation,
+            count_duplicates: parameters.count_duplicates,
+            explain: parameters.explain,
             rule_strategy,
-            input_manager,
+            rule_executions,
+            pending_rule: None,
+            new_derivations: None,
+            finished: false,
             table_manager,
             predicate_fragmentation: HashMap::new(),
             predicate_last_union: HashMap::new(),
@@ -119,21 +366,34 @@ impl<Strategy: RuleSelectionStrategy> ExecutionEngine<Strategy> {
 
     /// Add edb tables to the [TableManager]
     /// based on the import declaration of the given progam.
+    ///
+    /// Imported values of predicates with declared types are checked while loading.
     fn add_imports(
         table_manager: &mut TableManager,
         input_manager: &ImportManager,
         program: &ChaseProgram,
+        declared_types: &HashMap<Identifier, Vec<ValueType>>,
     ) -> Result<(), Error> {
         let mut predicate_to_sources = HashMap::<Identifier, Vec<TableSource>>::new();
 
         // Add all the import specifications
-        for (import_predicate, import_handler) in program.imports() {
+        for ((import_predicate, import_handler), position) in
+            program.imports().zip(program.import_positions())
+        {
             let import_arity = table_manager.arity(import_predicate);
-            let table_source = TableSource::new(
+            let mut table_source = TableSource::new(
                 input_manager.table_provider_from_handler(&**import_handler, import_arity)?,
                 import_arity,
             );
 
+            if let Some(types) = declared_types.get(import_predicate) {
+                table_source = table_source.with_validator(Box::new(DeclaredTypeValidator::new(
+                    import_predicate.clone(),
+                    types.clone(),
+                    position,
+                )));
+            }
+
             predicate_to_sources
                 .entry(import_predicate.clone())
                 .or_default()
@@ -165,83 +425,802 @@ impl<Strategy: RuleSelectionStrategy> ExecutionEngine<Strategy> {
         Ok(())
     }
 
+    /// Add the given rows as facts of the given predicate,
+    /// which are treated like the facts of the program.
+    ///
+    /// Facts can only be added before the program is executed
+    /// and for predicates that occur in the program.
+    pub fn add_facts<Rows>(&mut self, predicate: &Identifier, rows: Rows) -> Result<(), Error>
+    where
+        Rows: IntoIterator<Item = Vec<AnyDataValue>>,
+    {
+        let arity = self.input_arity(predicate)?;
+
+        let mut table = SimpleTable::new(arity);
+        for row in rows {
+            if row.len() != arity {
+                return Err(Error::FactArity {
+                    predicate: predicate.clone(),
+                    expected: arity,
+                    found: row.len(),
+                });
+            }
+
+            table.add_row(row);
+        }
+
+        self.add_input_source(predicate, TableSource::from_simple_table(table));
+
+        Ok(())
+    }
+
+    /// Add the rows of the given Arrow [RecordBatch][arrow_array::RecordBatch]es
+    /// as facts of the given predicate, which are treated like the facts of the program.
+    ///
+    /// Facts can only be added before the program is executed
+    /// and for predicates that occur in the program.
+    #[cfg(feature = "arrow")]
+    pub fn add_record_batches(
+        &mut self,
+        predicate: &Identifier,
+        batches: Vec<arrow_array::RecordBatch>,
+    ) -> Result<(), Error> {
+        let arity = self.input_arity(predicate)?;
+        let provider = crate::io::formats::arrow::ArrowTableProvider::new(batches, arity)?;
+
+        self.add_input_source(predicate, TableSource::new(Box::new(provider), arity));
+
+        Ok(())
+    }
+
+    /// Return the arity of a predicate to which facts are added.
+    ///
+    /// Returns an error if the predicate does not occur in the program
+    /// or if the program has already been executed.
+    fn input_arity(&self, predicate: &Identifier) -> Result<usize, Error> {
+        let Some(&arity) = self.analysis.all_predicates.get(predicate) else {
+            return Err(Error::UnknownPredicate(predicate.clone()));
+        };
+        if self.current_step > 1 {
+            return Err(Error::FactsAfterReasoning(predicate.clone()));
+        }
+
+        Ok(arity)
+    }
+
+    /// Add a source of facts to the input facts of the given predicate.
+    fn add_input_source(&mut self, predicate: &Identifier, mut source: TableSource) {
+        if let Some(types) = self.declared_types.get(predicate) {
+            source = source.with_validator(Box::new(DeclaredTypeValidator::new(
+                predicate.clone(),
+                types.clone(),
+                None,
+            )));
+        }
+
+        self.table_manager.add_edb_source(predicate.clone(), source);
+
+        if self.compute_provenance {
+            if let Some(table_id) = self
+                .table_manager
+                .table_id(&SubtableIdentifier::new(predicate.clone(), 0))
+            {
+                self.input_tables.insert(predicate.clone(), table_id);
+            }
+        }
+    }
+
+    /// Return the [CancellationToken] that is checked before every rule application.
+    ///
+    /// Cancelling the token (possibly from another thread)
+    /// makes [ExecutionEngine::execute] return [Error::Cancelled].
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation.clone()
+    }
+
+    /// Set the [ExecutionLimits] that are checked before every rule application.
+    ///
+    /// Reaching one of the limits makes [ExecutionEngine::execute] return [Error::LimitExceeded].
+    pub fn set_limits(&mut self, limits: ExecutionLimits) {
+        self.limits = limits;
+    }
+
+    /// Set the function that is called after every rule application.
+    pub fn set_progress_callback(&mut self, progress: Option<ProgressCallback>) {
+        self.progress = progress;
+    }
+
+    /// Return the time spent on the different phases of reasoning by this engine.
+    pub fn timing(&self) -> &TimedCode {
+        self.table_manager.timing()
+    }
+
+    /// Return a mutable reference to the timings of this engine,
+    /// which allows users to record the time spent on their own tasks next to the timings of reasoning.
+    pub fn timing_mut(&mut self) -> &mut TimedCode {
+        self.table_manager.timing_mut()
+    }
+
+    /// Return the rule counters and memory snapshots collected by this engine.
+    pub fn instrumentation(&self) -> &Instrumentation {
+        &self.instrumentation
+    }
+
+    /// Describe how the rule with the given index would be applied in the next step,
+    /// without applying it.
+    ///
+    /// Rules are indexed like [Instrumentation::rules].
+    ///
+    /// # Panics
+    /// Panics if there is no rule with the given index.
+    pub fn explain_rule(&mut self, rule: usize) -> Result<RuleExplanation, Error> {
+        self.rule_executions[rule].explain(
+            &mut self.table_manager,
+            &self.rule_infos[rule],
+            self.current_step,
+        )
+    }
+
     /// Executes the program.
     pub fn execute(&mut self) -> Result<(), Error> {
-        TimedCode::instance().sub("Reasoning/Rules").start();
-        TimedCode::instance().sub("Reasoning/Execution").start();
+        self.execute_bounded(ExecutionLimits::default()).map(|_| ())
+    }
 
-        let rule_execution: Vec<RuleExecution> = self
-            .program
-            .rules()
-            .iter()
-            .zip(self.analysis.rule_analysis.iter())
-            .map(|(r, a)| RuleExecution::initialize(r, a))
-            .collect();
+    /// Executes the program until it is finished
+    /// or until the given number of rule applications or the given time is used up.
+    ///
+    /// In the latter case, [ExecutionStatus::Paused] is returned
+    /// and reasoning continues where it stopped on the next call of this function
+    /// or of [ExecutionEngine::execute].
+    /// This is also the case if reasoning was cancelled or exceeded the limits of the engine,
+    /// once the [CancellationToken] is reset or the limits are raised.
+    pub fn execute_bounded(&mut self, bound: ExecutionLimits) -> Result<ExecutionStatus, Error> {
+        self.table_manager
+            .timing_mut()
+            .sub("Reasoning/Rules")
+            .start();
+        self.table_manager
+            .timing_mut()
+            .sub("Reasoning/Execution")
+            .start();
+
+        // Time is only measured if it is limited, since clocks are not available on all platforms
+        let start = (self.limits.time.is_some() || bound.time.is_some()).then(Instant::now);
+        let elapsed = || start.map(|start| start.elapsed()).unwrap_or_default();
+        let mut applications = 0;
+
+        let result = loop {
+            let Some(rule_index) = self.next_rule() else {
+                break Ok(ExecutionStatus::Finished);
+            };
+
+            if self.cancellation.is_cancelled() {
+                break Err(Error::Cancelled);
+            }
+            if let Some(limit) = self
+                .limits
+                .exceeded(self.current_step - 1, self.reasoning_time + elapsed())
+            {
+                break Err(Error::LimitExceeded(limit));
+            }
+            if bound.exceeded(applications, elapsed()).is_some() {
+                break Ok(ExecutionStatus::Paused);
+            }
+
+            self.pending_rule = None;
+            if let Err(error) = self.apply_rule(rule_index) {
+                break Err(error);
+            }
+            applications += 1;
+        };
+        self.reasoning_time += elapsed();
+
+        self.table_manager
+            .timing_mut()
+            .sub("Reasoning/Rules")
+            .stop();
+        self.table_manager
+            .timing_mut()
+            .sub("Reasoning/Execution")
+            .stop();
+        result
+    }
 
-        let mut new_derivations: Option<bool> = None;
+    /// Return the number of rule applications so far.
+    pub fn steps(&self) -> usize {
+        self.current_step - 1
+    }
 
-        while let Some(current_rule_index) = self.rule_strategy.next_rule(new_derivations) {
-            let timing_string = format!("Reasoning/Rules/Rule {current_rule_index}");
+    /// Return the index of the rule that was applied last, if any.
+    pub fn last_applied_rule(&self) -> Option<usize> {
+        self.rule_history
+            .last()
+            .copied()
+            .filter(|&rule| rule != usize::MAX)
+    }
 
-            TimedCode::instance().sub(&timing_string).start();
-            log::info!(
-                "<<< {0}: APPLYING RULE {current_rule_index} >>>",
-                self.current_step
-            );
+    /// Return whether all facts that follow from the program have been derived.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
 
-            self.rule_history.push(current_rule_index);
+    /// Return the rule that is applied next,
+    /// or `None` if the strategy has no further rules to apply.
+    fn next_rule(&mut self) -> Option<usize> {
+        if self.pending_rule.is_none() && !self.finished {
+            self.pending_rule = self.rule_strategy.next_rule(self.new_derivations);
+            self.finished = self.pending_rule.is_none();
+        }
 
-            let current_info = &mut self.rule_infos[current_rule_index];
-            let current_execution = &rule_execution[current_rule_index];
+        self.pending_rule
+    }
 
-            let updated_predicates = current_execution.execute(
-                &mut self.table_manager,
-                current_info,
-                self.current_step,
+    /// Apply the rule with the given index in the current step.
+    fn apply_rule(&mut self, rule_index: usize) -> Result<(), Error> {
+        let timing_string = format!("Reasoning/Rules/Rule {rule_index}");
+
+        self.table_manager.timing_mut().sub(&timing_string).start();
+        log::info!("<<< {0}: APPLYING RULE {rule_index} >>>", self.current_step);
+
+        self.rule_history.push(rule_index);
+
+        let current_info = &mut self.rule_infos[rule_index];
+        let current_execution = &self.rule_executions[rule_index];
+
+        let execution = current_execution.execute(
+            &mut self.table_manager,
+            current_info,
+            self.current_step,
+            self.count_duplicates,
+            self.explain,
+        );
+        let (updated_predicates, application) = match execution {
+            Ok(execution) => execution,
+            Err(error) => {
+                self.table_manager.timing_mut().sub(&timing_string).stop();
+                return Err(error);
+            }
+        };
+
+        if self.compute_provenance && application.nulls > 0 {
+            if let Some(origin) = self.program.rules()[rule_index].origin() {
+                let end = self.table_manager.dictionary().num_nulls();
+                self.null_origins
+                    .push(((end - application.nulls)..end, origin));
+            }
+        }
+
+        let derived = !updated_predicates.is_empty();
+        self.new_derivations = Some(derived);
+
+        current_info.step_last_applied = self.current_step;
+
+        let derived_types = self.check_derived_types(rule_index, &updated_predicates);
+
+        let rule_duration = self.table_manager.timing_mut().sub(&timing_string).stop();
+        derived_types?;
+        log::info!("Rule duration: {} ms", rule_duration.as_millis());
+        self.instrumentation.record_rule_application(
+            rule_index,
+            derived,
+            rule_duration,
+            application,
+        );
+
+        self.check_constraint_violations(&updated_predicates)?;
+        let merged = self.merge_equal_values(rule_index, &updated_predicates)?;
+        let unnested = self.unnest_tuples(&updated_predicates)?;
+        let computed = self.compute_external_predicates(&updated_predicates)?;
+        if merged || unnested || computed {
+            // Merging values, unnesting tuples or computing external facts may enable derivations
+            // of rules that were already saturated
+            self.rule_strategy = Strategy::new(
+                self.program.rules().iter().collect(),
+                self.analysis.rule_analysis.iter().collect(),
             )?;
+            self.new_derivations = None;
+        }
 
-            new_derivations = Some(!updated_predicates.is_empty());
+        // We prevent fragmentation by periodically collecting single-step tables into larger ones
+        for updated_pred in updated_predicates {
+            let counter = self
+                .predicate_fragmentation
+                .entry(updated_pred.clone())
+                .or_insert(0);
+            *counter += 1;
 
-            current_info.step_last_applied = self.current_step;
+            if *counter == MAX_FRAGMENTATION {
+                let start = if let Some(last_union) = self.predicate_last_union.get(&updated_pred) {
+                    last_union + 1
+                } else {
+                    0
+                };
 
-            let rule_duration = TimedCode::instance().sub(&timing_string).stop();
-            log::info!("Rule duration: {} ms", rule_duration.as_millis());
+                let range = start..(self.current_step + 1);
 
-            // We prevent fragmentation by periodically collecting single-step tables into larger ones
-            for updated_pred in updated_predicates {
-                let counter = self
-                    .predicate_fragmentation
-                    .entry(updated_pred.clone())
-                    .or_insert(0);
-                *counter += 1;
+                self.table_manager.combine_tables(&updated_pred, range)?;
 
-                if *counter == MAX_FRAGMENTATION {
-                    let start =
-                        if let Some(last_union) = self.predicate_last_union.get(&updated_pred) {
-                            last_union + 1
-                        } else {
-                            0
-                        };
+                self.predicate_last_union
+                    .insert(updated_pred, self.current_step);
 
-                    let range = start..(self.current_step + 1);
+                *counter = 0;
+            }
+        }
+
+        if derived && self.instrumentation.memory_snapshot_due(self.current_step) {
+            self.instrumentation
+                .record_memory(self.current_step, self.table_manager.memory_consumption());
+        }
+
+        if let Some(progress) = &self.progress {
+            progress.report(&ExecutionProgress {
+                step: self.current_step,
+                rule: rule_index,
+                derived,
+            });
+        }
 
-                    self.table_manager.combine_tables(&updated_pred, range)?;
+        self.current_step += 1;
+        Ok(())
+    }
+
+    /// Check that the tables derived by the given rule in the current step
+    /// only contain values of the declared types of their predicates.
+    fn check_derived_types(
+        &mut self,
+        rule_index: usize,
+        predicates: &[Identifier],
+    ) -> Result<(), Error> {
+        for predicate in predicates {
+            let Some(types) = self.declared_types.get(predicate) else {
+                continue;
+            };
+            let Some(table_id) = self.table_manager.table_id(&SubtableIdentifier::new(
+                predicate.clone(),
+                self.current_step,
+            )) else {
+                continue;
+            };
 
-                    self.predicate_last_union
-                        .insert(updated_pred, self.current_step);
+            let location = || TypeErrorLocation::Rule {
+                rule: rule_index.to_string(),
+                position: self.program.rules()[rule_index].position(),
+            };
 
-                    *counter = 0;
+            for row in self.table_manager.table_row_iterator(table_id)? {
+                for (index, value) in row.iter().enumerate() {
+                    check_value(predicate, types, index, value, location)?;
                 }
             }
+        }
 
-            self.current_step += 1;
+        Ok(())
+    }
+
+    /// If violations of integrity constraints abort reasoning,
+    /// return an error for the first violation derived in the current step.
+    fn check_constraint_violations(&mut self, predicates: &[Identifier]) -> Result<(), Error> {
+        if self.violation_handling != ViolationHandling::Abort {
+            return Ok(());
+        }
+
+        for violation_predicate in &self.violation_predicates {
+            if !predicates.contains(&violation_predicate.predicate) {
+                continue;
+            }
+            let Some(table_id) = self.table_manager.table_id(&SubtableIdentifier::new(
+                violation_predicate.predicate.clone(),
+                self.current_step,
+            )) else {
+                continue;
+            };
+
+            if let Some(row) = self.table_manager.table_row_iterator(table_id)?.next() {
+                return Err(Error::ConstraintViolation(Box::new(
+                    violation_predicate.violation(row),
+                )));
+            }
         }
 
-        TimedCode::instance().sub("Reasoning/Rules").stop();
-        TimedCode::instance().sub("Reasoning/Execution").stop();
         Ok(())
     }
 
+    /// Merge the values that are equated by the equality atoms derived in the current step
+    /// and replace every merged value by the representative of its equivalence class.
+    ///
+    /// Nulls are merged into constants, i.e., a constant is always the representative of its class.
+    /// Hence, only the tables of predicates that may contain nulls have to be rewritten.
+    /// Rows that contain merged values are removed from their subtables
+    /// and added to the subtable of the current step, so that rules are applied to them again,
+    /// unless an earlier subtable already contains them.
+    /// Negation and aggregation over such predicates are rejected by the program analysis,
+    /// since facts derived by them could not be retracted.
+    ///
+    /// Returns an error if two distinct constants are equated
+    /// and `true` if the tables of any predicate changed.
+    fn merge_equal_values(
+        &mut self,
+        rule_index: usize,
+        predicates: &[Identifier],
+    ) -> Result<bool, Error> {
+        let equality_predicate = Atom::equality_predicate();
+        if !predicates.contains(&equality_predicate) {
+            return Ok(false);
+        }
+        let Some(table_id) = self.table_manager.table_id(&SubtableIdentifier::new(
+            equality_predicate,
+            self.current_step,
+        )) else {
+            return Ok(false);
+        };
+
+        let is_null = |value: &AnyDataValue| value.value_domain() == ValueDomain::Null;
+
+        let mut merged = false;
+        for row in self.table_manager.table_row_iterator(table_id)? {
+            let left = self.equalities.find(&row[0]);
+            let right = self.equalities.find(&row[1]);
+
+            if left != right && !is_null(&left) && !is_null(&right) {
+                let rule = match self.program.rules()[rule_index].position() {
+                    Some(position) => format!("rule at {position}"),
+                    None => format!("rule {rule_index}"),
+                };
+
+                return Err(Error::EqualityOfConstants {
+                    rule,
+                    left: left.to_string(),
+                    right: right.to_string(),
+                });
+            }
+
+            merged |= self.equalities.union(&left, &right, |left, right| {
+                is_null(left) && !is_null(right)
+            });
+        }
+
+        if !merged {
+            return Ok(false);
+        }
+
+        let mut changed = false;
+        for predicate in self.null_predicates.clone() {
+            let arity = self.table_manager.arity(&predicate);
+            let mut merged_rows = HashSet::new();
+            let mut current_rows = Vec::new();
+
+            for (step, table_id) in self.table_manager.subtables(&predicate) {
+                let mut kept_rows = Vec::new();
+                let mut subtable_changed = false;
+
+                for row in self.table_manager.table_row_iterator(table_id)? {
+                    let merged_row = row
+                        .iter()
+                        .map(|value| self.equalities.find(value))
+                        .collect::<Vec<_>>();
+
+                    if merged_row != row {
+                        subtable_changed = true;
+                        merged_rows.insert(merged_row);
+                    } else {
+                        kept_rows.push(row);
+                    }
+                }
+
+                if step == self.current_step {
+                    current_rows = kept_rows;
+                } else if subtable_changed {
+                    let sources = Self::rows_to_sources(arity, kept_rows);
+                    self.table_manager
+                        .replace_subtable(predicate.clone(), step, sources);
+                }
+            }
+
+            if merged_rows.is_empty() {
+                continue;
+            }
+            changed = true;
+
+            // Rewritten rows may already be contained in earlier subtables
+            merged_rows.retain(|row| {
+                self.table_manager
+                    .find_table_row(&predicate, row)
+                    .is_none_or(|step| step == self.current_step)
+            });
+            merged_rows.extend(current_rows);
+            let sources = Self::rows_to_sources(arity, merged_rows.into_iter().collect());
+            self.table_manager
+                .replace_subtable(predicate.clone(), self.current_step, sources);
+
+            self.predicate_fragmentation.remove(&predicate);
+            self.predicate_last_union.remove(&predicate);
+        }
+
+        Ok(changed)
+    }
+
+    /// Collect the given rows into table sources,
+    /// which are empty if there are no rows.
+    fn rows_to_sources(arity: usize, rows: Vec<Vec<AnyDataValue>>) -> Vec<TableSource> {
+        if rows.is_empty() {
+            return Vec::new();
+        }
+
+        let mut table = SimpleTable::new(arity);
+        for row in rows {
+            table.add_row(row);
+        }
+
+        vec![TableSource::from_simple_table(table)]
+    }
+
+    /// Compute the elements of the tuples that were collected for unnesting in the current step
+    /// and add them to the tables of the unnesting predicate.
+    ///
+    /// Returns `true` if any elements were added.
+    fn unnest_tuples(&mut self, predicates: &[Identifier]) -> Result<bool, Error> {
+        let input_predicate = unnest_input_predicate();
+        if !predicates.contains(&input_predicate) {
+            return Ok(false);
+        }
+        let Some(table_id) = self
+            .table_manager
+            .table_id(&SubtableIdentifier::new(input_predicate, self.current_step))
+        else {
+            return Ok(false);
+        };
+
+        let mut unnested_rows = HashSet::new();
+        for row in self.table_manager.table_row_iterator(table_id)? {
+            unnested_rows.extend(unnest_rows(&row[0]));
+        }
+
+        if unnested_rows.is_empty() {
+            return Ok(false);
+        }
+
+        let mut table = SimpleTable::new(2);
+        for row in unnested_rows {
+            table.add_row(row);
+        }
+
+        self.table_manager.add_table_sources(
+            unnest_predicate(),
+            self.current_step,
+            vec![TableSource::from_simple_table(table)],
+        );
+
+        Ok(true)
+    }
+
+    /// Call the external predicates for the inputs that were collected in the current step
+    /// and add the computed facts to the tables of the external predicates.
+    ///
+    /// Returns `true` if any facts were added.
+    fn compute_external_predicates(&mut self, predicates: &[Identifier]) -> Result<bool, Error> {
+        let mut changed = false;
+
+        for predicate in self.external_predicates.names() {
+            let input_predicate = external_input_predicate(predicate);
+            if !predicates.contains(&input_predicate) {
+                continue;
+            }
+            let Some(table_id) = self
+                .table_manager
+                .table_id(&SubtableIdentifier::new(input_predicate, self.current_step))
+            else {
+                continue;
+            };
+            let Some(external) = self.external_predicates.get(predicate) else {
+                continue;
+            };
+
+            let mut rows = HashSet::new();
+            for inputs in self.table_manager.table_row_iterator(table_id)? {
+                rows.extend(external_rows(predicate, external, &inputs)?);
+            }
+
+            if rows.is_empty() {
+                continue;
+            }
+
+            let mut table = SimpleTable::new(external.binding_pattern().arity());
+            for row in rows {
+                table.add_row(row);
+            }
+
+            self.table_manager.add_table_sources(
+                predicate.clone(),
+                self.current_step,
+                vec![TableSource::from_simple_table(table)],
+            );
+            changed = true;
+        }
+
+        Ok(changed)
+    }
+
+    /// Return all violations of the integrity constraints of the program.
+    pub fn constraint_violations(&mut self) -> Result<Vec<ConstraintViolation>, Error> {
+        let mut result = Vec::new();
+
+        for index in 0..self.violation_predicates.len() {
+            let violation_predicate = self.violation_predicates[index].clone();
+
+            if let Some(rows) = self.predicate_rows(&violation_predicate.predicate)? {
+                result.extend(rows.map(|row| violation_predicate.violation(row)));
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Return the provenance annotations of all facts,
+    /// or `None` if provenance was not enabled in the [ExecutionParameters].
+    ///
+    /// The annotations are computed on the first call after reasoning.
+    pub fn provenance(&mut self) -> Result<Option<&Provenance>, Error> {
+        if !self.compute_provenance {
+            return Ok(None);
+        }
+
+        if self.provenance.is_none() {
+            self.table_manager.timing_mut().sub("Provenance").start();
+
+            let instance_predicates = self
+                .provenance_rules
+                .iter()
+                .map(|rule| rule.predicate.clone())
+                .collect::<HashSet<_>>();
+            let mut predicates = self
+                .analysis
+                .all_predicates
+                .keys()
+                .filter(|predicate| !instance_predicates.contains(predicate))
+                .cloned()
+                .collect::<Vec<_>>();
+            predicates.sort();
+
+            let mut builder = ProvenanceBuilder::new(self.null_origins.clone());
+
+            for predicate in &predicates {
+                if let Some(rows) = self.predicate_rows(predicate)? {
+                    for row in rows.collect::<Vec<_>>() {
+                        builder.add_fact(predicate, row);
+                    }
+                }
+            }
+
+            for predicate in &predicates {
+                let Some(&table_id) = self.input_tables.get(predicate) else {
+                    continue;
+                };
+
+                // Input facts may contain values that were merged by equality rules
+                let rows = self
+                    .table_manager
+                    .table_row_iterator(table_id)?
+                    .collect::<Vec<_>>();
+                for row in rows {
+                    let row = row
+                        .iter()
+                        .map(|value| self.equalities.find(value))
+                        .collect();
+                    builder.add_input(predicate, row);
+                }
+            }
+
+            for index in 0..self.provenance_rules.len() {
+                let rule = self.provenance_rules[index].clone();
+
+                if let Some(rows) = self.predicate_rows(&rule.predicate)? {
+                    for row in rows.collect::<Vec<_>>() {
+                        builder.add_instantiation(&rule, &row);
+                    }
+                }
+            }
+
+            self.provenance = Some(builder.finalize());
+
+            self.table_manager.timing_mut().sub("Provenance").stop();
+        }
+
+        Ok(self.provenance.as_ref())
+    }
+
+    /// Replace the result of reasoning by its core,
+    /// i.e., remove facts with redundant nulls until no proper endomorphism remains,
+    /// and return statistics about the removed facts and nulls.
+    ///
+    /// Facts of internal predicates, such as those collecting violations of integrity constraints,
+    /// are not considered. Provenance annotations and traces are still based on the derivations
+    /// of the chase, which may involve facts that are not part of the core.
+    pub fn compute_core(&mut self) -> Result<CoreStatistics, Error> {
+        self.table_manager.timing_mut().sub("Core").start();
+
+        let internal_predicates = self
+            .violation_predicates
+            .iter()
+            .map(|violation| violation.predicate.clone())
+            .chain(
+                self.provenance_rules
+                    .iter()
+                    .map(|provenance| provenance.predicate.clone()),
+            )
+            .chain(std::iter::once(Atom::equality_predicate()))
+            .collect::<HashSet<_>>();
+        let mut predicates = self
+            .analysis
+            .all_predicates
+            .keys()
+            .filter(|predicate| {
+                !internal_predicates.contains(predicate)
+                    && !is_unnest_predicate(predicate)
+                    && !is_external_input_predicate(predicate)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        predicates.sort();
+
+        let mut instance = CoreInstance::default();
+        for predicate in &predicates {
+            if let Some(rows) = self.predicate_rows(predicate)? {
+                for row in rows.collect::<Vec<_>>() {
+                    instance.add_fact(predicate, row);
+                }
+            }
+        }
+
+        let statistics = instance.compute_core();
+
+        for (predicate, rows) in instance.reduced_predicates() {
+            let mut table = SimpleTable::new(self.table_manager.arity(&predicate));
+            for row in rows {
+                table.add_row(row);
+            }
+
+            let table_id = self.table_manager.replace_predicate(
+                predicate,
+                self.current_step,
+                vec![TableSource::from_simple_table(table)],
+            );
+            // Load the table, such that its facts are counted
+            let _ = self.table_manager.table_row_iterator(table_id)?;
+        }
+
+        self.table_manager.timing_mut().sub("Core").stop();
+
+        Ok(statistics)
+    }
+
+    /// Creates an [Iterator] over all facts of a predicate, where each row is extended
+    /// by the number of derivations of the fact and a description of the sources that contribute to it.
+    ///
+    /// Returns `None` if provenance was not enabled in the [ExecutionParameters]
+    /// or the predicate has no facts.
+    pub fn predicate_rows_with_provenance(
+        &mut self,
+        predicate: &Identifier,
+    ) -> Result<Option<impl Iterator<Item = Vec<AnyDataValue>> + '_>, Error> {
+        if self.provenance()?.is_none() {
+            return Ok(None);
+        }
+        let Some(table_id) = self.table_manager.combine_predicate(predicate)? else {
+            return Ok(None);
+        };
+
+        let provenance = self
+            .provenance
+            .as_ref()
+            .expect("provenance has been computed above");
+        let rows = self.table_manager.table_row_iterator(table_id)?;
+        let predicate = predicate.clone();
+
+        Ok(Some(
+            rows.map(move |row| provenance.annotate(&predicate, row)),
+        ))
+    }
+
     /// Get a reference to the loaded program.
     pub(crate) fn program(&self) -> &ChaseProgram {
         &self.program
@@ -259,6 +1238,31 @@ impl<Strategy: RuleSelectionStrategy> ExecutionEngine<Strategy> {
         Ok(Some(self.table_manager.table_row_iterator(table_id)?))
     }
 
+    /// Return all facts of a predicate as an Arrow [RecordBatch][arrow_array::RecordBatch],
+    /// whose columns are named after their positions, starting from 0.
+    ///
+    /// Columns that only contain integers or only floating point numbers have numeric types.
+    /// Values of all other columns are represented as strings
+    /// in the same way as values of DSV files with the format `any`.
+    #[cfg(feature = "arrow")]
+    pub fn predicate_record_batch(
+        &mut self,
+        predicate: &Identifier,
+    ) -> Result<Option<arrow_array::RecordBatch>, Error> {
+        let Some(table_id) = self.table_manager.combine_predicate(predicate)? else {
+            return Ok(None);
+        };
+        let column_names = (0..self.table_manager.arity(predicate))
+            .map(|column| column.to_string())
+            .collect::<Vec<_>>();
+
+        Ok(Some(self.table_manager.table_record_batch(
+            table_id,
+            &column_names,
+            crate::io::formats::arrow::serialize_value,
+        )?))
+    }
+
     /// Returns the arity of the predicate if the predicate is known to the engine,
     /// and `None` otherwise.
     pub fn predicate_arity(&self, predicate: &Identifier) -> Option<usize> {
@@ -279,6 +1283,20 @@ impl<Strategy: RuleSelectionStrategy> ExecutionEngine<Strategy> {
         let mut result = 0;
 
         for predicate in &self.analysis.derived_predicates {
+            if self
+                .violation_predicates
+                .iter()
+                .any(|violation| &violation.predicate == predicate)
+                || self
+                    .provenance_rules
+                    .iter()
+                    .any(|provenance| &provenance.predicate == predicate)
+                || is_unnest_predicate(predicate)
+                || is_external_input_predicate(predicate)
+            {
+                continue;
+            }
+
             if let Some(count) = self.count_facts_of_predicate(predicate) {
                 result += count;
             }
@@ -378,7 +1396,15 @@ impl<Strategy: RuleSelectionStrategy> ExecutionEngine<Strategy> {
 
             let rule = self.program.rules()[rule_index].clone();
             let analysis = &self.analysis.rule_analysis[rule_index];
-            let mut variable_order = analysis.promising_variable_orders[0].clone(); // TODO: This selection is arbitrary
+            // Tables have already been loaded during the execution, so statistics are available
+            let mut variable_order =
+                JoinCostModel::from_table_manager(rule.positive_body(), &mut self.table_manager)
+                    .map(|cost_model| {
+                        let (orders, chosen) =
+                            cost_model.choose(&analysis.promising_variable_orders);
+                        orders[chosen].0.clone()
+                    })
+                    .unwrap_or_else(|_| analysis.promising_variable_orders[0].clone());
             let trace_strategy = TracingStrategy::initialize(&rule, grounding);
 
             let mut execution_plan = SubtableExecutionPlan::default();
@@ -445,6 +1471,20 @@ impl<Strategy: RuleSelectionStrategy> ExecutionEngine<Strategy> {
         trace_handle
     }
 
+    /// Build an [ExecutionTrace] for a list of [ConstraintViolation]s.
+    /// Also returns a list containing a [TraceFactHandle] for each violation.
+    pub fn trace_violations(
+        &mut self,
+        violations: &[ConstraintViolation],
+    ) -> (ExecutionTrace, Vec<TraceFactHandle>) {
+        let facts = violations
+            .iter()
+            .map(|violation| violation.fact.clone())
+            .collect();
+
+        self.trace(self.constraint_program.clone(), facts)
+    }
+
     /// Build an [ExecutionTrace] for a list of facts.
     /// Also returns a list containing a [TraceFactHandle] for each fact.
     pub fn trace(
@@ -467,3 +1507,421 @@ impl<Strategy: RuleSelectionStrategy> ExecutionEngine<Strategy> {
         (trace, handles)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use nemo_physical::datavalues::AnyDataValue;
+
+    use crate::{
+        error::Error,
+        execution::{
+            DefaultExecutionEngine, ExceededLimit, ExecutionLimits, ExecutionParameters,
+            ExecutionStatus, ProgressCallback,
+        },
+        io::{parser::parse_program, resource_providers::ResourceProviders, ImportManager},
+        model::Identifier,
+        program_analysis::analysis::RuleAnalysisError,
+    };
+
+    fn initialize(program: &str) -> DefaultExecutionEngine {
+        DefaultExecutionEngine::initialize(
+            &parse_program(program).unwrap(),
+            ImportManager::new(ResourceProviders::empty()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn equality_merges_nulls() {
+        let mut engine = initialize(
+            r#"
+            person(alice). person(bob). mother(alice, carol).
+            hasMother(?x, !m) :- person(?x) .
+            ?m = ?n :- hasMother(?x, ?m), mother(?x, ?n) .
+            "#,
+        );
+        engine.execute().unwrap();
+
+        let rows = engine
+            .predicate_rows(&Identifier("hasMother".to_string()))
+            .unwrap()
+            .unwrap()
+            .map(|row| (row[0].to_string(), row[1].to_string()))
+            .collect::<Vec<_>>();
+
+        // The null of bob is not merged
+        assert_eq!(rows.len(), 2);
+        assert!(rows.contains(&("alice".to_string(), "carol".to_string())));
+        assert!(!rows.contains(&("bob".to_string(), "carol".to_string())));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn equality_rederives_merged_facts() {
+        let mut engine = initialize(
+            r#"
+            person(alice). mother(alice, carol). famous(carol).
+            hasMother(?x, !m) :- person(?x) .
+            ?m = ?n :- hasMother(?x, ?m), mother(?x, ?n) .
+            hasFamousMother(?x) :- hasMother(?x, ?m), famous(?m) .
+            "#,
+        );
+        engine.execute().unwrap();
+
+        assert_eq!(
+            engine.count_facts_of_predicate(&Identifier("hasFamousMother".to_string())),
+            Some(1)
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn equality_does_not_duplicate_merged_facts() {
+        let mut engine = initialize(
+            r#"
+            person(alice). mother(alice, carol). known(carol).
+            hasMother(?x, !m) :- person(?x) .
+            known(?m) :- hasMother(?x, ?m) .
+            ?m = ?n :- hasMother(?x, ?m), mother(?x, ?n) .
+            "#,
+        );
+        engine.execute().unwrap();
+
+        assert_eq!(
+            engine.count_facts_of_predicate(&Identifier("known".to_string())),
+            Some(1)
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn equality_rejects_negation_of_merged_values() {
+        let result = DefaultExecutionEngine::initialize(
+            &parse_program(
+                r#"
+                person(alice). mother(alice, carol).
+                hasMother(?x, !m) :- person(?x) .
+                ?m = ?n :- hasMother(?x, ?m), mother(?x, ?n) .
+                ownMother(?x) :- person(?x), ~hasMother(?x, ?x) .
+                "#,
+            )
+            .unwrap(),
+            ImportManager::new(ResourceProviders::empty()),
+        );
+
+        assert!(matches!(
+            result,
+            Err(Error::RuleAnalysisError(
+                RuleAnalysisError::UnsupportedFeatureMergedValues { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn equality_of_constants() {
+        let mut engine = initialize(
+            r#"
+            mother(alice, carol). mother(alice, dora).
+            ?m = ?n :- mother(?x, ?m), mother(?x, ?n) .
+            "#,
+        );
+
+        assert!(matches!(
+            engine.execute(),
+            Err(Error::EqualityOfConstants { .. })
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn add_facts() {
+        let mut engine = initialize(
+            r#"
+            edge(a, b) .
+            path(?x, ?y) :- edge(?x, ?y) .
+            path(?x, ?z) :- path(?x, ?y), edge(?y, ?z) .
+            "#,
+        );
+        let edge = Identifier("edge".to_string());
+        let row = |from: &str, to: &str| {
+            vec![
+                AnyDataValue::new_iri(from.to_string()),
+                AnyDataValue::new_iri(to.to_string()),
+            ]
+        };
+
+        engine
+            .add_facts(&edge, vec![row("b", "c"), row("c", "d")])
+            .unwrap();
+        assert!(matches!(
+            engine.add_facts(&edge, vec![vec![]]),
+            Err(Error::FactArity {
+                expected: 2,
+                found: 0,
+                ..
+            })
+        ));
+        assert!(matches!(
+            engine.add_facts(&Identifier("node".to_string()), vec![]),
+            Err(Error::UnknownPredicate(_))
+        ));
+
+        engine.execute().unwrap();
+
+        let count = engine
+            .predicate_rows(&Identifier("path".to_string()))
+            .unwrap()
+            .unwrap()
+            .count();
+        assert_eq!(count, 6);
+
+        assert!(matches!(
+            engine.add_facts(&edge, vec![row("d", "e")]),
+            Err(Error::FactsAfterReasoning(_))
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn cancel_from_progress_callback() {
+        let mut engine = initialize(
+            r#"
+            number(0) .
+            next(?x, !y) :- number(?x) .
+            number(?y) :- next(?x, ?y) .
+            "#,
+        );
+
+        let token = engine.cancellation_token();
+        engine.set_progress_callback(Some(ProgressCallback::new(move |progress| {
+            if progress.step >= 10 {
+                token.cancel();
+            }
+        })));
+
+        assert!(matches!(engine.execute(), Err(Error::Cancelled)));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn execute_in_slices() {
+        let program = r#"
+            edge(a, b). edge(b, c). edge(c, d).
+            path(?x, ?y) :- edge(?x, ?y) .
+            path(?x, ?z) :- path(?x, ?y), edge(?y, ?z) .
+            "#;
+        let path = Identifier("path".to_string());
+
+        let mut engine = initialize(program);
+        let slice = ExecutionLimits {
+            steps: Some(1),
+            time: None,
+        };
+
+        let mut slices = 0;
+        while engine.execute_bounded(slice).unwrap() == ExecutionStatus::Paused {
+            slices += 1;
+            assert_eq!(engine.steps(), slices);
+            assert!(!engine.is_finished());
+        }
+        assert!(engine.is_finished());
+        assert!(engine.last_applied_rule().is_some());
+        assert_eq!(engine.count_facts_of_predicate(&path), Some(6));
+
+        let mut complete = initialize(program);
+        complete.execute().unwrap();
+        assert_eq!(complete.steps(), engine.steps());
+
+        let mut engine = initialize(program);
+        let token = engine.cancellation_token();
+        token.cancel();
+        assert!(matches!(engine.execute(), Err(Error::Cancelled)));
+        assert_eq!(engine.steps(), 0);
+
+        token.reset();
+        engine.execute().unwrap();
+        assert_eq!(engine.count_facts_of_predicate(&path), Some(6));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn step_limit() {
+        let mut engine = initialize(
+            r#"
+            number(0) .
+            next(?x, !y) :- number(?x) .
+            number(?y) :- next(?x, ?y) .
+            "#,
+        );
+        engine.set_limits(ExecutionLimits {
+            steps: Some(10),
+            time: None,
+        });
+
+        assert!(matches!(
+            engine.execute(),
+            Err(Error::LimitExceeded(ExceededLimit::Steps(10)))
+        ));
+        assert_eq!(
+            engine
+                .instrumentation()
+                .rules()
+                .iter()
+                .map(|rule| rule.applications)
+                .sum::<usize>(),
+            10
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn time_limit_across_slices() {
+        let mut engine = initialize(
+            r#"
+            number(0) .
+            next(?x, !y) :- number(?x) .
+            number(?y) :- next(?x, ?y) .
+            "#,
+        );
+        engine.set_limits(ExecutionLimits {
+            steps: None,
+            time: Some(Duration::from_millis(10)),
+        });
+        engine.set_progress_callback(Some(ProgressCallback::new(|_| {
+            std::thread::sleep(Duration::from_millis(2))
+        })));
+
+        let slice = ExecutionLimits {
+            steps: Some(1),
+            time: None,
+        };
+        let result = loop {
+            match engine.execute_bounded(slice) {
+                Ok(ExecutionStatus::Paused) => assert!(engine.steps() < 100),
+                result => break result,
+            }
+        };
+
+        assert!(matches!(
+            result,
+            Err(Error::LimitExceeded(ExceededLimit::Time(_)))
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn rule_statistics() {
+        let mut engine = initialize(
+            r#"
+            edge(a, b). edge(b, c). edge(c, d).
+            path(?x, ?y) :- edge(?x, ?y) .
+            path(?x, ?z) :- path(?x, ?y), edge(?y, ?z) .
+            "#,
+        );
+        engine.execute().unwrap();
+
+        let instrumentation = engine.instrumentation();
+        let rules = instrumentation.rules();
+
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].productive_applications, 1);
+        assert!(rules[1].applications >= rules[1].productive_applications);
+        assert!(!instrumentation.memory_snapshots().is_empty());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn detailed_rule_statistics() {
+        let program = parse_program(
+            r#"
+            edge(a, b). edge(b, c). edge(a, c).
+            path(?x, ?y) :- edge(?x, ?y) .
+            path(?x, ?z) :- path(?x, ?y), edge(?y, ?z) .
+            successor(?x, !n) :- edge(?x, ?y) .
+            "#,
+        )
+        .unwrap();
+        let mut engine = DefaultExecutionEngine::initialize_with_parameters(
+            &program,
+            ImportManager::new(ResourceProviders::empty()),
+            ExecutionParameters {
+                count_duplicates: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        engine.execute().unwrap();
+
+        let rules = engine.instrumentation().rules();
+        let path = Identifier::new("path".to_string());
+
+        assert_eq!(rules[0].derived_facts.get(&path), Some(&3));
+        assert_eq!(rules[0].position.map(|position| position.line), Some(3));
+        // `path(a, c)` is derived again from `path(a, b)` and `edge(b, c)`
+        assert_eq!(rules[1].duplicates, Some(1));
+        assert_eq!(rules[1].total_derived_facts(), 0);
+        assert_eq!(rules[2].nulls, 2);
+        assert_eq!(rules[2].total_derived_facts(), 2);
+        assert_eq!(engine.instrumentation().rules_by_cost().len(), 3);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn explain_rule_applications() {
+        let program = parse_program(
+            r#"
+            edge(a, b). edge(b, c). edge(c, d).
+            path(?x, ?y) :- edge(?x, ?y) .
+            path(?x, ?z) :- path(?x, ?y), edge(?y, ?z) .
+            "#,
+        )
+        .unwrap();
+        let mut engine = DefaultExecutionEngine::initialize_with_parameters(
+            &program,
+            ImportManager::new(ResourceProviders::empty()),
+            ExecutionParameters {
+                explain: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let planned = engine.explain_rule(1).unwrap();
+        assert!(planned.plan.tables.iter().all(|table| table.rows.is_none()));
+
+        engine.execute().unwrap();
+
+        // Only the first and the most recent application are kept
+        let explanations = engine.instrumentation().explanations(1);
+        assert!(engine.instrumentation().rules()[1].applications > 2);
+        assert_eq!(explanations.len(), 2);
+        assert!(explanations[0].step < explanations[1].step);
+
+        let first = &explanations[0];
+        assert_eq!(first.variable_order.len(), 3);
+
+        let text = first.to_string();
+        assert!(text.contains("Join (new facts of path)"));
+        assert!(text.contains("Fetch edge (step 0)"));
+
+        let stored = first
+            .plan
+            .tables
+            .iter()
+            .find(|table| table.permanent_name.is_some())
+            .expect("new facts are stored permanently");
+        // Only `path(a, c)` and `path(b, d)` are new
+        assert_eq!(stored.rows, Some(2));
+    }
+
+    #[test]
+    fn engine_is_send() {
+        // Required for reasoning in a background thread, e.g., without holding the GIL in Python
+        fn assert_send<T: Send>() {}
+        assert_send::<DefaultExecutionEngine>();
+    }
+}
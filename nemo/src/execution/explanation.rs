@@ -0,0 +1,35 @@
+//! Descriptions of how rules are evaluated, see [ExecutionEngine::explain_rule][super::ExecutionEngine::explain_rule]
+//! and [ExecutionParameters::explain][super::ExecutionParameters::explain].
+
+use std::fmt::Display;
+
+use nemo_physical::management::plan_explanation::PlanExplanation;
+
+use crate::model::Variable;
+
+/// Description of the execution plan of a single application of a rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleExplanation {
+    /// Execution step of the application
+    pub step: usize,
+    /// Order in which the variables of the rule are bound while evaluating its body
+    pub variable_order: Vec<Variable>,
+    /// Tables computed by the execution plan of the application
+    ///
+    /// Row counts are only present if the plan has been executed.
+    pub plan: PlanExplanation,
+}
+
+impl Display for RuleExplanation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let variable_order = self
+            .variable_order
+            .iter()
+            .map(|variable| variable.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        writeln!(f, "Step {}, variable order [{variable_order}]", self.step)?;
+        write!(f, "{}", self.plan)
+    }
+}
@@ -0,0 +1,655 @@
+//! Predicates whose facts are computed by external code.
+//!
+//! An external predicate has a [BindingPattern], which splits its positions into inputs and outputs.
+//! Whenever the body of a rule binds the input positions of an external atom,
+//! the [ExternalPredicate] is called with the input values and returns the matching output values.
+//!
+//! Rules that use external predicates are complemented by rules that collect
+//! all bindings of the input positions in a predicate obtained from [external_input_predicate].
+//! After each step of the reasoning process, the execution engine calls the external predicates
+//! for the newly collected inputs and adds the results as facts of the external predicate,
+//! which are then joined with the rest of the rule body.
+
+use std::{collections::HashMap, fmt::Debug, str::FromStr, sync::Arc};
+
+use nemo_physical::datavalues::AnyDataValue;
+use thiserror::Error;
+
+use crate::model::{Atom, Fact, Identifier, Literal, Program, Rule, Term};
+
+use super::unnesting::available_constraints;
+
+/// Prefix of the predicates that collect the inputs of external predicates
+const EXTERNAL_INPUT_PREDICATE_PREFIX: &str = "__EXTERNAL_INPUT_";
+
+/// Return the predicate that collects the inputs of the given external predicate.
+pub(crate) fn external_input_predicate(predicate: &Identifier) -> Identifier {
+    Identifier(format!("{EXTERNAL_INPUT_PREDICATE_PREFIX}{predicate}"))
+}
+
+/// Return whether the given predicate is used internally for collecting inputs of external predicates.
+pub(crate) fn is_external_input_predicate(predicate: &Identifier) -> bool {
+    predicate.0.starts_with(EXTERNAL_INPUT_PREDICATE_PREFIX)
+}
+
+/// Whether a position of an external predicate is an input or an output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Binding {
+    /// Values of this position have to be bound by the rule body
+    /// and are passed to the external predicate
+    Input,
+    /// Values of this position are computed by the external predicate
+    Output,
+}
+
+/// Inputs and outputs of the positions of an external predicate
+///
+/// A binding pattern can be parsed from a string such as `"bbf"`,
+/// where `b` marks a bound input position and `f` marks a free output position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BindingPattern(Vec<Binding>);
+
+impl BindingPattern {
+    /// Construct a new [BindingPattern].
+    pub fn new(bindings: Vec<Binding>) -> Self {
+        Self(bindings)
+    }
+
+    /// Return the [Binding]s of the positions of the predicate.
+    pub fn bindings(&self) -> &[Binding] {
+        &self.0
+    }
+
+    /// Return the arity of the predicate.
+    pub fn arity(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Return the input positions of the predicate.
+    pub fn input_positions(&self) -> impl Iterator<Item = usize> + '_ {
+        self.positions(Binding::Input)
+    }
+
+    /// Return the output positions of the predicate.
+    pub fn output_positions(&self) -> impl Iterator<Item = usize> + '_ {
+        self.positions(Binding::Output)
+    }
+
+    fn positions(&self, binding: Binding) -> impl Iterator<Item = usize> + '_ {
+        self.0
+            .iter()
+            .enumerate()
+            .filter(move |(_, &b)| b == binding)
+            .map(|(position, _)| position)
+    }
+}
+
+impl FromStr for BindingPattern {
+    type Err = ExternalPredicateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.chars()
+            .map(|character| match character {
+                'b' => Ok(Binding::Input),
+                'f' => Ok(Binding::Output),
+                _ => Err(ExternalPredicateError::InvalidBindingPattern(s.to_string())),
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+/// Error reported by the implementation of an [ExternalPredicate]
+pub type ExternalEvaluationError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A predicate whose facts are computed by external code
+///
+/// The predicate is assumed to be deterministic,
+/// i.e., it returns the same results whenever it is called with the same inputs.
+pub trait ExternalPredicate: Debug + Send + Sync {
+    /// Return the [BindingPattern] of this predicate.
+    fn binding_pattern(&self) -> BindingPattern;
+
+    /// Compute the facts of this predicate for the given values of the input positions.
+    ///
+    /// Returns the values of the output positions of each matching fact.
+    fn evaluate(
+        &self,
+        inputs: &[AnyDataValue],
+    ) -> Result<Vec<Vec<AnyDataValue>>, ExternalEvaluationError>;
+}
+
+/// [ExternalPredicate] that is implemented by a closure
+struct ClosurePredicate<Function> {
+    /// Inputs and outputs of the predicate
+    pattern: BindingPattern,
+    /// Implementation of the predicate
+    function: Function,
+}
+
+impl<Function> Debug for ClosurePredicate<Function> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClosurePredicate")
+            .field("pattern", &self.pattern)
+            .finish()
+    }
+}
+
+impl<Function> ExternalPredicate for ClosurePredicate<Function>
+where
+    Function: Fn(&[AnyDataValue]) -> Result<Vec<Vec<AnyDataValue>>, ExternalEvaluationError>
+        + Send
+        + Sync,
+{
+    fn binding_pattern(&self) -> BindingPattern {
+        self.pattern.clone()
+    }
+
+    fn evaluate(
+        &self,
+        inputs: &[AnyDataValue],
+    ) -> Result<Vec<Vec<AnyDataValue>>, ExternalEvaluationError> {
+        (self.function)(inputs)
+    }
+}
+
+/// Errors related to external predicates
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ExternalPredicateError {
+    /// A binding pattern contains characters other than `b` and `f`.
+    #[error(
+        r#"invalid binding pattern "{0}", expected a sequence of "b" (input) and "f" (output)"#
+    )]
+    InvalidBindingPattern(String),
+    /// An external predicate with the same name is already registered.
+    #[error(r#"external predicate "{0}" is already registered"#)]
+    Duplicate(Identifier),
+    /// An external predicate is derived by a rule or a fact.
+    #[error(r#"external predicate "{0}" must not occur in the head of a rule or in a fact"#)]
+    Derived(Identifier),
+    /// An external predicate occurs in a negated atom.
+    #[error(r#"external predicate "{0}" must not occur in a negated atom"#)]
+    Negated(Identifier),
+    /// An external predicate is used with a different arity than its binding pattern.
+    #[error(
+        r#"external predicate "{predicate}" has arity {expected}, but is used with arity {found}"#
+    )]
+    Arity {
+        /// The external predicate
+        predicate: Identifier,
+        /// Arity of the binding pattern
+        expected: usize,
+        /// Arity of the atom
+        found: usize,
+    },
+    /// An input position of an external atom is not bound by the rest of the rule body.
+    #[error(
+        r#"input position {position} of external predicate "{predicate}" is not bound in {rule}"#
+    )]
+    UnboundInput {
+        /// The external predicate
+        predicate: Identifier,
+        /// The unbound position, starting from 0
+        position: usize,
+        /// Description of the rule by its position in the source text or the rule itself
+        rule: String,
+    },
+    /// The external code returned values that do not match the binding pattern.
+    #[error(r#"external predicate "{predicate}" returned {found} values, but has {expected} output positions"#)]
+    OutputArity {
+        /// The external predicate
+        predicate: Identifier,
+        /// Number of output positions
+        expected: usize,
+        /// Number of returned values
+        found: usize,
+    },
+    /// The external code reported an error.
+    #[error(r#"computation of external predicate "{predicate}" failed: {message}"#)]
+    Evaluation {
+        /// The external predicate
+        predicate: Identifier,
+        /// Message of the reported error
+        message: String,
+    },
+}
+
+/// Collection of [ExternalPredicate]s that may be used in the bodies of rules
+#[derive(Debug, Clone, Default)]
+pub struct ExternalPredicates {
+    /// Registered predicates by their names
+    predicates: HashMap<Identifier, Arc<dyn ExternalPredicate>>,
+}
+
+impl ExternalPredicates {
+    /// Construct a new, empty collection of [ExternalPredicate]s.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the given [ExternalPredicate] under the given name.
+    ///
+    /// Returns an error if the name is already used by another external predicate.
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        predicate: impl ExternalPredicate + 'static,
+    ) -> Result<(), ExternalPredicateError> {
+        let name = Identifier(name.into());
+
+        if self.predicates.contains_key(&name) {
+            return Err(ExternalPredicateError::Duplicate(name));
+        }
+        self.predicates.insert(name, Arc::new(predicate));
+
+        Ok(())
+    }
+
+    /// Register an external predicate with the given name and [BindingPattern]
+    /// that is implemented by the given closure.
+    ///
+    /// Returns an error if the name is already used by another external predicate.
+    pub fn register_fn<Function>(
+        &mut self,
+        name: impl Into<String>,
+        pattern: BindingPattern,
+        function: Function,
+    ) -> Result<(), ExternalPredicateError>
+    where
+        Function: Fn(&[AnyDataValue]) -> Result<Vec<Vec<AnyDataValue>>, ExternalEvaluationError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.register(name, ClosurePredicate { pattern, function })
+    }
+
+    /// Return the external predicate with the given name, if any.
+    pub fn get(&self, predicate: &Identifier) -> Option<&dyn ExternalPredicate> {
+        self.predicates.get(predicate).map(|predicate| &**predicate)
+    }
+
+    /// Return whether no external predicates are registered.
+    pub fn is_empty(&self) -> bool {
+        self.predicates.is_empty()
+    }
+
+    /// Return an iterator over the names of all registered predicates.
+    pub fn names(&self) -> impl Iterator<Item = &Identifier> {
+        self.predicates.keys()
+    }
+}
+
+/// Compute the facts of the given external predicate for the given values of its input positions.
+pub(crate) fn external_rows(
+    predicate: &Identifier,
+    external: &dyn ExternalPredicate,
+    inputs: &[AnyDataValue],
+) -> Result<Vec<Vec<AnyDataValue>>, ExternalPredicateError> {
+    let pattern = external.binding_pattern();
+    let expected = pattern.output_positions().count();
+
+    let outputs =
+        external
+            .evaluate(inputs)
+            .map_err(|error| ExternalPredicateError::Evaluation {
+                predicate: predicate.clone(),
+                message: error.to_string(),
+            })?;
+
+    outputs
+        .into_iter()
+        .map(|output| {
+            if output.len() != expected {
+                return Err(ExternalPredicateError::OutputArity {
+                    predicate: predicate.clone(),
+                    expected,
+                    found: output.len(),
+                });
+            }
+
+            let mut inputs = inputs.iter();
+            let mut output = output.into_iter();
+            Ok(pattern
+                .bindings()
+                .iter()
+                .filter_map(|binding| match binding {
+                    Binding::Input => inputs.next().cloned(),
+                    Binding::Output => output.next(),
+                })
+                .collect())
+        })
+        .collect()
+}
+
+/// Return a copy of the given program, where rules that use external predicates
+/// are complemented by rules that collect the inputs of the external predicates.
+///
+/// Returns an error if external predicates are derived, negated, used with the wrong arity,
+/// or if their inputs are not bound by the rest of the rule body.
+pub(crate) fn add_external_input_rules(
+    program: &Program,
+    predicates: &ExternalPredicates,
+) -> Result<Program, ExternalPredicateError> {
+    if predicates.is_empty() {
+        return Ok(program.clone());
+    }
+
+    if let Some(fact) = program
+        .facts()
+        .iter()
+        .find(|fact| predicates.get(&fact.0.predicate()).is_some())
+    {
+        return Err(ExternalPredicateError::Derived(fact.0.predicate()));
+    }
+
+    let mut input_rules = Vec::new();
+    let mut input_facts = Vec::new();
+    for rule in program.rules() {
+        if let Some(atom) = rule
+            .head()
+            .iter()
+            .find(|atom| predicates.get(&atom.predicate()).is_some())
+        {
+            return Err(ExternalPredicateError::Derived(atom.predicate()));
+        }
+
+        add_input_rules(rule, predicates, &mut input_rules, &mut input_facts)?;
+    }
+
+    let mut result = program.clone();
+    let input_predicates = input_rules
+        .iter()
+        .flat_map(|rule: &Rule| rule.head().iter().map(Atom::predicate))
+        .chain(input_facts.iter().map(|fact: &Fact| fact.0.predicate()))
+        .collect::<Vec<_>>();
+
+    result.add_internal_outputs(input_predicates);
+    result.add_rules(input_rules);
+    result.add_facts(input_facts);
+
+    Ok(result)
+}
+
+/// Add the rules or facts that collect the inputs of the external atoms in the body of the given rule
+/// to `input_rules` or `input_facts`, respectively.
+fn add_input_rules(
+    rule: &Rule,
+    predicates: &ExternalPredicates,
+    input_rules: &mut Vec<Rule>,
+    input_facts: &mut Vec<Fact>,
+) -> Result<(), ExternalPredicateError> {
+    let mut body = Vec::new();
+    let mut remaining = Vec::new();
+
+    for literal in rule.body() {
+        let predicate = literal.predicate();
+        let Some(external) = predicates.get(&predicate) else {
+            if literal.is_positive() {
+                body.push(literal.clone());
+            }
+            continue;
+        };
+
+        if !literal.is_positive() {
+            return Err(ExternalPredicateError::Negated(predicate));
+        }
+
+        let pattern = external.binding_pattern();
+        if pattern.arity() != literal.terms().len() {
+            return Err(ExternalPredicateError::Arity {
+                predicate,
+                expected: pattern.arity(),
+                found: literal.terms().len(),
+            });
+        }
+
+        let inputs = pattern
+            .input_positions()
+            .map(|position| (position, literal.terms()[position].clone()))
+            .collect::<Vec<_>>();
+        remaining.push((literal, inputs));
+    }
+
+    // Inputs are collected in an order in which each external atom
+    // only depends on the outputs of external atoms that were collected before
+    while !remaining.is_empty() {
+        let (available, available_constraints) = available_constraints(&body, rule.constraints());
+        let is_bound = |term: &Term| {
+            term.variables()
+                .all(|variable| available.contains(variable))
+        };
+
+        let next = remaining
+            .iter()
+            .position(|(_, inputs)| inputs.iter().all(|(_, term)| is_bound(term)));
+        let Some(next) = next else {
+            let (literal, inputs) = &remaining[0];
+            let (position, _) = inputs
+                .iter()
+                .find(|(_, term)| !is_bound(term))
+                .expect("some input is unbound");
+
+            return Err(ExternalPredicateError::UnboundInput {
+                predicate: literal.predicate(),
+                position: *position,
+                rule: match rule.position() {
+                    Some(position) => format!("rule at {position}"),
+                    None => format!("rule {rule}"),
+                },
+            });
+        };
+        let (literal, inputs) = remaining.remove(next);
+
+        let input_atom = Atom::new(
+            external_input_predicate(&literal.predicate()),
+            inputs.into_iter().map(|(_, term)| term).collect(),
+        );
+
+        if body.is_empty() && available_constraints.is_empty() {
+            input_facts.push(Fact(input_atom));
+        } else {
+            let mut input_rule = Rule::new(vec![input_atom], body.clone(), available_constraints);
+            if let Some(position) = rule.position() {
+                input_rule.set_position(position);
+            }
+            input_rules.push(input_rule);
+        }
+
+        body.push(Literal::Positive(literal.atom().clone()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use nemo_physical::datavalues::{AnyDataValue, DataValue};
+
+    use crate::{
+        error::Error,
+        execution::{DefaultExecutionEngine, ExecutionParameters},
+        io::{parser::parse_program, resource_providers::ResourceProviders, ImportManager},
+        model::{Identifier, Program},
+    };
+
+    use super::{
+        add_external_input_rules, external_rows, Binding, BindingPattern, ExternalPredicateError,
+        ExternalPredicates,
+    };
+
+    fn integer(value: i64) -> AnyDataValue {
+        AnyDataValue::new_integer_from_i64(value)
+    }
+
+    /// Predicate `divides(?x, ?y)`, which returns the divisors `?y` of `?x`
+    fn predicates() -> ExternalPredicates {
+        let mut predicates = ExternalPredicates::new();
+        predicates
+            .register_fn("divides", "bf".parse().unwrap(), |inputs| {
+                let number = inputs[0].to_i64().ok_or("not an integer")?;
+                Ok((1..=number)
+                    .filter(|divisor| number % divisor == 0)
+                    .map(|divisor| vec![integer(divisor)])
+                    .collect())
+            })
+            .unwrap();
+
+        predicates
+    }
+
+    fn rules(program: &Program) -> Vec<String> {
+        program.rules().iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn parse_binding_pattern() {
+        assert_eq!(
+            "bfb".parse::<BindingPattern>(),
+            Ok(BindingPattern::new(vec![
+                Binding::Input,
+                Binding::Output,
+                Binding::Input
+            ]))
+        );
+        assert_eq!(
+            "bx".parse::<BindingPattern>(),
+            Err(ExternalPredicateError::InvalidBindingPattern(
+                "bx".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn compute_rows() {
+        let predicates = predicates();
+        let divides = Identifier::new("divides".to_string());
+
+        assert_eq!(
+            external_rows(&divides, predicates.get(&divides).unwrap(), &[integer(6)]),
+            Ok(vec![
+                vec![integer(6), integer(1)],
+                vec![integer(6), integer(2)],
+                vec![integer(6), integer(3)],
+                vec![integer(6), integer(6)],
+            ])
+        );
+        assert!(matches!(
+            external_rows(
+                &divides,
+                predicates.get(&divides).unwrap(),
+                &[AnyDataValue::new_plain_string("six".to_string())]
+            ),
+            Err(ExternalPredicateError::Evaluation { .. })
+        ));
+    }
+
+    #[test]
+    fn rewrite_external_atoms() {
+        let program = parse_program(
+            r#"
+            r(?x, ?z) :- p(?x), divides(?x, ?y), ?y > 1, ?w = ?y * 2, divides(?w, ?z) .
+            s(?y) :- divides(12, ?y) .
+            "#,
+        )
+        .unwrap();
+        let rewritten = add_external_input_rules(&program, &predicates()).unwrap();
+
+        assert_eq!(
+            rules(&rewritten),
+            vec![
+                "r(?x, ?z) :- p(?x), divides(?x, ?y), divides(?w, ?z), ?y > 1, ?w = ?y * 2 .",
+                "s(?y) :- divides(12, ?y) .",
+                "__EXTERNAL_INPUT_divides(?x) :- p(?x) .",
+                "__EXTERNAL_INPUT_divides(?w) :- p(?x), divides(?x, ?y), ?y > 1, ?w = ?y * 2 .",
+            ]
+        );
+        assert_eq!(
+            rewritten
+                .facts()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["__EXTERNAL_INPUT_divides(12)"]
+        );
+    }
+
+    #[test]
+    fn reject_invalid_uses() {
+        let predicates = predicates();
+        let rewrite = |program: &str| {
+            add_external_input_rules(&parse_program(program).unwrap(), &predicates)
+                .map(|_| ())
+                .unwrap_err()
+        };
+
+        assert!(matches!(
+            rewrite("divides(?x, ?x) :- p(?x) ."),
+            ExternalPredicateError::Derived(_)
+        ));
+        assert!(matches!(
+            rewrite("r(?x) :- p(?x), ~divides(?x, 2) ."),
+            ExternalPredicateError::Negated(_)
+        ));
+        assert!(matches!(
+            rewrite("r(?x) :- p(?x), divides(?x) ."),
+            ExternalPredicateError::Arity {
+                expected: 2,
+                found: 1,
+                ..
+            }
+        ));
+        assert!(matches!(
+            rewrite("r(?x) :- divides(?y, ?x) ."),
+            ExternalPredicateError::UnboundInput { position: 0, .. }
+        ));
+    }
+
+    fn execute(program: &str) -> Result<DefaultExecutionEngine, Error> {
+        let parameters = ExecutionParameters {
+            external_predicates: predicates(),
+            ..Default::default()
+        };
+        let mut engine = DefaultExecutionEngine::initialize_with_parameters(
+            &parse_program(program).unwrap(),
+            ImportManager::new(ResourceProviders::empty()),
+            parameters,
+        )?;
+        engine.execute()?;
+
+        Ok(engine)
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn reason_with_external_predicates() {
+        let mut engine = execute(
+            r#"
+            number(12) .
+            number(?y) :- number(?x), divides(?x, ?y), ?y < ?x, ?y > 2 .
+            "#,
+        )
+        .unwrap();
+
+        let mut numbers = engine
+            .predicate_rows(&Identifier::new("number".to_string()))
+            .unwrap()
+            .unwrap()
+            .map(|row| row[0].to_i64().unwrap())
+            .collect::<Vec<_>>();
+        numbers.sort();
+        assert_eq!(numbers, vec![3, 4, 6, 12]);
+
+        assert!(matches!(
+            execute(
+                r#"
+                number("twelve") .
+                number(?y) :- number(?x), divides(?x, ?y) .
+                "#,
+            ),
+            Err(Error::ExternalPredicateError(
+                ExternalPredicateError::Evaluation { .. }
+            ))
+        ));
+    }
+}
@@ -0,0 +1,248 @@
+//! Measurements that are collected while an [ExecutionEngine][super::ExecutionEngine] evaluates a program.
+//!
+//! Timings of the individual phases of reasoning are recorded in a [TimedCode][nemo_physical::meta::timing::TimedCode]
+//! that is owned by the engine, see [ExecutionEngine::timing][super::ExecutionEngine::timing].
+
+use std::{collections::HashMap, time::Duration};
+
+use bytesize::ByteSize;
+
+use crate::model::{Identifier, SourcePosition};
+
+use super::explanation::RuleExplanation;
+
+/// Number of execution steps between two [MemorySnapshot]s,
+/// before any snapshots have been thinned out
+const MEMORY_SNAPSHOT_INTERVAL: usize = 16;
+/// Maximal number of [MemorySnapshot]s that are kept
+const MAX_MEMORY_SNAPSHOTS: usize = 1024;
+/// Maximal number of [RuleExplanation]s that are kept for each rule,
+/// namely those of its first and its most recent application
+const MAX_RULE_EXPLANATIONS: usize = 2;
+
+/// Statistics about the applications of a single rule
+///
+/// Times are zero if the crate is compiled without the `timing` feature.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RuleStatistics {
+    /// Position of the rule in the source text, if it was parsed
+    pub position: Option<SourcePosition>,
+    /// Number of times the rule was applied
+    pub applications: usize,
+    /// Number of applications that derived new facts
+    pub productive_applications: usize,
+    /// Total (thread) time spent on applying the rule
+    pub time: Duration,
+    /// Time spent on computing the matches of the body
+    pub body_time: Duration,
+    /// Time spent on computing the facts of the head from the matches of the body
+    pub head_time: Duration,
+    /// Number of new facts derived for each predicate in the head
+    pub derived_facts: HashMap<Identifier, usize>,
+    /// Number of facts that were derived again and therefore discarded,
+    /// if counting them was enabled with
+    /// [ExecutionParameters::count_duplicates][super::execution_engine::ExecutionParameters::count_duplicates]
+    pub duplicates: Option<usize>,
+    /// Number of nulls created for existential variables
+    pub nulls: usize,
+}
+
+impl RuleStatistics {
+    /// Return the total number of new facts derived by the rule.
+    pub fn total_derived_facts(&self) -> usize {
+        self.derived_facts.values().sum()
+    }
+}
+
+/// Measurements of a single application of a rule
+#[derive(Debug, Default, Clone)]
+pub(crate) struct RuleApplication {
+    /// Time spent on computing the matches of the body
+    pub(crate) body_time: Duration,
+    /// Time spent on computing the facts of the head
+    pub(crate) head_time: Duration,
+    /// Number of new facts for each predicate in the head
+    pub(crate) derived_facts: HashMap<Identifier, usize>,
+    /// Number of discarded facts, if they were counted
+    pub(crate) duplicates: Option<usize>,
+    /// Number of created nulls
+    pub(crate) nulls: usize,
+    /// Description of the execution plan, if it was requested
+    pub(crate) explanation: Option<RuleExplanation>,
+}
+
+/// Memory consumed by the tables of the engine after an execution step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemorySnapshot {
+    /// The execution step after which the snapshot was taken
+    pub step: usize,
+    /// Memory consumed by all tables
+    pub memory: ByteSize,
+}
+
+/// Counters and memory snapshots of an [ExecutionEngine][super::ExecutionEngine]
+#[derive(Debug, Default, Clone)]
+pub struct Instrumentation {
+    /// Statistics for each rule of the program, indexed like the rules
+    rules: Vec<RuleStatistics>,
+    /// Memory consumption after some of the steps that derived new facts
+    memory: Vec<MemorySnapshot>,
+    /// How often the snapshots were thinned out,
+    /// which doubles the interval between them each time
+    memory_thinning: u32,
+    /// Largest memory consumption of all snapshots that were taken
+    peak_memory: ByteSize,
+    /// Execution plans of the first and the most recent application of each rule,
+    /// if they are explained
+    explanations: Vec<Vec<RuleExplanation>>,
+}
+
+impl Instrumentation {
+    /// Create a new [Instrumentation] for a program whose rules are at the given positions.
+    pub(crate) fn new(rule_positions: impl IntoIterator<Item = Option<SourcePosition>>) -> Self {
+        let rules = rule_positions
+            .into_iter()
+            .map(|position| RuleStatistics {
+                position,
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            explanations: vec![Vec::new(); rules.len()],
+            rules,
+            memory: Vec::new(),
+            memory_thinning: 0,
+            peak_memory: ByteSize::default(),
+        }
+    }
+
+    /// Return the [RuleStatistics] of every rule, indexed like the rules of the program.
+    pub fn rules(&self) -> &[RuleStatistics] {
+        &self.rules
+    }
+
+    /// Return the [RuleExplanation]s of the first and the most recent application
+    /// of the rule with the given index, in the order of these applications,
+    /// which are only recorded if [ExecutionParameters::explain][super::execution_engine::ExecutionParameters::explain] is set.
+    ///
+    /// Explanations of the applications in between are dropped,
+    /// so that their number does not grow with the number of execution steps.
+    pub fn explanations(&self, rule: usize) -> &[RuleExplanation] {
+        &self.explanations[rule]
+    }
+
+    /// Return the [MemorySnapshot]s taken after steps that derived new facts.
+    ///
+    /// Snapshots are only taken every few steps and at most a fixed number of them is kept.
+    /// Once this number is reached, every second snapshot is dropped
+    /// and the interval between snapshots is doubled.
+    pub fn memory_snapshots(&self) -> &[MemorySnapshot] {
+        &self.memory
+    }
+
+    /// Return the largest amount of memory consumed by the tables of the engine
+    /// in any of the snapshots taken so far.
+    pub fn peak_memory(&self) -> ByteSize {
+        self.peak_memory
+    }
+
+    /// Record an application of the rule with the given index.
+    pub(crate) fn record_rule_application(
+        &mut self,
+        rule: usize,
+        derived: bool,
+        time: Duration,
+        application: RuleApplication,
+    ) {
+        let statistics = &mut self.rules[rule];
+
+        statistics.applications += 1;
+        if derived {
+            statistics.productive_applications += 1;
+        }
+        statistics.time += time;
+        statistics.body_time += application.body_time;
+        statistics.head_time += application.head_time;
+        for (predicate, facts) in application.derived_facts {
+            *statistics.derived_facts.entry(predicate).or_default() += facts;
+        }
+        if let Some(duplicates) = application.duplicates {
+            *statistics.duplicates.get_or_insert(0) += duplicates;
+        }
+        statistics.nulls += application.nulls;
+
+        if let Some(explanation) = application.explanation {
+            let explanations = &mut self.explanations[rule];
+            if explanations.len() == MAX_RULE_EXPLANATIONS {
+                explanations.pop();
+            }
+            explanations.push(explanation);
+        }
+    }
+
+    /// Return the indices of all rules that were applied,
+    /// sorted by decreasing time spent on applying them.
+    pub fn rules_by_cost(&self) -> Vec<usize> {
+        let mut rules = (0..self.rules.len())
+            .filter(|&rule| self.rules[rule].applications > 0)
+            .collect::<Vec<_>>();
+        rules.sort_by_key(|&rule| {
+            let statistics = &self.rules[rule];
+            std::cmp::Reverse((statistics.time, statistics.total_derived_facts()))
+        });
+
+        rules
+    }
+
+    /// Return whether a [MemorySnapshot] should be taken after the given step.
+    pub(crate) fn memory_snapshot_due(&self, step: usize) -> bool {
+        let interval = MEMORY_SNAPSHOT_INTERVAL << self.memory_thinning;
+        self.memory
+            .last()
+            .is_none_or(|snapshot| step >= snapshot.step + interval)
+    }
+
+    /// Record the memory consumption after the given step.
+    pub(crate) fn record_memory(&mut self, step: usize, memory: ByteSize) {
+        self.peak_memory = self.peak_memory.max(memory);
+        self.memory.push(MemorySnapshot { step, memory });
+
+        if self.memory.len() >= MAX_MEMORY_SNAPSHOTS {
+            let mut index = 0;
+            self.memory.retain(|_| {
+                index += 1;
+                index % 2 == 1
+            });
+            self.memory_thinning += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bytesize::ByteSize;
+
+    use super::{Instrumentation, MAX_MEMORY_SNAPSHOTS, MEMORY_SNAPSHOT_INTERVAL};
+
+    #[test]
+    fn memory_snapshots_are_capped() {
+        let mut instrumentation = Instrumentation::new([]);
+
+        let mut recorded = 0;
+        let mut last_step = 0;
+        for step in 1..(MEMORY_SNAPSHOT_INTERVAL * MAX_MEMORY_SNAPSHOTS * 4) {
+            if instrumentation.memory_snapshot_due(step) {
+                instrumentation.record_memory(step, ByteSize::b(step as u64));
+                recorded += 1;
+                last_step = step;
+            }
+        }
+
+        let snapshots = instrumentation.memory_snapshots();
+        assert!(snapshots.len() < MAX_MEMORY_SNAPSHOTS);
+        assert!(recorded < 4 * MAX_MEMORY_SNAPSHOTS);
+        assert!(snapshots.windows(2).all(|pair| pair[0].step < pair[1].step));
+        assert_eq!(instrumentation.peak_memory(), ByteSize::b(last_step as u64));
+    }
+}
@@ -0,0 +1,198 @@
+//! Checking of the integrity constraints of a program.
+
+use nemo_physical::datavalues::AnyDataValue;
+
+use crate::model::{
+    Atom, Fact, Identifier, IntegrityConstraint, PrimitiveTerm, Program, Term, Variable,
+};
+
+/// Prefix of the predicates that collect the violations of integrity constraints
+const VIOLATION_PREDICATE_PREFIX: &str = "VIOLATION_OF_CONSTRAINT_";
+
+/// Determines what happens if an integrity constraint is violated
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ViolationHandling {
+    /// Stop reasoning with an error as soon as a violation is derived
+    #[default]
+    Abort,
+    /// Complete reasoning and report all violations afterwards
+    Report,
+}
+
+/// An instantiation of the body of an [IntegrityConstraint] that holds in the result of a program
+#[derive(Debug, Clone)]
+pub struct ConstraintViolation {
+    /// The violated constraint
+    constraint: IntegrityConstraint,
+    /// Values of the variables of the constraint
+    assignment: Vec<(Variable, AnyDataValue)>,
+    /// Fact derived for this violation
+    pub(crate) fact: Fact,
+}
+
+impl ConstraintViolation {
+    /// Return the violated constraint.
+    pub fn constraint(&self) -> &IntegrityConstraint {
+        &self.constraint
+    }
+
+    /// Return the values of the variables of the constraint for this violation.
+    pub fn assignment(&self) -> &[(Variable, AnyDataValue)] {
+        &self.assignment
+    }
+}
+
+impl std::fmt::Display for ConstraintViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let assignment = self
+            .assignment
+            .iter()
+            .map(|(variable, value)| format!("{variable} = {value}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        write!(f, "{} is violated for {assignment}", self.constraint)
+    }
+}
+
+/// An [IntegrityConstraint] of a program together with the predicate that collects its violations
+#[derive(Debug, Clone)]
+pub(crate) struct ViolationPredicate {
+    /// Predicate that collects the violations
+    pub(crate) predicate: Identifier,
+    /// The checked constraint
+    pub(crate) constraint: IntegrityConstraint,
+    /// Variables of the constraint, in the order of the positions of the predicate
+    pub(crate) variables: Vec<Variable>,
+}
+
+impl ViolationPredicate {
+    /// Create a [ConstraintViolation] from a row of the table of the violation predicate.
+    pub(crate) fn violation(&self, row: Vec<AnyDataValue>) -> ConstraintViolation {
+        let fact = Fact(Atom::new(
+            self.predicate.clone(),
+            row.iter()
+                .cloned()
+                .map(|value| Term::Primitive(PrimitiveTerm::GroundTerm(value)))
+                .collect(),
+        ));
+
+        ConstraintViolation {
+            constraint: self.constraint.clone(),
+            assignment: self.variables.iter().cloned().zip(row).collect(),
+            fact,
+        }
+    }
+}
+
+/// Return a copy of the given program with additional rules
+/// that derive the violations of each of its integrity constraints,
+/// together with the predicates that collect these violations.
+///
+/// The additional rules are added after the rules of the given program.
+pub(crate) fn add_violation_rules(program: &Program) -> (Program, Vec<ViolationPredicate>) {
+    let mut violation_predicates = Vec::new();
+    let mut rules = Vec::new();
+
+    for (index, constraint) in program.constraints().iter().enumerate() {
+        let predicate = Identifier(format!("{VIOLATION_PREDICATE_PREFIX}{index}"));
+        let denial_rules = constraint.denial_rules();
+
+        let Some(first) = denial_rules.first() else {
+            continue;
+        };
+        let variables = first.variables();
+
+        rules.extend(
+            denial_rules
+                .iter()
+                .map(|rule| rule.to_rule(predicate.clone())),
+        );
+        violation_predicates.push(ViolationPredicate {
+            predicate,
+            constraint: constraint.clone(),
+            variables,
+        });
+    }
+
+    let mut result = program.clone();
+
+    result.add_internal_outputs(
+        violation_predicates
+            .iter()
+            .map(|violation| violation.predicate.clone()),
+    );
+    result.add_rules(rules);
+
+    (result, violation_predicates)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        error::Error,
+        execution::{DefaultExecutionEngine, ExecutionParameters},
+        io::{parser::parse_program, resource_providers::ResourceProviders, ImportManager},
+    };
+
+    use super::ViolationHandling;
+
+    const PROGRAM: &str = r#"
+        person(alice, 30). person(bob, 40). person(alice, 31). person(carol, 20).
+        parent(alice, bob). parent(bob, carol).
+        ancestor(?x, ?y) :- parent(?x, ?y) .
+        ancestor(?x, ?z) :- ancestor(?x, ?y), parent(?y, ?z) .
+        @key person(?name, _) .
+        ⊥ :- ancestor(?x, ?y), person(?x, ?a), person(?y, ?b), ?a < ?b .
+        false :- ancestor(?x, ?x) .
+    "#;
+
+    fn initialize(constraint_violations: ViolationHandling) -> DefaultExecutionEngine {
+        let parameters = ExecutionParameters {
+            constraint_violations,
+            ..Default::default()
+        };
+
+        DefaultExecutionEngine::initialize_with_parameters(
+            &parse_program(PROGRAM).unwrap(),
+            ImportManager::new(ResourceProviders::empty()),
+            parameters,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn report_violations() {
+        let mut engine = initialize(ViolationHandling::Report);
+        engine.execute().unwrap();
+
+        let violations = engine.constraint_violations().unwrap();
+        // The key is violated by both orders of the two facts about alice,
+        // and alice is younger than her descendants bob and carol
+        assert_eq!(violations.len(), 2 + 2);
+        assert_eq!(
+            violations
+                .iter()
+                .filter(|violation| violation.constraint().to_string().starts_with("⊥"))
+                .count(),
+            2
+        );
+
+        let (trace, handles) = engine.trace_violations(&violations);
+        for handle in handles {
+            assert!(trace.tree(handle).is_some());
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn abort_on_violation() {
+        let mut engine = initialize(ViolationHandling::Abort);
+
+        assert!(matches!(
+            engine.execute(),
+            Err(Error::ConstraintViolation(_))
+        ));
+    }
+}
@@ -2,6 +2,8 @@
 
 pub(crate) mod operations;
 
+pub(crate) mod cost_model;
+
 pub(crate) mod plan_aggregate;
 pub(crate) mod plan_body_seminaive;
 pub(crate) mod plan_head_datalog;
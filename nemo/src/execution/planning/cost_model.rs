@@ -0,0 +1,380 @@
+//! Module defining a cost model for choosing the variable order of a join
+//! based on statistics about the tables involved.
+
+use std::collections::{HashMap, HashSet};
+
+use nemo_physical::tabular::statistics::TableStatistics;
+
+use crate::{
+    error::Error,
+    model::{
+        chase_model::{ChaseAtom, VariableAtom},
+        Identifier, Variable,
+    },
+    program_analysis::variable_order::{column_order_for, VariableOrder},
+    table_manager::{PredicateStatistics, TableManager},
+};
+
+/// Estimates the cost of joining a list of atoms in a given [VariableOrder]
+///
+/// The join is evaluated by binding one variable after the other.
+/// The cost of a variable order is the estimated number of partial bindings
+/// that are produced on the way, summed over all variables,
+/// plus the number of rows of the tables that need to be reordered for it.
+#[derive(Debug)]
+pub(crate) struct JoinCostModel<'a> {
+    /// Atoms that are joined
+    atoms: &'a [VariableAtom],
+    /// Statistics about the tables of the predicates of the atoms
+    statistics: HashMap<Identifier, PredicateStatistics>,
+}
+
+impl<'a> JoinCostModel<'a> {
+    /// Create a new [JoinCostModel] from given statistics.
+    pub(crate) fn new(
+        atoms: &'a [VariableAtom],
+        statistics: HashMap<Identifier, PredicateStatistics>,
+    ) -> Self {
+        Self { atoms, statistics }
+    }
+
+    /// Create a new [JoinCostModel] using the current statistics of the tables in the [TableManager].
+    pub(crate) fn from_table_manager(
+        atoms: &'a [VariableAtom],
+        table_manager: &mut TableManager,
+    ) -> Result<Self, Error> {
+        let mut statistics = HashMap::new();
+        for atom in atoms {
+            let predicate = atom.predicate();
+            if statistics.contains_key(&predicate) {
+                continue;
+            }
+
+            if let Some(predicate_statistics) = table_manager.predicate_statistics(&predicate)? {
+                statistics.insert(predicate, predicate_statistics);
+            }
+        }
+
+        Ok(Self::new(atoms, statistics))
+    }
+
+    /// Estimate the number of distinct combinations of values
+    /// in the given columns of a table.
+    fn estimate_distinct(statistics: &TableStatistics, columns: &[usize]) -> f64 {
+        if columns.is_empty() {
+            return 1.0;
+        }
+
+        columns
+            .iter()
+            .map(|&column| statistics.columns[column].num_distinct as f64)
+            .product::<f64>()
+            .min(statistics.num_rows as f64)
+    }
+
+    /// Estimate the number of values for `variable` that match a given atom
+    /// for each binding of the variables in `bound`.
+    ///
+    /// Returns `None` if the variable does not occur in the atom.
+    fn estimate_extensions(
+        &self,
+        atom: &VariableAtom,
+        bound: &HashSet<&Variable>,
+        variable: &Variable,
+    ) -> Option<f64> {
+        let column = atom.terms().iter().position(|term| term == variable)?;
+
+        let Some(statistics) = self
+            .statistics
+            .get(&atom.predicate())
+            .map(|statistics| &statistics.table)
+        else {
+            return Some(0.0);
+        };
+        if statistics.num_rows == 0 {
+            return Some(0.0);
+        }
+
+        let mut columns = atom
+            .terms()
+            .iter()
+            .enumerate()
+            .filter(|(_, term)| bound.contains(term))
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        let before = Self::estimate_distinct(statistics, &columns);
+        columns.push(column);
+        let after = Self::estimate_distinct(statistics, &columns);
+
+        Some(after / before)
+    }
+
+    /// Estimate the number of values for `variable`
+    /// for each binding of the variables in `bound`.
+    fn estimate_step(&self, bound: &HashSet<&Variable>, variable: &Variable) -> f64 {
+        self.atoms
+            .iter()
+            .filter_map(|atom| self.estimate_extensions(atom, bound, variable))
+            .reduce(f64::min)
+            .unwrap_or(1.0)
+    }
+
+    /// Estimate the cost of reordering the tables of the atoms
+    /// into the column orders that are required for the given [VariableOrder].
+    ///
+    /// Reordering a table that is not yet available in a required order
+    /// is estimated to cost one unit per row.
+    fn reorder_cost(&self, variable_order: &VariableOrder) -> f64 {
+        let required_orders = self
+            .atoms
+            .iter()
+            .map(|atom| (atom.predicate(), column_order_for(atom, variable_order)))
+            .collect::<HashSet<_>>();
+
+        required_orders
+            .iter()
+            .filter_map(|(predicate, order)| {
+                let statistics = self.statistics.get(predicate)?;
+
+                Some(
+                    statistics
+                        .subtables
+                        .iter()
+                        .filter(|(_, orders)| !orders.contains(order))
+                        .map(|&(num_rows, _)| num_rows as f64)
+                        .sum::<f64>(),
+                )
+            })
+            .sum()
+    }
+
+    /// Estimate the cost of computing the join with the given [VariableOrder].
+    pub(crate) fn cost(&self, variable_order: &VariableOrder) -> f64 {
+        let mut bound = HashSet::new();
+        let mut bindings = 1.0;
+        let mut cost = self.reorder_cost(variable_order);
+
+        for variable in variable_order.iter() {
+            bindings *= self.estimate_step(&bound, variable);
+            cost += bindings;
+
+            bound.insert(variable);
+        }
+
+        cost
+    }
+
+    /// Compute a [VariableOrder] for the variables of the given order
+    /// by repeatedly choosing the variable with the fewest estimated values.
+    ///
+    /// Variables that share an atom with an already chosen variable are preferred,
+    /// so as to avoid computing cartesian products.
+    pub(crate) fn greedy_order(&self, variable_order: &VariableOrder) -> VariableOrder {
+        let mut remaining = variable_order.as_ordered_list();
+        let mut bound = HashSet::<&Variable>::new();
+        let mut result = VariableOrder::new();
+
+        while !remaining.is_empty() {
+            let is_connected = |variable: &Variable| {
+                self.atoms.iter().any(|atom| {
+                    atom.terms().contains(variable)
+                        && atom.terms().iter().any(|term| bound.contains(term))
+                })
+            };
+            let any_connected = remaining.iter().any(is_connected);
+
+            let (index, _) = remaining
+                .iter()
+                .enumerate()
+                .filter(|(_, variable)| !any_connected || is_connected(variable))
+                .map(|(index, variable)| (index, self.estimate_step(&bound, variable)))
+                .fold(
+                    None,
+                    |best: Option<(usize, f64)>, (index, estimate)| match best {
+                        Some((_, best_estimate)) if best_estimate <= estimate => best,
+                        _ => Some((index, estimate)),
+                    },
+                )
+                .expect("there is at least one remaining variable");
+
+            let variable = remaining.remove(index);
+            result.push(variable);
+            bound = result.iter().collect();
+        }
+
+        result
+    }
+
+    /// Choose the [VariableOrder] with the lowest estimated cost
+    /// among the given candidates and the order computed by [JoinCostModel::greedy_order].
+    ///
+    /// Returns all considered orders together with their estimated cost
+    /// and the index of the chosen order.
+    pub(crate) fn choose(
+        &self,
+        candidates: &[VariableOrder],
+    ) -> (Vec<(VariableOrder, f64)>, usize) {
+        let mut orders = candidates.to_vec();
+        if let Some(first) = candidates.first() {
+            let greedy = self.greedy_order(first);
+            if !orders.contains(&greedy) {
+                orders.push(greedy);
+            }
+        }
+
+        let costed = orders
+            .into_iter()
+            .map(|order| {
+                let cost = self.cost(&order);
+                (order, cost)
+            })
+            .collect::<Vec<_>>();
+
+        let chosen =
+            costed.iter().enumerate().fold(
+                0,
+                |best, (index, (_, cost))| {
+                    if *cost < costed[best].1 {
+                        index
+                    } else {
+                        best
+                    }
+                },
+            );
+
+        (costed, chosen)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use nemo_physical::{
+        management::execution_plan::ColumnOrder,
+        tabular::statistics::{ColumnStatistics, TableStatistics},
+    };
+
+    use crate::{
+        model::{chase_model::VariableAtom, Identifier, Variable},
+        program_analysis::variable_order::VariableOrder,
+        table_manager::PredicateStatistics,
+    };
+
+    use super::JoinCostModel;
+
+    /// Statistics of a predicate with a single subtable that is available in the given orders
+    fn statistics_in_orders(
+        num_rows: usize,
+        num_distinct: &[usize],
+        orders: Vec<ColumnOrder>,
+    ) -> PredicateStatistics {
+        PredicateStatistics {
+            table: TableStatistics {
+                num_rows,
+                columns: num_distinct
+                    .iter()
+                    .map(|&num_distinct| ColumnStatistics { num_distinct })
+                    .collect(),
+            },
+            subtables: vec![(num_rows, orders)],
+        }
+    }
+
+    /// Statistics of a predicate with a single subtable that is available in the default order
+    fn statistics(num_rows: usize, num_distinct: &[usize]) -> PredicateStatistics {
+        statistics_in_orders(num_rows, num_distinct, vec![ColumnOrder::default()])
+    }
+
+    fn order(variables: &[&Variable]) -> VariableOrder {
+        let mut result = VariableOrder::new();
+        for &variable in variables {
+            result.push(variable.clone());
+        }
+
+        result
+    }
+
+    #[test]
+    fn join_cost_prefers_selective_variables() {
+        let x = Variable::Universal("x".to_string());
+        let y = Variable::Universal("y".to_string());
+        let z = Variable::Universal("z".to_string());
+
+        let big = Identifier("big".to_string());
+        let small = Identifier("small".to_string());
+
+        // big(?x, ?y), small(?y, ?z)
+        let atoms = vec![
+            VariableAtom::new(big.clone(), vec![x.clone(), y.clone()]),
+            VariableAtom::new(small.clone(), vec![y.clone(), z.clone()]),
+        ];
+        let statistics = HashMap::from([
+            (big, statistics(10000, &[1000, 100])),
+            (small, statistics(10, &[2, 5])),
+        ]);
+        let model = JoinCostModel::new(&atoms, statistics);
+
+        let x_first = order(&[&x, &y, &z]);
+        let y_first = order(&[&y, &x, &z]);
+        assert!(model.cost(&y_first) < model.cost(&x_first));
+
+        let greedy = model.greedy_order(&x_first);
+        assert_eq!(greedy.as_ordered_list()[0], y);
+        assert!(model.cost(&greedy) <= model.cost(&y_first));
+
+        let (orders, chosen) = model.choose(std::slice::from_ref(&x_first));
+        assert_eq!(orders.len(), 2);
+        assert_ne!(orders[chosen].0, x_first);
+    }
+
+    #[test]
+    fn join_cost_of_empty_table() {
+        let x = Variable::Universal("x".to_string());
+        let y = Variable::Universal("y".to_string());
+
+        let empty = Identifier("empty".to_string());
+        let full = Identifier("full".to_string());
+
+        let atoms = vec![
+            VariableAtom::new(full.clone(), vec![x.clone()]),
+            VariableAtom::new(empty.clone(), vec![x.clone(), y.clone()]),
+        ];
+        let statistics = HashMap::from([
+            (full, statistics(100, &[100])),
+            (
+                empty,
+                PredicateStatistics {
+                    table: TableStatistics::empty(2),
+                    subtables: Vec::new(),
+                },
+            ),
+        ]);
+        let model = JoinCostModel::new(&atoms, statistics);
+
+        assert_eq!(model.cost(&order(&[&x, &y])), 0.0);
+    }
+
+    #[test]
+    fn join_cost_includes_reordering() {
+        let x = Variable::Universal("x".to_string());
+        let y = Variable::Universal("y".to_string());
+
+        let edge = Identifier("edge".to_string());
+
+        // edge(?x, ?y), with the table of edge only available in the order (?y, ?x)
+        let atoms = vec![VariableAtom::new(edge.clone(), vec![x.clone(), y.clone()])];
+        let statistics = HashMap::from([(
+            edge,
+            statistics_in_orders(1000, &[10, 10], vec![ColumnOrder::from_vector(vec![1, 0])]),
+        )]);
+        let model = JoinCostModel::new(&atoms, statistics);
+
+        let x_first = order(&[&x, &y]);
+        let y_first = order(&[&y, &x]);
+        assert_eq!(model.cost(&x_first), model.cost(&y_first) + 1000.0);
+
+        let (orders, chosen) = model.choose(&[x_first, y_first.clone()]);
+        assert_eq!(orders[chosen].0, y_first);
+    }
+}
@@ -8,5 +8,6 @@ pub(crate) mod append;
 pub(crate) mod filter;
 pub(crate) mod functions;
 pub(crate) mod join;
+pub(crate) mod lattice;
 pub(crate) mod negation;
 pub(crate) mod union;
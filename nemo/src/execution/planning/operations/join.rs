@@ -53,6 +53,10 @@ pub(crate) fn node_join(
 
     for atom_index in 0..main_atoms.len() {
         let mut seminaive_node = plan.join_empty(output_markers.clone());
+        seminaive_node.annotate(&format!(
+            "new facts of {}",
+            main_atoms[atom_index].predicate().name()
+        ));
 
         // For every atom that did not receive any update since the last rule application take all available elements
         for atom in &side_atoms {
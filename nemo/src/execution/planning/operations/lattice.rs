@@ -0,0 +1,71 @@
+//! This module contains a helper function for computing a node in an execution plan,
+//! which keeps only those values of a lattice position that improve on the known values.
+
+use nemo_physical::{
+    function::tree::FunctionTree,
+    management::execution_plan::{ExecutionNodeRef, ExecutionPlan},
+    tabular::operations::OperationTable,
+};
+
+use crate::{
+    model::{
+        chase_model::{LatticeOrder, LatticePosition},
+        Identifier,
+    },
+    table_manager::TableManager,
+};
+
+use super::union::subplan_union;
+
+/// Compute the appropriate execution plan to remove every row from `node_candidates`
+/// that does not improve on the tables of `predicate`.
+///
+/// A row is removed if the table already contains a row that agrees with it
+/// on all but the lattice position and has an equal or better value in the lattice position.
+pub(crate) fn node_lattice_improvements(
+    plan: &mut ExecutionPlan,
+    table_manager: &TableManager,
+    predicate: &Identifier,
+    current_step_number: usize,
+    position: LatticePosition,
+    node_candidates: ExecutionNodeRef,
+) -> ExecutionNodeRef {
+    plan.write_temporary(node_candidates.clone(), "Lattice candidates");
+
+    let candidate_markers = node_candidates.markers_cloned();
+    let candidate_marker = *candidate_markers.get(position.index);
+
+    let mut join_markers = candidate_markers.clone();
+    let known_marker = *join_markers.push_new();
+
+    let mut known_markers = OperationTable::default();
+    for (index, marker) in candidate_markers.iter().enumerate() {
+        if index == position.index {
+            known_markers.push(known_marker);
+        } else {
+            known_markers.push(*marker);
+        }
+    }
+
+    let node_known = subplan_union(
+        plan,
+        table_manager,
+        predicate,
+        0..current_step_number,
+        known_markers,
+    );
+    let node_join = plan.join(join_markers, vec![node_candidates.clone(), node_known]);
+
+    let candidate_value = FunctionTree::reference(candidate_marker);
+    let known_value = FunctionTree::reference(known_marker);
+    let is_dominated = match position.order {
+        LatticeOrder::Decreasing => FunctionTree::numeric_lessthaneq(known_value, candidate_value),
+        LatticeOrder::Increasing => {
+            FunctionTree::numeric_greaterthaneq(known_value, candidate_value)
+        }
+    };
+    let node_dominated = plan.filter(node_join, vec![is_dominated]);
+    let node_dominated = plan.projectreorder(candidate_markers, node_dominated);
+
+    plan.subtract(node_candidates, vec![node_dominated])
+}
@@ -60,6 +60,15 @@ pub(super) fn term_to_function_tree(
                 BinaryOperation::StringAfter => FunctionTree::string_after(left, right),
                 BinaryOperation::StringStarts => FunctionTree::string_starts(left, right),
                 BinaryOperation::StringEnds => FunctionTree::string_ends(left, right),
+                BinaryOperation::TupleElement => FunctionTree::tuple_element(left, right),
+                BinaryOperation::MapElement => FunctionTree::map_element(left, right),
+                BinaryOperation::RdfTypedLiteral => FunctionTree::rdf_typed_literal(left, right),
+                BinaryOperation::RdfLanguageString => {
+                    FunctionTree::rdf_language_string(left, right)
+                }
+                BinaryOperation::RdfLanguageMatches => {
+                    FunctionTree::rdf_language_matches(left, right)
+                }
             }
         }
         Term::Unary(operation, subterm) => {
@@ -88,15 +97,35 @@ pub(super) fn term_to_function_tree(
                 UnaryOperation::CheckIsNumeric => FunctionTree::check_is_numeric(sub),
                 UnaryOperation::CheckIsNull => FunctionTree::check_is_null(sub),
                 UnaryOperation::CheckIsString => FunctionTree::check_is_string(sub),
+                UnaryOperation::CheckIsLiteral => FunctionTree::check_is_literal(sub),
+                UnaryOperation::StringEncodeForUri => FunctionTree::string_encode_for_uri(sub),
+                UnaryOperation::HashMd5 => FunctionTree::hash_md5(sub),
+                UnaryOperation::HashSha1 => FunctionTree::hash_sha1(sub),
+                UnaryOperation::HashSha256 => FunctionTree::hash_sha256(sub),
+                UnaryOperation::RdfIri => FunctionTree::rdf_iri(sub),
+                UnaryOperation::RdfBlankNode => FunctionTree::rdf_blank_node(sub),
                 UnaryOperation::Datatype => FunctionTree::datatype(sub),
                 UnaryOperation::LanguageTag => FunctionTree::languagetag(sub),
                 UnaryOperation::LexicalValue => FunctionTree::lexical_value(sub),
                 UnaryOperation::CastToInteger => FunctionTree::casting_to_integer64(sub),
                 UnaryOperation::CastToDouble => FunctionTree::casting_to_double(sub),
                 UnaryOperation::CastToFloat => FunctionTree::casting_to_float(sub),
+                UnaryOperation::CollectionLength => FunctionTree::collection_length(sub),
+                UnaryOperation::MapKeys => FunctionTree::map_keys(sub),
+                UnaryOperation::Unnest => {
+                    unreachable!("Unnesting is rewritten into a join before planning")
+                }
             }
         }
         Term::Aggregation(_) => unimplemented!("Aggregates are not implement yet"),
+        Term::UserFunction(function, parameters) => {
+            let parameters = parameters
+                .iter()
+                .map(|term| term_to_function_tree(translation, term))
+                .collect::<Vec<_>>();
+
+            FunctionTree::external(function.external_function(), parameters)
+        }
         Term::Function(name, _) => unimplemented!(
             "Function symbols are not supported yet. {} is not recognized as a builtin function.",
             name
@@ -130,6 +159,11 @@ pub(super) fn term_to_function_tree(
                 NaryOperation::StringConcatenation => {
                     FunctionTree::string_concatenation(parameters)
                 }
+                NaryOperation::StringRegex => FunctionTree::string_regex(parameters),
+                NaryOperation::StringReplace => FunctionTree::string_replace(parameters),
+                NaryOperation::StringRegexGroup => FunctionTree::string_regex_group(parameters),
+                NaryOperation::TupleConstruction => FunctionTree::tuple_construction(parameters),
+                NaryOperation::MapConstruction => FunctionTree::map_construction(parameters),
                 NaryOperation::BooleanConjunction => FunctionTree::boolean_conjunction(parameters),
                 NaryOperation::BooleanDisjunction => FunctionTree::boolean_disjunction(parameters),
                 NaryOperation::BitAnd => FunctionTree::bit_and(parameters),
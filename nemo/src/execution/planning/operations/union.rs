@@ -28,7 +28,15 @@ pub(crate) fn subplan_union(
         .map(|id| plan.fetch_table(OperationTable::default(), id))
         .collect();
 
-    plan.union(output_markers, subtables)
+    let union = plan.union(output_markers, subtables);
+    union.annotate(&format!(
+        "{} steps {}..{}",
+        predicate.name(),
+        steps.start,
+        steps.end
+    ));
+
+    union
 }
 
 /// Given a predicate and a range of execution steps,
@@ -1,15 +1,17 @@
 //! Module defining the strategy for calculating all body matches for a rule application.
 
-use nemo_physical::management::execution_plan::ExecutionNodeRef;
+use nemo_physical::{
+    aggregates::operation::AggregateOperation, management::execution_plan::ExecutionNodeRef,
+};
 
 use crate::{
     execution::{execution_engine::RuleInfo, rule_execution::VariableTranslation},
     model::{
-        chase_model::{ChaseRule, Constructor, VariableAtom},
+        chase_model::{ChaseAtom, ChaseRule, Constructor, VariableAtom},
         Constraint,
     },
     program_analysis::{analysis::RuleAnalysis, variable_order::VariableOrder},
-    table_manager::{SubtableExecutionPlan, TableManager},
+    table_manager::{ComputedTableRole, SubtableExecutionPlan, TableManager},
 };
 
 use super::{
@@ -28,17 +30,31 @@ pub(crate) struct SeminaiveStrategy {
 
     negative_atoms: Vec<VariableAtom>,
     negative_constraints: Vec<Vec<Constraint>>,
+
+    /// Whether all body matches need to be recomputed
+    /// whenever one of the body predicates received new elements,
+    /// which is the case for monotone aggregates that cannot be updated incrementally
+    recompute_matches: bool,
 }
 
 impl SeminaiveStrategy {
     /// Create new [SeminaiveStrategy] object.
-    pub(crate) fn initialize(rule: &ChaseRule, _analysis: &RuleAnalysis) -> Self {
+    pub(crate) fn initialize(rule: &ChaseRule, analysis: &RuleAnalysis) -> Self {
+        let recompute_matches = analysis.is_monotone_aggregate
+            && rule.aggregate().as_ref().is_some_and(|aggregate| {
+                matches!(
+                    aggregate.aggregate_operation,
+                    AggregateOperation::Count | AggregateOperation::Sum
+                )
+            });
+
         Self {
             positive_atoms: rule.positive_body().clone(),
             positive_constraints: rule.positive_constraints().clone(),
             negative_atoms: rule.negative_body().clone(),
             negative_constraints: rule.negative_constraints().clone(),
             positive_constructors: rule.positive_constructors().clone(),
+            recompute_matches,
         }
     }
 }
@@ -53,12 +69,24 @@ impl BodyStrategy for SeminaiveStrategy {
         variable_order: &mut VariableOrder,
         step_number: usize,
     ) -> ExecutionNodeRef {
+        // Starting from step 0 results in the computation of all matches
+        let body_changed = self.positive_atoms.iter().any(|atom| {
+            table_manager
+                .last_step(&atom.predicate())
+                .is_some_and(|step| step >= rule_info.step_last_applied)
+        });
+        let step_last_applied = if self.recompute_matches && body_changed {
+            0
+        } else {
+            rule_info.step_last_applied
+        };
+
         let join_output_markers = variable_translation.operation_table(variable_order.iter());
         let node_join = node_join(
             current_plan.plan_mut(),
             table_manager,
             variable_translation,
-            rule_info.step_last_applied,
+            step_last_applied,
             step_number,
             &self.positive_atoms,
             join_output_markers,
@@ -90,7 +118,8 @@ impl BodyStrategy for SeminaiveStrategy {
 
         let node_result = node_negation;
 
-        current_plan.add_temporary_table(node_result.clone(), "Body");
+        let body_id = current_plan.add_temporary_table(node_result.clone(), "Body");
+        current_plan.add_role(body_id, ComputedTableRole::Body);
         node_result
     }
 }
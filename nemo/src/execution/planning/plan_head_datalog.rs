@@ -11,7 +11,7 @@ use nemo_physical::{
 use crate::{
     execution::{execution_engine::RuleInfo, rule_execution::VariableTranslation},
     model::{
-        chase_model::{ChaseAtom, ChaseRule},
+        chase_model::{ChaseAtom, ChaseRule, LatticePosition},
         Identifier,
     },
     program_analysis::analysis::RuleAnalysis,
@@ -19,7 +19,10 @@ use crate::{
 };
 
 use super::{
-    operations::append::{head_instruction_from_atom, node_head_instruction, HeadInstruction},
+    operations::{
+        append::{head_instruction_from_atom, node_head_instruction, HeadInstruction},
+        lattice::node_lattice_improvements,
+    },
     HeadStrategy,
 };
 
@@ -27,11 +30,13 @@ use super::{
 #[derive(Debug)]
 pub(crate) struct DatalogStrategy {
     predicate_to_atoms: HashMap<Identifier, Vec<(HeadInstruction, bool)>>,
+    /// Head predicates that only receive values improving on their known values
+    lattice_positions: HashMap<Identifier, LatticePosition>,
 }
 
 impl DatalogStrategy {
     /// Create a new [DatalogStrategy] object.
-    pub(crate) fn initialize(rule: &ChaseRule, _analysis: &RuleAnalysis) -> Self {
+    pub(crate) fn initialize(rule: &ChaseRule, analysis: &RuleAnalysis) -> Self {
         let mut predicate_to_atoms = HashMap::<Identifier, Vec<(HeadInstruction, bool)>>::new();
 
         for (head_index, head_atom) in rule.head().iter().enumerate() {
@@ -45,7 +50,10 @@ impl DatalogStrategy {
             atoms.push((head_instruction_from_atom(head_atom), is_aggregate_atom));
         }
 
-        Self { predicate_to_atoms }
+        Self {
+            predicate_to_atoms,
+            lattice_positions: analysis.lattice_head_positions.clone(),
+        }
     }
 }
 
@@ -88,10 +96,23 @@ impl HeadStrategy for DatalogStrategy {
                 })
                 .collect();
 
-            let new_tables_union = current_plan
+            let mut new_tables_union = current_plan
                 .plan_mut()
                 .union(OperationTable::new_unique(arity), project_append_nodes);
 
+            if let Some(position) = self.lattice_positions.get(predicate) {
+                new_tables_union = node_lattice_improvements(
+                    current_plan.plan_mut(),
+                    table_manager,
+                    predicate,
+                    step,
+                    *position,
+                    new_tables_union,
+                );
+            }
+
+            current_plan.add_candidate_table(new_tables_union.clone(), predicate);
+
             let old_subtables = table_manager.tables_in_range(predicate, &(0..step));
             let old_table_nodes: Vec<ExecutionNodeRef> = old_subtables
                 .into_iter()
@@ -317,6 +317,8 @@ impl HeadStrategy for RestrictedChaseStrategy {
             } else {
                 // Duplicate elimination for atoms thats do not contain existential variables
                 // Same as in plan_head_datalog
+                current_plan.add_candidate_table(new_tables_union.clone(), predicate);
+
                 let old_tables: Vec<PermanentTableId> =
                     table_manager.tables_in_range(predicate, &(0..step));
                 let old_table_nodes: Vec<ExecutionNodeRef> = old_tables
@@ -11,7 +11,7 @@ use crate::{
 use std::fmt::Debug;
 
 /// Strategies for calculating the newly derived tables.
-pub(crate) trait HeadStrategy: Debug {
+pub(crate) trait HeadStrategy: Debug + Send {
     /// Calculate the concrete plan given a variable order.
     #[allow(clippy::too_many_arguments)]
     fn add_plan_head(
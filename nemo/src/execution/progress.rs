@@ -0,0 +1,138 @@
+//! Observing, limiting and cancelling the execution of a program.
+//!
+//! The [ExecutionEngine][super::ExecutionEngine] checks its [CancellationToken]
+//! and its [ExecutionLimits] before every rule application
+//! and reports its progress to an optional [ProgressCallback] after every rule application.
+//! Reasoning may also be split into slices that end after some rule applications or some time,
+//! see [ExecutionStatus].
+//! Tokens and callbacks can be used from other threads while reasoning is running.
+
+use std::{
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use thiserror::Error;
+
+/// Flag that requests the cancellation of reasoning
+///
+/// Clones of a token share the same flag,
+/// so a token may be cancelled from another thread than the one that executes the program.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Create a new token that has not been cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request the cancellation of reasoning.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Return whether cancellation has been requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Withdraw a request for cancellation,
+    /// so that reasoning may be started again.
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
+}
+
+/// State of reasoning after the application of a rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutionProgress {
+    /// The execution step in which the rule was applied
+    pub step: usize,
+    /// Index of the applied rule
+    pub rule: usize,
+    /// Whether the rule derived new facts
+    pub derived: bool,
+}
+
+/// State of reasoning after a call of [ExecutionEngine::execute_bounded][super::ExecutionEngine::execute_bounded]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionStatus {
+    /// All facts that follow from the program have been derived
+    Finished,
+    /// Reasoning stopped at the given bound and can be continued
+    Paused,
+}
+
+/// Function that is called with the [ExecutionProgress] after every rule application
+#[derive(Clone)]
+pub struct ProgressCallback(Arc<dyn Fn(&ExecutionProgress) + Send + Sync>);
+
+impl ProgressCallback {
+    /// Create a new callback from the given closure.
+    pub fn new<Function>(function: Function) -> Self
+    where
+        Function: Fn(&ExecutionProgress) + Send + Sync + 'static,
+    {
+        Self(Arc::new(function))
+    }
+
+    /// Report the given [ExecutionProgress].
+    pub(crate) fn report(&self, progress: &ExecutionProgress) {
+        (self.0)(progress)
+    }
+}
+
+impl Debug for ProgressCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProgressCallback").finish_non_exhaustive()
+    }
+}
+
+/// Bounds on the effort spent on reasoning
+///
+/// Reasoning is aborted with [Error::LimitExceeded][crate::error::Error::LimitExceeded]
+/// before the first rule application that would exceed one of the limits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExecutionLimits {
+    /// Maximal number of rule applications
+    pub steps: Option<usize>,
+    /// Maximal duration of reasoning
+    ///
+    /// For the limits of an engine, this is the total duration of all calls of
+    /// [ExecutionEngine::execute][super::ExecutionEngine::execute]
+    /// and [ExecutionEngine::execute_bounded][super::ExecutionEngine::execute_bounded]
+    /// while time is limited.
+    /// For the bound given to [ExecutionEngine::execute_bounded][super::ExecutionEngine::execute_bounded],
+    /// this is the duration of that call.
+    pub time: Option<Duration>,
+}
+
+impl ExecutionLimits {
+    /// Return the limit that is exceeded after the given number of rule applications
+    /// and the given duration of reasoning, if any.
+    pub(crate) fn exceeded(&self, steps: usize, time: Duration) -> Option<ExceededLimit> {
+        if let Some(limit) = self.steps.filter(|&limit| steps >= limit) {
+            return Some(ExceededLimit::Steps(limit));
+        }
+
+        self.time
+            .filter(|&limit| time >= limit)
+            .map(ExceededLimit::Time)
+    }
+}
+
+/// One of the [ExecutionLimits] that has been reached
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExceededLimit {
+    /// The maximal number of rule applications
+    #[error("limit of {0} rule applications")]
+    Steps(usize),
+    /// The maximal duration of reasoning
+    #[error("time limit of {0:?}")]
+    Time(Duration),
+}
@@ -0,0 +1,744 @@
+//! Provenance annotations of the facts derived by a program.
+//!
+//! For every rule of the program, an additional rule collects the instantiations of its body,
+//! which are materialized together with the rest of the program.
+//! After reasoning, these instantiations are used to annotate every fact
+//! with the number of its derivations and the set of inputs and rules that contribute to it.
+//!
+//! Since the values of all body variables are stored for every instantiation,
+//! the memory needed for these additional predicates grows with the number of body matches
+//! of the rules, which may be much larger than the number of derived facts.
+//!
+//! Head atoms with existential variables or aggregates cannot be instantiated directly:
+//! * A fact is only derived by an instantiation of a rule with existential variables
+//!   if the nulls at the positions of the existential variables were created by that rule.
+//!   Instantiations whose head was already satisfied when the rule was applied
+//!   therefore only count as derivations of the facts created for other instantiations
+//!   with the same values of the frontier variables. Facts whose nulls were merged
+//!   with other values by equality rules are not considered to be derived by such rules.
+//! * The instantiations of a rule with an aggregate that agree on the values of the
+//!   remaining head terms together count as a single derivation of the aggregated fact.
+//!   This fact is only identified if it is the only fact with these values,
+//!   otherwise the instantiations are ignored.
+
+use std::{
+    collections::{hash_map::Entry, BTreeSet, HashMap},
+    ops::Range,
+};
+
+use nemo_physical::datavalues::{AnyDataValue, DataValue, ValueDomain};
+
+use crate::model::{Atom, Identifier, Literal, PrimitiveTerm, Program, Rule, Term, Variable};
+
+/// Prefix of the predicates that collect the instantiations of the rules of a program
+const PROVENANCE_PREDICATE_PREFIX: &str = "PROVENANCE_OF_RULE_";
+
+/// Something that contributes to the derivation of a fact
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ProvenanceSource {
+    /// The facts of the given predicate that are imported or given in the program
+    Input(Identifier),
+    /// The rule with the given index
+    Rule(usize),
+}
+
+impl std::fmt::Display for ProvenanceSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProvenanceSource::Input(predicate) => write!(f, "input {predicate}"),
+            ProvenanceSource::Rule(index) => write!(f, "rule {index}"),
+        }
+    }
+}
+
+/// Annotation of a single fact
+#[derive(Debug, Clone, Default)]
+struct FactAnnotation {
+    /// Number of derivations of the fact
+    derivations: usize,
+    /// Indices of the sources that contribute to the fact
+    sources: BTreeSet<usize>,
+}
+
+/// Provenance annotations of all facts derived by a program
+///
+/// The number of derivations of a fact counts the instantiations of rules
+/// that derive it in a single step, and one additional derivation if it is an input fact.
+/// The sources of a fact are its input predicate, if it is an input fact,
+/// and the sources of all rules and facts that occur in one of its derivations.
+#[derive(Debug, Clone, Default)]
+pub struct Provenance {
+    /// All sources, referenced by their index
+    sources: Vec<ProvenanceSource>,
+    /// For each predicate, the index of the annotation of each of its facts
+    facts: HashMap<Identifier, HashMap<Vec<AnyDataValue>, usize>>,
+    /// Annotations of all facts
+    annotations: Vec<FactAnnotation>,
+}
+
+impl Provenance {
+    /// Return the annotation of the given fact, if it is known.
+    fn annotation(&self, predicate: &Identifier, row: &[AnyDataValue]) -> Option<&FactAnnotation> {
+        let index = self.facts.get(predicate)?.get(row)?;
+        Some(&self.annotations[*index])
+    }
+
+    /// Return the number of derivations of the given fact,
+    /// or `None` if the fact was not derived.
+    pub fn derivations(&self, predicate: &Identifier, row: &[AnyDataValue]) -> Option<usize> {
+        Some(self.annotation(predicate, row)?.derivations)
+    }
+
+    /// Return the sources that contribute to the given fact in ascending order,
+    /// or `None` if the fact was not derived.
+    pub fn sources(
+        &self,
+        predicate: &Identifier,
+        row: &[AnyDataValue],
+    ) -> Option<Vec<&ProvenanceSource>> {
+        let mut sources = self
+            .annotation(predicate, row)?
+            .sources
+            .iter()
+            .map(|&index| &self.sources[index])
+            .collect::<Vec<_>>();
+        sources.sort();
+
+        Some(sources)
+    }
+
+    /// Append the number of derivations and the list of sources of the given fact to its row.
+    pub(crate) fn annotate(
+        &self,
+        predicate: &Identifier,
+        mut row: Vec<AnyDataValue>,
+    ) -> Vec<AnyDataValue> {
+        let derivations = self.derivations(predicate, &row).unwrap_or_default();
+        let sources = self
+            .sources(predicate, &row)
+            .unwrap_or_default()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        row.push(AnyDataValue::new_integer_from_u64(derivations as u64));
+        row.push(AnyDataValue::new_plain_string(sources));
+        row
+    }
+}
+
+/// A term in a body atom of a [ProvenanceRule]
+#[derive(Debug, Clone)]
+enum ProvenanceTerm {
+    /// The value in the given column of the instantiation
+    Column(usize),
+    /// A constant
+    Constant(AnyDataValue),
+}
+
+/// A term in a head atom of a [ProvenanceRule]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HeadTerm {
+    /// The value in the given column of the instantiation
+    Column(usize),
+    /// A term with an existential variable
+    Existential,
+    /// A term with an aggregate
+    Aggregate,
+}
+
+/// A rule of a program together with the predicate that collects its instantiations
+///
+/// The columns of the predicate contain the values of all head terms that do not contain
+/// existential variables or aggregates, followed by the values of the body variables.
+#[derive(Debug, Clone)]
+pub(crate) struct ProvenanceRule {
+    /// Predicate that collects the instantiations
+    pub(crate) predicate: Identifier,
+    /// Index of the rule in the program
+    pub(crate) rule: usize,
+    /// For each head atom, its predicate and terms
+    head: Vec<(Identifier, Vec<HeadTerm>)>,
+    /// Predicate and terms of each positive body atom
+    body: Vec<(Identifier, Vec<ProvenanceTerm>)>,
+}
+
+/// Return a copy of the given program with an additional rule for each of its rules
+/// that collects the instantiations of the body of that rule,
+/// together with a description of these additional rules.
+///
+/// Rules whose positive body atoms contain complex terms are not annotated.
+pub(crate) fn add_provenance_rules(program: &Program) -> (Program, Vec<ProvenanceRule>) {
+    let mut provenance_rules = Vec::new();
+    let mut rules = Vec::new();
+
+    for (index, rule) in program.rules().iter().enumerate() {
+        let predicate = Identifier(format!("{PROVENANCE_PREDICATE_PREFIX}{index}"));
+
+        if let Some((instance_rule, provenance_rule)) = instance_rule(predicate, index, rule) {
+            rules.push(instance_rule);
+            provenance_rules.push(provenance_rule);
+        }
+    }
+
+    let mut result = program.clone();
+
+    result.add_internal_outputs(
+        provenance_rules
+            .iter()
+            .map(|provenance| provenance.predicate.clone()),
+    );
+    result.add_rules(rules);
+
+    (result, provenance_rules)
+}
+
+/// Return the rule that collects the instantiations of the given rule in the given predicate.
+fn instance_rule(
+    predicate: Identifier,
+    index: usize,
+    rule: &Rule,
+) -> Option<(Rule, ProvenanceRule)> {
+    // Wildcards in positive literals are named, so that their values can be collected
+    let body = rule
+        .body()
+        .iter()
+        .map(|literal| match literal {
+            Literal::Positive(atom) => Literal::Positive(name_wildcards(atom)),
+            Literal::Negative(_) => literal.clone(),
+        })
+        .collect::<Vec<_>>();
+
+    let variables = body
+        .iter()
+        .filter(|literal| literal.is_positive())
+        .flat_map(|literal| literal.variables())
+        .cloned()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+
+    let mut terms = Vec::new();
+    let mut head = Vec::new();
+    for atom in rule.head() {
+        let columns = atom
+            .terms()
+            .iter()
+            .map(|term| {
+                if !term.aggregates().is_empty() {
+                    return HeadTerm::Aggregate;
+                }
+                if term.existential_variables().next().is_some() {
+                    return HeadTerm::Existential;
+                }
+
+                terms.push(term.clone());
+                HeadTerm::Column(terms.len() - 1)
+            })
+            .collect();
+
+        head.push((atom.predicate(), columns));
+    }
+
+    let variable_offset = terms.len();
+    let mut provenance_body = Vec::new();
+    for literal in body.iter().filter(|literal| literal.is_positive()) {
+        let atom_terms = literal
+            .terms()
+            .iter()
+            .map(|term| match term {
+                Term::Primitive(PrimitiveTerm::Variable(variable)) => {
+                    let position = variables.binary_search(variable).ok()?;
+                    Some(ProvenanceTerm::Column(variable_offset + position))
+                }
+                Term::Primitive(PrimitiveTerm::GroundTerm(value)) => {
+                    Some(ProvenanceTerm::Constant(value.clone()))
+                }
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        provenance_body.push((literal.predicate(), atom_terms));
+    }
+
+    terms.extend(
+        variables
+            .into_iter()
+            .map(|variable| Term::Primitive(PrimitiveTerm::Variable(variable))),
+    );
+    // Predicates must have at least one position
+    if terms.is_empty() {
+        terms.push(Term::Primitive(PrimitiveTerm::GroundTerm(
+            AnyDataValue::new_boolean(true),
+        )));
+    }
+
+    let mut instance_rule = Rule::new(
+        vec![Atom::new(predicate.clone(), terms)],
+        body,
+        rule.constraints().clone(),
+    );
+    if let Some(position) = rule.position() {
+        instance_rule.set_position(position);
+    }
+
+    Some((
+        instance_rule,
+        ProvenanceRule {
+            predicate,
+            rule: index,
+            head,
+            body: provenance_body,
+        },
+    ))
+}
+
+/// Return a copy of the given atom in which every wildcard is replaced by a named variable.
+fn name_wildcards(atom: &Atom) -> Atom {
+    let terms = atom
+        .terms()
+        .iter()
+        .map(|term| match term {
+            Term::Primitive(PrimitiveTerm::Variable(Variable::UnnamedUniversal(index))) => {
+                Term::Primitive(PrimitiveTerm::Variable(Variable::Universal(format!(
+                    "_WILDCARD_{index}"
+                ))))
+            }
+            _ => term.clone(),
+        })
+        .collect();
+
+    Atom::new(atom.predicate(), terms)
+}
+
+/// A rule instantiation that derives a fact from other facts
+#[derive(Debug)]
+struct Derivation {
+    /// Index of the source of the rule
+    rule: usize,
+    /// Index of the derived fact
+    head: usize,
+    /// Indices of the facts in the body
+    body: Vec<usize>,
+}
+
+/// For each predicate, set of positions, and rule that created the nulls at the remaining positions,
+/// if these must be nulls, the facts with the given values at these positions
+type FactIndices =
+    HashMap<(Identifier, Vec<usize>, Option<usize>), HashMap<Vec<AnyDataValue>, Vec<usize>>>;
+
+/// Return the index of the rule whose application created the given value
+/// according to the given ranges of null ids,
+/// or `None` if it is not a null or its origin is unknown.
+fn null_origin(null_origins: &[(Range<usize>, usize)], value: &AnyDataValue) -> Option<usize> {
+    if value.value_domain() != ValueDomain::Null {
+        return None;
+    }
+
+    let id = value.null_id_unchecked();
+    let position = null_origins.partition_point(|(range, _)| range.end <= id);
+    null_origins
+        .get(position)
+        .filter(|(range, _)| range.contains(&id))
+        .map(|(_, rule)| *rule)
+}
+
+/// Computes the [Provenance] of all facts
+/// from the input facts and the instantiations of all rules
+#[derive(Debug, Default)]
+pub(crate) struct ProvenanceBuilder {
+    provenance: Provenance,
+    /// Index of each source in the list of sources
+    source_indices: HashMap<ProvenanceSource, usize>,
+    /// All derivations
+    derivations: Vec<Derivation>,
+    /// Index of the facts that are matched against head atoms with existential variables or aggregates
+    indices: FactIndices,
+    /// Rules whose applications created nulls, with the ids of the created nulls
+    null_origins: Vec<(Range<usize>, usize)>,
+    /// Facts derived by the instantiations of rules with aggregates,
+    /// for each rule, head atom, and values of the remaining head terms
+    aggregate_groups: HashMap<(usize, usize, Vec<AnyDataValue>), Option<usize>>,
+}
+
+impl ProvenanceBuilder {
+    /// Create a new [ProvenanceBuilder],
+    /// where each null was created by an application of the rule of the program
+    /// that is given for the range of ids that contains the id of the null.
+    pub(crate) fn new(null_origins: Vec<(Range<usize>, usize)>) -> Self {
+        Self {
+            null_origins,
+            ..Default::default()
+        }
+    }
+
+    /// Return the index of the given fact, adding it if it is not yet known.
+    pub(crate) fn add_fact(&mut self, predicate: &Identifier, row: Vec<AnyDataValue>) -> usize {
+        let facts = self.provenance.facts.entry(predicate.clone()).or_default();
+        let annotations = &mut self.provenance.annotations;
+
+        *facts.entry(row).or_insert_with(|| {
+            annotations.push(FactAnnotation::default());
+            annotations.len() - 1
+        })
+    }
+
+    /// Return the index of the given source, adding it if it is not yet known.
+    fn add_source(&mut self, source: ProvenanceSource) -> usize {
+        let sources = &mut self.provenance.sources;
+
+        *self
+            .source_indices
+            .entry(source.clone())
+            .or_insert_with(|| {
+                sources.push(source);
+                sources.len() - 1
+            })
+    }
+
+    /// Record that the given fact is an input fact.
+    pub(crate) fn add_input(&mut self, predicate: &Identifier, row: Vec<AnyDataValue>) {
+        let fact = self.add_fact(predicate, row);
+        let source = self.add_source(ProvenanceSource::Input(predicate.clone()));
+
+        let annotation = &mut self.provenance.annotations[fact];
+        annotation.derivations += 1;
+        annotation.sources.insert(source);
+    }
+
+    /// Return the indices of the facts of the given predicate
+    /// that have the given values at the positions that contain a column
+    /// and nulls created by the given rule at the positions with existential variables.
+    ///
+    /// Only facts that were added before the first call for this predicate and positions are found.
+    fn matching_facts(
+        &mut self,
+        predicate: &Identifier,
+        terms: &[HeadTerm],
+        row: &[AnyDataValue],
+        rule: usize,
+    ) -> Vec<usize> {
+        let columns = terms
+            .iter()
+            .map(|term| match term {
+                HeadTerm::Column(column) => Some(*column),
+                HeadTerm::Existential | HeadTerm::Aggregate => None,
+            })
+            .collect::<Vec<_>>();
+        let positions = columns
+            .iter()
+            .enumerate()
+            .filter_map(|(position, column)| column.map(|_| position))
+            .collect::<Vec<_>>();
+        let key = columns.iter().flatten().map(|&column| row[column].clone());
+
+        if positions.len() == columns.len() {
+            return vec![self.add_fact(predicate, key.collect())];
+        }
+
+        let existential = terms
+            .iter()
+            .enumerate()
+            .filter_map(|(position, term)| (*term == HeadTerm::Existential).then_some(position))
+            .collect::<Vec<_>>();
+        let origin = (!existential.is_empty()).then_some(rule);
+
+        let null_origins = &self.null_origins;
+        let index = self
+            .indices
+            .entry((predicate.clone(), positions.clone(), origin))
+            .or_insert_with(|| {
+                let mut index = HashMap::<Vec<AnyDataValue>, Vec<usize>>::new();
+
+                for (fact_row, &fact) in self.provenance.facts.get(predicate).into_iter().flatten()
+                {
+                    if existential
+                        .iter()
+                        .any(|&position| null_origin(null_origins, &fact_row[position]) != origin)
+                    {
+                        continue;
+                    }
+
+                    let values = positions
+                        .iter()
+                        .map(|&position| fact_row[position].clone())
+                        .collect();
+                    index.entry(values).or_default().push(fact);
+                }
+
+                index
+            });
+
+        index
+            .get(&key.collect::<Vec<_>>())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Record the derivations described by the given instantiation of a [ProvenanceRule].
+    ///
+    /// For head atoms with existential variables, every fact that agrees with the instantiation
+    /// on the remaining positions and contains nulls created by the rule at the existential positions
+    /// is derived. For head atoms with aggregates, the only fact that agrees with the instantiation
+    /// on the remaining positions is derived, once for all instantiations with these values.
+    pub(crate) fn add_instantiation(&mut self, rule: &ProvenanceRule, row: &[AnyDataValue]) {
+        let source = self.add_source(ProvenanceSource::Rule(rule.rule));
+
+        let body = rule
+            .body
+            .iter()
+            .map(|(predicate, terms)| {
+                let fact_row = terms
+                    .iter()
+                    .map(|term| match term {
+                        ProvenanceTerm::Column(column) => row[*column].clone(),
+                        ProvenanceTerm::Constant(value) => value.clone(),
+                    })
+                    .collect();
+
+                self.add_fact(predicate, fact_row)
+            })
+            .collect::<Vec<_>>();
+
+        for (atom, (predicate, terms)) in rule.head.iter().enumerate() {
+            let heads = self.matching_facts(predicate, terms, row, rule.rule);
+
+            if terms.contains(&HeadTerm::Aggregate) {
+                let group = terms
+                    .iter()
+                    .filter_map(|term| match term {
+                        HeadTerm::Column(column) => Some(row[*column].clone()),
+                        HeadTerm::Existential | HeadTerm::Aggregate => None,
+                    })
+                    .collect();
+
+                let key = (rule.rule, atom, group);
+                let (head, new_group) = match self.aggregate_groups.entry(key) {
+                    Entry::Occupied(entry) => (*entry.get(), false),
+                    Entry::Vacant(entry) => {
+                        let head = (heads.len() == 1).then(|| heads[0]);
+                        (*entry.insert(head), true)
+                    }
+                };
+                let Some(head) = head else {
+                    continue;
+                };
+
+                if new_group {
+                    self.provenance.annotations[head].derivations += 1;
+                }
+                self.add_derivation(source, head, body.clone());
+                continue;
+            }
+
+            for head in heads {
+                self.provenance.annotations[head].derivations += 1;
+                self.add_derivation(source, head, body.clone());
+            }
+        }
+    }
+
+    /// Record that the given fact is derived by the rule with the given source
+    /// from the given facts.
+    fn add_derivation(&mut self, rule: usize, head: usize, body: Vec<usize>) {
+        self.provenance.annotations[head].sources.insert(rule);
+        self.derivations.push(Derivation { rule, head, body });
+    }
+
+    /// Propagate the sources of all facts along the derivations and return the result.
+    pub(crate) fn finalize(mut self) -> Provenance {
+        let mut dependents = vec![Vec::new(); self.provenance.annotations.len()];
+        for (index, derivation) in self.derivations.iter().enumerate() {
+            for &fact in &derivation.body {
+                dependents[fact].push(index);
+            }
+        }
+
+        let annotations = &mut self.provenance.annotations;
+        let mut pending = (0..annotations.len()).collect::<Vec<_>>();
+
+        while let Some(fact) = pending.pop() {
+            for &index in &dependents[fact] {
+                let derivation = &self.derivations[index];
+
+                let mut sources = BTreeSet::from([derivation.rule]);
+                for &body_fact in &derivation.body {
+                    sources.extend(annotations[body_fact].sources.iter().copied());
+                }
+
+                let head_sources = &mut annotations[derivation.head].sources;
+                if !sources.is_subset(head_sources) {
+                    head_sources.extend(sources);
+                    pending.push(derivation.head);
+                }
+            }
+        }
+
+        self.provenance
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use nemo_physical::datavalues::AnyDataValue;
+
+    use crate::{
+        execution::{DefaultExecutionEngine, ExecutionParameters},
+        io::{parser::parse_program, resource_providers::ResourceProviders, ImportManager},
+        model::Identifier,
+    };
+
+    use super::ProvenanceSource;
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn annotate_facts() {
+        let program = parse_program(
+            r#"
+            edge(a, b). edge(b, c). edge(a, c). other(a, c).
+            reach(?x, ?y) :- edge(?x, ?y) .
+            reach(?x, ?z) :- reach(?x, ?y), edge(?y, ?z) .
+            reach(?x, ?y) :- other(?x, ?y) .
+            named(?x, !n) :- reach(?x, _) .
+            "#,
+        )
+        .unwrap();
+        let parameters = ExecutionParameters {
+            provenance: true,
+            ..Default::default()
+        };
+        let mut engine = DefaultExecutionEngine::initialize_with_parameters(
+            &program,
+            ImportManager::new(ResourceProviders::empty()),
+            parameters,
+        )
+        .unwrap();
+        engine.execute().unwrap();
+
+        let reach = Identifier("reach".to_string());
+        let named = Identifier("named".to_string());
+        let constant = |name: &str| AnyDataValue::new_iri(name.to_string());
+        let provenance = engine.provenance().unwrap().unwrap();
+
+        let fact = [constant("a"), constant("c")];
+        assert_eq!(provenance.derivations(&reach, &fact), Some(3));
+        assert_eq!(
+            provenance.sources(&reach, &fact).unwrap(),
+            vec![
+                &ProvenanceSource::Input(Identifier("edge".to_string())),
+                &ProvenanceSource::Input(Identifier("other".to_string())),
+                &ProvenanceSource::Rule(0),
+                &ProvenanceSource::Rule(1),
+                &ProvenanceSource::Rule(2),
+            ]
+        );
+
+        let fact = [constant("b"), constant("c")];
+        assert_eq!(provenance.derivations(&reach, &fact), Some(1));
+        assert_eq!(provenance.sources(&reach, &fact).unwrap().len(), 2);
+        assert_eq!(
+            provenance.derivations(&reach, &[constant("c"), constant("a")]),
+            None
+        );
+
+        let rows = engine
+            .predicate_rows_with_provenance(&named)
+            .unwrap()
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(rows.len(), 2);
+        for row in rows {
+            assert_eq!(row.len(), 4);
+            if row[0] == constant("a") {
+                assert_eq!(row[2], AnyDataValue::new_integer_from_u64(2));
+            }
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn annotate_existential_and_aggregate_facts() {
+        let program = parse_program(
+            r#"
+            person(alice). person(bob). parent(alice, carol). count(alice, 5).
+            parent(?x, !p) :- person(?x) .
+            count(?x, #count(?p)) :- parent(?x, ?p) .
+            "#,
+        )
+        .unwrap();
+        let parameters = ExecutionParameters {
+            provenance: true,
+            ..Default::default()
+        };
+        let mut engine = DefaultExecutionEngine::initialize_with_parameters(
+            &program,
+            ImportManager::new(ResourceProviders::empty()),
+            parameters,
+        )
+        .unwrap();
+        engine.execute().unwrap();
+
+        let parent = Identifier("parent".to_string());
+        let count = Identifier("count".to_string());
+        let constant = |name: &str| AnyDataValue::new_iri(name.to_string());
+        let null = engine
+            .predicate_rows(&parent)
+            .unwrap()
+            .unwrap()
+            .find(|row| row[0] == constant("bob"))
+            .unwrap()[1]
+            .clone();
+        let provenance = engine.provenance().unwrap().unwrap();
+
+        // The trigger for alice was satisfied by the input fact
+        let fact = [constant("alice"), constant("carol")];
+        assert_eq!(provenance.derivations(&parent, &fact), Some(1));
+        assert_eq!(
+            provenance.sources(&parent, &fact).unwrap(),
+            vec![&ProvenanceSource::Input(parent.clone())]
+        );
+
+        let fact = [constant("bob"), null];
+        assert_eq!(provenance.derivations(&parent, &fact), Some(1));
+        assert_eq!(
+            provenance.sources(&parent, &fact).unwrap(),
+            vec![
+                &ProvenanceSource::Input(Identifier("person".to_string())),
+                &ProvenanceSource::Rule(0),
+            ]
+        );
+
+        let fact = [constant("bob"), AnyDataValue::new_integer_from_u64(1)];
+        assert_eq!(provenance.derivations(&count, &fact), Some(1));
+        assert!(provenance
+            .sources(&count, &fact)
+            .unwrap()
+            .contains(&&ProvenanceSource::Rule(1)));
+
+        // The aggregated fact for alice cannot be distinguished from the input fact
+        let fact = [constant("alice"), AnyDataValue::new_integer_from_u64(5)];
+        assert_eq!(provenance.derivations(&count, &fact), Some(1));
+        assert_eq!(
+            provenance.sources(&count, &fact).unwrap(),
+            vec![&ProvenanceSource::Input(count.clone())]
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn provenance_disabled() {
+        let mut engine = DefaultExecutionEngine::initialize(
+            &parse_program("p(a). q(?x) :- p(?x) .").unwrap(),
+            ImportManager::new(ResourceProviders::empty()),
+        )
+        .unwrap();
+        engine.execute().unwrap();
+
+        assert!(engine.provenance().unwrap().is_none());
+        assert!(engine
+            .predicate_rows_with_provenance(&Identifier("q".to_string()))
+            .unwrap()
+            .is_none());
+    }
+}
@@ -4,17 +4,24 @@ use nemo_physical::tabular::operations::OperationTableGenerator;
 
 use crate::{
     error::Error,
-    model::{chase_model::ChaseRule, Identifier, Variable},
+    model::{
+        chase_model::{ChaseAtom, ChaseRule, VariableAtom},
+        Identifier, Variable,
+    },
     program_analysis::{analysis::RuleAnalysis, variable_order::VariableOrder},
-    table_manager::{SubtableExecutionPlan, TableManager},
+    table_manager::{
+        ComputedTableRole, SubtableExecutionPlan, SubtableExecutionStatistics, TableManager,
+    },
 };
 
 use super::{
     execution_engine::RuleInfo,
+    explanation::RuleExplanation,
+    instrumentation::RuleApplication,
     planning::{
-        plan_aggregate::AggregateStategy, plan_body_seminaive::SeminaiveStrategy,
-        plan_head_datalog::DatalogStrategy, plan_head_restricted::RestrictedChaseStrategy,
-        BodyStrategy, HeadStrategy,
+        cost_model::JoinCostModel, plan_aggregate::AggregateStategy,
+        plan_body_seminaive::SeminaiveStrategy, plan_head_datalog::DatalogStrategy,
+        plan_head_restricted::RestrictedChaseStrategy, BodyStrategy, HeadStrategy,
     },
 };
 
@@ -30,6 +37,11 @@ pub(crate) struct RuleExecution {
     /// List of variable orders which might be considered for this rule
     promising_variable_orders: Vec<VariableOrder>,
 
+    /// Atoms of the positive body of the rule, used for estimating the cost of variable orders
+    positive_atoms: Vec<VariableAtom>,
+    /// Predicates of the head of the rule
+    head_predicates: Vec<Identifier>,
+
     /// Object for generating an execution plan,
     /// which evaluates the body expression of the rule
     body_strategy: Box<dyn BodyStrategy>,
@@ -63,9 +75,19 @@ impl RuleExecution {
             .as_ref()
             .map(|_| AggregateStategy::initialize(rule, analysis));
 
+        let mut head_predicates = rule
+            .head()
+            .iter()
+            .map(|atom| atom.predicate())
+            .collect::<Vec<_>>();
+        head_predicates.sort();
+        head_predicates.dedup();
+
         let promising_variable_orders = analysis.promising_variable_orders.clone();
         Self {
             promising_variable_orders,
+            positive_atoms: rule.positive_body().clone(),
+            head_predicates,
             variable_translation,
             body_strategy,
             aggregate_strategy,
@@ -73,27 +95,47 @@ impl RuleExecution {
         }
     }
 
-    /// Execute the current rule.
-    /// Returns the predicates which received new elements.
-    pub(crate) fn execute(
+    /// Choose the most promising variable order for the next application of this rule,
+    /// based on the current statistics of the tables of its body predicates.
+    fn choose_variable_order(
         &self,
         table_manager: &mut TableManager,
-        rule_info: &RuleInfo,
-        step_number: usize,
-    ) -> Result<Vec<Identifier>, Error> {
+    ) -> Result<VariableOrder, Error> {
+        let cost_model = JoinCostModel::from_table_manager(&self.positive_atoms, table_manager)?;
+        let (orders, chosen) = cost_model.choose(&self.promising_variable_orders);
+
         log::info!(
             "Available orders: {}",
-            self.promising_variable_orders.iter().enumerate().fold(
+            orders.iter().enumerate().fold(
                 "".to_string(),
-                |acc, (index, promising_order)| {
-                    format!("{}\n   ({}) {}", acc, index, promising_order.debug())
+                |acc, (index, (promising_order, cost))| {
+                    format!(
+                        "{}\n   ({}) {} with estimated cost {}",
+                        acc,
+                        index,
+                        promising_order.debug(),
+                        cost
+                    )
                 }
             )
         );
-        // TODO: Just because its the first doesn't mean its the best
-        let mut best_variable_order = self.promising_variable_orders[0].clone();
+        log::info!("Chosen order: ({}) {}", chosen, orders[chosen].0.debug());
+
+        Ok(orders[chosen].0.clone())
+    }
 
-        let mut subtable_execution_plan = SubtableExecutionPlan::default();
+    /// Build the execution plan for applying the current rule in the given step,
+    /// and return it together with the variable order it uses.
+    fn plan(
+        &self,
+        table_manager: &mut TableManager,
+        rule_info: &RuleInfo,
+        step_number: usize,
+        count_duplicates: bool,
+    ) -> Result<(SubtableExecutionPlan, VariableOrder), Error> {
+        let mut best_variable_order = self.choose_variable_order(table_manager)?;
+
+        let mut subtable_execution_plan = SubtableExecutionPlan::new(count_duplicates);
         let body_node = self.body_strategy.add_plan_body(
             table_manager,
             &mut subtable_execution_plan,
@@ -121,6 +163,113 @@ impl RuleExecution {
             step_number,
         );
 
-        table_manager.execute_plan(subtable_execution_plan)
+        Ok((subtable_execution_plan, best_variable_order))
+    }
+
+    /// Describe the given execution plan of this rule.
+    fn explanation(
+        &self,
+        table_manager: &TableManager,
+        plan: &SubtableExecutionPlan,
+        variable_order: &VariableOrder,
+        step_number: usize,
+    ) -> RuleExplanation {
+        RuleExplanation {
+            step: step_number,
+            variable_order: variable_order.iter().cloned().collect(),
+            plan: table_manager.explain_plan(plan, |marker| {
+                match self.variable_translation.find_external(marker) {
+                    Some(variable) => variable.to_string(),
+                    None => format!("#{}", marker.0),
+                }
+            }),
+        }
+    }
+
+    /// Describe how the current rule would be applied in the given step,
+    /// without applying it.
+    pub(crate) fn explain(
+        &self,
+        table_manager: &mut TableManager,
+        rule_info: &RuleInfo,
+        step_number: usize,
+    ) -> Result<RuleExplanation, Error> {
+        let (plan, variable_order) = self.plan(table_manager, rule_info, step_number, false)?;
+
+        Ok(self.explanation(table_manager, &plan, &variable_order, step_number))
+    }
+
+    /// Execute the current rule.
+    /// Returns the predicates which received new elements,
+    /// together with measurements of the application.
+    ///
+    /// Facts that are derived again are only counted if `count_duplicates` is true.
+    /// The execution plan is described in the measurements if `explain` is true.
+    pub(crate) fn execute(
+        &self,
+        table_manager: &mut TableManager,
+        rule_info: &RuleInfo,
+        step_number: usize,
+        count_duplicates: bool,
+        explain: bool,
+    ) -> Result<(Vec<Identifier>, RuleApplication), Error> {
+        let (subtable_execution_plan, variable_order) =
+            self.plan(table_manager, rule_info, step_number, count_duplicates)?;
+
+        let mut explanation = explain.then(|| {
+            self.explanation(
+                table_manager,
+                &subtable_execution_plan,
+                &variable_order,
+                step_number,
+            )
+        });
+
+        let nulls_before = table_manager.dictionary().num_nulls();
+        let (updated_predicates, statistics) =
+            table_manager.execute_plan(subtable_execution_plan)?;
+
+        if let Some(explanation) = explanation.as_mut() {
+            explanation.plan.add_statistics(&statistics.tables);
+        }
+
+        let mut application = self.rule_application(statistics, count_duplicates);
+        application.nulls = table_manager.dictionary().num_nulls() - nulls_before;
+        application.explanation = explanation;
+
+        Ok((updated_predicates, application))
+    }
+
+    /// Summarize the statistics of the tables computed in an application of this rule.
+    fn rule_application(
+        &self,
+        statistics: SubtableExecutionStatistics,
+        count_duplicates: bool,
+    ) -> RuleApplication {
+        let mut application = RuleApplication {
+            derived_facts: statistics
+                .new_rows
+                .iter()
+                .filter(|(predicate, _)| self.head_predicates.contains(predicate))
+                .map(|(predicate, rows)| (predicate.clone(), *rows))
+                .collect(),
+            duplicates: count_duplicates.then_some(0),
+            ..Default::default()
+        };
+
+        for (role, tree) in statistics.roles {
+            match role {
+                ComputedTableRole::Body => application.body_time += tree.time,
+                ComputedTableRole::Candidates(predicate) => {
+                    let new_rows = statistics.new_rows.get(&predicate).copied().unwrap_or(0);
+                    if let Some(duplicates) = application.duplicates.as_mut() {
+                        *duplicates += tree.rows.unwrap_or(0).saturating_sub(new_rows);
+                    }
+                }
+            }
+        }
+        application.head_time = statistics.time.saturating_sub(application.body_time);
+
+        application
     }
 }
@@ -39,8 +39,13 @@ impl<SubStrategy: RuleSelectionStrategy> StrategyStratifiedNegation<SubStrategy>
         let mut predicate_to_rules_head = HashMap::<Identifier, Vec<usize>>::new();
 
         for (rule_index, rule_analysis) in rule_analyses.iter().enumerate() {
+            // Monotone aggregates are maintained during the computation of their body predicates
+            // and may therefore occur in the same stratum
+            let is_stratified_aggregate =
+                rule_analysis.has_aggregates && !rule_analysis.is_monotone_aggregate;
+
             for body_predicate in &rule_analysis.positive_body_predicates {
-                let indices = if rule_analysis.has_aggregates {
+                let indices = if is_stratified_aggregate {
                     // An aggregate in a head means that the head predicates need to be in a higher stratum than the body predicates
                     // This is the same as when all body literals are negative
                     // Therefore, we can easily compute strata for aggregates by acting if all body atoms in the rule were negated
@@ -48,7 +53,7 @@ impl<SubStrategy: RuleSelectionStrategy> StrategyStratifiedNegation<SubStrategy>
                         .entry(body_predicate.clone())
                         .or_default()
                 } else {
-                    // No aggregates in the rule
+                    // No aggregates in the rule that require stratification
                     predicate_to_rules_body_positive
                         .entry(body_predicate.clone())
                         .or_default()
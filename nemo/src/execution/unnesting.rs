@@ -0,0 +1,290 @@
+//! Unnesting of tuples in the body of rules.
+//!
+//! A constraint `?x = UNNEST(t)` binds `?x` to every element of the tuple `t`.
+//! Such rules are rewritten into two kinds of rules:
+//! one that collects all tuples that are unnested in the predicate [UNNEST_INPUT_PREDICATE_NAME],
+//! and the original rule, where the constraint is replaced by a join with the predicate
+//! [UNNEST_PREDICATE_NAME], which relates each collected tuple to its elements.
+//! The facts of the latter predicate are computed by the execution engine during reasoning.
+
+use std::collections::HashSet;
+
+use nemo_physical::datavalues::{AnyDataValue, DataValue};
+
+use crate::model::{
+    Atom, Constraint, Identifier, Literal, PrimitiveTerm, Program, Rule, Term, Variable,
+};
+
+/// Name of the predicate that collects all tuples that are unnested by some rule
+const UNNEST_INPUT_PREDICATE_NAME: &str = "__UNNEST_INPUT";
+/// Name of the predicate that relates each unnested tuple to its elements
+const UNNEST_PREDICATE_NAME: &str = "__UNNEST";
+/// Prefix of the variables that are introduced for unnested terms that are not variables
+const UNNEST_VARIABLE_PREFIX: &str = "_UNNEST_";
+
+/// Return the predicate that collects all tuples that are unnested by some rule.
+pub(crate) fn unnest_input_predicate() -> Identifier {
+    Identifier(UNNEST_INPUT_PREDICATE_NAME.to_string())
+}
+
+/// Return the predicate that relates each unnested tuple to its elements.
+pub(crate) fn unnest_predicate() -> Identifier {
+    Identifier(UNNEST_PREDICATE_NAME.to_string())
+}
+
+/// Return whether the given predicate is used internally for unnesting tuples.
+pub(crate) fn is_unnest_predicate(predicate: &Identifier) -> bool {
+    predicate.0 == UNNEST_INPUT_PREDICATE_NAME || predicate.0 == UNNEST_PREDICATE_NAME
+}
+
+/// Return the rows of [UNNEST_PREDICATE_NAME] for the given value,
+/// i.e., a pair of the value and each of its elements if it is a tuple.
+pub(crate) fn unnest_rows(value: &AnyDataValue) -> Vec<Vec<AnyDataValue>> {
+    let length = value.length().unwrap_or_default();
+
+    (0..length)
+        .filter_map(|index| value.tuple_element(index))
+        .map(|element| vec![value.clone(), element.clone()])
+        .collect()
+}
+
+/// Return a copy of the given program, where each rule that unnests tuples
+/// is rewritten into a rule that joins with [UNNEST_PREDICATE_NAME],
+/// together with rules that collect the unnested tuples.
+///
+/// Returns the given program unchanged if no rule unnests tuples.
+pub(crate) fn add_unnesting_rules(program: &Program) -> Program {
+    if !program.rules().iter().any(|rule| {
+        rule.constraints()
+            .iter()
+            .any(|c| c.has_form_unnest().is_some())
+    }) {
+        return program.clone();
+    }
+
+    let mut result = program.clone();
+    let mut next_variable = 0;
+    let mut input_rules = Vec::new();
+
+    for rule in result.rules_mut() {
+        if let Some(rewritten) = rewrite_rule(rule, &mut next_variable, &mut input_rules) {
+            *rule = rewritten;
+        }
+    }
+
+    result.add_internal_outputs([unnest_input_predicate(), unnest_predicate()]);
+    result.add_rules(input_rules);
+
+    result
+}
+
+/// A constraint `?variable = UNNEST(term)` of a rule
+struct Unnesting {
+    /// Variable that is bound to the elements of the tuple
+    variable: Variable,
+    /// Variable that is bound to the tuple
+    tuple: Variable,
+    /// Assignment of the tuple variable if the unnested term is not a variable
+    assignment: Option<Constraint>,
+}
+
+impl Unnesting {
+    /// Return the atom that relates the tuple to its elements.
+    fn atom(&self) -> Atom {
+        Atom::new(
+            unnest_predicate(),
+            vec![
+                Term::Primitive(PrimitiveTerm::Variable(self.tuple.clone())),
+                Term::Primitive(PrimitiveTerm::Variable(self.variable.clone())),
+            ],
+        )
+    }
+}
+
+/// Rewrite the given rule if it unnests tuples,
+/// and add the rules that collect the unnested tuples to `input_rules`.
+///
+/// Returns `None` if the rule does not unnest tuples.
+fn rewrite_rule(
+    rule: &Rule,
+    next_variable: &mut usize,
+    input_rules: &mut Vec<Rule>,
+) -> Option<Rule> {
+    let mut unnestings = Vec::new();
+    let mut constraints = Vec::new();
+
+    for constraint in rule.constraints() {
+        let Some((variable, term)) = constraint.has_form_unnest() else {
+            constraints.push(constraint.clone());
+            continue;
+        };
+
+        let (tuple, assignment) = match term {
+            Term::Primitive(PrimitiveTerm::Variable(tuple)) => (tuple.clone(), None),
+            _ => {
+                let name = format!("{UNNEST_VARIABLE_PREFIX}{next_variable}");
+                let tuple = Variable::Universal(name);
+                *next_variable += 1;
+
+                let assignment = Constraint::Equals(
+                    Term::Primitive(PrimitiveTerm::Variable(tuple.clone())),
+                    term.clone(),
+                );
+                (tuple, Some(assignment))
+            }
+        };
+
+        unnestings.push(Unnesting {
+            variable: variable.clone(),
+            tuple,
+            assignment,
+        });
+    }
+
+    if unnestings.is_empty() {
+        return None;
+    }
+
+    let positive_body = rule
+        .body()
+        .iter()
+        .filter(|literal| literal.is_positive())
+        .cloned()
+        .collect::<Vec<_>>();
+
+    // Tuples are collected in an order in which each tuple
+    // only depends on the elements of tuples that were collected before
+    let mut body = positive_body;
+    let mut remaining = unnestings.iter().collect::<Vec<_>>();
+    while !remaining.is_empty() {
+        let (available, available_constraints) = available_constraints(&body, &constraints);
+
+        let next = remaining
+            .iter()
+            .position(|unnesting| match &unnesting.assignment {
+                Some(assignment) => assignment
+                    .right()
+                    .variables()
+                    .all(|v| available.contains(v)),
+                None => available.contains(&unnesting.tuple),
+            })
+            .unwrap_or_default();
+        let unnesting = remaining.remove(next);
+
+        let mut input_constraints = available_constraints;
+        input_constraints.extend(unnesting.assignment.clone());
+
+        let mut input_rule = Rule::new(
+            vec![Atom::new(
+                unnest_input_predicate(),
+                vec![Term::Primitive(PrimitiveTerm::Variable(
+                    unnesting.tuple.clone(),
+                ))],
+            )],
+            body.clone(),
+            input_constraints,
+        );
+        if let Some(position) = rule.position() {
+            input_rule.set_position(position);
+        }
+        input_rules.push(input_rule);
+
+        body.push(Literal::Positive(unnesting.atom()));
+        constraints.extend(unnesting.assignment.clone());
+    }
+
+    let mut rewritten_body = rule.body().clone();
+    rewritten_body.extend(
+        unnestings
+            .iter()
+            .map(|unnesting| Literal::Positive(unnesting.atom())),
+    );
+
+    let mut rewritten = Rule::new(rule.head().clone(), rewritten_body, constraints);
+    if let Some(position) = rule.position() {
+        rewritten.set_position(position);
+    }
+
+    Some(rewritten)
+}
+
+/// Return the variables that are bound by the given positive literals or by assignments
+/// among the given constraints, together with all constraints that only use such variables.
+pub(crate) fn available_constraints(
+    body: &[Literal],
+    constraints: &[Constraint],
+) -> (HashSet<Variable>, Vec<Constraint>) {
+    let mut available = body
+        .iter()
+        .flat_map(|literal| literal.variables())
+        .cloned()
+        .collect::<HashSet<_>>();
+    let mut used = vec![false; constraints.len()];
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+
+        for (constraint, used) in constraints.iter().zip(used.iter_mut()) {
+            if *used {
+                continue;
+            }
+
+            if let Some((variable, term)) = constraint.has_form_assignment() {
+                if !available.contains(variable) {
+                    if term.variables().all(|v| available.contains(v)) {
+                        available.insert(variable.clone());
+                        *used = true;
+                        changed = true;
+                    }
+
+                    continue;
+                }
+            }
+
+            if constraint.variables().all(|v| available.contains(v)) {
+                *used = true;
+                changed = true;
+            }
+        }
+    }
+
+    let result = constraints
+        .iter()
+        .zip(used)
+        .filter(|(_, used)| *used)
+        .map(|(constraint, _)| constraint.clone())
+        .collect();
+
+    (available, result)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{execution::unnesting::add_unnesting_rules, io::parser::parse_program};
+
+    #[test]
+    fn rewrite_unnesting() {
+        let program = parse_program(
+            r#"
+            r(?x, ?e, ?f) :- p(?x, ?t), ?e = UNNEST(?t), ?f = UNNEST(TUPLE(?e, ?x)), ?e != 1 .
+            "#,
+        )
+        .unwrap();
+        let rewritten = add_unnesting_rules(&program);
+
+        let rules = rewritten
+            .rules()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rules,
+            vec![
+                "r(?x, ?e, ?f) :- p(?x, ?t), __UNNEST(?t, ?e), __UNNEST(?_UNNEST_0, ?f), ?e != 1, ?_UNNEST_0 = TUPLE(?e, ?x) .",
+                "__UNNEST_INPUT(?t) :- p(?x, ?t) .",
+                "__UNNEST_INPUT(?_UNNEST_0) :- p(?x, ?t), __UNNEST(?t, ?e), ?e != 1, ?_UNNEST_0 = TUPLE(?e, ?x) .",
+            ]
+        );
+    }
+}
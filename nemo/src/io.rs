@@ -5,6 +5,7 @@
 pub mod compression_format;
 pub mod export_manager;
 pub mod formats;
+pub mod formatter;
 pub mod import_manager;
 pub mod parser;
 pub mod resource_providers;
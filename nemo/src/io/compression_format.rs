@@ -72,7 +72,7 @@ impl CompressionFormat {
     /// For [CompressionFormat::None], the input reader is returned unchanged.
     ///
     /// This is public so that it can be used by external resource providers.
-    pub fn try_decompression<R: BufRead + 'static>(&self, read: R) -> Option<Box<dyn BufRead>> {
+    pub fn try_decompression<R: BufRead + Send + 'static>(&self, read: R) -> Option<Box<dyn BufRead + Send>> {
         match self {
             Self::None => Some(Box::new(read)),
             Self::Gzip => {
@@ -15,6 +15,9 @@ pub mod rdf_writer;
 pub mod json;
 pub mod json_reader;
 
+#[cfg(feature = "arrow")]
+pub mod arrow;
+
 pub(crate) use dsv::DsvHandler;
 pub(crate) use rdf::RdfHandler;
 
@@ -0,0 +1,296 @@
+//! Exchange of tables in the Arrow format.
+//!
+//! Columns of [RecordBatch]es with numeric or boolean types are imported as values of the corresponding type.
+//! Values of string columns are interpreted in the same way as values of DSV files with the format `any`,
+//! which is also used for representing values as strings when exporting tables.
+//! Missing values are imported as fresh nulls.
+
+use std::fmt::Debug;
+
+use arrow_array::{cast::AsArray, types::*, Array, RecordBatch};
+use arrow_schema::DataType;
+use bytesize::ByteSize;
+use nemo_physical::{
+    datasources::{table_providers::TableProvider, tuple_writer::TupleWriter},
+    datavalues::AnyDataValue,
+    management::bytesized::ByteSized,
+};
+
+use super::{dsv_value_format::DsvValueFormat, import_export::ImportExportError};
+
+/// Represent the given value as a string in an exported Arrow table.
+pub(crate) fn serialize_value(value: &AnyDataValue) -> String {
+    DsvValueFormat::serialize_any_value_to_string(value)
+        .expect("all values can be serialized in the any format")
+}
+
+/// Return whether columns of the given [DataType] can be imported.
+fn is_supported(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Boolean
+            | DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32
+            | DataType::UInt64
+            | DataType::Float32
+            | DataType::Float64
+            | DataType::Utf8
+            | DataType::LargeUtf8
+    )
+}
+
+/// Convert the value at the given index of an Arrow column.
+///
+/// Returns `None` if the value is missing.
+fn column_value(
+    column: &dyn Array,
+    index: usize,
+) -> Result<Option<AnyDataValue>, Box<dyn std::error::Error>> {
+    if column.is_null(index) {
+        return Ok(None);
+    }
+
+    let value = match column.data_type() {
+        DataType::Boolean => AnyDataValue::new_boolean(column.as_boolean().value(index)),
+        DataType::Int8 => AnyDataValue::new_integer_from_i64(
+            column.as_primitive::<Int8Type>().value(index).into(),
+        ),
+        DataType::Int16 => AnyDataValue::new_integer_from_i64(
+            column.as_primitive::<Int16Type>().value(index).into(),
+        ),
+        DataType::Int32 => AnyDataValue::new_integer_from_i64(
+            column.as_primitive::<Int32Type>().value(index).into(),
+        ),
+        DataType::Int64 => {
+            AnyDataValue::new_integer_from_i64(column.as_primitive::<Int64Type>().value(index))
+        }
+        DataType::UInt8 => AnyDataValue::new_integer_from_u64(
+            column.as_primitive::<UInt8Type>().value(index).into(),
+        ),
+        DataType::UInt16 => AnyDataValue::new_integer_from_u64(
+            column.as_primitive::<UInt16Type>().value(index).into(),
+        ),
+        DataType::UInt32 => AnyDataValue::new_integer_from_u64(
+            column.as_primitive::<UInt32Type>().value(index).into(),
+        ),
+        DataType::UInt64 => {
+            AnyDataValue::new_integer_from_u64(column.as_primitive::<UInt64Type>().value(index))
+        }
+        DataType::Float32 => {
+            AnyDataValue::new_float_from_f32(column.as_primitive::<Float32Type>().value(index))?
+        }
+        DataType::Float64 => {
+            AnyDataValue::new_double_from_f64(column.as_primitive::<Float64Type>().value(index))?
+        }
+        DataType::Utf8 => DsvValueFormat::parse_any_value_from_string(
+            column.as_string::<i32>().value(index).to_string(),
+        )?,
+        DataType::LargeUtf8 => DsvValueFormat::parse_any_value_from_string(
+            column.as_string::<i64>().value(index).to_string(),
+        )?,
+        data_type => unreachable!("columns of type {data_type} are rejected when importing"),
+    };
+
+    Ok(Some(value))
+}
+
+/// [TableProvider] for tables given as Arrow [RecordBatch]es
+pub struct ArrowTableProvider {
+    /// The imported batches, which all have the same number of columns
+    batches: Vec<RecordBatch>,
+}
+
+impl ArrowTableProvider {
+    /// Create a new [ArrowTableProvider] for tables of the given arity.
+    ///
+    /// Returns an error if a batch does not have the given number of columns
+    /// or contains columns of a type that cannot be imported.
+    pub fn new(batches: Vec<RecordBatch>, arity: usize) -> Result<Self, ImportExportError> {
+        for batch in &batches {
+            if batch.num_columns() != arity {
+                return Err(ImportExportError::InvalidArity {
+                    arity: batch.num_columns(),
+                    expected: arity,
+                });
+            }
+
+            if let Some(field) = batch
+                .schema()
+                .fields()
+                .iter()
+                .find(|field| !is_supported(field.data_type()))
+            {
+                return Err(ImportExportError::UnsupportedArrowType(
+                    field.data_type().to_string(),
+                ));
+            }
+        }
+
+        Ok(Self { batches })
+    }
+}
+
+impl Debug for ArrowTableProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ArrowTableProvider")
+            .field("batches", &self.batches.len())
+            .finish()
+    }
+}
+
+impl ByteSized for ArrowTableProvider {
+    fn size_bytes(&self) -> ByteSize {
+        ByteSize::b(
+            self.batches
+                .iter()
+                .map(|batch| batch.get_array_memory_size() as u64)
+                .sum(),
+        )
+    }
+}
+
+impl TableProvider for ArrowTableProvider {
+    fn provide_table_data(
+        self: Box<Self>,
+        tuple_writer: &mut TupleWriter,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        for batch in self.batches {
+            for row in 0..batch.num_rows() {
+                for column in batch.columns() {
+                    let value = match column_value(column, row)? {
+                        Some(value) => value,
+                        None => tuple_writer.fresh_null().into(),
+                    };
+
+                    tuple_writer.add_tuple_value(value);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use arrow_array::{
+        cast::AsArray, types::Int64Type, Float64Array, Int32Array, RecordBatch, StringArray,
+    };
+    use arrow_schema::{DataType, Field, Schema};
+    use nemo_physical::datavalues::AnyDataValue;
+
+    use crate::{
+        execution::DefaultExecutionEngine,
+        io::{parser::parse_program, resource_providers::ResourceProviders, ImportManager},
+        model::Identifier,
+    };
+
+    fn initialize(program: &str) -> DefaultExecutionEngine {
+        DefaultExecutionEngine::initialize(
+            &parse_program(program).unwrap(),
+            ImportManager::new(ResourceProviders::empty()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn export_record_batch() {
+        let mut engine = initialize(
+            r#"
+            data(1, 0.5, a) .
+            data(2, 1.5, "b") .
+            data(3, 2.5, 4) .
+            "#,
+        );
+        engine.execute().unwrap();
+
+        let batch = engine
+            .predicate_record_batch(&Identifier("data".to_string()))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(batch.num_rows(), 3);
+        assert_eq!(batch.schema().field(0).name(), "0");
+        assert_eq!(batch.column(0).data_type(), &DataType::Int64);
+        assert_eq!(batch.column(1).data_type(), &DataType::Float64);
+        assert_eq!(batch.column(2).data_type(), &DataType::Utf8);
+        assert_eq!(
+            batch
+                .column(0)
+                .as_primitive::<Int64Type>()
+                .values()
+                .to_vec(),
+            vec![1, 2, 3]
+        );
+
+        let mut strings = batch
+            .column(2)
+            .as_string::<i32>()
+            .iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        strings.sort();
+        assert_eq!(strings, vec!["\"b\"", "4", "a"]);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn import_record_batches() {
+        let mut engine = initialize("copy(?x, ?y, ?z) :- data(?x, ?y, ?z) .");
+        let data = Identifier("data".to_string());
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("x", DataType::Int32, false),
+            Field::new("y", DataType::Float64, true),
+            Field::new("z", DataType::Utf8, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2])),
+                Arc::new(Float64Array::from(vec![Some(0.5), None])),
+                Arc::new(StringArray::from(vec!["a", "\"b\""])),
+            ],
+        )
+        .unwrap();
+
+        assert!(engine
+            .add_record_batches(&data, vec![batch.project(&[0, 1]).unwrap()])
+            .is_err());
+        engine.add_record_batches(&data, vec![batch]).unwrap();
+        engine.execute().unwrap();
+
+        let mut rows = engine
+            .predicate_rows(&Identifier("copy".to_string()))
+            .unwrap()
+            .unwrap()
+            .map(|row| row.iter().map(ToString::to_string).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        rows.sort();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(
+            rows[0],
+            [
+                AnyDataValue::new_integer_from_i64(1),
+                AnyDataValue::new_double_from_f64(0.5).unwrap(),
+                AnyDataValue::new_iri("a".to_string()),
+            ]
+            .map(|value| value.to_string())
+        );
+        assert_eq!(rows[1][0], "2");
+        assert!(rows[1][1].starts_with("_:"));
+        assert_eq!(
+            rows[1][2],
+            AnyDataValue::new_plain_string("b".to_string()).to_string()
+        );
+    }
+}
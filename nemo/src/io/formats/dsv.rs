@@ -204,7 +204,7 @@ impl ImportExportHandler for DsvHandler {
 
     fn reader(
         &self,
-        read: Box<dyn BufRead>,
+        read: Box<dyn BufRead + Send>,
         arity: usize,
     ) -> Result<Box<dyn TableProvider>, Error> {
         Ok(Box::new(DsvReader::new(
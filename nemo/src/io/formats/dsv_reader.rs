@@ -22,7 +22,7 @@ use super::dsv_value_format::{DataValueParserFunction, DsvValueFormat};
 /// Parsing of individual values can be done in several ways (DSV does not specify a data model at this level),
 /// as defined by [DsvValueFormat].
 pub(super) struct DsvReader {
-    read: Box<dyn BufRead>,
+    read: Box<dyn BufRead + Send>,
     delimiter: u8,
     escape: Option<u8>,
     value_formats: Vec<DsvValueFormat>,
@@ -32,7 +32,7 @@ pub(super) struct DsvReader {
 impl DsvReader {
     /// Instantiate a [DsvReader] for a given delimiter
     pub(super) fn new(
-        read: Box<dyn BufRead>,
+        read: Box<dyn BufRead + Send>,
         delimiter: u8,
         value_formats: Vec<DsvValueFormat>,
         limit: Option<u64>,
@@ -48,7 +48,7 @@ impl DsvReader {
     }
 
     /// Create a low-level reader for parsing the DSV format.
-    fn reader(self) -> Reader<Box<dyn BufRead>> {
+    fn reader(self) -> Reader<Box<dyn BufRead + Send>> {
         ReaderBuilder::new()
             .delimiter(self.delimiter)
             .escape(self.escape)
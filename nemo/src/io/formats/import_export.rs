@@ -77,7 +77,7 @@ pub(crate) trait ImportExportHandler: std::fmt::Debug + DynClone + Send {
     /// then this will normally be the same value (clashing arity requirements are detected
     /// during program analysis). However, even if not speciied, the arity might also be inferred
     /// from the usage of the imported data in a program, or other requirements known to the caller.
-    fn reader(&self, read: Box<dyn BufRead>, arity: usize)
+    fn reader(&self, read: Box<dyn BufRead + Send>, arity: usize)
         -> Result<Box<dyn TableProvider>, Error>;
 
     /// Obtain a [TableWriter] for this format and the given writer, if supported.
@@ -599,6 +599,9 @@ pub enum ImportExportError {
         /// The file format.
         format: FileFormat,
     },
+    /// Arrow columns of the given data type cannot be imported
+    #[error(r#"Arrow columns of type "{0}" cannot be imported"#)]
+    UnsupportedArrowType(String),
     /// File could not be read
     #[error(r#"File "{path}" could not be read."#)]
     IoError {
@@ -35,7 +35,7 @@ impl ImportExportHandler for JsonHandler {
 
     fn reader(
         &self,
-        read: Box<dyn BufRead>,
+        read: Box<dyn BufRead + Send>,
         arity: usize,
     ) -> Result<Box<dyn TableProvider>, crate::error::Error> {
         if arity != 3 {
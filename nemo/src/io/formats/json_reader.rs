@@ -12,11 +12,11 @@ use nemo_physical::{
 use serde_json::Value;
 
 pub(crate) struct JsonReader {
-    read: Box<dyn BufRead>,
+    read: Box<dyn BufRead + Send>,
 }
 
 impl JsonReader {
-    pub(super) fn new(read: Box<dyn BufRead>) -> Self {
+    pub(super) fn new(read: Box<dyn BufRead + Send>) -> Self {
         JsonReader { read }
     }
 }
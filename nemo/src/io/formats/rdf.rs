@@ -260,7 +260,7 @@ impl ImportExportHandler for RdfHandler {
 
     fn reader(
         &self,
-        read: Box<dyn BufRead>,
+        read: Box<dyn BufRead + Send>,
         arity: usize,
     ) -> Result<Box<dyn TableProvider>, Error> {
         Ok(Box::new(RdfReader::new(
@@ -30,7 +30,7 @@ const DEFAULT_GRAPH: &str = "tag:nemo:defaultgraph";
 
 /// A [TableProvider] for RDF 1.1 files containing triples.
 pub(super) struct RdfReader {
-    read: Box<dyn BufRead>,
+    read: Box<dyn BufRead + Send>,
     variant: RdfVariant,
     base: Option<Iri<String>>,
     value_formats: Vec<RdfValueFormat>,
@@ -47,7 +47,7 @@ pub(super) struct RdfReader {
 impl RdfReader {
     /// Create a new [RDFReader]
     pub(super) fn new(
-        read: Box<dyn BufRead>,
+        read: Box<dyn BufRead + Send>,
         variant: RdfVariant,
         base: Option<Iri<String>>,
         value_formats: Vec<RdfValueFormat>,
@@ -140,7 +140,7 @@ impl RdfReader {
     fn read_triples_with_parser<Parser>(
         mut self,
         tuple_writer: &mut TupleWriter,
-        make_parser: impl Fn(Box<dyn BufRead>) -> Parser,
+        make_parser: impl Fn(Box<dyn BufRead + Send>) -> Parser,
     ) -> Result<(), Box<dyn std::error::Error>>
     where
         Parser: TriplesParser,
@@ -221,7 +221,7 @@ impl RdfReader {
     fn read_quads_with_parser<Parser>(
         mut self,
         tuple_writer: &mut TupleWriter,
-        make_parser: impl Fn(Box<dyn BufRead>) -> Parser,
+        make_parser: impl Fn(Box<dyn BufRead + Send>) -> Parser,
     ) -> Result<(), Box<dyn std::error::Error>>
     where
         Parser: QuadsParser,
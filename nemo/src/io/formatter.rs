@@ -0,0 +1,1014 @@
+//! Formatting of programs in the syntax of the rules language.
+//!
+//! [SyntaxWriter] renders the individual parts of a [Program] such that the result
+//! can be read back by the parser, resulting in the same program.
+//! [format_program] reformats program text into a canonical layout,
+//! keeping the order of statements and all comments.
+
+use std::{collections::HashMap, ops::Range};
+
+use nemo_physical::datavalues::{AnyDataValue, DataValue, ValueDomain};
+
+use crate::{
+    error::Error,
+    io::parser::{all_input_consumed, LocatedProgramElement, ProgramElement, RuleParser},
+    model::{
+        Atom, BinaryOperation, Constraint, DenialRule, ExportDirective, Fact, FileFormat,
+        Identifier, ImportDirective, IntegrityConstraint, KeyConstraint, Literal,
+        LogicalAggregateOperation, PredicateDeclaration, PrimitiveTerm, Program, RdfVariant, Rule,
+        Statement, Term, UnaryOperation, FILE_FORMAT_CSV, FILE_FORMAT_DSV, FILE_FORMAT_JSON,
+        FILE_FORMAT_RDF_NQUADS, FILE_FORMAT_RDF_NTRIPLES, FILE_FORMAT_RDF_TRIG,
+        FILE_FORMAT_RDF_TURTLE, FILE_FORMAT_RDF_UNSPECIFIED, FILE_FORMAT_RDF_XML, FILE_FORMAT_TSV,
+    },
+};
+
+/// Rules that would be longer than this are written with one body element per line.
+const MAX_LINE_WIDTH: usize = 100;
+/// Indentation of body elements of rules that are split over several lines.
+const INDENTATION: &str = "    ";
+
+/// Renders parts of a program in the syntax of the rules language.
+///
+/// IRIs are abbreviated using the known prefixes wherever possible.
+#[derive(Debug, Clone, Default)]
+pub struct SyntaxWriter {
+    /// Pairs of prefix and expansion, longest expansion first
+    prefixes: Vec<(String, String)>,
+}
+
+impl SyntaxWriter {
+    /// Create a new [SyntaxWriter] that abbreviates IRIs using the given prefixes.
+    pub fn new(prefixes: &HashMap<String, String>) -> Self {
+        let mut prefixes: Vec<(String, String)> = prefixes
+            .iter()
+            .filter(|(_, expansion)| !expansion.is_empty())
+            .map(|(prefix, expansion)| (prefix.clone(), expansion.clone()))
+            .collect();
+        prefixes.sort_by(|(prefix_a, expansion_a), (prefix_b, expansion_b)| {
+            expansion_b
+                .len()
+                .cmp(&expansion_a.len())
+                .then_with(|| prefix_a.cmp(prefix_b))
+        });
+
+        Self { prefixes }
+    }
+
+    /// Render an IRI, as a bare name or prefixed name if possible.
+    pub fn iri(&self, iri: &str) -> String {
+        if let Some(label) = iri.strip_prefix("_:") {
+            if is_local_name(label) {
+                return iri.to_string();
+            }
+        }
+
+        if is_bare_name(iri) {
+            return iri.to_string();
+        }
+
+        self.iri_reference(iri)
+    }
+
+    /// Render an IRI in places where bare names are not allowed, e.g., for datatypes.
+    fn iri_reference(&self, iri: &str) -> String {
+        for (prefix, expansion) in &self.prefixes {
+            if let Some(local) = iri.strip_prefix(expansion.as_str()) {
+                if is_local_name(local) {
+                    return format!("{prefix}:{local}");
+                }
+            }
+        }
+
+        format!("<{iri}>")
+    }
+
+    /// Render an [Identifier], e.g., the name of a predicate.
+    pub fn identifier(&self, identifier: &Identifier) -> String {
+        self.iri(&identifier.name())
+    }
+
+    /// Render a data value.
+    pub fn datavalue(&self, value: &AnyDataValue) -> String {
+        match value.value_domain() {
+            ValueDomain::PlainString => quote_string(&value.to_plain_string_unchecked()),
+            ValueDomain::LanguageTaggedString => {
+                let (string, tag) = value.to_language_tagged_string_unchecked();
+                format!("{}@{tag}", quote_string(&string))
+            }
+            ValueDomain::Iri => self.iri(&value.to_iri_unchecked()),
+            ValueDomain::Double => match value.to_f64() {
+                // the debug representation is the shortest one that is read back as the same value,
+                // and it always contains a decimal point or an exponent
+                Some(double) if double.is_finite() => format!("{double:?}"),
+                _ => self.typed_literal(value),
+            },
+            ValueDomain::UnsignedLong
+            | ValueDomain::NonNegativeLong
+            | ValueDomain::UnsignedInt
+            | ValueDomain::NonNegativeInt
+            | ValueDomain::Long
+            | ValueDomain::Int => match value.to_i64() {
+                Some(integer) => integer.to_string(),
+                None => self.typed_literal(value),
+            },
+            ValueDomain::Tuple => {
+                let elements = (0..value.len_unchecked())
+                    .map(|index| self.datavalue(value.tuple_element_unchecked(index)))
+                    .collect::<Vec<_>>();
+                format!("({})", elements.join(", "))
+            }
+            ValueDomain::Map => self.map(value),
+            ValueDomain::Float | ValueDomain::Boolean | ValueDomain::Other => {
+                self.typed_literal(value)
+            }
+            ValueDomain::Null => value.to_string(),
+        }
+    }
+
+    /// Render a value as a literal with explicit datatype.
+    fn typed_literal(&self, value: &AnyDataValue) -> String {
+        format!(
+            "{}^^{}",
+            quote_string(&value.lexical_value()),
+            self.iri_reference(&value.datatype_iri())
+        )
+    }
+
+    /// Render a map, e.g., the attributes of an import or export directive.
+    fn map(&self, value: &impl DataValue) -> String {
+        let entries = value
+            .map_keys()
+            .into_iter()
+            .flatten()
+            .map(|key| {
+                format!(
+                    "{} = {}",
+                    self.datavalue(key),
+                    self.datavalue(value.map_element_unchecked(key))
+                )
+            })
+            .collect::<Vec<_>>();
+
+        format!("{{{}}}", entries.join(", "))
+    }
+
+    /// Render a [Term].
+    pub fn term(&self, term: &Term) -> String {
+        match term {
+            Term::Primitive(PrimitiveTerm::GroundTerm(value)) => self.datavalue(value),
+            Term::Primitive(PrimitiveTerm::Variable(variable)) => variable.to_string(),
+            Term::Binary {
+                operation,
+                lhs,
+                rhs,
+            } => match operation.infix() {
+                Some(operator) if *operation != BinaryOperation::NumericRemainder => {
+                    let precedence = precedence(term);
+                    format!(
+                        "{} {operator} {}",
+                        self.operand(lhs, precedence, false),
+                        self.operand(rhs, precedence, true)
+                    )
+                }
+                _ => format!(
+                    "{}({}, {})",
+                    binary_operation_name(*operation),
+                    self.term(lhs),
+                    self.term(rhs)
+                ),
+            },
+            Term::Ternary {
+                operation,
+                first,
+                second,
+                third,
+            } => format!(
+                "{}({}, {}, {})",
+                operation.name(),
+                self.term(first),
+                self.term(second),
+                self.term(third)
+            ),
+            Term::Nary {
+                operation,
+                parameters,
+            } => format!("{}({})", operation.name(), self.terms(parameters)),
+            Term::Unary(UnaryOperation::NumericNegation, inner) => {
+                format!("-{}", self.operand(inner, precedence(term), false))
+            }
+            Term::Unary(operation, inner) => {
+                format!("{}({})", unary_operation_name(*operation), self.term(inner))
+            }
+            Term::Aggregation(aggregate) => format!(
+                "#{}({})",
+                aggregate_operation_name(aggregate.logical_aggregate_operation),
+                self.terms(&aggregate.terms)
+            ),
+            Term::Function(name, subterms) => {
+                format!("{}({})", self.identifier(name), self.terms(subterms))
+            }
+            Term::UserFunction(function, subterms) => {
+                format!("{}({})", self.iri(function.name()), self.terms(subterms))
+            }
+        }
+    }
+
+    /// Render an operand of an infix operation with the given precedence,
+    /// adding parentheses where they are needed to keep the structure of the term.
+    fn operand(&self, term: &Term, parent_precedence: usize, right: bool) -> String {
+        let precedence = precedence(term);
+
+        if precedence < parent_precedence || (right && precedence == parent_precedence) {
+            format!("({})", self.term(term))
+        } else {
+            self.term(term)
+        }
+    }
+
+    /// Render a comma-separated list of [Term]s.
+    fn terms(&self, terms: &[Term]) -> String {
+        terms
+            .iter()
+            .map(|term| self.term(term))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Render an [Atom].
+    pub fn atom(&self, atom: &Atom) -> String {
+        if atom.is_equality() {
+            return format!(
+                "{} = {}",
+                self.term(&atom.terms()[0]),
+                self.term(&atom.terms()[1])
+            );
+        }
+
+        format!(
+            "{}({})",
+            self.identifier(&atom.predicate()),
+            self.terms(atom.terms())
+        )
+    }
+
+    /// Render a [Literal].
+    pub fn literal(&self, literal: &Literal) -> String {
+        match literal {
+            Literal::Positive(atom) => self.atom(atom),
+            Literal::Negative(atom) => format!("~{}", self.atom(atom)),
+        }
+    }
+
+    /// Render a [Constraint].
+    pub fn constraint(&self, constraint: &Constraint) -> String {
+        format!(
+            "{} {} {}",
+            self.term(constraint.left()),
+            constraint.operator_string(),
+            self.term(constraint.right())
+        )
+    }
+
+    /// Render a [Fact].
+    pub fn fact(&self, fact: &Fact) -> String {
+        format!("{}.", self.atom(&fact.0))
+    }
+
+    /// Render a [Rule].
+    ///
+    /// Rules that do not fit into a single line are written
+    /// with each element of the body on its own line.
+    pub fn rule(&self, rule: &Rule) -> String {
+        let head = rule
+            .head()
+            .iter()
+            .map(|atom| self.atom(atom))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        self.rule_layout(&head, rule.body(), rule.constraints())
+    }
+
+    /// Render a [DenialRule], using the same layout as for [Rule]s.
+    pub fn denial_rule(&self, rule: &DenialRule) -> String {
+        self.rule_layout("⊥", rule.body(), rule.constraints())
+    }
+
+    /// Render a rule with the given head.
+    fn rule_layout(&self, head: &str, literals: &[Literal], constraints: &[Constraint]) -> String {
+        let body = literals
+            .iter()
+            .map(|literal| self.literal(literal))
+            .chain(
+                constraints
+                    .iter()
+                    .map(|constraint| self.constraint(constraint)),
+            )
+            .collect::<Vec<_>>();
+
+        let line = format!("{head} :- {}.", body.join(", "));
+        if line.len() <= MAX_LINE_WIDTH {
+            line
+        } else {
+            format!(
+                "{head} :-\n{INDENTATION}{}.",
+                body.join(&format!(",\n{INDENTATION}"))
+            )
+        }
+    }
+
+    /// Render a [KeyConstraint].
+    pub fn key(&self, key: &KeyConstraint) -> String {
+        let terms = (0..key.arity())
+            .map(|index| match key.key_variable(index) {
+                Some(variable) => variable.to_string(),
+                None => "_".to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!("@key {}({terms}).", self.identifier(key.predicate()))
+    }
+
+    /// Render an [IntegrityConstraint].
+    pub fn integrity_constraint(&self, constraint: &IntegrityConstraint) -> String {
+        match constraint {
+            IntegrityConstraint::Denial(rule) => self.denial_rule(rule),
+            IntegrityConstraint::Key(key) => self.key(key),
+        }
+    }
+
+    /// Render a [Statement].
+    pub fn statement(&self, statement: &Statement) -> String {
+        match statement {
+            Statement::Fact(fact) => self.fact(fact),
+            Statement::Rule(rule) => self.rule(rule),
+        }
+    }
+
+    /// Render a [PredicateDeclaration].
+    pub fn declaration(&self, declaration: &PredicateDeclaration) -> String {
+        let types = declaration
+            .types()
+            .iter()
+            .map(|value_type| value_type.name())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!(
+            "@declare {}({types}).",
+            self.identifier(declaration.predicate())
+        )
+    }
+
+    /// Render an [ImportDirective].
+    pub fn import(&self, import: &ImportDirective) -> String {
+        format!(
+            "@import {} :- {} {}.",
+            self.identifier(import.predicate()),
+            file_format_name(import.file_format()),
+            self.map(import.attributes())
+        )
+    }
+
+    /// Render an [ExportDirective].
+    pub fn export(&self, export: &ExportDirective) -> String {
+        format!(
+            "@export {} :- {} {}.",
+            self.identifier(export.predicate()),
+            file_format_name(export.file_format()),
+            self.map(export.attributes())
+        )
+    }
+
+    /// Render an output directive for the given predicate.
+    pub fn output(&self, predicate: &Identifier) -> String {
+        format!("@output {} .", self.identifier(predicate))
+    }
+
+    /// Render a base declaration.
+    pub fn base(&self, base: &str) -> String {
+        format!("@base <{base}> .")
+    }
+
+    /// Render a prefix declaration.
+    pub fn prefix(&self, prefix: &str, iri: &str) -> String {
+        format!("@prefix {prefix}: <{iri}> .")
+    }
+
+    /// Render a [ProgramElement].
+    fn element(&self, element: &ProgramElement) -> String {
+        match element {
+            ProgramElement::Base(base) => self.base(base),
+            ProgramElement::Prefix { prefix, iri } => self.prefix(prefix, iri),
+            ProgramElement::Declaration(declaration) => self.declaration(declaration),
+            ProgramElement::Import(import) => self.import(import),
+            ProgramElement::Export(export) => self.export(export),
+            ProgramElement::Output(predicate) => self.output(predicate),
+            ProgramElement::Statement(statement) => self.statement(statement),
+            ProgramElement::Constraint(constraint) => self.integrity_constraint(constraint),
+        }
+    }
+
+    /// Render a whole [Program].
+    ///
+    /// The result lists base and prefix declarations, type declarations, imports, facts,
+    /// rules, integrity constraints, exports, and output directives in this order,
+    /// separated by empty lines.
+    pub fn program(&self, program: &Program) -> String {
+        let mut prefixes = program.prefixes().iter().collect::<Vec<_>>();
+        prefixes.sort();
+
+        let header = program
+            .base()
+            .map(|base| self.base(&base))
+            .into_iter()
+            .chain(
+                prefixes
+                    .into_iter()
+                    .map(|(prefix, iri)| self.prefix(prefix, iri)),
+            )
+            .collect::<Vec<_>>();
+        let declarations = program
+            .declarations()
+            .iter()
+            .map(|declaration| self.declaration(declaration))
+            .collect();
+        let imports = program
+            .imports()
+            .map(|import| self.import(import))
+            .collect();
+        let facts = program.facts().iter().map(|fact| self.fact(fact)).collect();
+        let rules = program.rules().iter().map(|rule| self.rule(rule)).collect();
+        let constraints = program
+            .constraints()
+            .iter()
+            .map(|constraint| self.integrity_constraint(constraint))
+            .collect();
+        let exports = program
+            .exports()
+            .map(|export| self.export(export))
+            .collect();
+        let outputs = program
+            .output_predicates()
+            .map(|predicate| self.output(predicate))
+            .collect();
+
+        let sections: [Vec<String>; 8] = [
+            header,
+            declarations,
+            imports,
+            facts,
+            rules,
+            constraints,
+            exports,
+            outputs,
+        ];
+
+        sections
+            .into_iter()
+            .filter(|section| !section.is_empty())
+            .map(|section| section.join("\n") + "\n")
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Whether the given string is a bare name, i.e., an IRI that can be written without delimiters.
+fn is_bare_name(name: &str) -> bool {
+    let mut characters = name.chars();
+
+    characters
+        .next()
+        .is_some_and(|character| character.is_ascii_alphabetic())
+        && characters.all(|character| {
+            character.is_ascii_alphanumeric() || character == '_' || character == '-'
+        })
+}
+
+/// Whether the given string can be used as the local part of a prefixed name.
+///
+/// This only covers a simple subset of the allowed local names.
+fn is_local_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('-')
+        && name.chars().all(|character| {
+            character.is_ascii_alphanumeric() || character == '_' || character == '-'
+        })
+}
+
+/// Put quotes around the content of a string literal.
+///
+/// Since the parser keeps escape sequences as they are written,
+/// the content is not escaped again. Instead, a kind of quotes is
+/// chosen that can contain every character in the string.
+fn quote_string(content: &str) -> String {
+    let mut has_double_quote = false;
+    let mut has_single_quote = false;
+    let mut has_line_break = false;
+
+    let mut characters = content.chars();
+    while let Some(character) = characters.next() {
+        match character {
+            '\\' => {
+                characters.next();
+            }
+            '"' => has_double_quote = true,
+            '\'' => has_single_quote = true,
+            '\n' | '\r' => has_line_break = true,
+            _ => {}
+        }
+    }
+
+    // Only long strings can contain line breaks, and double quotes
+    // may only occur unescaped in long strings with single quotes.
+    match (has_double_quote, has_single_quote, has_line_break) {
+        (false, _, false) => format!("\"{content}\""),
+        (false, _, true) => format!("\"\"\"{content}\"\"\""),
+        (true, false, _) => format!("'''{content}'''"),
+        (true, true, _) => {
+            let mut result = String::from("\"");
+            let mut characters = content.chars();
+            while let Some(character) = characters.next() {
+                match character {
+                    '\\' => {
+                        result.push('\\');
+                        result.extend(characters.next());
+                    }
+                    '"' => result.push_str("\\\""),
+                    '\n' => result.push_str("\\n"),
+                    '\r' => result.push_str("\\r"),
+                    _ => result.push(character),
+                }
+            }
+            result.push('"');
+            result
+        }
+    }
+}
+
+/// Precedence of the top-level operation of a [Term] when written in infix notation.
+fn precedence(term: &Term) -> usize {
+    match term {
+        Term::Binary { operation, .. } => match operation {
+            BinaryOperation::NumericAddition | BinaryOperation::NumericSubtraction => 1,
+            BinaryOperation::NumericMultiplication | BinaryOperation::NumericDivision => 2,
+            BinaryOperation::Equal
+            | BinaryOperation::Unequals
+            | BinaryOperation::NumericGreaterthan
+            | BinaryOperation::NumericGreaterthaneq
+            | BinaryOperation::NumericLessthan
+            | BinaryOperation::NumericLessthaneq => 0,
+            _ => 4,
+        },
+        Term::Unary(UnaryOperation::NumericNegation, _) => 3,
+        _ => 4,
+    }
+}
+
+/// Name of a [BinaryOperation] that is written as a function.
+fn binary_operation_name(operation: BinaryOperation) -> String {
+    match operation {
+        BinaryOperation::NumericLogarithm => "LOG".to_string(),
+        BinaryOperation::NumericRemainder => "REM".to_string(),
+        BinaryOperation::StringCompare => "COMPARE".to_string(),
+        _ => operation.name(),
+    }
+}
+
+/// Name of a [UnaryOperation] that is written as a function.
+fn unary_operation_name(operation: UnaryOperation) -> String {
+    // The parser currently maps `UCASE` to [UnaryOperation::StringLowercase]
+    // and `LCASE` to [UnaryOperation::StringUppercase].
+    // Names are chosen such that the result is parsed as the same operation.
+    match operation {
+        UnaryOperation::StringLowercase => "UCASE".to_string(),
+        UnaryOperation::StringUppercase => "LCASE".to_string(),
+        _ => operation.name(),
+    }
+}
+
+/// Name of an aggregate operation as it is written after `#`.
+fn aggregate_operation_name(operation: LogicalAggregateOperation) -> &'static str {
+    match operation {
+        LogicalAggregateOperation::CountValues => "count",
+        LogicalAggregateOperation::MinNumber => "min",
+        LogicalAggregateOperation::MaxNumber => "max",
+        LogicalAggregateOperation::SumOfNumbers => "sum",
+    }
+}
+
+/// Name of a [FileFormat] in import and export directives.
+fn file_format_name(format: FileFormat) -> &'static str {
+    match format {
+        FileFormat::CSV => FILE_FORMAT_CSV,
+        FileFormat::DSV => FILE_FORMAT_DSV,
+        FileFormat::TSV => FILE_FORMAT_TSV,
+        FileFormat::JSON => FILE_FORMAT_JSON,
+        FileFormat::RDF(RdfVariant::Unspecified) => FILE_FORMAT_RDF_UNSPECIFIED,
+        FileFormat::RDF(RdfVariant::NTriples) => FILE_FORMAT_RDF_NTRIPLES,
+        FileFormat::RDF(RdfVariant::NQuads) => FILE_FORMAT_RDF_NQUADS,
+        FileFormat::RDF(RdfVariant::Turtle) => FILE_FORMAT_RDF_TURTLE,
+        FileFormat::RDF(RdfVariant::TriG) => FILE_FORMAT_RDF_TRIG,
+        FileFormat::RDF(RdfVariant::RDFXML) => FILE_FORMAT_RDF_XML,
+    }
+}
+
+/// Find the byte ranges of all comments in the given program text.
+///
+/// Percent signs within strings and IRIs do not start a comment.
+fn comment_ranges(input: &str) -> Vec<Range<usize>> {
+    let bytes = input.as_bytes();
+    let mut comments = Vec::new();
+
+    let mut index = 0;
+    while index < bytes.len() {
+        index = match bytes[index] {
+            b'%' => {
+                let end = bytes[index..]
+                    .iter()
+                    .position(|byte| *byte == b'\n' || *byte == b'\r')
+                    .map_or(bytes.len(), |offset| index + offset);
+                comments.push(index..end);
+                end
+            }
+            b'"' | b'\'' => skip_string(bytes, index),
+            b'<' => skip_iri(bytes, index),
+            _ => index + 1,
+        };
+    }
+
+    comments
+}
+
+/// Return the position after the string literal that starts at `start`.
+fn skip_string(bytes: &[u8], start: usize) -> usize {
+    let quote = bytes[start];
+    let long = bytes[start..].starts_with(&[quote; 3]);
+    let delimiter_length = if long { 3 } else { 1 };
+
+    let mut index = start + delimiter_length;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\\' => index += 2,
+            byte if byte == quote && (!long || bytes[index..].starts_with(&[quote; 3])) => {
+                return index + delimiter_length;
+            }
+            b'\n' | b'\r' if !long => return index,
+            _ => index += 1,
+        }
+    }
+
+    bytes.len()
+}
+
+/// Return the position after the IRI that starts at `start`,
+/// or the position after `start` if this is not the start of an IRI.
+fn skip_iri(bytes: &[u8], start: usize) -> usize {
+    for (offset, byte) in bytes[start + 1..].iter().enumerate() {
+        match byte {
+            b'>' => return start + offset + 2,
+            b'<' | b'"' => break,
+            byte if byte.is_ascii_whitespace() => break,
+            _ => {}
+        }
+    }
+
+    start + 1
+}
+
+/// Reformat the given program text into a canonical layout.
+///
+/// Statements and directives keep their order, and comments are kept
+/// at the statement they belong to. Comments within a statement are moved
+/// in front of it. Empty lines are kept, but multiple empty lines are merged.
+///
+/// # Error
+/// Returns an appropriate [Error] if the program cannot be parsed.
+pub fn format_program(input: &str) -> Result<String, Error> {
+    let elements = all_input_consumed(RuleParser::new().parse_program_elements())(input)?;
+
+    let prefixes = elements
+        .iter()
+        .filter_map(|located| match &located.element {
+            ProgramElement::Prefix { prefix, iri } => Some((prefix.clone(), iri.clone())),
+            _ => None,
+        })
+        .collect();
+    let writer = SyntaxWriter::new(&prefixes);
+
+    Ok(SourceLayout::new(input, &elements).render(&writer))
+}
+
+/// Information about the positions of program elements and comments
+/// in a source text, used for keeping comments and empty lines.
+struct SourceLayout<'a> {
+    input: &'a str,
+    elements: &'a [LocatedProgramElement],
+    /// For every element, the position after the last byte that is not whitespace or comment
+    code_ends: Vec<usize>,
+    /// Byte offsets at which lines start
+    line_starts: Vec<usize>,
+    /// For every line, whether it only contains whitespace
+    empty_lines: Vec<bool>,
+    /// Comments to be placed before each element,
+    /// with an additional last entry for comments after all elements
+    leading_comments: Vec<Vec<Range<usize>>>,
+    /// Comment to be placed at the end of the last line of each element
+    trailing_comments: Vec<Option<Range<usize>>>,
+}
+
+impl<'a> SourceLayout<'a> {
+    /// Compute the layout of the given elements in the source text.
+    fn new(input: &'a str, elements: &'a [LocatedProgramElement]) -> Self {
+        let comments = comment_ranges(input);
+
+        let code_ends = elements
+            .iter()
+            .map(|located| {
+                let mut end = located.range.end;
+                loop {
+                    end = input[..end].trim_end().len().max(located.range.start);
+                    match comments
+                        .iter()
+                        .find(|comment| comment.start < end && end <= comment.end)
+                    {
+                        Some(comment) => end = comment.start,
+                        None => break end,
+                    }
+                }
+            })
+            .collect();
+
+        let line_starts = std::iter::once(0)
+            .chain(input.match_indices('\n').map(|(index, _)| index + 1))
+            .collect::<Vec<_>>();
+        let empty_lines = input
+            .split('\n')
+            .map(|line| line.trim().is_empty())
+            .collect();
+
+        let mut layout = Self {
+            input,
+            elements,
+            code_ends,
+            line_starts,
+            empty_lines,
+            leading_comments: vec![Vec::new(); elements.len() + 1],
+            trailing_comments: vec![None; elements.len()],
+        };
+
+        for comment in comments {
+            let element = elements
+                .iter()
+                .position(|located| comment.start < located.range.end);
+
+            match element {
+                None => layout.leading_comments[elements.len()].push(comment),
+                Some(index) if comment.start < layout.code_ends[index] => {
+                    layout.leading_comments[index].push(comment)
+                }
+                Some(index)
+                    if layout.trailing_comments[index].is_none()
+                        && layout.line(comment.start)
+                            == layout.line(layout.code_ends[index] - 1) =>
+                {
+                    layout.trailing_comments[index] = Some(comment)
+                }
+                Some(index) => layout.leading_comments[index + 1].push(comment),
+            }
+        }
+
+        layout
+    }
+
+    /// Line of the given byte offset, starting at 0.
+    fn line(&self, offset: usize) -> usize {
+        self.line_starts.partition_point(|start| *start <= offset) - 1
+    }
+
+    /// Whether there is an empty line strictly between the given lines.
+    fn has_empty_line_between(&self, first: usize, last: usize) -> bool {
+        (first + 1..last).any(|line| self.empty_lines[line])
+    }
+
+    /// Render the formatted program.
+    fn render(&self, writer: &SyntaxWriter) -> String {
+        // blocks of text together with the first and last line they originate from
+        let mut blocks: Vec<(usize, usize, String)> = Vec::new();
+
+        for (index, comments) in self.leading_comments.iter().enumerate() {
+            for comment in comments {
+                let line = self.line(comment.start);
+                blocks.push((line, line, self.comment_text(comment)));
+            }
+
+            if let Some(located) = self.elements.get(index) {
+                let mut text = writer.element(&located.element);
+                if let Some(comment) = &self.trailing_comments[index] {
+                    text.push(' ');
+                    text.push_str(&self.comment_text(comment));
+                }
+
+                blocks.push((
+                    self.line(located.range.start),
+                    self.line(self.code_ends[index] - 1),
+                    text,
+                ));
+            }
+        }
+
+        let mut result = String::new();
+        let mut previous_line = None;
+        for (first_line, last_line, text) in blocks {
+            if previous_line
+                .is_some_and(|previous| self.has_empty_line_between(previous, first_line))
+            {
+                result.push('\n');
+            }
+
+            result.push_str(&text);
+            result.push('\n');
+            previous_line = Some(last_line);
+        }
+
+        result
+    }
+
+    /// Text of a comment, without trailing whitespace.
+    fn comment_text(&self, comment: &Range<usize>) -> String {
+        self.input[comment.clone()].trim_end().to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_log::test;
+
+    use crate::{
+        io::parser::parse_program,
+        model::{Atom, Fact, Identifier, PrimitiveTerm, Program, Rule, Term, Variable},
+    };
+
+    use super::format_program;
+
+    /// Check that the given programs contain the same statements and directives.
+    fn assert_same_program(left: &Program, right: &Program) {
+        assert_eq!(left.base(), right.base());
+        assert_eq!(left.prefixes(), right.prefixes());
+        assert_eq!(left.declarations(), right.declarations());
+        assert_eq!(left.rules(), right.rules());
+        assert_eq!(left.facts(), right.facts());
+        assert_eq!(left.constraints(), right.constraints());
+        assert_eq!(
+            left.imports().collect::<Vec<_>>(),
+            right.imports().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            left.exports().collect::<Vec<_>>(),
+            right.exports().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            left.output_predicates().collect::<Vec<_>>(),
+            right.output_predicates().collect::<Vec<_>>()
+        );
+    }
+
+    /// Format the given program, check that the result is parsed as the same program,
+    /// and that formatting is idempotent. Returns the formatted program.
+    fn assert_round_trip(input: &str) -> String {
+        let formatted = format_program(input).unwrap();
+
+        let original = parse_program(input).unwrap();
+        let reparsed = parse_program(&formatted)
+            .unwrap_or_else(|error| panic!("{error}\nin formatted program:\n{formatted}"));
+        assert_same_program(&original, &reparsed);
+        assert_eq!(format_program(&formatted).unwrap(), formatted);
+
+        formatted
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn round_trip_statements() {
+        assert_round_trip(
+            r#"@base <http://example.org/base/> .
+            @prefix ex: <http://example.org/> .
+            @prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+            @declare data(int, any) .
+            @declare ex:result(string, double, any).
+            @import data :- csv{resource="data.csv", format=(int, any), limit=10} .
+            @export ex:result :- tsv {resource = <http://example.org/out.tsv>, compression = "gzip"} .
+            @source old[2]: load-csv("old.csv").
+            @output ex:result .
+            p(a, ex:b, <http://other.org/c>, _:n, 42, -3, 2.5, 1e10, "s", '''q"uote''', "it's", "l"@en, "1.5"^^xsd:float, "true"^^xsd:boolean) .
+            ex:result(?x, !v) :- data(?x, ?y), ~old(?y, _), data(_, ?x), ?x >= 3 .
+            agg(?x, #count(?y, ?z)) :- data(?x, ?y), data(?y, ?z) .
+            r(?x + 2 * ?y, ?x - (?y - 1), (?x + ?y) / 2, ?x / (?y * 3)) :- data(?x, ?y) .
+            s(REM(?x, 3), LOG(?x, 2), COMPARE(?y, "a"), UCASE(?y), LCASE(?y), isNumeric(?x), ABS(?x)) :- data(?x, ?y) .
+            t(SUBSTRING(?y, 1, 2), CONCAT(?y, "a", "b"), MAX(1, ?x)) :- data(?x, ?y) .
+            u(REPLACE(?y, "a+", "b", "i"), REGEXGROUP(?y, "(a)", 1), ?z) :- data(?x, ?y), ?z = REGEX(?y, "^a") .
+            v(TUPLE(?x, 1), NTH(?z, 2), LENGTH(?z), MAP_GET(MAP("a", ?x), "a"), KEYS(MAP(?x, ?y))) :- data(?x, ?y), ?z = UNNEST(?y) .
+            w(STRDT(?y, xsd:integer), STRLANG(?y, "en"), LANGMATCHES(?y, "*"), IRI(?y), BNODE(?y), isLiteral(?x)) :- data(?x, ?y) .
+            w(ENCODE_FOR_URI(?y), MD5(?y), SHA1(?y), SHA256(?y), isNull(?x), isNull(?y)) :- data(?x, ?y) .
+            ?x = ?y, t(?x) :- data(?x, ?y), data(?y, ?x) .
+            @key data(?x, _) .
+            ⊥ :- data(?x, ?y), ~old(?y, _), ?x < 0 .
+            false :- ex:result(?x, ?x, _) .
+            "#,
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn format_layout() {
+        let input = r#"% header comment
+
+@prefix ex: <http://example.org/> .
+p(<http://example.org/a>).   % about a
+
+% about the rule
+
+
+q(?x) :- p(?x),  % inner comment
+    ?x != "%" .
+% footer
+"#;
+        let expected = r#"% header comment
+
+@prefix ex: <http://example.org/> .
+p(ex:a). % about a
+
+% about the rule
+
+% inner comment
+q(?x) :- p(?x), ?x != "%".
+% footer
+"#;
+
+        assert_eq!(assert_round_trip(input), expected);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn format_long_rule() {
+        let input = "result(?first, ?second, ?third) :- first_relation(?first, ?second), \
+            second_relation(?second, ?third), ?first < ?third, ?second != ?third .";
+        let expected =
+            "result(?first, ?second, ?third) :-\n    first_relation(?first, ?second),\n    \
+            second_relation(?second, ?third),\n    ?first < ?third,\n    ?second != ?third.\n";
+
+        assert_eq!(assert_round_trip(input), expected);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn round_trip_test_cases() {
+        let directory = concat!(env!("CARGO_MANIFEST_DIR"), "/../resources/testcases");
+        let mut directories = vec![std::path::PathBuf::from(directory)];
+
+        while let Some(directory) = directories.pop() {
+            for entry in std::fs::read_dir(directory).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    directories.push(path);
+                } else if path.extension().is_some_and(|extension| extension == "rls") {
+                    assert_round_trip(&std::fs::read_to_string(path).unwrap());
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn display_program() {
+        let x = Term::Primitive(PrimitiveTerm::Variable(Variable::Universal(
+            "x".to_string(),
+        )));
+        let program = Program::builder()
+            .prefix("ex".to_string(), "http://example.org/".to_string())
+            .rule(Rule::new(
+                vec![Atom::new(
+                    Identifier("http://example.org/q".to_string()),
+                    vec![x.clone()],
+                )],
+                vec![crate::model::Literal::Positive(Atom::new(
+                    Identifier("p".to_string()),
+                    vec![x],
+                ))],
+                vec![],
+            ))
+            .fact(Fact(Atom::new(
+                Identifier("p".to_string()),
+                vec![Term::from(
+                    nemo_physical::datavalues::AnyDataValue::new_plain_string("a".to_string()),
+                )],
+            )))
+            .output_predicate(Identifier("http://example.org/q".to_string()))
+            .build();
+
+        let expected = "@prefix ex: <http://example.org/> .\n\np(\"a\").\n\nex:q(?x) :- p(?x).\n\n@output ex:q .\n";
+        assert_eq!(program.to_string(), expected);
+        assert_same_program(&program, &parse_program(program.to_string()).unwrap());
+    }
+}
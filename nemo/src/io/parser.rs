@@ -24,12 +24,13 @@ use macros::traced;
 
 mod types;
 
-use types::{ConstraintOperator, IntermediateResult, Span};
+use types::{source_position, ConstraintOperator, IntermediateResult, Span};
 pub(crate) mod iri;
 pub(crate) mod rfc5234;
 pub(crate) mod sparql;
 pub(crate) mod turtle;
 pub use types::{span_from_str, LocatedParseError, ParseError, ParseResult};
+pub(crate) use types::{LocatedProgramElement, ProgramElement};
 
 /// Parse a program in the given `input`-String and return a [Program].
 ///
@@ -42,6 +43,22 @@ pub fn parse_program(input: impl AsRef<str>) -> Result<Program, Error> {
     Ok(program)
 }
 
+/// Parse a program in the given `input`-String and return a [Program],
+/// where the functions of the given [FunctionRegistry] may be used in addition to the builtin functions.
+///
+/// The program will be parsed and checked for unsupported features.
+///
+/// # Error
+/// Returns an appropriate [Error] variant on parsing and feature check issues.
+pub fn parse_program_with_functions(
+    input: impl AsRef<str>,
+    functions: &FunctionRegistry,
+) -> Result<Program, Error> {
+    let program =
+        all_input_consumed(RuleParser::with_functions(functions).parse_program())(input.as_ref())?;
+    Ok(program)
+}
+
 /// Parse a single fact in the given `input`-String and return a [Program].
 ///
 /// The program will be parsed and checked for unsupported features.
@@ -298,6 +315,8 @@ pub struct RuleParser<'a> {
     prefixes: RefCell<HashMap<&'a str, &'a str>>,
     /// Number counting up for generating distinct wildcards.
     wildcard_generator: RefCell<usize>,
+    /// Functions that may be used in addition to the builtin functions, if any.
+    functions: Option<&'a FunctionRegistry>,
 }
 
 impl<'a> RuleParser<'a> {
@@ -306,6 +325,15 @@ impl<'a> RuleParser<'a> {
         Default::default()
     }
 
+    /// Construct a new [RuleParser] that resolves the names of the functions
+    /// in the given [FunctionRegistry].
+    pub fn with_functions(functions: &'a FunctionRegistry) -> Self {
+        Self {
+            functions: Some(functions),
+            ..Default::default()
+        }
+    }
+
     fn parse_complex_constant_term(
         &'a self,
     ) -> impl FnMut(Span<'a>) -> IntermediateResult<'a, AnyDataValue> {
@@ -494,6 +522,7 @@ impl<'a> RuleParser<'a> {
                                         predicate: predicate.clone(),
                                         format: FileFormat::CSV,
                                         attributes,
+                                        position: None,
                                     }))
                                 },
                             ),
@@ -532,6 +561,7 @@ impl<'a> RuleParser<'a> {
                                         predicate: predicate.clone(),
                                         format: FileFormat::TSV,
                                         attributes,
+                                        position: None,
                                     }))
                                 },
                             ),
@@ -579,6 +609,7 @@ impl<'a> RuleParser<'a> {
                                         predicate: predicate.clone(),
                                         format: FileFormat::RDF(RdfVariant::Unspecified),
                                         attributes,
+                                        position: None,
                                     }))
                                 },
                             ),
@@ -606,7 +637,8 @@ impl<'a> RuleParser<'a> {
                         remainder
                     )?;
 
-                    let spec = datasource.map_err(|e| Err::Failure(e.at(input)))?;
+                    let mut spec = datasource.map_err(|e| Err::Failure(e.at(input)))?;
+                    spec.0.position = Some(source_position(input));
 
                     Ok((remainder, spec))
                 },
@@ -633,6 +665,50 @@ impl<'a> RuleParser<'a> {
         )
     }
 
+    /// Parse the name of a [ValueType].
+    fn parse_value_type(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<ValueType> {
+        traced(
+            "parse_value_type",
+            map_error(
+                map_res(parse_bare_name, |name: Span<'a>| {
+                    ValueType::from_name(name.fragment())
+                        .ok_or_else(|| ParseError::UnknownValueType(name.fragment().to_string()))
+                }),
+                || ParseError::ExpectedLogicalTypeName,
+            ),
+        )
+    }
+
+    /// Parse a type declaration of a predicate.
+    fn parse_declaration(
+        &'a self,
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<PredicateDeclaration> {
+        traced(
+            "parse_declaration",
+            map_error(
+                move |input| {
+                    let (remainder, (predicate, types)) = delimited(
+                        terminated(token("@declare"), cut(multispace_or_comment1)),
+                        cut(pair(
+                            self.parse_iri_like_identifier(),
+                            self.parenthesised(separated_list1(
+                                self.parse_comma(),
+                                self.parse_value_type(),
+                            )),
+                        )),
+                        cut(self.parse_dot()),
+                    )(input)?;
+
+                    let mut declaration = PredicateDeclaration::new(predicate, types);
+                    declaration.position = Some(source_position(input));
+
+                    Ok((remainder, declaration))
+                },
+                || ParseError::ExpectedPredicateDeclaration,
+            ),
+        )
+    }
+
     /// Parse an entry in a [MapDataValue], i.e., am [AnyDataValue]--[AnyDataValue] pair.
     fn parse_map_entry(
         &'a self,
@@ -719,6 +795,7 @@ impl<'a> RuleParser<'a> {
                     predicate,
                     format,
                     attributes,
+                    position: None,
                 },
             ))
         })
@@ -726,26 +803,30 @@ impl<'a> RuleParser<'a> {
 
     /// Parse an import directive.
     fn parse_import(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<ImportDirective> {
-        traced(
-            "parse_import",
-            delimited(
+        traced("parse_import", move |input| {
+            let (remainder, mut directive) = delimited(
                 terminated(token("@import"), multispace_or_comment1),
                 cut(map(self.parse_import_export_spec(), ImportDirective::from)),
                 cut(self.parse_dot()),
-            ),
-        )
+            )(input)?;
+            directive.0.position = Some(source_position(input));
+
+            Ok((remainder, directive))
+        })
     }
 
     /// Parse an export directive.
     fn parse_export(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<ExportDirective> {
-        traced(
-            "parse_export",
-            delimited(
+        traced("parse_export", move |input| {
+            let (remainder, mut directive) = delimited(
                 terminated(token("@export"), multispace_or_comment1),
                 cut(map(self.parse_import_export_spec(), ExportDirective::from)),
                 cut(self.parse_dot()),
-            ),
-        )
+            )(input)?;
+            directive.0.position = Some(source_position(input));
+
+            Ok((remainder, directive))
+        })
     }
 
     /// Parse a statement.
@@ -879,7 +960,7 @@ impl<'a> RuleParser<'a> {
                 move |input| {
                     let (remainder, (head, body)) = pair(
                         terminated(
-                            separated_list1(self.parse_comma(), self.parse_atom()),
+                            separated_list1(self.parse_comma(), self.parse_head_atom()),
                             self.parse_arrow(),
                         ),
                         cut(terminated(
@@ -904,17 +985,119 @@ impl<'a> RuleParser<'a> {
                             _ => None,
                         })
                         .collect();
-                    Ok((
-                        remainder,
-                        Rule::new_validated(head, literals, constraints)
-                            .map_err(|e| Err::Failure(e.at(input)))?,
-                    ))
+                    let mut rule = Rule::new_validated(head, literals, constraints)
+                        .map_err(|e| Err::Failure(e.at(input)))?;
+                    rule.set_position(source_position(input));
+
+                    Ok((remainder, rule))
                 },
                 || ParseError::ExpectedRule,
             ),
         )
     }
 
+    /// Parse a denial rule, i.e., a rule with head `⊥` or `false`.
+    fn parse_denial_rule(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<DenialRule> {
+        traced(
+            "parse_denial_rule",
+            map_error(
+                move |input| {
+                    let (remainder, body) = preceded(
+                        pair(alt((token("⊥"), token("false"))), self.parse_arrow()),
+                        cut(terminated(
+                            separated_list1(self.parse_comma(), self.parse_body_expression()),
+                            self.parse_dot(),
+                        )),
+                    )(input)?;
+
+                    log::trace!(target: "parser", r#"found denial rule "{body:?}""#);
+
+                    let literals = body
+                        .iter()
+                        .filter_map(|expr| match expr {
+                            BodyExpression::Literal(l) => Some(l.clone()),
+                            _ => None,
+                        })
+                        .collect();
+                    let constraints = body
+                        .into_iter()
+                        .filter_map(|expr| match expr {
+                            BodyExpression::Constraint(c) => Some(c),
+                            _ => None,
+                        })
+                        .collect();
+                    let mut rule = DenialRule::new_validated(literals, constraints)
+                        .map_err(|e| Err::Failure(e.at(input)))?;
+                    rule.position = Some(source_position(input));
+
+                    Ok((remainder, rule))
+                },
+                || ParseError::ExpectedDenialRule,
+            ),
+        )
+    }
+
+    /// Parse a key directive, e.g. `@key p(?id, _, _) .`
+    fn parse_key_directive(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<KeyConstraint> {
+        traced(
+            "parse_key_directive",
+            map_error(
+                move |input| {
+                    let (remainder, atom) = delimited(
+                        terminated(token("@key"), cut(multispace_or_comment1)),
+                        cut(self.parse_atom()),
+                        cut(self.parse_dot()),
+                    )(input)?;
+
+                    let key = atom
+                        .terms()
+                        .iter()
+                        .map(|term| match term {
+                            Term::Primitive(PrimitiveTerm::Variable(variable))
+                                if variable.is_universal() =>
+                            {
+                                Ok(Some(variable.clone()))
+                            }
+                            Term::Primitive(PrimitiveTerm::Variable(variable))
+                                if variable.is_unnamed() =>
+                            {
+                                Ok(None)
+                            }
+                            _ => Err(Err::Failure(
+                                ParseError::InvalidKeyTerm(term.to_string()).at(input),
+                            )),
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    let mut key = KeyConstraint::new(atom.predicate(), key);
+                    key.position = Some(source_position(input));
+
+                    Ok((remainder, key))
+                },
+                || ParseError::ExpectedKeyDirective,
+            ),
+        )
+    }
+
+    /// Parse an atom in the head of a rule, which is either an ordinary atom
+    /// or an equality between two primitive terms, e.g. `?x = ?y`.
+    fn parse_head_atom(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Atom> {
+        traced(
+            "parse_head_atom",
+            alt((
+                self.parse_atom(),
+                map(
+                    separated_pair(
+                        self.parse_primitive_term(),
+                        self.parse_equals(),
+                        self.parse_primitive_term(),
+                    ),
+                    |(left, right)| Atom::equality(left, right),
+                ),
+            )),
+        )
+    }
+
     /// Parse an atom.
     fn parse_atom(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Atom> {
         traced(
@@ -1217,6 +1400,26 @@ impl<'a> RuleParser<'a> {
                                 parameters: subterms,
                             },
                         ))
+                    } else if let Some(function) =
+                        self.functions.and_then(|functions| functions.get(&name.0))
+                    {
+                        let (remainder, subterms) = (self.parenthesised(separated_list0(
+                            self.parse_comma(),
+                            self.parse_term(),
+                        )))(remainder)?;
+
+                        if subterms.len() != function.signature().arity() {
+                            return Err(Err::Failure(
+                                ParseError::UserFunctionArity(
+                                    function.name().to_string(),
+                                    function.signature().arity(),
+                                    subterms.len(),
+                                )
+                                .at(input),
+                            ));
+                        }
+
+                        Ok((remainder, Term::UserFunction(function.clone(), subterms)))
                     } else {
                         let (remainder, subterms) = (self.parenthesised(separated_list0(
                             self.parse_comma(),
@@ -1368,8 +1571,11 @@ impl<'a> RuleParser<'a> {
         )
     }
 
-    /// Parse a program in the rules language.
-    pub fn parse_program(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Program> {
+    /// Parse the directives and statements of a program in the order in
+    /// which they appear, together with their location in the source text.
+    pub(crate) fn parse_program_elements(
+        &'a self,
+    ) -> impl FnMut(Span<'a>) -> IntermediateResult<Vec<LocatedProgramElement>> {
         fn check_for_invalid_statement<'a, F>(
             parser: &mut F,
             input: Span<'a>,
@@ -1384,16 +1590,34 @@ impl<'a> RuleParser<'a> {
             Ok((input, ()))
         }
 
-        traced("parse_program", move |input| {
+        fn located<'a>(
+            mut parser: impl FnMut(Span<'a>) -> IntermediateResult<'a, ProgramElement>,
+        ) -> impl FnMut(Span<'a>) -> IntermediateResult<'a, LocatedProgramElement> {
+            move |input| {
+                let (remainder, element) = parser(input)?;
+                let range = input.location_offset()..remainder.location_offset();
+
+                Ok((remainder, LocatedProgramElement { element, range }))
+            }
+        }
+
+        traced("parse_program_elements", move |input| {
             let (remainder, _) = multispace_or_comment0(input)?;
-            let (remainder, _) = opt(self.parse_base())(remainder)?;
+            let (remainder, base) = opt(located(map(self.parse_base(), |base| {
+                ProgramElement::Base(base.name())
+            })))(remainder)?;
 
             check_for_invalid_statement(
                 &mut map(self.parse_base(), |_| ParseError::LateBaseDeclaration),
                 remainder,
             )?;
 
-            let (remainder, _) = many0(self.parse_prefix())(remainder)?;
+            let (remainder, prefixes) = many0(located(map(self.parse_prefix(), |prefix| {
+                ProgramElement::Prefix {
+                    prefix: prefix.to_string(),
+                    iri: self.prefixes.borrow()[*prefix.fragment()].to_string(),
+                }
+            })))(remainder)?;
 
             check_for_invalid_statement(
                 &mut map(self.parse_base(), |_| ParseError::LateBaseDeclaration),
@@ -1404,23 +1628,20 @@ impl<'a> RuleParser<'a> {
                 remainder,
             )?;
 
-            let mut statements = Vec::new();
-            let mut output_predicates = Vec::new();
-            let mut sources = Vec::new();
-            let mut imports = Vec::new();
-            let mut exports = Vec::new();
-
-            let (remainder, _) = many0(alt((
-                map(self.parse_source(), |source| sources.push(source)),
-                map(self.parse_import(), |import| imports.push(import)),
-                map(self.parse_export(), |export| exports.push(export)),
-                map(self.parse_statement(), |statement| {
-                    statements.push(statement)
+            let (remainder, statements) = many0(located(alt((
+                map(self.parse_declaration(), ProgramElement::Declaration),
+                map(self.parse_key_directive(), |key| {
+                    ProgramElement::Constraint(IntegrityConstraint::Key(key))
                 }),
-                map(self.parse_output_directive(), |output_predicate| {
-                    output_predicates.push(output_predicate)
+                map(self.parse_source(), ProgramElement::Import),
+                map(self.parse_import(), ProgramElement::Import),
+                map(self.parse_export(), ProgramElement::Export),
+                map(self.parse_denial_rule(), |rule| {
+                    ProgramElement::Constraint(IntegrityConstraint::Denial(rule))
                 }),
-            )))(remainder)?;
+                map(self.parse_statement(), ProgramElement::Statement),
+                map(self.parse_output_directive(), ProgramElement::Output),
+            ))))(remainder)?;
 
             check_for_invalid_statement(
                 &mut map(self.parse_base(), |_| ParseError::LateBaseDeclaration),
@@ -1431,35 +1652,37 @@ impl<'a> RuleParser<'a> {
                 remainder,
             )?;
 
-            let base = self.base().map(String::from);
-            let prefixes = self
-                .prefixes
-                .borrow()
-                .iter()
-                .map(|(&prefix, &iri)| (prefix.to_string(), iri.to_string()))
-                .collect::<Vec<_>>();
-            let mut rules = Vec::new();
-            let mut facts = Vec::new();
-
-            statements.iter().for_each(|statement| match statement {
-                Statement::Fact(value) => facts.push(value.clone()),
-                Statement::Rule(value) => rules.push(value.clone()),
-            });
-
-            let mut program_builder = Program::builder()
-                .prefixes(prefixes)
-                .imports(sources)
-                .imports(imports)
-                .exports(exports)
-                .rules(rules)
-                .facts(facts);
-
-            if let Some(base) = base {
-                program_builder = program_builder.base(base);
-            }
+            let elements = base.into_iter().chain(prefixes).chain(statements).collect();
+
+            Ok((remainder, elements))
+        })
+    }
+
+    /// Parse a program in the rules language.
+    pub fn parse_program(&'a self) -> impl FnMut(Span<'a>) -> IntermediateResult<Program> {
+        traced("parse_program", move |input| {
+            let (remainder, elements) = self.parse_program_elements()(input)?;
+
+            let mut program_builder = Program::builder();
 
-            if !output_predicates.is_empty() {
-                program_builder = program_builder.output_predicates(output_predicates);
+            for LocatedProgramElement { element, .. } in elements {
+                program_builder = match element {
+                    ProgramElement::Base(base) => program_builder.base(base),
+                    ProgramElement::Prefix { prefix, iri } => program_builder.prefix(prefix, iri),
+                    ProgramElement::Declaration(declaration) => {
+                        program_builder.declaration(declaration)
+                    }
+                    ProgramElement::Import(import) => program_builder.import(import),
+                    ProgramElement::Export(export) => program_builder.export(export),
+                    ProgramElement::Output(predicate) => {
+                        program_builder.output_predicate(predicate)
+                    }
+                    ProgramElement::Statement(Statement::Fact(fact)) => program_builder.fact(fact),
+                    ProgramElement::Statement(Statement::Rule(rule)) => program_builder.rule(rule),
+                    ProgramElement::Constraint(constraint) => {
+                        program_builder.constraint(constraint)
+                    }
+                };
             }
 
             Ok((remainder, program_builder.build()))
@@ -1564,6 +1787,7 @@ mod test {
                 predicate,
                 format: FileFormat::CSV,
                 attributes,
+                position: None,
             })
         }
 
@@ -1865,6 +2089,118 @@ mod test {
         assert_parse!(parser.parse_rule(), &rule, expected_rule,);
     }
 
+    #[test]
+    fn parse_integrity_constraints() {
+        let parser = RuleParser::new();
+
+        let p = Identifier("p".to_string());
+        let x = Variable::Universal("x".to_string());
+        let atom = Atom::new(
+            p.clone(),
+            vec![
+                Term::Primitive(PrimitiveTerm::Variable(x.clone())),
+                Term::Primitive(PrimitiveTerm::Variable(x.clone())),
+            ],
+        );
+        let denial = DenialRule::new(vec![Literal::Positive(atom)], vec![]);
+
+        assert_parse!(
+            parser.parse_denial_rule(),
+            "⊥ :- p(?x, ?x) .",
+            denial.clone()
+        );
+        assert_parse!(parser.parse_denial_rule(), "false :- p(?x, ?x) .", denial);
+        assert_parse_error!(
+            parser.parse_denial_rule(),
+            "false(?x) :- p(?x, ?x) .",
+            ParseError::ExpectedDenialRule
+        );
+
+        let key = KeyConstraint::new(p, vec![Some(x), None]);
+        assert_parse!(parser.parse_key_directive(), "@key p(?x, _) .", key);
+        assert_parse_error!(
+            parser.parse_key_directive(),
+            "@key p(?x, 1) .",
+            ParseError::ExpectedKeyDirective
+        );
+    }
+
+    #[test]
+    fn parse_equality_rule() {
+        let parser = RuleParser::new();
+
+        let x = PrimitiveTerm::Variable(Variable::Universal("x".to_string()));
+        let y = PrimitiveTerm::Variable(Variable::Universal("y".to_string()));
+        let body = Atom::new(
+            Identifier("p".to_string()),
+            vec![Term::Primitive(x.clone()), Term::Primitive(y.clone())],
+        );
+        let rule = Rule::new(
+            vec![Atom::equality(x, y)],
+            vec![Literal::Positive(body)],
+            vec![],
+        );
+
+        assert_parse!(parser.parse_rule(), "?x = ?y :- p(?x, ?y) .", rule);
+        assert_parse_error!(
+            parser.parse_rule(),
+            "?x = !z :- p(?x, ?x) .",
+            ParseError::ExpectedRule
+        );
+    }
+
+    #[test]
+    fn parse_unnest() {
+        let parser = RuleParser::new();
+        let is_misplaced_unnest = |input| {
+            let result = all_input_consumed(parser.parse_rule())(input);
+            matches!(
+                result,
+                Err(LocatedParseError { context, .. })
+                    if matches!(
+                        context[..],
+                        [LocatedParseError { source: ParseError::MisplacedUnnest(_), .. }]
+                    )
+            )
+        };
+
+        assert!(all_input_consumed(parser.parse_rule())(
+            "q(?y) :- p(?x), ?y = UNNEST(TUPLE(?x, 1)) ."
+        )
+        .is_ok());
+        assert!(is_misplaced_unnest(
+            "q(?y) :- p(?x), ?y = LENGTH(UNNEST(?x)) ."
+        ));
+        assert!(is_misplaced_unnest("q(UNNEST(?x)) :- p(?x) ."));
+    }
+
+    #[test]
+    fn parse_user_function() {
+        let mut functions = FunctionRegistry::new();
+        functions
+            .register_fn(
+                "twice",
+                FunctionSignature::new(vec![ValueType::String], ValueType::String),
+                |parameters| Some(parameters[0].clone()),
+            )
+            .unwrap();
+        let parser = RuleParser::with_functions(&functions);
+
+        let rule = all_input_consumed(parser.parse_rule())("q(twice(?x)) :- p(?x) .").unwrap();
+        assert!(matches!(
+            &rule.head()[0].terms()[0],
+            Term::UserFunction(function, parameters)
+                if function.name() == "twice" && parameters.len() == 1
+        ));
+
+        fn is_arity_error(error: &LocatedParseError) -> bool {
+            matches!(error.source, ParseError::UserFunctionArity(_, 1, 2))
+                || error.context.iter().any(is_arity_error)
+        }
+        let result = all_input_consumed(parser.parse_rule())("q(twice(?x, ?x)) :- p(?x) .");
+        assert!(is_arity_error(&result.unwrap_err()));
+    }
+
     #[test]
     #[allow(clippy::redundant_clone)]
     fn parse_output() {
@@ -1880,6 +2216,27 @@ mod test {
         );
     }
 
+    #[test]
+    fn parse_declaration() {
+        let parser = RuleParser::new();
+
+        let declaration = PredicateDeclaration::new(
+            Identifier("p".to_string()),
+            vec![ValueType::Int, ValueType::String, ValueType::Any],
+        );
+
+        assert_parse!(
+            parser.parse_declaration(),
+            "@declare p(int, string, any) .",
+            declaration
+        );
+        assert_parse_error!(
+            parser.parse_declaration(),
+            "@declare p(integer) .",
+            ParseError::ExpectedPredicateDeclaration
+        );
+    }
+
     #[test]
     fn parse_errors() {
         let parser = RuleParser::new();
@@ -2402,6 +2759,7 @@ mod test {
                 predicate: predicate.clone(),
                 format: FileFormat::DSV,
                 attributes: attributes.clone(),
+                position: None,
             }
         );
 
@@ -2411,7 +2769,8 @@ mod test {
             ImportDirective::from(ImportExportDirective {
                 predicate: predicate.clone(),
                 format: FileFormat::DSV,
-                attributes: attributes.clone()
+                attributes: attributes.clone(),
+                position: None,
             })
         );
 
@@ -2421,7 +2780,8 @@ mod test {
             ExportDirective::from(ImportExportDirective {
                 predicate: predicate.clone(),
                 format: FileFormat::DSV,
-                attributes: attributes.clone()
+                attributes: attributes.clone(),
+                position: None,
             })
         );
     }
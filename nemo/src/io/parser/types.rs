@@ -10,7 +10,11 @@ use thiserror::Error;
 
 use crate::{
     io::formats::import_export::ImportExportError,
-    model::rule_model::{Aggregate, Constraint, Literal, Term},
+    model::{
+        rule_model::{Aggregate, Constraint, Literal, SourcePosition, Term},
+        ExportDirective, Identifier, ImportDirective, IntegrityConstraint, PredicateDeclaration,
+        Statement,
+    },
 };
 
 use super::Variable;
@@ -74,6 +78,42 @@ pub enum BodyExpression {
     Constraint(Constraint),
 }
 
+/// A top-level element of a program, i.e., a directive or a statement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ProgramElement {
+    /// Base declaration
+    Base(String),
+    /// Prefix declaration
+    Prefix {
+        /// The declared prefix
+        prefix: String,
+        /// The IRI that the prefix expands to
+        iri: String,
+    },
+    /// Type declaration of a predicate
+    Declaration(PredicateDeclaration),
+    /// Import directive, also used for `@source` declarations
+    Import(ImportDirective),
+    /// Export directive
+    Export(ExportDirective),
+    /// Output directive
+    Output(Identifier),
+    /// Fact or rule
+    Statement(Statement),
+    /// Integrity constraint, i.e., a denial rule or a key directive
+    Constraint(IntegrityConstraint),
+}
+
+/// A [ProgramElement] together with the range of bytes it occupies in the source text.
+/// The range includes any whitespace and comments that directly follow the element.
+#[derive(Debug, Clone)]
+pub(crate) struct LocatedProgramElement {
+    /// The parsed element
+    pub(crate) element: ProgramElement,
+    /// Byte range in the source text
+    pub(crate) range: std::ops::Range<usize>,
+}
+
 /// Different operators allows in a constraint.
 /// Has one entry for every variant in [Constraint].
 #[derive(Debug, Clone, Copy)]
@@ -124,6 +164,9 @@ pub enum ParseError {
     /// An error related to a file format.
     #[error(r#"unknown file format "{0}""#)]
     FileFormatError(String),
+    /// An unknown type in a predicate declaration.
+    #[error(r#"unknown type "{0}", expected "any", "string", "int", or "double""#)]
+    UnknownValueType(String),
     /// A syntax error. Note that we cannot take [&'a str] here, as
     /// bounds on [std::error::Error] require ['static] lifetime.
     #[error("syntax error: {0}")]
@@ -164,6 +207,17 @@ pub enum ParseError {
     /// Aggregates cannot be used within existential rules
     #[error("Aggregates may not appear in existential rules.")]
     AggregatesPlusExistentials,
+    /// An existentially quantified variable occurs in an equality in the rule head.
+    #[error(r#"variable "{0}" occurs existentially quantified in an equality"#)]
+    ExistentialInEquality(Variable),
+    /// A registered function is applied to the wrong number of parameters.
+    #[error(r#"function "{0}" expects {1} parameters, but {2} are given"#)]
+    UserFunctionArity(String, usize, usize),
+    /// Unnesting occurs outside of an assignment in the body of a rule.
+    #[error(
+        r#"unnesting in "{0}" may only occur as "?variable = UNNEST(...)" in the body of a rule"#
+    )]
+    MisplacedUnnest(String),
     /// A variable is both existentially and universally quantified
     #[error(r#"variables named "{0}" occur with existential and universal quantification"#)]
     BothQuantifiers(String),
@@ -195,6 +249,15 @@ pub enum ParseError {
     /// Expected a predicate declaration.
     #[error(r#"Expected a "@declare" type declaration"#)]
     ExpectedPredicateDeclaration,
+    /// Expected a denial rule.
+    #[error(r#"Expected a denial rule with head "⊥" or "false""#)]
+    ExpectedDenialRule,
+    /// Expected a key directive.
+    #[error(r#"Expected a "@key" directive"#)]
+    ExpectedKeyDirective,
+    /// A key directive contains a term that is not a variable.
+    #[error(r#"key directive must only contain variables for key positions and "_" otherwise, found "{0}""#)]
+    InvalidKeyTerm(String),
     /// Expected a prefix.
     #[error(r#"Expected a prefix"#)]
     ExpectedPnameNs,
@@ -316,6 +379,18 @@ pub enum ParseError {
     UnknownAggregateOperation(String),
 }
 
+/// Compute the [SourcePosition] at which the given [Span] starts.
+pub(crate) fn source_position(span: Span) -> SourcePosition {
+    // miri doesn't like nom_locate, cf. https://github.com/fflorent/nom_locate/issues/88
+    let column = if cfg!(not(miri)) {
+        span.naive_get_utf8_column()
+    } else {
+        0
+    };
+
+    SourcePosition::new(span.location_line(), column)
+}
+
 impl ParseError {
     /// Locate this error by adding a position.
     pub fn at(self, position: Span) -> LocatedParseError {
@@ -34,7 +34,7 @@ pub trait ResourceProvider: std::fmt::Debug {
         &self,
         resource: &Resource,
         compression: CompressionFormat,
-    ) -> Result<Option<Box<dyn BufRead>>, ReadingError>;
+    ) -> Result<Option<Box<dyn BufRead + Send>>, ReadingError>;
 }
 
 /// A list of [ResourceProvider] sorted by decreasing priority.
@@ -70,7 +70,7 @@ impl ResourceProviders {
         &self,
         resource: &Resource,
         compression: CompressionFormat,
-    ) -> Result<Box<dyn BufRead>, ReadingError> {
+    ) -> Result<Box<dyn BufRead + Send>, ReadingError> {
         for resource_provider in self.0.iter() {
             if let Some(reader) = resource_provider.open_resource(resource, compression)? {
                 return Ok(reader);
@@ -59,7 +59,7 @@ impl ResourceProvider for FileResourceProvider {
         &self,
         resource: &Resource,
         compression: CompressionFormat,
-    ) -> Result<Option<Box<dyn BufRead>>, ReadingError> {
+    ) -> Result<Option<Box<dyn BufRead + Send>>, ReadingError> {
         // Try to parse as file IRI
         if let Some(path) = self.parse_resource(resource)? {
             let file = File::open(&path).map_err(|e| ReadingError::IoReading {
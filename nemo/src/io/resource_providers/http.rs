@@ -61,7 +61,7 @@ impl ResourceProvider for HttpResourceProvider {
         &self,
         resource: &Resource,
         compression: CompressionFormat,
-    ) -> Result<Option<Box<dyn BufRead>>, ReadingError> {
+    ) -> Result<Option<Box<dyn BufRead + Send>>, ReadingError> {
         if !is_iri(resource) {
             return Ok(None);
         }
@@ -30,7 +30,7 @@ pub mod execution;
 pub mod model;
 pub mod util;
 
-mod program_analysis;
+pub mod program_analysis;
 mod table_manager;
 
 // we use datavalues and meta from nemo_physical in our API, so re-export it here.
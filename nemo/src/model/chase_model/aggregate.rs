@@ -68,3 +68,34 @@ impl ChaseAggregate {
         &self.input_variable
     }
 }
+
+/// Order in which the results of an aggregate improve
+/// when the aggregate is maintained during a recursive computation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LatticeOrder {
+    /// Smaller values are better, as for `#min`
+    Decreasing,
+    /// Larger values are better, as for `#max`, `#count`, and `#sum`
+    Increasing,
+}
+
+impl LatticeOrder {
+    /// Return the [LatticeOrder] in which the results of the given [AggregateOperation] improve.
+    pub fn from_operation(operation: AggregateOperation) -> Self {
+        match operation {
+            AggregateOperation::Min => Self::Decreasing,
+            AggregateOperation::Max | AggregateOperation::Count | AggregateOperation::Sum => {
+                Self::Increasing
+            }
+        }
+    }
+}
+
+/// Position of a predicate that receives the results of an aggregate in a recursive rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatticePosition {
+    /// Index of the aggregated position
+    pub index: usize,
+    /// Order in which the values in this position improve
+    pub order: LatticeOrder,
+}
@@ -7,21 +7,28 @@ use nemo_physical::datavalues::AnyDataValue;
 use crate::{
     error::Error,
     io::formats::import_export::{ImportExportHandler, ImportExportHandlers},
-    model::{ExportDirective, Identifier, ImportDirective, Program},
+    model::{
+        Atom, ExportDirective, Identifier, ImportDirective, PredicateDeclaration, PrimitiveTerm,
+        Program, SourcePosition, Variable,
+    },
+    program_analysis::type_checking::declared_types,
 };
 
-use super::{ChaseAtom, ChaseFact, ChaseRule};
+use super::{ChaseAtom, ChaseFact, ChaseRule, LatticePosition};
 
 /// Representation of a datalog program that is used for generating execution plans for the physical layer.
 #[derive(Debug, Default, Clone)]
 pub(crate) struct ChaseProgram {
     base: Option<String>,
     prefixes: HashMap<String, String>,
+    declarations: Vec<PredicateDeclaration>,
     import_handlers: Vec<(Identifier, Box<dyn ImportExportHandler>)>,
+    import_positions: Vec<Option<SourcePosition>>,
     export_handlers: Vec<(Identifier, Box<dyn ImportExportHandler>)>,
     rules: Vec<ChaseRule>,
     facts: Vec<ChaseFact>,
     output_predicates: Vec<Identifier>,
+    lattice_positions: HashMap<Identifier, LatticePosition>,
 }
 
 /// A Builder for a [ChaseProgram].
@@ -63,12 +70,22 @@ impl ChaseProgramBuilder {
         self
     }
 
+    /// Add type declarations of predicates.
+    pub fn declarations<T>(mut self, declarations: T) -> Self
+    where
+        T: IntoIterator<Item = PredicateDeclaration>,
+    {
+        self.program.declarations.extend(declarations);
+        self
+    }
+
     /// Add an imported table.
     pub fn import(mut self, import: &ImportDirective) -> Result<Self, Error> {
         let handler = ImportExportHandlers::import_handler(import)?;
         self.program
             .import_handlers
             .push((import.predicate().clone(), handler));
+        self.program.import_positions.push(import.position());
         Ok(self)
     }
 
@@ -162,12 +179,34 @@ impl ChaseProgram {
         &self.rules
     }
 
+    /// Return all rules in the program - mutable.
+    #[must_use]
+    pub fn rules_mut(&mut self) -> &mut Vec<ChaseRule> {
+        &mut self.rules
+    }
+
     /// Return all facts in the program.
     #[must_use]
     pub fn facts(&self) -> &Vec<ChaseFact> {
         &self.facts
     }
 
+    /// Return all facts in the program - mutable.
+    #[must_use]
+    pub fn facts_mut(&mut self) -> &mut Vec<ChaseFact> {
+        &mut self.facts
+    }
+
+    /// Return all type declarations of predicates in the program.
+    pub fn declarations(&self) -> &Vec<PredicateDeclaration> {
+        &self.declarations
+    }
+
+    /// Return all type declarations of predicates in the program - mutable.
+    pub fn declarations_mut(&mut self) -> &mut Vec<PredicateDeclaration> {
+        &mut self.declarations
+    }
+
     /// Return a HashSet of all idb predicates (predicates occuring rule heads) in the program.
     #[must_use]
     pub fn idb_predicates(&self) -> HashSet<Identifier> {
@@ -178,6 +217,52 @@ impl ChaseProgram {
             .collect()
     }
 
+    /// Return whether some rule of the program has an equality atom in its head.
+    pub fn has_equality_rules(&self) -> bool {
+        let equality = Atom::equality_predicate();
+
+        self.rules
+            .iter()
+            .any(|rule| rule.head().iter().any(|atom| atom.predicate() == equality))
+    }
+
+    /// Return all predicates whose tables may contain nulls,
+    /// i.e., the predicates in the head of rules with existential variables
+    /// and all predicates that depend on them.
+    pub fn null_predicates(&self) -> HashSet<Identifier> {
+        let mut result = self
+            .rules
+            .iter()
+            .filter(|rule| {
+                rule.head().iter().any(|atom| {
+                    atom.terms().iter().any(|term| {
+                        matches!(term, PrimitiveTerm::Variable(Variable::Existential(_)))
+                    })
+                })
+            })
+            .flat_map(|rule| rule.head().iter().map(|atom| atom.predicate()))
+            .collect::<HashSet<_>>();
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+
+            for rule in &self.rules {
+                if rule
+                    .positive_body()
+                    .iter()
+                    .any(|atom| result.contains(&atom.predicate()))
+                {
+                    for atom in rule.head() {
+                        changed |= result.insert(atom.predicate());
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
     /// Return all imports in the program.
     pub(crate) fn imports(
         &self,
@@ -185,6 +270,35 @@ impl ChaseProgram {
         self.import_handlers.iter()
     }
 
+    /// Return all imports in the program - mutable.
+    pub(crate) fn imports_mut(
+        &mut self,
+    ) -> impl Iterator<Item = &mut (Identifier, Box<dyn ImportExportHandler>)> {
+        self.import_handlers.iter_mut()
+    }
+
+    /// Return the positions of all imports in the source text, if known,
+    /// in the same order as [ChaseProgram::imports].
+    pub(crate) fn import_positions(&self) -> impl Iterator<Item = Option<SourcePosition>> + '_ {
+        self.import_positions.iter().copied()
+    }
+
+    /// Return all predicates that were explicitly selected for output.
+    pub fn output_predicates(&self) -> impl Iterator<Item = &Identifier> {
+        self.output_predicates.iter()
+    }
+
+    /// Return the positions of predicates that receive the results of aggregates in recursive rules.
+    pub fn lattice_positions(&self) -> &HashMap<Identifier, LatticePosition> {
+        &self.lattice_positions
+    }
+
+    /// Return the positions of predicates that receive the results of aggregates
+    /// in recursive rules - mutable.
+    pub fn lattice_positions_mut(&mut self) -> &mut HashMap<Identifier, LatticePosition> {
+        &mut self.lattice_positions
+    }
+
     /// Return all exports in the program.
     pub fn exports(&self) -> impl Iterator<Item = &(Identifier, Box<dyn ImportExportHandler>)> {
         self.export_handlers.iter()
@@ -200,16 +314,29 @@ impl TryFrom<Program> for ChaseProgram {
     type Error = Error;
 
     fn try_from(program: Program) -> Result<Self, Error> {
+        let declared_types = declared_types(program.declarations())?;
+
         let mut builder = Self::builder()
             .prefixes(program.prefixes().clone())
-            .imports(program.imports().cloned())?
+            .declarations(program.declarations().iter().cloned())
+            .imports(program.imports().map(
+                |import| match declared_types.get(import.predicate()) {
+                    Some(types) => import.with_declared_value_formats(types),
+                    None => import.clone(),
+                },
+            ))?
             .exports(program.exports().cloned())?
             .rules(
                 program
                     .rules()
                     .iter()
                     .cloned()
-                    .map(ChaseRule::try_from)
+                    .enumerate()
+                    .map(|(index, rule)| {
+                        let mut rule = ChaseRule::try_from(rule)?;
+                        rule.set_origin(index);
+                        Ok(rule)
+                    })
                     .collect::<Result<Vec<_>, Error>>()?,
             )
             .facts(
@@ -8,7 +8,7 @@ use crate::{
     error::Error,
     model::{
         chase_model::variable::{AGGREGATE_VARIABLE_PREFIX, CONSTRUCT_VARIABLE_PREFIX},
-        Aggregate, Constraint, Literal, PrimitiveTerm, Rule, Term, Variable,
+        Aggregate, Constraint, Literal, PrimitiveTerm, Rule, SourcePosition, Term, Variable,
     },
 };
 
@@ -54,6 +54,11 @@ pub struct ChaseRule {
     head: Vec<PrimitiveAtom>,
     /// Index of the head atom which contains the aggregate
     aggregate_head_index: Option<usize>,
+
+    /// Position of the original rule in the source text, if it was parsed
+    position: Option<SourcePosition>,
+    /// Index of the rule of the [Program][crate::model::Program] from which this rule was derived
+    origin: Option<usize>,
 }
 
 #[allow(dead_code)]
@@ -75,6 +80,21 @@ impl ChaseRule {
             aggregate_constraints: vec![],
             head,
             aggregate_head_index: None,
+            position: None,
+            origin: None,
+        }
+    }
+
+    /// Construct a new [ChaseRule] whose only head atom contains the given aggregate.
+    pub fn aggregate_rule(
+        head: PrimitiveAtom,
+        positive_body: Vec<VariableAtom>,
+        aggregate: ChaseAggregate,
+    ) -> Self {
+        Self {
+            aggregate: Some(aggregate),
+            aggregate_head_index: Some(0),
+            ..Self::positive_rule(vec![head], positive_body, vec![])
         }
     }
 
@@ -90,6 +110,23 @@ impl ChaseRule {
         &mut self.head
     }
 
+    /// Return the position of the original rule in the source text, if known.
+    pub fn position(&self) -> Option<SourcePosition> {
+        self.position
+    }
+
+    /// Return the index of the rule of the [Program][crate::model::Program]
+    /// from which this rule was derived, if known.
+    pub fn origin(&self) -> Option<usize> {
+        self.origin
+    }
+
+    /// Record the index of the rule of the [Program][crate::model::Program]
+    /// from which this rule was derived.
+    pub(crate) fn set_origin(&mut self, origin: usize) {
+        self.origin = Some(origin);
+    }
+
     /// Return the positive constructors of the rule.
     pub fn positive_constructors(&self) -> &Vec<Constructor> {
         &self.positive_constructors
@@ -623,6 +660,8 @@ impl TryFrom<Rule> for ChaseRule {
             aggregate_constraints,
             head,
             aggregate_head_index,
+            position: rule.position(),
+            origin: None,
         })
     }
 }
@@ -21,9 +21,21 @@ pub use term::*;
 mod predicate;
 pub use predicate::*;
 
+mod predicate_declaration;
+pub use predicate_declaration::*;
+
+mod position;
+pub use position::*;
+
 mod constraint;
 pub use constraint::*;
 
+mod integrity_constraint;
+pub use integrity_constraint::*;
+
+mod user_function;
+pub use user_function::*;
+
 pub mod import_export;
 pub use import_export::*;
 
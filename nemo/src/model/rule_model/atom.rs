@@ -2,6 +2,9 @@ use crate::model::VariableAssignment;
 
 use super::{Aggregate, Identifier, PrimitiveTerm, Term, Variable};
 
+/// Name of the predicate used for equality atoms `s = t` in rule heads
+pub(crate) const EQUALITY_PREDICATE_NAME: &str = "__SAME_AS";
+
 /// An atom.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Atom {
@@ -17,6 +20,27 @@ impl Atom {
         Self { predicate, terms }
     }
 
+    /// Construct a new equality atom, stating that two terms are equal.
+    ///
+    /// Such atoms may only occur in the head of rules,
+    /// where they merge the values that the terms are bound to.
+    pub fn equality(left: PrimitiveTerm, right: PrimitiveTerm) -> Self {
+        Self::new(
+            Self::equality_predicate(),
+            vec![Term::Primitive(left), Term::Primitive(right)],
+        )
+    }
+
+    /// Return the predicate that is used for equality atoms.
+    pub fn equality_predicate() -> Identifier {
+        Identifier(EQUALITY_PREDICATE_NAME.to_string())
+    }
+
+    /// Return whether this is an equality atom.
+    pub fn is_equality(&self) -> bool {
+        self.predicate.0 == EQUALITY_PREDICATE_NAME
+    }
+
     /// Return the predicate [Identifier].
     #[must_use]
     pub fn predicate(&self) -> Identifier {
@@ -75,6 +99,10 @@ impl Atom {
 
 impl std::fmt::Display for Atom {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_equality() {
+            return write!(f, "{} = {}", self.terms[0], self.terms[1]);
+        }
+
         self.predicate.fmt(f)?;
         f.write_str("(")?;
         for (index, term) in self.terms().iter().enumerate() {
@@ -2,7 +2,7 @@ use nemo_physical::datavalues::AnyDataValue;
 
 use crate::model::{Term, Variable, VariableAssignment};
 
-use super::{Aggregate, BinaryOperation, PrimitiveTerm};
+use super::{Aggregate, BinaryOperation, PrimitiveTerm, UnaryOperation};
 
 /// Represents a constraint which is expressed as a binary operator applied to two terms
 #[derive(Debug, Eq, PartialEq, Clone, PartialOrd, Ord)]
@@ -131,11 +131,23 @@ impl Constraint {
             None
         }
     }
+
+    /// Check if the constraint is an assignment of the elements of a tuple to a variable,
+    /// i.e., has the form `?variable = UNNEST(term)`.
+    ///
+    /// If so returns a tuple containing the variable and the unnested term.
+    /// Returns `None` otherwise.
+    pub fn has_form_unnest(&self) -> Option<(&Variable, &Term)> {
+        match self.has_form_assignment()? {
+            (variable, Term::Unary(UnaryOperation::Unnest, term)) => Some((variable, term)),
+            _ => None,
+        }
+    }
 }
 
 impl Constraint {
     /// Returns a string representation for the operation defined by this constraint.
-    fn operator_string(&self) -> &'static str {
+    pub(crate) fn operator_string(&self) -> &'static str {
         match self {
             Constraint::Equals(_, _) => "=",
             Constraint::Unequals(_, _) => "!=",
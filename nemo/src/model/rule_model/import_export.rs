@@ -1,9 +1,12 @@
 //! Import and export directives are a direct representation of the syntactic information
 //! given in rule files.
 
-use nemo_physical::datavalues::MapDataValue;
+use nemo_physical::datavalues::{AnyDataValue, DataValue, MapDataValue, TupleDataValue};
 
-use crate::model::Identifier;
+use crate::model::{
+    Identifier, SourcePosition, ValueType, PARAMETER_NAME_FORMAT, VALUE_FORMAT_ANY,
+    VALUE_FORMAT_STRING,
+};
 
 /// An import/export specification. This object captures all information that is typically
 /// present in an import or export directive in a Nemo program, including the main format,
@@ -17,6 +20,8 @@ pub(crate) struct ImportExportDirective {
     pub(crate) format: FileFormat,
     /// The attributes we've been given.
     pub(crate) attributes: MapDataValue,
+    /// Position of the directive in the source text, if it was parsed.
+    pub(crate) position: Option<SourcePosition>,
 }
 
 impl PartialEq for ImportExportDirective {
@@ -48,6 +53,55 @@ impl ImportDirective {
     pub fn attributes(&self) -> &MapDataValue {
         &self.0.attributes
     }
+
+    /// Return the position of the directive in the source text, if known.
+    pub fn position(&self) -> Option<SourcePosition> {
+        self.0.position
+    }
+
+    /// Return a copy of this directive that reads columns declared as strings
+    /// in the string value format, unless value formats are given explicitly
+    /// or the file format does not support them.
+    ///
+    /// Other columns are read in the best-effort format, because readers drop rows with
+    /// values that cannot be parsed in a typed format, whereas such values should be
+    /// reported as violations of the declared types.
+    pub(crate) fn with_declared_value_formats(&self, types: &[ValueType]) -> ImportDirective {
+        let format_key = AnyDataValue::new_iri(PARAMETER_NAME_FORMAT.to_string());
+        let supports_value_formats = matches!(
+            self.file_format(),
+            FileFormat::CSV | FileFormat::DSV | FileFormat::TSV
+        );
+
+        if !supports_value_formats || self.attributes().map_element(&format_key).is_some() {
+            return self.clone();
+        }
+
+        let value_formats = TupleDataValue::from_iter(types.iter().map(|value_type| {
+            let format = match value_type {
+                ValueType::String => VALUE_FORMAT_STRING,
+                ValueType::Any | ValueType::Int | ValueType::Double => VALUE_FORMAT_ANY,
+            };
+            AnyDataValue::new_plain_string(format.to_string())
+        }));
+        let attributes = self
+            .attributes()
+            .map_keys()
+            .into_iter()
+            .flatten()
+            .map(|key| {
+                (
+                    key.clone(),
+                    self.attributes().map_element_unchecked(key).clone(),
+                )
+            })
+            .chain(std::iter::once((format_key, value_formats.into())))
+            .collect();
+
+        let mut directive = self.clone();
+        directive.0.attributes = attributes;
+        directive
+    }
 }
 
 impl From<ImportExportDirective> for ImportDirective {
@@ -76,12 +130,40 @@ impl ExportDirective {
         &self.0.attributes
     }
 
+    /// Return the position of the directive in the source text, if known.
+    pub fn position(&self) -> Option<SourcePosition> {
+        self.0.position
+    }
+
+    /// Obtain an [ExportDirective] for the given predicate in the given format,
+    /// where the attributes are pairs of parameter names and values,
+    /// such as `("delimiter", ";")` for [FileFormat::DSV].
+    pub fn new<Attributes>(
+        predicate: Identifier,
+        format: FileFormat,
+        attributes: Attributes,
+    ) -> ExportDirective
+    where
+        Attributes: IntoIterator<Item = (String, AnyDataValue)>,
+    {
+        ExportDirective(ImportExportDirective {
+            format,
+            predicate,
+            attributes: attributes
+                .into_iter()
+                .map(|(name, value)| (AnyDataValue::new_iri(name), value))
+                .collect(),
+            position: None,
+        })
+    }
+
     /// Obtain a default [ExportDirective] for the given predicate.
     pub fn default(predicate: Identifier) -> ExportDirective {
         ExportDirective(ImportExportDirective {
             format: FileFormat::CSV,
             predicate,
             attributes: MapDataValue::from_iter([]),
+            position: None,
         })
     }
 }
@@ -0,0 +1,289 @@
+//! Integrity constraints, i.e., conditions that must never hold in the result of a program.
+
+use std::collections::BTreeSet;
+
+use nemo_physical::datavalues::AnyDataValue;
+
+use crate::io::parser::ParseError;
+
+use super::{
+    Atom, Constraint, Identifier, Literal, PrimitiveTerm, Rule, SourcePosition, Term, Variable,
+};
+
+/// A denial rule, i.e., a rule with the empty head `⊥`,
+/// whose body must not be satisfied by any assignment.
+#[derive(Debug, Clone)]
+pub struct DenialRule {
+    /// Body literals of the rule
+    body: Vec<Literal>,
+    /// Constraints on the body of the rule
+    constraints: Vec<Constraint>,
+    /// Position of the rule in the source text, if it was parsed
+    pub(crate) position: Option<SourcePosition>,
+}
+
+impl PartialEq for DenialRule {
+    fn eq(&self, other: &Self) -> bool {
+        self.body == other.body && self.constraints == other.constraints
+    }
+}
+
+impl Eq for DenialRule {}
+
+impl DenialRule {
+    /// Construct a new denial rule.
+    pub fn new(body: Vec<Literal>, constraints: Vec<Constraint>) -> Self {
+        Self {
+            body,
+            constraints,
+            position: None,
+        }
+    }
+
+    /// Construct a new denial rule, validating constraints on variable usage.
+    pub(crate) fn new_validated(
+        body: Vec<Literal>,
+        constraints: Vec<Constraint>,
+    ) -> Result<Self, ParseError> {
+        let rule = Rule::new_validated(Vec::new(), body, constraints)?;
+
+        Ok(Self::new(rule.body().clone(), rule.constraints().clone()))
+    }
+
+    /// Return the body literals of the rule.
+    pub fn body(&self) -> &Vec<Literal> {
+        &self.body
+    }
+
+    /// Return the constraints of the rule.
+    pub fn constraints(&self) -> &Vec<Constraint> {
+        &self.constraints
+    }
+
+    /// Return the position of the rule in the source text, if known.
+    pub fn position(&self) -> Option<SourcePosition> {
+        self.position
+    }
+
+    /// Return the named variables that occur in positive body literals, ordered by name.
+    ///
+    /// An assignment of these variables identifies an instantiation of the rule.
+    pub fn variables(&self) -> Vec<Variable> {
+        self.body
+            .iter()
+            .filter(|literal| literal.is_positive())
+            .flat_map(|literal| literal.variables())
+            .filter(|variable| variable.is_universal())
+            .cloned()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Return a [Rule] with the same body that derives
+    /// an atom with the given predicate for each instantiation of this rule.
+    ///
+    /// The terms of the derived atom are the values of [DenialRule::variables].
+    /// If there are no such variables, the atom contains the constant `true` instead,
+    /// since predicates must have at least one position.
+    pub fn to_rule(&self, predicate: Identifier) -> Rule {
+        let mut terms = self
+            .variables()
+            .into_iter()
+            .map(|variable| Term::Primitive(PrimitiveTerm::Variable(variable)))
+            .collect::<Vec<_>>();
+        if terms.is_empty() {
+            terms.push(Term::Primitive(PrimitiveTerm::GroundTerm(
+                AnyDataValue::new_boolean(true),
+            )));
+        }
+
+        let mut rule = Rule::new(
+            vec![Atom::new(predicate, terms)],
+            self.body.clone(),
+            self.constraints.clone(),
+        );
+        if let Some(position) = self.position {
+            rule.set_position(position);
+        }
+
+        rule
+    }
+}
+
+impl std::fmt::Display for DenialRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let body = self
+            .body
+            .iter()
+            .map(ToString::to_string)
+            .chain(self.constraints.iter().map(ToString::to_string))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        write!(f, "⊥ :- {body} .")
+    }
+}
+
+/// A key constraint on a predicate, i.e., a set of positions
+/// that functionally determines the values in all other positions.
+///
+/// It is written like an atom in which the key positions contain named variables
+/// and all other positions contain the anonymous variable, e.g. `@key p(?id, _, _) .`
+#[derive(Debug, Clone)]
+pub struct KeyConstraint {
+    /// The constrained predicate
+    predicate: Identifier,
+    /// For each position of the predicate, the variable naming it if it belongs to the key
+    key: Vec<Option<Variable>>,
+    /// Position of the directive in the source text, if it was parsed
+    pub(crate) position: Option<SourcePosition>,
+}
+
+impl PartialEq for KeyConstraint {
+    fn eq(&self, other: &Self) -> bool {
+        self.predicate == other.predicate && self.key == other.key
+    }
+}
+
+impl Eq for KeyConstraint {}
+
+impl KeyConstraint {
+    /// Construct a new [KeyConstraint].
+    ///
+    /// Each entry of `key` corresponds to a position of the predicate
+    /// and contains a variable if the position belongs to the key.
+    pub fn new(predicate: Identifier, key: Vec<Option<Variable>>) -> Self {
+        Self {
+            predicate,
+            key,
+            position: None,
+        }
+    }
+
+    /// Return the constrained predicate.
+    pub fn predicate(&self) -> &Identifier {
+        &self.predicate
+    }
+
+    /// Return the arity of the constrained predicate.
+    pub fn arity(&self) -> usize {
+        self.key.len()
+    }
+
+    /// Return the variable naming the given position, starting at 0,
+    /// or `None` if the position does not belong to the key.
+    pub fn key_variable(&self, index: usize) -> Option<&Variable> {
+        self.key[index].as_ref()
+    }
+
+    /// Return the indices of the key positions, starting at 0.
+    pub fn key_positions(&self) -> impl Iterator<Item = usize> + '_ {
+        self.key
+            .iter()
+            .enumerate()
+            .filter(|(_, variable)| variable.is_some())
+            .map(|(index, _)| index)
+    }
+
+    /// Return the position of the directive in the source text, if known.
+    pub fn position(&self) -> Option<SourcePosition> {
+        self.position
+    }
+
+    /// Return one [DenialRule] for each non-key position,
+    /// which is violated by two facts that agree on the key but differ in this position.
+    pub fn denial_rules(&self) -> Vec<DenialRule> {
+        let atom = |name: &str| {
+            let terms = self
+                .key
+                .iter()
+                .enumerate()
+                .map(|(index, variable)| {
+                    let variable = variable
+                        .clone()
+                        .unwrap_or_else(|| Variable::Universal(format!("_KEY_{name}_{index}")));
+                    Term::Primitive(PrimitiveTerm::Variable(variable))
+                })
+                .collect();
+
+            Literal::Positive(Atom::new(self.predicate.clone(), terms))
+        };
+
+        self.key
+            .iter()
+            .enumerate()
+            .filter(|(_, variable)| variable.is_none())
+            .map(|(index, _)| {
+                let mut rule = DenialRule::new(
+                    vec![atom("FIRST"), atom("SECOND")],
+                    vec![Constraint::Unequals(
+                        Term::Primitive(PrimitiveTerm::Variable(Variable::Universal(format!(
+                            "_KEY_FIRST_{index}"
+                        )))),
+                        Term::Primitive(PrimitiveTerm::Variable(Variable::Universal(format!(
+                            "_KEY_SECOND_{index}"
+                        )))),
+                    )],
+                );
+                rule.position = self.position;
+
+                rule
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Display for KeyConstraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let terms = self
+            .key
+            .iter()
+            .map(|variable| match variable {
+                Some(variable) => variable.to_string(),
+                None => "_".to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        write!(f, "@key {}({terms}) .", self.predicate)
+    }
+}
+
+/// A condition that must never hold in the result of a program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegrityConstraint {
+    /// A denial rule
+    Denial(DenialRule),
+    /// A key constraint
+    Key(KeyConstraint),
+}
+
+impl IntegrityConstraint {
+    /// Return the [DenialRule]s whose instantiations are violations of this constraint.
+    ///
+    /// All of the returned rules share the same variables.
+    pub fn denial_rules(&self) -> Vec<DenialRule> {
+        match self {
+            IntegrityConstraint::Denial(rule) => vec![rule.clone()],
+            IntegrityConstraint::Key(key) => key.denial_rules(),
+        }
+    }
+
+    /// Return the position of the constraint in the source text, if known.
+    pub fn position(&self) -> Option<SourcePosition> {
+        match self {
+            IntegrityConstraint::Denial(rule) => rule.position(),
+            IntegrityConstraint::Key(key) => key.position(),
+        }
+    }
+}
+
+impl std::fmt::Display for IntegrityConstraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IntegrityConstraint::Denial(rule) => rule.fmt(f),
+            IntegrityConstraint::Key(key) => key.fmt(f),
+        }
+    }
+}
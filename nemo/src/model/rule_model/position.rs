@@ -0,0 +1,23 @@
+//! Positions of program elements in the source text they were parsed from.
+
+/// Line and column at which an element of a program starts in its source text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SourcePosition {
+    /// Line number, starting at 1
+    pub line: u32,
+    /// Column number, starting at 1
+    pub column: usize,
+}
+
+impl SourcePosition {
+    /// Create a new [SourcePosition].
+    pub fn new(line: u32, column: usize) -> Self {
+        Self { line, column }
+    }
+}
+
+impl std::fmt::Display for SourcePosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
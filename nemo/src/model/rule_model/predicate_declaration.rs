@@ -0,0 +1,132 @@
+//! Declarations of the types of values that may occur in the positions of a predicate.
+
+use nemo_physical::datavalues::{AnyDataValue, DataValue, ValueDomain};
+
+use super::{
+    Identifier, SourcePosition, VALUE_FORMAT_ANY, VALUE_FORMAT_DOUBLE, VALUE_FORMAT_INT,
+    VALUE_FORMAT_STRING,
+};
+
+/// Type of the values that may occur in some position of a predicate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ValueType {
+    /// Any value
+    #[default]
+    Any,
+    /// Plain strings
+    String,
+    /// Integers
+    Int,
+    /// Double-precision floating point numbers
+    Double,
+}
+
+impl ValueType {
+    /// Return the [ValueType] with the given name, if there is one.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            VALUE_FORMAT_ANY => Some(Self::Any),
+            VALUE_FORMAT_STRING => Some(Self::String),
+            VALUE_FORMAT_INT => Some(Self::Int),
+            VALUE_FORMAT_DOUBLE => Some(Self::Double),
+            _ => None,
+        }
+    }
+
+    /// Return the name of this type as used in declarations.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Any => VALUE_FORMAT_ANY,
+            Self::String => VALUE_FORMAT_STRING,
+            Self::Int => VALUE_FORMAT_INT,
+            Self::Double => VALUE_FORMAT_DOUBLE,
+        }
+    }
+
+    /// Return `true` if the given value belongs to this type.
+    pub fn admits(&self, value: &AnyDataValue) -> bool {
+        match self {
+            Self::Any => true,
+            Self::String => value.value_domain() == ValueDomain::PlainString,
+            Self::Int => matches!(
+                value.value_domain(),
+                ValueDomain::UnsignedLong
+                    | ValueDomain::NonNegativeLong
+                    | ValueDomain::UnsignedInt
+                    | ValueDomain::NonNegativeInt
+                    | ValueDomain::Long
+                    | ValueDomain::Int
+            ),
+            Self::Double => value.value_domain() == ValueDomain::Double,
+        }
+    }
+}
+
+impl std::fmt::Display for ValueType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Declaration of the [ValueType]s of the positions of a predicate.
+#[derive(Debug, Clone)]
+pub struct PredicateDeclaration {
+    /// The declared predicate
+    predicate: Identifier,
+    /// Type of each position of the predicate
+    types: Vec<ValueType>,
+    /// Position of the declaration in the source text, if it was parsed
+    pub(crate) position: Option<SourcePosition>,
+}
+
+impl PartialEq for PredicateDeclaration {
+    fn eq(&self, other: &Self) -> bool {
+        self.predicate == other.predicate && self.types == other.types
+    }
+}
+
+impl Eq for PredicateDeclaration {}
+
+impl PredicateDeclaration {
+    /// Construct a new [PredicateDeclaration].
+    pub fn new(predicate: Identifier, types: Vec<ValueType>) -> Self {
+        Self {
+            predicate,
+            types,
+            position: None,
+        }
+    }
+
+    /// Return the declared predicate.
+    pub fn predicate(&self) -> &Identifier {
+        &self.predicate
+    }
+
+    /// Return the declared types of the positions of the predicate.
+    pub fn types(&self) -> &[ValueType] {
+        &self.types
+    }
+
+    /// Return the arity of the declared predicate.
+    pub fn arity(&self) -> usize {
+        self.types.len()
+    }
+
+    /// Return the position of the declaration in the source text, if known.
+    pub fn position(&self) -> Option<SourcePosition> {
+        self.position
+    }
+}
+
+impl std::fmt::Display for PredicateDeclaration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let types = self
+            .types
+            .iter()
+            .map(ValueType::name)
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        write!(f, "@declare {}({types}).", self.predicate)
+    }
+}
@@ -1,8 +1,11 @@
 use std::collections::{HashMap, HashSet};
 
-use crate::model::{ExportDirective, ImportDirective};
+use crate::{
+    io::formatter::SyntaxWriter,
+    model::{ExportDirective, ImportDirective},
+};
 
-use super::{Atom, Identifier, Rule};
+use super::{Atom, Identifier, IntegrityConstraint, PredicateDeclaration, Rule};
 
 /// A (ground) fact.
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -28,13 +31,21 @@ pub enum Statement {
 pub struct Program {
     base: Option<String>,
     prefixes: HashMap<String, String>,
+    declarations: Vec<PredicateDeclaration>,
     rules: Vec<Rule>,
     facts: Vec<Fact>,
+    constraints: Vec<IntegrityConstraint>,
     imports: Vec<ImportDirective>,
     exports: Vec<ExportDirective>,
     output_predicates: Vec<Identifier>,
 }
 
+impl std::fmt::Display for Program {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&SyntaxWriter::new(&self.prefixes).program(self))
+    }
+}
+
 /// A Builder for a program.
 #[derive(Debug, Default)]
 pub struct ProgramBuilder {
@@ -73,6 +84,21 @@ impl ProgramBuilder {
         self
     }
 
+    /// Add a type declaration of a predicate.
+    pub fn declaration(mut self, declaration: PredicateDeclaration) -> Self {
+        self.program.declarations.push(declaration);
+        self
+    }
+
+    /// Add type declarations of predicates.
+    pub fn declarations<T>(mut self, declarations: T) -> Self
+    where
+        T: IntoIterator<Item = PredicateDeclaration>,
+    {
+        self.program.declarations.extend(declarations);
+        self
+    }
+
     /// Add an imported table.
     pub fn import(mut self, import: ImportDirective) -> Self {
         self.program.imports.push(import);
@@ -133,6 +159,21 @@ impl ProgramBuilder {
         self
     }
 
+    /// Add an integrity constraint.
+    pub fn constraint(mut self, constraint: IntegrityConstraint) -> Self {
+        self.program.constraints.push(constraint);
+        self
+    }
+
+    /// Add integrity constraints.
+    pub fn constraints<T>(mut self, constraints: T) -> Self
+    where
+        T: IntoIterator<Item = IntegrityConstraint>,
+    {
+        self.program.constraints.extend(constraints);
+        self
+    }
+
     /// Mark predicate as output predicate.
     pub fn output_predicate(self, predicate: Identifier) -> Self {
         self.output_predicates([predicate])
@@ -166,13 +207,49 @@ impl Program {
         &self.rules
     }
 
+    /// Return all rules in the program - mutable.
+    #[must_use]
+    pub fn rules_mut(&mut self) -> &mut Vec<Rule> {
+        &mut self.rules
+    }
+
+    /// Add [Rule]s to the program.
+    pub fn add_rules<T>(&mut self, rules: T)
+    where
+        T: IntoIterator<Item = Rule>,
+    {
+        self.rules.extend(rules);
+    }
+
+    /// Return all type declarations of predicates in the program.
+    #[must_use]
+    pub fn declarations(&self) -> &Vec<PredicateDeclaration> {
+        &self.declarations
+    }
+
+    /// Return all integrity constraints of the program.
+    #[must_use]
+    pub fn constraints(&self) -> &Vec<IntegrityConstraint> {
+        &self.constraints
+    }
+
     /// Return all facts in the program.
     #[must_use]
     pub fn facts(&self) -> &Vec<Fact> {
         &self.facts
     }
 
+    /// Add [Fact]s to the program.
+    pub fn add_facts<T>(&mut self, facts: T)
+    where
+        T: IntoIterator<Item = Fact>,
+    {
+        self.facts.extend(facts);
+    }
+
     /// Return a HashSet of all predicates in the program (in rules and facts).
+    ///
+    /// The predicate of equality atoms is not included.
     #[must_use]
     pub fn predicates(&self) -> HashSet<Identifier> {
         self.rules()
@@ -180,6 +257,7 @@ impl Program {
             .flat_map(|rule| {
                 rule.head()
                     .iter()
+                    .filter(|atom| !atom.is_equality())
                     .map(|atom| atom.predicate())
                     .chain(rule.body().iter().map(|literal| literal.predicate()))
             })
@@ -188,11 +266,14 @@ impl Program {
     }
 
     /// Return a HashSet of all idb predicates (predicates occuring rule heads) in the program.
+    ///
+    /// The predicate of equality atoms is not included.
     #[must_use]
     pub fn idb_predicates(&self) -> HashSet<Identifier> {
         self.rules()
             .iter()
             .flat_map(|rule| rule.head())
+            .filter(|atom| !atom.is_equality())
             .map(|atom| atom.predicate())
             .collect()
     }
@@ -220,6 +301,18 @@ impl Program {
         self.output_predicates.extend(predicates);
     }
 
+    /// Add predicates that are only used during the execution to the output predicates,
+    /// if the program has output predicates or exports,
+    /// so that they are derived even if only the output predicates are computed.
+    pub(crate) fn add_internal_outputs<T>(&mut self, predicates: T)
+    where
+        T: IntoIterator<Item = Identifier>,
+    {
+        if !self.output_predicates.is_empty() || !self.exports.is_empty() {
+            self.output_predicates.extend(predicates);
+        }
+    }
+
     /// Remove all output predicates of the program.
     pub fn clear_output_predicates(&mut self) {
         self.output_predicates.clear();
@@ -2,10 +2,10 @@ use std::collections::{HashMap, HashSet};
 
 use crate::{io::parser::ParseError, model::VariableAssignment};
 
-use super::{Atom, Constraint, Literal, PrimitiveTerm, Term, Variable};
+use super::{Atom, Constraint, Literal, PrimitiveTerm, SourcePosition, Term, Variable};
 
 /// A rule.
-#[derive(Debug, Eq, PartialEq, Clone)]
+#[derive(Debug, Clone)]
 pub struct Rule {
     /// Head atoms of the rule
     head: Vec<Atom>,
@@ -13,8 +13,18 @@ pub struct Rule {
     body: Vec<Literal>,
     /// Constraints on the body of the rule
     constraints: Vec<Constraint>,
+    /// Position of the rule in the source text, if it was parsed
+    position: Option<SourcePosition>,
 }
 
+impl PartialEq for Rule {
+    fn eq(&self, other: &Self) -> bool {
+        self.head == other.head && self.body == other.body && self.constraints == other.constraints
+    }
+}
+
+impl Eq for Rule {}
+
 impl Rule {
     /// Construct a new rule.
     pub fn new(head: Vec<Atom>, body: Vec<Literal>, constraints: Vec<Constraint>) -> Self {
@@ -22,6 +32,7 @@ impl Rule {
             head,
             body,
             constraints,
+            position: None,
         }
     }
 
@@ -99,7 +110,7 @@ impl Rule {
     }
 
     /// Construct a new rule, validating constraints on variable usage.
-    pub(crate) fn new_validated(
+    pub fn new_validated(
         head: Vec<Atom>,
         body: Vec<Literal>,
         constraints: Vec<Constraint>,
@@ -209,8 +220,40 @@ impl Rule {
             }
         }
 
+        // Unnesting may only occur as the right-hand side of an assignment in the body
+        for constraint in &constraints {
+            let misplaced = match constraint.has_form_unnest() {
+                Some((_, term)) => term.contains_unnest(),
+                None => constraint.left().contains_unnest() || constraint.right().contains_unnest(),
+            };
+
+            if misplaced {
+                return Err(ParseError::MisplacedUnnest(constraint.to_string()));
+            }
+        }
+        for term in body
+            .iter()
+            .flat_map(|l| l.terms())
+            .chain(head.iter().flat_map(|a| a.terms()))
+        {
+            if term.contains_unnest() {
+                return Err(ParseError::MisplacedUnnest(term.to_string()));
+            }
+        }
+
         let mut is_existential = false;
 
+        // Equality atoms may only merge values that are bound by the body
+        for variable in head
+            .iter()
+            .filter(|atom| atom.is_equality())
+            .flat_map(|atom| atom.variables())
+        {
+            if variable.is_existential() {
+                return Err(ParseError::ExistentialInEquality(variable.clone()));
+            }
+        }
+
         // Head atoms may only use variables that are safe or derived
         for variable in head.iter().flat_map(|a| a.variables()) {
             if variable.is_existential() {
@@ -261,6 +304,7 @@ impl Rule {
             head,
             body,
             constraints,
+            position: None,
         })
     }
 
@@ -338,6 +382,16 @@ impl Rule {
             .for_each(|f| f.apply_assignment(assignment));
     }
 
+    /// Return the position of the rule in the source text, if known.
+    pub fn position(&self) -> Option<SourcePosition> {
+        self.position
+    }
+
+    /// Set the position of the rule in the source text.
+    pub fn set_position(&mut self, position: SourcePosition) {
+        self.position = Some(position);
+    }
+
     /// Return the number of negative body atoms contained in the rule.
     pub fn num_negative_body(&self) -> usize {
         self.body
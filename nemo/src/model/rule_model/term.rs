@@ -4,7 +4,7 @@ use nemo_physical::datavalues::AnyDataValue;
 
 use crate::{error::Error, model::VariableAssignment};
 
-use super::{Aggregate, Identifier};
+use super::{Aggregate, Identifier, RegisteredFunction};
 
 /// Variable that can be bound to a specific value.
 /// Variables are identified by a string name or (in the case of
@@ -141,6 +141,16 @@ pub enum BinaryOperation {
     StringStarts,
     /// Whether string ends with a certain string
     StringEnds,
+    /// Element of a tuple at some position
+    TupleElement,
+    /// Value of a map for some key
+    MapElement,
+    /// Literal with a given lexical value and datatype, corresponding to SPARQL function STRDT
+    RdfTypedLiteral,
+    /// String with a given language tag, corresponding to SPARQL function STRLANG
+    RdfLanguageString,
+    /// Whether a language tag matches a language range, corresponding to SPARQL function LANGMATCHES
+    RdfLanguageMatches,
 }
 
 impl BinaryOperation {
@@ -158,6 +168,11 @@ impl BinaryOperation {
             "STRBEFORE" => Self::StringBefore,
             "STRAFTER" => Self::StringAfter,
             "REM" => Self::NumericRemainder,
+            "NTH" => Self::TupleElement,
+            "MAP_GET" => Self::MapElement,
+            "STRDT" => Self::RdfTypedLiteral,
+            "STRLANG" => Self::RdfLanguageString,
+            "LANGMATCHES" => Self::RdfLanguageMatches,
             _ => return None,
         })
     }
@@ -185,6 +200,11 @@ impl BinaryOperation {
             Self::StringAfter => "STRAFTER",
             Self::StringStarts => "STRSTARTS",
             Self::StringEnds => "STRENDS",
+            Self::TupleElement => "NTH",
+            Self::MapElement => "MAP_GET",
+            Self::RdfTypedLiteral => "STRDT",
+            Self::RdfLanguageString => "STRLANG",
+            Self::RdfLanguageMatches => "LANGMATCHES",
         };
 
         String::from(name)
@@ -213,7 +233,12 @@ impl BinaryOperation {
             | Self::StringStarts
             | Self::StringEnds
             | Self::StringBefore
-            | Self::StringAfter => None,
+            | Self::StringAfter
+            | Self::TupleElement
+            | Self::MapElement
+            | Self::RdfTypedLiteral
+            | Self::RdfLanguageString
+            | Self::RdfLanguageMatches => None,
         }
     }
 }
@@ -270,6 +295,16 @@ pub enum NaryOperation {
     NumericLukasiewicz,
     /// Concatentation of two string values, correspondng to SPARQL function CONCAT.
     StringConcatenation,
+    /// Matching of a regular expression, corresponding to SPARQL function REGEX.
+    StringRegex,
+    /// Replacement of the matches of a regular expression, corresponding to SPARQL function REPLACE.
+    StringReplace,
+    /// Extraction of a capture group of a regular expression.
+    StringRegexGroup,
+    /// Construction of a tuple
+    TupleConstruction,
+    /// Construction of a map from alternating keys and values
+    MapConstruction,
 }
 
 impl NaryOperation {
@@ -288,6 +323,11 @@ impl NaryOperation {
             "AND" => Self::BooleanConjunction,
             "OR" => Self::BooleanDisjunction,
             "CONCAT" => Self::StringConcatenation,
+            "REGEX" => Self::StringRegex,
+            "REPLACE" => Self::StringReplace,
+            "REGEXGROUP" => Self::StringRegexGroup,
+            "TUPLE" => Self::TupleConstruction,
+            "MAP" => Self::MapConstruction,
             _ => return None,
         })
     }
@@ -296,6 +336,11 @@ impl NaryOperation {
     pub fn name(&self) -> String {
         let name = match self {
             Self::StringConcatenation => "CONCAT",
+            Self::StringRegex => "REGEX",
+            Self::StringReplace => "REPLACE",
+            Self::StringRegexGroup => "REGEXGROUP",
+            Self::TupleConstruction => "TUPLE",
+            Self::MapConstruction => "MAP",
             Self::BooleanConjunction => "AND",
             Self::BooleanDisjunction => "OR",
             Self::BitAnd => "BITAND",
@@ -339,6 +384,8 @@ pub enum UnaryOperation {
     CheckIsNull,
     /// Check if value is a string
     CheckIsString,
+    /// Check if value is a literal
+    CheckIsLiteral,
     /// Get datatype of a value
     Datatype,
     /// Get language tag of a languaged tagged string
@@ -371,6 +418,25 @@ pub enum UnaryOperation {
     StringLowercase,
     /// String converted to uppercase letters
     StringUppercase,
+    /// Percent-encoding of a string, corresponding to SPARQL function ENCODE_FOR_URI
+    StringEncodeForUri,
+    /// MD5 hash of a string
+    HashMd5,
+    /// SHA-1 hash of a string
+    HashSha1,
+    /// SHA-256 hash of a string
+    HashSha256,
+    /// Iri with the given content, corresponding to SPARQL function IRI
+    RdfIri,
+    /// Null determined by a string, corresponding to SPARQL function BNODE
+    RdfBlankNode,
+    /// Number of elements of a tuple or map
+    CollectionLength,
+    /// Tuple of the keys of a map
+    MapKeys,
+    /// Elements of a tuple, one per result,
+    /// which may only occur as the right-hand side of an assignment in the body of a rule
+    Unnest,
 }
 
 impl UnaryOperation {
@@ -383,8 +449,9 @@ impl UnaryOperation {
             "isDouble" => Ok(UnaryOperation::CheckIsDouble),
             "isIri" => Ok(UnaryOperation::CheckIsIri),
             "isNumeric" => Ok(UnaryOperation::CheckIsNumeric),
-            "isNull" => Ok(UnaryOperation::CheckIsNull),
+            "isNull" | "isBlank" => Ok(UnaryOperation::CheckIsNull),
             "isString" => Ok(UnaryOperation::CheckIsString),
+            "isLiteral" => Ok(UnaryOperation::CheckIsLiteral),
             "ABS" => Ok(UnaryOperation::NumericAbsolute),
             "SQRT" => Ok(UnaryOperation::NumericSquareroot),
             "NOT" => Ok(UnaryOperation::BooleanNegation),
@@ -405,6 +472,15 @@ impl UnaryOperation {
             "INT" => Ok(UnaryOperation::CastToInteger),
             "DOUBLE" => Ok(UnaryOperation::CastToDouble),
             "FLOAT" => Ok(UnaryOperation::CastToFloat),
+            "LENGTH" => Ok(UnaryOperation::CollectionLength),
+            "KEYS" => Ok(UnaryOperation::MapKeys),
+            "UNNEST" => Ok(UnaryOperation::Unnest),
+            "ENCODE_FOR_URI" => Ok(UnaryOperation::StringEncodeForUri),
+            "MD5" => Ok(UnaryOperation::HashMd5),
+            "SHA1" => Ok(UnaryOperation::HashSha1),
+            "SHA256" => Ok(UnaryOperation::HashSha256),
+            "IRI" | "URI" => Ok(UnaryOperation::RdfIri),
+            "BNODE" => Ok(UnaryOperation::RdfBlankNode),
             s => Err(Error::UnknownUnaryOpertation {
                 operation: s.into(),
             }),
@@ -436,12 +512,22 @@ impl UnaryOperation {
             Self::CheckIsFloat => "isFloat",
             Self::CheckIsDouble => "isDouble",
             Self::CheckIsIri => "isIri",
-            Self::CheckIsNumeric => "IsNumeric",
+            Self::CheckIsNumeric => "isNumeric",
             Self::CheckIsNull => "isNull",
             Self::CheckIsString => "isString",
+            Self::CheckIsLiteral => "isLiteral",
             Self::Datatype => "DATATYPE",
             Self::LanguageTag => "LANG",
             Self::LexicalValue => "STR",
+            Self::CollectionLength => "LENGTH",
+            Self::MapKeys => "KEYS",
+            Self::Unnest => "UNNEST",
+            Self::StringEncodeForUri => "ENCODE_FOR_URI",
+            Self::HashMd5 => "MD5",
+            Self::HashSha1 => "SHA1",
+            Self::HashSha256 => "SHA256",
+            Self::RdfIri => "IRI",
+            Self::RdfBlankNode => "BNODE",
         };
 
         String::from(name)
@@ -486,6 +572,8 @@ pub enum Term {
     Aggregation(Aggregate),
     /// Abstract Function.
     Function(Identifier, Vec<Term>),
+    /// Application of a function that was registered by the application.
+    UserFunction(RegisteredFunction, Vec<Term>),
 }
 
 impl Term {
@@ -538,7 +626,7 @@ impl Term {
                 .flat_map(|p| p.primitive_terms())
                 .collect(),
             Term::Unary(_, inner) => inner.primitive_terms(),
-            Term::Function(_, subterms) => {
+            Term::Function(_, subterms) | Term::UserFunction(_, subterms) => {
                 subterms.iter().flat_map(|t| t.primitive_terms()).collect()
             }
             Term::Aggregation(aggregate) => aggregate
@@ -597,7 +685,7 @@ impl Term {
             }
             Term::Unary(_, inner) => inner.apply_assignment(assignment),
             Term::Aggregation(aggregate) => aggregate.apply_assignment(assignment),
-            Term::Function(_, subterms) => subterms
+            Term::Function(_, subterms) | Term::UserFunction(_, subterms) => subterms
                 .iter_mut()
                 .for_each(|t| t.apply_assignment(assignment)),
             Term::Ternary {
@@ -640,7 +728,9 @@ impl Term {
                 parameters,
             } => parameters.iter_mut().collect(),
             Term::Aggregation(_aggregate) => Vec::new(),
-            Term::Function(_, subterms) => subterms.iter_mut().collect(),
+            Term::Function(_, subterms) | Term::UserFunction(_, subterms) => {
+                subterms.iter_mut().collect()
+            }
         }
     }
 
@@ -707,9 +797,37 @@ impl Term {
 
                 result
             }
+            Term::UserFunction(_, parameters) => {
+                let mut result = Vec::<Aggregate>::new();
+                for subterm in parameters {
+                    result.extend(subterm.aggregates());
+                }
+                result
+            }
             Term::Function(_, _) => panic!("Function symbols not supported"),
         }
     }
+
+    /// Return whether this term contains an unnesting operation.
+    pub(crate) fn contains_unnest(&self) -> bool {
+        match self {
+            Term::Primitive(_) => false,
+            Term::Unary(operation, subterm) => {
+                *operation == UnaryOperation::Unnest || subterm.contains_unnest()
+            }
+            Term::Binary { lhs, rhs, .. } => lhs.contains_unnest() || rhs.contains_unnest(),
+            Term::Ternary {
+                first,
+                second,
+                third,
+                ..
+            } => first.contains_unnest() || second.contains_unnest() || third.contains_unnest(),
+            Term::Nary { parameters, .. }
+            | Term::Function(_, parameters)
+            | Term::UserFunction(_, parameters) => parameters.iter().any(Term::contains_unnest),
+            Term::Aggregation(aggregate) => aggregate.terms.iter().any(Term::contains_unnest),
+        }
+    }
 }
 
 impl From<PrimitiveTerm> for Term {
@@ -737,6 +855,10 @@ impl Term {
                 function.to_string(),
                 subterms.iter().map(|s| s.ascii_tree()).collect(),
             ),
+            Term::UserFunction(function, subterms) => ascii_tree::Tree::Node(
+                function.to_string(),
+                subterms.iter().map(|s| s.ascii_tree()).collect(),
+            ),
             Term::Ternary {
                 operation,
                 first,
@@ -783,6 +905,7 @@ impl Term {
             Term::Unary(_, _) => 5,
             Term::Aggregation(_) => 5,
             Term::Function(_, _) => 5,
+            Term::UserFunction(_, _) => 5,
         }
     }
 
@@ -879,6 +1002,12 @@ impl Display for Term {
                 write!(f, "{}({})", operation.name(), inner)
             }
             Term::Aggregation(aggregate) => write!(f, "{}", aggregate),
+            Term::UserFunction(function, subterms) => {
+                f.write_str(function.name())?;
+                f.write_str("(")?;
+                self.format_nary_operation(f, subterms, ", ")?;
+                f.write_str(")")
+            }
             Term::Function(function, subterms) => {
                 f.write_str(&function.to_string())?;
                 f.write_str("(")?;
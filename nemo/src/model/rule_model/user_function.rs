@@ -0,0 +1,355 @@
+//! Builtin functions that are defined by applications embedding Nemo.
+
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    fmt::{Debug, Display},
+    sync::Arc,
+};
+
+use nemo_physical::{
+    datavalues::{AnyDataValue, DataValue, ValueDomain},
+    function::external::ExternalFunction,
+};
+use thiserror::Error;
+
+use super::{BinaryOperation, NaryOperation, TernaryOperation, UnaryOperation, ValueType};
+
+/// Type signature of a [UserFunction]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionSignature {
+    /// Types of the parameters of the function
+    parameters: Vec<ValueType>,
+    /// Type of the results of the function
+    output: ValueType,
+}
+
+impl FunctionSignature {
+    /// Construct a new [FunctionSignature].
+    pub fn new(parameters: Vec<ValueType>, output: ValueType) -> Self {
+        Self { parameters, output }
+    }
+
+    /// Return the types of the parameters of the function.
+    pub fn parameters(&self) -> &[ValueType] {
+        &self.parameters
+    }
+
+    /// Return the type of the results of the function.
+    pub fn output(&self) -> ValueType {
+        self.output
+    }
+
+    /// Return the number of parameters of the function.
+    pub fn arity(&self) -> usize {
+        self.parameters.len()
+    }
+}
+
+/// A builtin function that is defined by an application embedding Nemo
+///
+/// The function is assumed to be deterministic,
+/// i.e., it returns the same result whenever it is called with the same parameters.
+pub trait UserFunction: Debug + Send + Sync {
+    /// Return the [FunctionSignature] of this function.
+    fn signature(&self) -> FunctionSignature;
+
+    /// Evaluate this function on the given parameters,
+    /// which are guaranteed to match the signature of the function.
+    ///
+    /// Returns `None` if the result of the function is undefined.
+    fn evaluate(&self, parameters: &[AnyDataValue]) -> Option<AnyDataValue>;
+}
+
+/// [UserFunction] that is implemented by a closure
+struct ClosureFunction<Function> {
+    /// Type signature of the function
+    signature: FunctionSignature,
+    /// Implementation of the function
+    function: Function,
+}
+
+impl<Function> Debug for ClosureFunction<Function> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClosureFunction")
+            .field("signature", &self.signature)
+            .finish()
+    }
+}
+
+impl<Function> UserFunction for ClosureFunction<Function>
+where
+    Function: Fn(&[AnyDataValue]) -> Option<AnyDataValue> + Send + Sync,
+{
+    fn signature(&self) -> FunctionSignature {
+        self.signature.clone()
+    }
+
+    fn evaluate(&self, parameters: &[AnyDataValue]) -> Option<AnyDataValue> {
+        (self.function)(parameters)
+    }
+}
+
+/// A [UserFunction] together with the name under which it was registered
+///
+/// Functions are compared by their names.
+#[derive(Clone)]
+pub struct RegisteredFunction {
+    /// Name of the function
+    name: String,
+    /// Type signature of the function
+    signature: FunctionSignature,
+    /// Implementation of the function
+    function: Arc<dyn UserFunction>,
+}
+
+impl RegisteredFunction {
+    /// Return the name of the function.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Return the [FunctionSignature] of the function.
+    pub fn signature(&self) -> &FunctionSignature {
+        &self.signature
+    }
+
+    /// Return the function as an [ExternalFunction] that can be evaluated by the physical layer.
+    pub(crate) fn external_function(&self) -> Arc<dyn ExternalFunction> {
+        Arc::new(self.clone())
+    }
+}
+
+impl Debug for RegisteredFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RegisteredFunction")
+            .field("name", &self.name)
+            .field("signature", &self.signature)
+            .finish()
+    }
+}
+
+impl Display for RegisteredFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+impl PartialEq for RegisteredFunction {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for RegisteredFunction {}
+
+impl PartialOrd for RegisteredFunction {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RegisteredFunction {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.name.cmp(&other.name)
+    }
+}
+
+impl ExternalFunction for RegisteredFunction {
+    fn evaluate(&self, parameters: &[AnyDataValue]) -> Option<AnyDataValue> {
+        let admitted = parameters.len() == self.signature.arity()
+            && parameters
+                .iter()
+                .zip(self.signature.parameters())
+                .all(|(parameter, value_type)| value_type.admits(parameter));
+        if !admitted {
+            return None;
+        }
+
+        let result = self.function.evaluate(parameters)?;
+
+        // Results must fit into the storage types that are reported by `output_domain`
+        let representable = match self.signature.output() {
+            ValueType::Int => result.to_i64().is_some(),
+            _ => true,
+        };
+
+        (representable && self.signature.output().admits(&result)).then_some(result)
+    }
+
+    fn output_domain(&self) -> Option<ValueDomain> {
+        match self.signature.output() {
+            ValueType::Any => None,
+            ValueType::String => Some(ValueDomain::PlainString),
+            ValueType::Int => Some(ValueDomain::Long),
+            ValueType::Double => Some(ValueDomain::Double),
+        }
+    }
+}
+
+/// Error that occurs when registering a [UserFunction]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum FunctionRegistrationError {
+    /// The name of the function is the name of a builtin function.
+    #[error(r#"function "{0}" cannot be registered, since it is a builtin function"#)]
+    Builtin(String),
+    /// A function with the same name is already registered.
+    #[error(r#"function "{0}" is already registered"#)]
+    Duplicate(String),
+}
+
+/// Collection of [UserFunction]s that may be used in programs
+/// in addition to the builtin functions
+#[derive(Debug, Clone, Default)]
+pub struct FunctionRegistry {
+    /// Registered functions by their names
+    functions: HashMap<String, RegisteredFunction>,
+}
+
+impl FunctionRegistry {
+    /// Construct a new, empty [FunctionRegistry].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the given [UserFunction] under the given name.
+    ///
+    /// Returns an error if the name is already used by a builtin or registered function.
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        function: impl UserFunction + 'static,
+    ) -> Result<(), FunctionRegistrationError> {
+        let name = name.into();
+
+        if is_builtin(&name) {
+            return Err(FunctionRegistrationError::Builtin(name));
+        }
+        if self.functions.contains_key(&name) {
+            return Err(FunctionRegistrationError::Duplicate(name));
+        }
+
+        let function = RegisteredFunction {
+            name: name.clone(),
+            signature: function.signature(),
+            function: Arc::new(function),
+        };
+        self.functions.insert(name, function);
+
+        Ok(())
+    }
+
+    /// Register a function with the given name and [FunctionSignature]
+    /// that is implemented by the given closure.
+    ///
+    /// Returns an error if the name is already used by a builtin or registered function.
+    pub fn register_fn<Function>(
+        &mut self,
+        name: impl Into<String>,
+        signature: FunctionSignature,
+        function: Function,
+    ) -> Result<(), FunctionRegistrationError>
+    where
+        Function: Fn(&[AnyDataValue]) -> Option<AnyDataValue> + Send + Sync + 'static,
+    {
+        self.register(
+            name,
+            ClosureFunction {
+                signature,
+                function,
+            },
+        )
+    }
+
+    /// Return the function that is registered under the given name, if any.
+    pub fn get(&self, name: &str) -> Option<&RegisteredFunction> {
+        self.functions.get(name)
+    }
+
+    /// Return an iterator over all registered functions.
+    pub fn functions(&self) -> impl Iterator<Item = &RegisteredFunction> {
+        self.functions.values()
+    }
+}
+
+/// Return whether the given name refers to a builtin function.
+fn is_builtin(name: &str) -> bool {
+    UnaryOperation::construct_from_name(name).is_ok()
+        || BinaryOperation::construct_from_name(name).is_some()
+        || TernaryOperation::construct_from_name(name).is_some()
+        || NaryOperation::construct_from_name(name).is_some()
+}
+
+#[cfg(test)]
+mod test {
+    use nemo_physical::{
+        datavalues::{AnyDataValue, DataValue},
+        function::external::ExternalFunction,
+    };
+
+    use super::{FunctionRegistrationError, FunctionRegistry, FunctionSignature, ValueType};
+
+    fn string_length(parameters: &[AnyDataValue]) -> Option<AnyDataValue> {
+        let length = parameters[0].to_plain_string()?.chars().count();
+        Some(AnyDataValue::new_integer_from_i64(length as i64))
+    }
+
+    #[test]
+    fn register_functions() {
+        let mut registry = FunctionRegistry::new();
+        let signature = FunctionSignature::new(vec![ValueType::String], ValueType::Int);
+
+        assert_eq!(
+            registry.register_fn("strlen", signature.clone(), string_length),
+            Ok(())
+        );
+        assert_eq!(
+            registry.register_fn("strlen", signature.clone(), string_length),
+            Err(FunctionRegistrationError::Duplicate("strlen".to_string()))
+        );
+        assert_eq!(
+            registry.register_fn("concat", signature, string_length),
+            Err(FunctionRegistrationError::Builtin("concat".to_string()))
+        );
+
+        let function = registry.get("strlen").unwrap();
+        assert_eq!(function.signature().arity(), 1);
+        assert!(registry.get("other").is_none());
+    }
+
+    #[test]
+    fn evaluate_checks_signature() {
+        let mut registry = FunctionRegistry::new();
+        registry
+            .register_fn(
+                "strlen",
+                FunctionSignature::new(vec![ValueType::String], ValueType::Int),
+                string_length,
+            )
+            .unwrap();
+        registry
+            .register_fn(
+                "wrong",
+                FunctionSignature::new(vec![ValueType::Any], ValueType::Double),
+                |parameters| Some(parameters[0].clone()),
+            )
+            .unwrap();
+
+        let strlen = registry.get("strlen").unwrap();
+        assert_eq!(
+            strlen.evaluate(&[AnyDataValue::new_plain_string("abc".to_string())]),
+            Some(AnyDataValue::new_integer_from_i64(3))
+        );
+        assert_eq!(
+            strlen.evaluate(&[AnyDataValue::new_integer_from_i64(3)]),
+            None
+        );
+        assert_eq!(strlen.evaluate(&[]), None);
+
+        let wrong = registry.get("wrong").unwrap();
+        assert_eq!(
+            wrong.evaluate(&[AnyDataValue::new_integer_from_i64(3)]),
+            None
+        );
+    }
+}
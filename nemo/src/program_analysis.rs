@@ -1,7 +1,19 @@
 //! Functionality for collecting useful information about a existential rule program befire its execution.
 
 /// Computes useful information of a program before its execution
-pub mod analysis;
+pub(crate) mod analysis;
+
+/// Rewriting of programs for demand-driven evaluation
+pub(crate) mod magic_sets;
+
+/// Maintenance of aggregates in recursive rules
+pub(crate) mod monotone_aggregates;
+
+/// Static checks for likely mistakes in a program
+pub mod lint;
+
+/// Checks of values against the declared types of predicates
+pub(crate) mod type_checking;
 
 /// Functionality for computing promising variable orders from a program
-pub mod variable_order;
+pub(crate) mod variable_order;
@@ -6,7 +6,7 @@ use crate::{
     error::Error,
     model::chase_model::{ChaseProgram, ChaseRule},
     model::{
-        chase_model::{ChaseAtom, PrimitiveAtom, VariableAtom},
+        chase_model::{ChaseAtom, LatticePosition, PrimitiveAtom, VariableAtom},
         Constraint, Identifier, PrimitiveTerm, Term, Variable,
     },
 };
@@ -28,6 +28,9 @@ pub struct RuleAnalysis {
     pub has_positive_constraints: bool,
     /// Whether the rule has at least one aggregate term in the head.
     pub has_aggregates: bool,
+    /// Whether the aggregate of the rule is maintained monotonically,
+    /// so that the rule may be part of a recursive computation.
+    pub is_monotone_aggregate: bool,
 
     /// Predicates appearing in the positive part of the body.
     pub positive_body_predicates: HashSet<Identifier>,
@@ -35,6 +38,9 @@ pub struct RuleAnalysis {
     pub negative_body_predicates: HashSet<Identifier>,
     /// Predicates appearing in the head.
     pub head_predicates: HashSet<Identifier>,
+    /// Head predicates that receive the results of aggregates in recursive rules,
+    /// together with their aggregated position.
+    pub lattice_head_positions: HashMap<Identifier, LatticePosition>,
 
     /// Variables occurring in the positive part of the body.
     pub positive_body_variables: HashSet<Variable>,
@@ -70,6 +76,29 @@ pub enum RuleAnalysisError {
     /// There is a predicate whose arity could not be determined  
     #[error("arity of predicate \"{predicate}\" could not be derived")]
     UnspecifiedPredicateArity { predicate: Identifier },
+    /// Unsupported feature: Negation or aggregation of a predicate whose values may be merged by equality rules
+    #[error(
+        "predicate \"{predicate}\" may contain values that are merged by equality rules and can therefore not be negated or aggregated"
+    )]
+    UnsupportedFeatureMergedValues { predicate: Identifier },
+    /// An aggregate is used in a recursive rule, but its value cannot be maintained monotonically
+    #[error("aggregate for predicate \"{predicate}\" cannot be computed recursively: {reason}")]
+    NonMonotoneAggregate {
+        predicate: Identifier,
+        reason: String,
+    },
+}
+
+impl RuleAnalysisError {
+    /// Return the predicate this error refers to.
+    pub fn predicate(&self) -> &Identifier {
+        match self {
+            RuleAnalysisError::UnsupportedFeaturePredicateOverloading { predicate, .. }
+            | RuleAnalysisError::UnspecifiedPredicateArity { predicate }
+            | RuleAnalysisError::UnsupportedFeatureMergedValues { predicate }
+            | RuleAnalysisError::NonMonotoneAggregate { predicate, .. } => predicate,
+        }
+    }
 }
 
 /// Return true if there is a predicate in the positive part of the rule that also appears in the head of the rule.
@@ -196,6 +225,7 @@ fn construct_existential_aux_rule(
 
 fn analyze_rule(
     rule: &ChaseRule,
+    lattice_positions: &HashMap<Identifier, LatticePosition>,
     promising_variable_orders: Vec<VariableOrder>,
     promising_column_orders: &[HashMap<Identifier, HashSet<ColumnOrder>>],
     rule_index: usize,
@@ -209,14 +239,29 @@ fn analyze_rule(
         (ChaseRule::default(), VariableOrder::new())
     };
 
+    let lattice_head_positions = rule
+        .head()
+        .iter()
+        .filter_map(|atom| {
+            lattice_positions
+                .get(&atom.predicate())
+                .map(|position| (atom.predicate(), *position))
+        })
+        .collect::<HashMap<_, _>>();
+    let is_monotone_aggregate = rule
+        .aggregate_head_index()
+        .is_some_and(|index| lattice_head_positions.contains_key(&rule.head()[index].predicate()));
+
     RuleAnalysis {
         is_existential: num_existential > 0,
         is_recursive: is_recursive(rule),
         has_positive_constraints: !rule.positive_constraints().is_empty(),
         has_aggregates: rule.aggregate().is_some(),
+        is_monotone_aggregate,
         positive_body_predicates: get_predicates(rule.positive_body()),
         negative_body_predicates: get_predicates(rule.negative_body()),
         head_predicates: get_predicates(rule.head()),
+        lattice_head_positions,
         positive_body_variables: get_variables(rule.positive_body()),
         negative_body_variables: get_variables(rule.negative_body()),
         head_variables: get_variables(rule.head()),
@@ -290,6 +335,16 @@ impl ChaseProgram {
             }
         }
 
+        // Predicates in type declarations
+        for declaration in self.declarations() {
+            add_arity(
+                declaration.predicate().clone(),
+                declaration.arity(),
+                &mut result,
+                &mut missing,
+            )?;
+        }
+
         // Predicates in import statements
         for (pred, handler) in self.imports() {
             if let Some(arity) = handler.predicate_arity() {
@@ -366,8 +421,33 @@ impl ChaseProgram {
     /// Check if the program contains rules with unsupported features.
     /// This is always performed as part of [ChaseProgram::analyze].
     fn check_for_unsupported_features(&self) -> Result<(), RuleAnalysisError> {
-        // Currently no interesting checks here. Uniqueness of arities is already checked in the analysis phase.
+        // Uniqueness of arities is already checked in the analysis phase.
         // In general, should we maybe just do all checks in the analysis?
+
+        // Merging values could invalidate facts that were derived through negation or aggregation
+        if self.has_equality_rules() {
+            let null_predicates = self.null_predicates();
+
+            for rule in self.rules() {
+                let aggregated_atoms = if rule.aggregate().is_some() {
+                    rule.positive_body().as_slice()
+                } else {
+                    &[]
+                };
+
+                if let Some(atom) = rule
+                    .negative_body()
+                    .iter()
+                    .chain(aggregated_atoms)
+                    .find(|atom| null_predicates.contains(&atom.predicate()))
+                {
+                    return Err(RuleAnalysisError::UnsupportedFeatureMergedValues {
+                        predicate: atom.predicate(),
+                    });
+                }
+            }
+        }
+
         Ok(())
     }
 
@@ -392,6 +472,7 @@ impl ChaseProgram {
             .map(|(idx, rule)| {
                 analyze_rule(
                     rule,
+                    self.lattice_positions(),
                     all_variable_orders[idx].clone(),
                     &all_column_orders,
                     idx,
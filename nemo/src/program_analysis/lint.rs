@@ -0,0 +1,457 @@
+//! Static checks that detect likely mistakes in a program without reasoning over it.
+
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+};
+
+use nemo_physical::datavalues::{AnyDataValue, DataValue};
+
+use crate::{
+    error::Error,
+    execution::{execution_engine::prepare_program, ExecutionParameters},
+    model::{Constraint, Identifier, PrimitiveTerm, Program, Rule, SourcePosition, Term, Variable},
+};
+
+/// Severity of a [Lint]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LintSeverity {
+    /// The program can be executed but likely contains a mistake
+    Warning,
+    /// The program cannot be executed
+    Error,
+}
+
+impl std::fmt::Display for LintSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LintSeverity::Warning => write!(f, "warning"),
+            LintSeverity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Kinds of problems that are detected by [check_program]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LintKind {
+    /// The program was rejected by the analysis that precedes its execution
+    InvalidProgram(String),
+    /// A predicate is used in a rule body but never derived, imported or given as a fact
+    UndefinedPredicate(Identifier),
+    /// An imported predicate is never used
+    UnusedImport(Identifier),
+    /// A predicate is exported or marked as output but never derived, imported or given as a fact
+    UnderivedOutput(Identifier),
+    /// A named variable occurs only once in a rule
+    SingletonVariable(Variable),
+    /// A constraint of a rule can never be satisfied
+    UnsatisfiableConstraint(Constraint),
+}
+
+impl LintKind {
+    /// Return the [LintSeverity] of this kind of lint.
+    pub fn severity(&self) -> LintSeverity {
+        match self {
+            LintKind::InvalidProgram(_) => LintSeverity::Error,
+            _ => LintSeverity::Warning,
+        }
+    }
+
+    /// Return a short name identifying this kind of lint.
+    pub fn code(&self) -> &'static str {
+        match self {
+            LintKind::InvalidProgram(_) => "invalid-program",
+            LintKind::UndefinedPredicate(_) => "undefined-predicate",
+            LintKind::UnusedImport(_) => "unused-import",
+            LintKind::UnderivedOutput(_) => "underived-output",
+            LintKind::SingletonVariable(_) => "singleton-variable",
+            LintKind::UnsatisfiableConstraint(_) => "unsatisfiable-constraint",
+        }
+    }
+}
+
+impl std::fmt::Display for LintKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LintKind::InvalidProgram(message) => write!(f, "{message}"),
+            LintKind::UndefinedPredicate(predicate) => write!(
+                f,
+                "predicate \"{predicate}\" is used but never derived, imported or given as a fact"
+            ),
+            LintKind::UnusedImport(predicate) => {
+                write!(f, "imported predicate \"{predicate}\" is never used")
+            }
+            LintKind::UnderivedOutput(predicate) => write!(
+                f,
+                "output predicate \"{predicate}\" is never derived, imported or given as a fact"
+            ),
+            LintKind::SingletonVariable(variable) => write!(
+                f,
+                "variable {variable} occurs only once in the rule (use _ if this is intended)"
+            ),
+            LintKind::UnsatisfiableConstraint(constraint) => {
+                write!(f, "constraint {constraint} can never be satisfied")
+            }
+        }
+    }
+}
+
+/// A problem found in a program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lint {
+    /// What kind of problem was found
+    pub kind: LintKind,
+    /// Position in the source text of the statement in which the problem was found, if known
+    pub position: Option<SourcePosition>,
+}
+
+impl Lint {
+    fn new(kind: LintKind, position: Option<SourcePosition>) -> Self {
+        Self { kind, position }
+    }
+
+    /// Return the [LintSeverity] of this lint.
+    pub fn severity(&self) -> LintSeverity {
+        self.kind.severity()
+    }
+}
+
+impl std::fmt::Display for Lint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}[{}]: {}",
+            self.severity(),
+            self.kind.code(),
+            self.kind
+        )
+    }
+}
+
+/// Check the given [Program] for problems without executing it.
+///
+/// This runs the same analysis and type checks that precede the execution of a program,
+/// reporting their failures as errors, followed by a number of lints
+/// that are reported as warnings.
+/// The result is ordered by the position of the lints in the source text.
+pub fn check_program(program: &Program) -> Vec<Lint> {
+    let mut lints = Vec::new();
+
+    if let Err(error) = prepare_program(program, &ExecutionParameters::default()) {
+        let position = error_position(program, &error);
+        lints.push(Lint::new(
+            LintKind::InvalidProgram(error.to_string()),
+            position,
+        ));
+    }
+
+    check_predicates(program, &mut lints);
+
+    for rule in program.rules() {
+        check_singleton_variables(rule, &mut lints);
+        check_constraints(rule, &mut lints);
+    }
+
+    lints.sort_by_key(|lint| lint.position);
+    lints
+}
+
+/// Return the position of the rule that an error of the program preparation refers to, if any.
+///
+/// Errors about a predicate are located at the first rule deriving it
+/// or, if there is none, at the first rule using it.
+fn error_position(program: &Program, error: &Error) -> Option<SourcePosition> {
+    let error = match error {
+        Error::RuleAnalysisError(error) => error,
+        Error::TypeError(error) => return error.position(),
+        _ => return None,
+    };
+    let predicate = error.predicate();
+
+    program
+        .rules()
+        .iter()
+        .find(|rule| {
+            rule.head()
+                .iter()
+                .any(|atom| atom.predicate() == *predicate)
+        })
+        .or_else(|| {
+            program.rules().iter().find(|rule| {
+                rule.body()
+                    .iter()
+                    .any(|literal| literal.predicate() == *predicate)
+            })
+        })
+        .and_then(Rule::position)
+}
+
+/// Check for predicates that are used without being defined and vice versa.
+fn check_predicates(program: &Program, lints: &mut Vec<Lint>) {
+    let imported = program
+        .imports()
+        .map(|import| import.predicate().clone())
+        .collect::<HashSet<_>>();
+    let mut defined = program.idb_predicates();
+    defined.extend(program.facts().iter().map(|fact| fact.0.predicate()));
+    defined.extend(imported.iter().cloned());
+
+    let mut used = HashSet::new();
+
+    for rule in program.rules() {
+        let mut reported = HashSet::new();
+
+        for literal in rule.body() {
+            let predicate = literal.predicate();
+
+            if !defined.contains(&predicate) && reported.insert(predicate.clone()) {
+                lints.push(Lint::new(
+                    LintKind::UndefinedPredicate(predicate.clone()),
+                    rule.position(),
+                ));
+            }
+
+            used.insert(predicate);
+        }
+    }
+
+    for export in program.exports() {
+        if !defined.contains(export.predicate()) {
+            lints.push(Lint::new(
+                LintKind::UnderivedOutput(export.predicate().clone()),
+                export.position(),
+            ));
+        }
+
+        used.insert(export.predicate().clone());
+    }
+
+    for output in program.output_predicates() {
+        if !defined.contains(output) {
+            lints.push(Lint::new(LintKind::UnderivedOutput(output.clone()), None));
+        }
+
+        used.insert(output.clone());
+    }
+
+    for import in program.imports() {
+        if !used.contains(import.predicate()) {
+            lints.push(Lint::new(
+                LintKind::UnusedImport(import.predicate().clone()),
+                import.position(),
+            ));
+        }
+    }
+}
+
+/// Check for named universal variables that occur only once in a rule.
+fn check_singleton_variables(rule: &Rule, lints: &mut Vec<Lint>) {
+    let terms = rule
+        .head()
+        .iter()
+        .flat_map(|atom| atom.terms())
+        .chain(rule.body().iter().flat_map(|literal| literal.terms()))
+        .chain(
+            rule.constraints()
+                .iter()
+                .flat_map(|constraint| [constraint.left(), constraint.right()]),
+        );
+
+    let mut occurrences = HashMap::<&Variable, usize>::new();
+    let mut order = Vec::new();
+    for variable in terms.flat_map(|term| term.variables()) {
+        let count = occurrences.entry(variable).or_default();
+        if *count == 0 {
+            order.push(variable);
+        }
+        *count += 1;
+    }
+
+    for variable in order {
+        if variable.is_universal() && occurrences[variable] == 1 {
+            lints.push(Lint::new(
+                LintKind::SingletonVariable(variable.clone()),
+                rule.position(),
+            ));
+        }
+    }
+}
+
+/// Check for constraints that can never be satisfied.
+fn check_constraints(rule: &Rule, lints: &mut Vec<Lint>) {
+    for constraint in rule.constraints() {
+        if is_unsatisfiable(constraint) {
+            lints.push(Lint::new(
+                LintKind::UnsatisfiableConstraint(constraint.clone()),
+                rule.position(),
+            ));
+        }
+    }
+}
+
+/// Compare two ground values, if they are both numeric.
+fn compare_numeric(left: &AnyDataValue, right: &AnyDataValue) -> Option<Ordering> {
+    if let (Some(left), Some(right)) = (left.to_i64(), right.to_i64()) {
+        return Some(left.cmp(&right));
+    }
+
+    let as_f64 = |value: &AnyDataValue| {
+        value
+            .to_f64()
+            .or_else(|| value.to_f32().map(f64::from))
+            .or_else(|| value.to_i64().map(|value| value as f64))
+    };
+
+    as_f64(left)?.partial_cmp(&as_f64(right)?)
+}
+
+/// Return `true` if the given [Constraint] is false for every assignment of its variables.
+///
+/// This only detects simple cases, i.e., constraints that compare a variable with itself
+/// and constraints that compare two constants.
+fn is_unsatisfiable(constraint: &Constraint) -> bool {
+    let (left, right) = constraint.terms();
+
+    match (left, right) {
+        (
+            Term::Primitive(PrimitiveTerm::Variable(left)),
+            Term::Primitive(PrimitiveTerm::Variable(right)),
+        ) if left == right && !left.is_unnamed() => matches!(
+            constraint,
+            Constraint::Unequals(_, _) | Constraint::LessThan(_, _) | Constraint::GreaterThan(_, _)
+        ),
+        (
+            Term::Primitive(PrimitiveTerm::GroundTerm(left)),
+            Term::Primitive(PrimitiveTerm::GroundTerm(right)),
+        ) => match constraint {
+            // Values of different types, such as integers and doubles, are never equal
+            Constraint::Equals(_, _) => left != right,
+            Constraint::Unequals(_, _) => left == right,
+            Constraint::LessThan(_, _) => compare_numeric(left, right).is_some_and(Ordering::is_ge),
+            Constraint::GreaterThan(_, _) => {
+                compare_numeric(left, right).is_some_and(Ordering::is_le)
+            }
+            Constraint::LessThanEq(_, _) => {
+                compare_numeric(left, right).is_some_and(Ordering::is_gt)
+            }
+            Constraint::GreaterThanEq(_, _) => {
+                compare_numeric(left, right).is_some_and(Ordering::is_lt)
+            }
+        },
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        io::parser::parse_program,
+        model::{Identifier, SourcePosition},
+    };
+
+    use super::{check_program, LintKind, LintSeverity};
+
+    fn lint_codes(program: &str) -> Vec<&'static str> {
+        let program = parse_program(program).unwrap();
+        check_program(&program)
+            .iter()
+            .map(|lint| lint.kind.code())
+            .collect()
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn clean_program() {
+        let program = r#"
+            @import e :- csv{resource="edges.csv"}.
+            path(?x, ?y) :- e(?x, ?y).
+            path(?x, ?z) :- path(?x, ?y), e(?y, ?z).
+            @export path :- csv{}.
+        "#;
+
+        assert!(lint_codes(program).is_empty());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn predicates() {
+        let program = r#"
+            @import e :- csv{resource="edges.csv", format=(any, any)}.
+            @import unused :- csv{resource="unused.csv", format=(any)}.
+            p(?x) :- q(?x), r(?x).
+            @output r.
+        "#;
+
+        let program = parse_program(program).unwrap();
+        let lints = check_program(&program);
+        let kinds = lints.iter().map(|lint| &lint.kind).collect::<Vec<_>>();
+
+        assert!(kinds.contains(&&LintKind::UndefinedPredicate(Identifier("q".to_string()))));
+        assert!(kinds.contains(&&LintKind::UnusedImport(Identifier("e".to_string()))));
+        assert!(kinds.contains(&&LintKind::UnusedImport(Identifier("unused".to_string()))));
+        assert!(kinds.contains(&&LintKind::UnderivedOutput(Identifier("r".to_string()))));
+        assert!(lints
+            .iter()
+            .all(|lint| lint.severity() == LintSeverity::Warning));
+
+        let undefined = lints
+            .iter()
+            .find(|lint| matches!(lint.kind, LintKind::UndefinedPredicate(_)))
+            .unwrap();
+        assert_eq!(undefined.position, Some(SourcePosition::new(4, 13)));
+
+        let unused = lints
+            .iter()
+            .find(|lint| lint.kind == LintKind::UnusedImport(Identifier("unused".to_string())))
+            .unwrap();
+        assert_eq!(unused.position, Some(SourcePosition::new(3, 13)));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn singleton_variables() {
+        assert_eq!(
+            lint_codes("a(1, 2). p(?x) :- a(?x, ?y)."),
+            vec!["singleton-variable"]
+        );
+        assert!(lint_codes("a(1, 2). p(?x) :- a(?x, _).").is_empty());
+        assert!(lint_codes("a(1, 2). p(?x, !z) :- a(?x, ?x).").is_empty());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn unsatisfiable_constraints() {
+        assert_eq!(
+            lint_codes("a(1). p(?x) :- a(?x), ?x < ?x."),
+            vec!["unsatisfiable-constraint"]
+        );
+        assert_eq!(
+            lint_codes("a(1). p(?x) :- a(?x), 2 < 1."),
+            vec!["unsatisfiable-constraint"]
+        );
+        assert_eq!(
+            lint_codes("a(1). p(?x) :- a(?x), 1 != 1."),
+            vec!["unsatisfiable-constraint"]
+        );
+        assert_eq!(
+            lint_codes("a(1). p(?x) :- a(?x), 1 = 1.0."),
+            vec!["unsatisfiable-constraint"]
+        );
+        assert!(lint_codes("a(1). p(?x) :- a(?x), ?x <= ?x, 1 < 2.").is_empty());
+        // Integers and doubles are distinct values
+        assert!(lint_codes("a(1). p(?x) :- a(?x), 1 != 1.0.").is_empty());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn invalid_program() {
+        let program = parse_program("a(1). a(1, 2). p(?x) :- a(?x).").unwrap();
+        let lints = check_program(&program);
+
+        assert_eq!(lints.len(), 1);
+        assert_eq!(lints[0].severity(), LintSeverity::Error);
+        assert!(matches!(lints[0].kind, LintKind::InvalidProgram(_)));
+        // The conflicting arities are reported at the rule using the predicate
+        assert_eq!(lints[0].position.map(|position| position.line), Some(1));
+    }
+}
@@ -0,0 +1,558 @@
+//! Demand-driven evaluation of programs using the magic sets transformation.
+//!
+//! The transformation restricts the computation of a [ChaseProgram] to the facts that are
+//! relevant for its output and export predicates. For this, every predicate is assigned
+//! an adornment, i.e., the set of its positions that are bound to known values whenever
+//! the predicate is used. Each rule for a predicate with bound positions is then guarded
+//! by a "magic" predicate that holds the values that are actually requested,
+//! and additional rules are introduced that compute these values.
+//!
+//! Output and export predicates are always computed completely.
+//! Predicates that are used in negated atoms, in the bodies of rules with aggregates, or
+//! in rules with existential variables are computed completely as well, together with all
+//! predicates they depend on. This ensures that the transformed program remains stratified
+//! and that the restricted chase creates the same nulls as for the original program.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    error::Error,
+    model::{
+        chase_model::{ChaseAtom, ChaseFact, ChaseProgram, ChaseRule, PrimitiveAtom, VariableAtom},
+        Constraint, Identifier, PrimitiveTerm, Term, Variable,
+    },
+};
+
+/// Prefix of the predicates that hold the requested values of a predicate
+const MAGIC_PREDICATE_PREFIX: &str = "MAGIC_SETS_DEMAND_FOR_";
+/// Prefix of variables introduced for guarding rules with magic predicates
+const MAGIC_VARIABLE_PREFIX: &str = "_MAGIC_";
+
+/// For each position of a predicate, whether it is bound to a requested value
+type Adornment = Vec<bool>;
+
+/// Return the predicate that holds the requested values for the given predicate.
+fn magic_predicate(predicate: &Identifier) -> Identifier {
+    Identifier(format!("{MAGIC_PREDICATE_PREFIX}{predicate}"))
+}
+
+/// Return the constant that a variable is equal to according to the given constraints, if any.
+fn assigned_constant<'a>(
+    variable: &Variable,
+    constraints: &'a [Constraint],
+) -> Option<&'a PrimitiveTerm> {
+    constraints.iter().find_map(|constraint| match constraint {
+        Constraint::Equals(Term::Primitive(PrimitiveTerm::Variable(left)), right)
+        | Constraint::Equals(right, Term::Primitive(PrimitiveTerm::Variable(left)))
+            if left == variable =>
+        {
+            match right {
+                Term::Primitive(constant @ PrimitiveTerm::GroundTerm(_)) => Some(constant),
+                _ => None,
+            }
+        }
+        _ => None,
+    })
+}
+
+/// Return the term that provides the value of `variable` given the `available` variables.
+///
+/// This is the variable itself if it is available, or a constant or another available variable
+/// that it is equal to according to `constraints`.
+fn binding(
+    variable: &Variable,
+    available: &HashSet<Variable>,
+    constraints: &[Constraint],
+) -> Option<PrimitiveTerm> {
+    if available.contains(variable) {
+        return Some(PrimitiveTerm::Variable(variable.clone()));
+    }
+
+    if let Some(constant) = assigned_constant(variable, constraints) {
+        return Some(constant.clone());
+    }
+
+    constraints.iter().find_map(|constraint| match constraint {
+        Constraint::Equals(
+            Term::Primitive(PrimitiveTerm::Variable(left)),
+            Term::Primitive(PrimitiveTerm::Variable(right)),
+        ) => {
+            if left == variable && available.contains(right) {
+                Some(PrimitiveTerm::Variable(right.clone()))
+            } else if right == variable && available.contains(left) {
+                Some(PrimitiveTerm::Variable(left.clone()))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    })
+}
+
+/// Atom that restricts a rule to the requested values of its head predicate,
+/// together with the constraints that connect it to the rest of the rule
+#[derive(Debug)]
+struct Guard {
+    /// Atom using the magic predicate of the head predicate
+    atom: VariableAtom,
+    /// Constraints on the variables of the atom
+    constraints: Vec<Constraint>,
+}
+
+impl Guard {
+    /// Compute the [Guard] for the given rule,
+    /// if the requested values of its head predicate can be used to restrict it.
+    ///
+    /// This requires that the rule has a single head atom without existential variables,
+    /// whose bound positions only contain constants or variables of the positive body.
+    fn for_rule(rule: &ChaseRule, adornments: &HashMap<Identifier, Adornment>) -> Option<Self> {
+        let [head] = rule.head().as_slice() else {
+            return None;
+        };
+        let adornment = adornments.get(&head.predicate())?;
+        if !adornment.contains(&true) {
+            return None;
+        }
+
+        let body_variables = rule
+            .positive_body()
+            .iter()
+            .flat_map(|atom| atom.get_variables())
+            .collect::<HashSet<_>>();
+
+        let mut variables = Vec::new();
+        let mut constraints = Vec::new();
+        for (term, _) in head
+            .terms()
+            .iter()
+            .zip(adornment)
+            .filter(|(_, &bound)| bound)
+        {
+            if let PrimitiveTerm::Variable(variable) = term {
+                if !body_variables.contains(variable) {
+                    return None;
+                }
+
+                if !variables.contains(variable) {
+                    variables.push(variable.clone());
+                    continue;
+                }
+            }
+
+            let fresh = Variable::Universal(format!("{MAGIC_VARIABLE_PREFIX}{}", variables.len()));
+            constraints.push(Constraint::Equals(
+                Term::Primitive(PrimitiveTerm::Variable(fresh.clone())),
+                Term::Primitive(term.clone()),
+            ));
+            variables.push(fresh);
+        }
+
+        Some(Self {
+            atom: VariableAtom::new(magic_predicate(&head.predicate()), variables),
+            constraints,
+        })
+    }
+
+    /// Return the variables of the head that are bound by the guard.
+    fn bound_variables(&self) -> HashSet<Variable> {
+        let mut result = self
+            .atom
+            .get_variables()
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        for constraint in &self.constraints {
+            result.extend(constraint.variables().cloned());
+        }
+
+        result
+    }
+}
+
+/// Computes the adornments of all predicates that are relevant for the output of a program.
+#[derive(Debug)]
+struct AdornmentComputation<'a> {
+    /// The program
+    program: &'a ChaseProgram,
+    /// Predicates that appear in rule heads
+    idb_predicates: HashSet<Identifier>,
+    /// Predicates that must be computed completely
+    complete: HashSet<Identifier>,
+    /// Current adornment of each relevant predicate that appears in a rule head
+    adornments: HashMap<Identifier, Adornment>,
+    /// Predicates whose adornment has changed since their rules were last considered
+    pending: Vec<Identifier>,
+}
+
+impl<'a> AdornmentComputation<'a> {
+    /// Create a new [AdornmentComputation] for the given program.
+    fn new(program: &'a ChaseProgram) -> Self {
+        Self {
+            program,
+            idb_predicates: program.idb_predicates(),
+            complete: Self::complete_predicates(program),
+            adornments: HashMap::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Return all predicates that must be computed completely,
+    /// independently of which of their values are requested.
+    fn complete_predicates(program: &ChaseProgram) -> HashSet<Identifier> {
+        let mut pending = Vec::<Identifier>::new();
+
+        for rule in program.rules() {
+            let is_existential = rule.head().iter().any(|atom| {
+                atom.terms()
+                    .iter()
+                    .any(|term| matches!(term, PrimitiveTerm::Variable(Variable::Existential(_))))
+            });
+
+            pending.extend(rule.negative_body().iter().map(|atom| atom.predicate()));
+
+            if rule.aggregate().is_some() || is_existential {
+                pending.extend(rule.positive_body().iter().map(|atom| atom.predicate()));
+            }
+
+            if is_existential {
+                pending.extend(rule.head().iter().map(|atom| atom.predicate()));
+            }
+        }
+
+        let mut result = HashSet::new();
+        while let Some(predicate) = pending.pop() {
+            if !result.insert(predicate.clone()) {
+                continue;
+            }
+
+            for rule in program.rules() {
+                if rule.head().iter().any(|atom| atom.predicate() == predicate) {
+                    pending.extend(rule.all_body().map(|atom| atom.predicate()));
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Record that the given predicate is used with the given adornment.
+    fn request(&mut self, predicate: Identifier, mut adornment: Adornment) {
+        if !self.idb_predicates.contains(&predicate) {
+            return;
+        }
+
+        if self.complete.contains(&predicate) {
+            adornment.iter_mut().for_each(|bound| *bound = false);
+        }
+
+        match self.adornments.get_mut(&predicate) {
+            Some(current) => {
+                let mut changed = false;
+                for (current_bound, bound) in current.iter_mut().zip(adornment) {
+                    if *current_bound && !bound {
+                        *current_bound = false;
+                        changed = true;
+                    }
+                }
+
+                if changed {
+                    self.pending.push(predicate);
+                }
+            }
+            None => {
+                self.adornments.insert(predicate.clone(), adornment);
+                self.pending.push(predicate);
+            }
+        }
+    }
+
+    /// Record the adornments of the body atoms of the given rule.
+    fn process_rule(&mut self, rule: &ChaseRule) {
+        let mut available = Guard::for_rule(rule, &self.adornments)
+            .map(|guard| guard.bound_variables())
+            .unwrap_or_default();
+
+        for atom in rule.positive_body() {
+            let adornment = atom
+                .terms()
+                .iter()
+                .map(|variable| {
+                    binding(variable, &available, rule.positive_constraints()).is_some()
+                })
+                .collect();
+            self.request(atom.predicate(), adornment);
+
+            available.extend(atom.get_variables());
+        }
+
+        for atom in rule.negative_body() {
+            self.request(atom.predicate(), vec![false; atom.arity()]);
+        }
+    }
+
+    /// Compute the adornments of all predicates that are relevant for the given output predicates.
+    fn compute(mut self, outputs: &HashSet<Identifier>) -> HashMap<Identifier, Adornment> {
+        for rule in self.program.rules() {
+            for atom in rule.head() {
+                if outputs.contains(&atom.predicate()) {
+                    self.request(atom.predicate(), vec![false; atom.arity()]);
+                }
+            }
+        }
+
+        while let Some(predicate) = self.pending.pop() {
+            for rule in self.program.rules() {
+                if rule.head().iter().any(|atom| atom.predicate() == predicate) {
+                    self.process_rule(rule);
+                }
+            }
+        }
+
+        self.adornments
+    }
+}
+
+/// Rewrite the given program such that only facts that are relevant
+/// for its output and export predicates are derived.
+///
+/// The output and export predicates of the resulting program contain the same facts
+/// as in the original program. Rules that are irrelevant for them are removed.
+/// If the program has neither output nor export predicates, it is returned unchanged.
+///
+/// Returns an error if the requested values of a predicate cannot be computed
+/// from the rules that use it.
+pub(crate) fn apply_magic_sets(mut program: ChaseProgram) -> Result<ChaseProgram, Error> {
+    let outputs = program
+        .output_predicates()
+        .chain(program.exports().map(|(predicate, _)| predicate))
+        .cloned()
+        .collect::<HashSet<_>>();
+    if outputs.is_empty() {
+        return Ok(program);
+    }
+
+    let adornments = AdornmentComputation::new(&program).compute(&outputs);
+
+    let mut rules = Vec::new();
+    let mut magic_facts = Vec::new();
+
+    for rule in program.rules() {
+        if !rule
+            .head()
+            .iter()
+            .any(|atom| adornments.contains_key(&atom.predicate()))
+        {
+            continue;
+        }
+
+        let guard = Guard::for_rule(rule, &adornments);
+        let mut available = guard
+            .as_ref()
+            .map(Guard::bound_variables)
+            .unwrap_or_default();
+        let mut magic_body = guard
+            .iter()
+            .map(|guard| guard.atom.clone())
+            .collect::<Vec<_>>();
+        let magic_constraints = guard
+            .iter()
+            .flat_map(|guard| guard.constraints.clone())
+            .collect::<Vec<_>>();
+
+        for atom in rule.positive_body() {
+            if let Some(adornment) = adornments
+                .get(&atom.predicate())
+                .filter(|adornment| adornment.contains(&true))
+            {
+                let terms = atom
+                    .terms()
+                    .iter()
+                    .zip(adornment)
+                    .filter(|(_, &bound)| bound)
+                    .map(|(variable, _)| binding(variable, &available, rule.positive_constraints()))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| Error::MagicSets(atom.predicate()))?;
+                let head = PrimitiveAtom::new(magic_predicate(&atom.predicate()), terms);
+
+                if magic_body.is_empty() {
+                    let constants = head
+                        .terms()
+                        .iter()
+                        .map(|term| match term {
+                            PrimitiveTerm::GroundTerm(constant) => Some(constant.clone()),
+                            PrimitiveTerm::Variable(_) => None,
+                        })
+                        .collect::<Option<Vec<_>>>()
+                        .ok_or_else(|| Error::MagicSets(atom.predicate()))?;
+                    magic_facts.push(ChaseFact::new(head.predicate(), constants));
+                } else {
+                    let constraints = rule
+                        .positive_constraints()
+                        .iter()
+                        .chain(&magic_constraints)
+                        .filter(|constraint| {
+                            constraint
+                                .variables()
+                                .all(|variable| available.contains(variable))
+                        })
+                        .cloned()
+                        .collect();
+                    rules.push(ChaseRule::positive_rule(
+                        vec![head],
+                        magic_body.clone(),
+                        constraints,
+                    ));
+                }
+            }
+
+            magic_body.push(atom.clone());
+            available.extend(atom.get_variables());
+        }
+
+        let mut rule = rule.clone();
+        if let Some(guard) = guard {
+            rule.positive_body_mut().insert(0, guard.atom);
+            rule.positive_constraints_mut().extend(guard.constraints);
+        }
+        rules.push(rule);
+    }
+
+    *program.rules_mut() = rules;
+    program.facts_mut().extend(magic_facts);
+
+    Ok(program)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use nemo_physical::datavalues::AnyDataValue;
+
+    use crate::{
+        execution::{DefaultExecutionEngine, ExecutionParameters},
+        io::{parser::parse_program, resource_providers::ResourceProviders, ImportManager},
+        model::{chase_model::ChaseProgram, Identifier, Program},
+    };
+
+    use super::apply_magic_sets;
+
+    /// Execute the given program, with or without magic sets.
+    fn execute(program: &Program, magic_sets: bool) -> DefaultExecutionEngine {
+        let parameters = ExecutionParameters {
+            magic_sets,
+            ..Default::default()
+        };
+        let mut engine = DefaultExecutionEngine::initialize_with_parameters(
+            program,
+            ImportManager::new(ResourceProviders::empty()),
+            parameters,
+        )
+        .unwrap();
+        engine.execute().unwrap();
+
+        engine
+    }
+
+    /// Compute the output predicates of the given program, with or without magic sets.
+    fn outputs(input: &str, magic_sets: bool) -> HashMap<Identifier, Vec<Vec<AnyDataValue>>> {
+        let program = parse_program(input).unwrap();
+        let mut engine = execute(&program, magic_sets);
+
+        program
+            .output_predicates()
+            .map(|predicate| {
+                let mut rows = engine
+                    .predicate_rows(predicate)
+                    .unwrap()
+                    .map(|rows| rows.collect::<Vec<_>>())
+                    .unwrap_or_default();
+                rows.sort_by_key(|row| format!("{row:?}"));
+
+                (predicate.clone(), rows)
+            })
+            .collect()
+    }
+
+    /// Check that the transformed program computes the same output as the original one.
+    fn assert_same_outputs(input: &str) {
+        assert_eq!(outputs(input, true), outputs(input, false));
+    }
+
+    /// Return the number of rules of the transformed program.
+    fn transformed_rule_count(input: &str) -> usize {
+        let program: ChaseProgram = parse_program(input).unwrap().try_into().unwrap();
+        apply_magic_sets(program).unwrap().rules().len()
+    }
+
+    const TRANSITIVE_CLOSURE: &str = r#"
+        edge(1, 2). edge(2, 3). edge(3, 4). edge(4, 2). edge(5, 6). edge(6, 7).
+        path(?x, ?y) :- edge(?x, ?y) .
+        path(?x, ?z) :- edge(?x, ?y), path(?y, ?z) .
+        fromThree(?y) :- path(3, ?y) .
+        @output fromThree .
+    "#;
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn magic_sets_transitive_closure() {
+        assert_same_outputs(TRANSITIVE_CLOSURE);
+
+        let outputs = outputs(TRANSITIVE_CLOSURE, true);
+        assert_eq!(outputs[&Identifier("fromThree".to_string())].len(), 3);
+
+        // Only paths starting in nodes reachable from 3 are derived
+        let program = parse_program(TRANSITIVE_CLOSURE).unwrap();
+        let path = Identifier("path".to_string());
+        assert_eq!(
+            execute(&program, false).count_facts_of_predicate(&path),
+            Some(15)
+        );
+        assert_eq!(
+            execute(&program, true).count_facts_of_predicate(&path),
+            Some(9)
+        );
+
+        // The demand of the recursive rule is propagated by one additional rule
+        assert_eq!(transformed_rule_count(TRANSITIVE_CLOSURE), 3 + 1);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn magic_sets_same_generation() {
+        assert_same_outputs(
+            r#"
+            parent(b, a). parent(c, a). parent(d, b). parent(e, c). parent(f, e). parent(g, x).
+            sg(?x, ?x) :- parent(?x, ?p) .
+            sg(?x, ?y) :- parent(?x, ?xp), sg(?xp, ?yp), parent(?y, ?yp) .
+            result(?y) :- sg(d, ?y) .
+            unused(?x, ?y) :- sg(?x, ?y), parent(?y, ?x) .
+            @output result .
+            "#,
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn magic_sets_unsupported_features() {
+        assert_same_outputs(
+            r#"
+            edge(1, 2). edge(2, 3). edge(3, 1). edge(4, 5). node(1). node(2). node(3). node(4). node(5).
+            path(?x, ?y) :- edge(?x, ?y) .
+            path(?x, ?z) :- path(?x, ?y), edge(?y, ?z) .
+            unreachable(?x, ?y) :- node(?x), node(?y), ~path(?x, ?y) .
+            fromFour(?y) :- unreachable(4, ?y) .
+            degree(?x, #count(?y)) :- path(?x, ?y) .
+            degreeOfOne(?d) :- degree(1, ?d) .
+            next(?x, ?y + 1) :- path(?x, ?y), ?y < 3 .
+            nextOfTwo(?y) :- next(2, ?y) .
+            named(?x, !n) :- node(?x) .
+            namedOne(?x) :- named(?x, ?n), path(1, ?x) .
+            @output fromFour .
+            @output degreeOfOne .
+            @output nextOfTwo .
+            @output namedOne .
+            "#,
+        );
+    }
+}
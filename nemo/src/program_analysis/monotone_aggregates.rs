@@ -0,0 +1,671 @@
+//! Aggregates in recursive rules.
+//!
+//! Aggregates are normally computed only after the predicates in the body of their rule
+//! have been computed completely, which rules out aggregates in recursive rules.
+//! The results of `#min`, `#max`, `#count`, and `#sum` over non-negative values, however,
+//! only ever improve when new facts are derived. Such aggregates may therefore be maintained
+//! during the computation of the recursive predicates, like the distances in a shortest path
+//! computation.
+//!
+//! Each predicate that receives the result of an aggregate in a recursive rule is a lattice predicate.
+//! Within its recursive cycle, the facts for a lattice predicate are stored in an auxiliary predicate,
+//! which only receives values for the aggregated position that improve on the values known so far.
+//! The lattice predicate itself is computed from the auxiliary predicate by a final aggregation.
+//!
+//! This yields the same results as a stratified computation if the aggregated values are only used
+//! in monotone ways within the recursive cycle, i.e., if better values never lead to fewer
+//! or worse derivations. For this, an aggregated value may only
+//! * be aggregated again in the same order, i.e., with `#min` for `#min` and with `#max` otherwise,
+//! * be combined with other values by addition, `SUM`, `MIN`, or `MAX`,
+//!   unless these are aggregated values of a different order,
+//! * be bounded from the side of the worse values, e.g., by `?distance < 10` for `#min`, or
+//! * occur in the aggregated position of a lattice predicate with the same order.
+//!
+//! Inputs of `#sum` need to be bounded from below by zero through a constraint in the rule.
+
+use std::collections::{HashMap, HashSet};
+
+use nemo_physical::{aggregates::operation::AggregateOperation, datavalues::DataValue};
+use petgraph::Directed;
+
+use crate::{
+    model::{
+        chase_model::{
+            ChaseAggregate, ChaseAtom, ChaseFact, ChaseProgram, ChaseRule, LatticeOrder,
+            LatticePosition, PrimitiveAtom, VariableAtom,
+        },
+        BinaryOperation, Constraint, Identifier, NaryOperation, PredicateDeclaration,
+        PrimitiveTerm, Term, Variable,
+    },
+    util::labeled_graph::LabeledGraph,
+};
+
+use super::analysis::RuleAnalysisError;
+
+/// Prefix of the auxiliary predicates that hold the values of lattice predicates during recursion
+const LATTICE_PREDICATE_PREFIX: &str = "RECURSIVE_AGGREGATE_FOR_";
+/// Prefix of variables introduced for the final aggregation of lattice predicates
+const LATTICE_VARIABLE_PREFIX: &str = "_LATTICE_";
+
+/// Return the auxiliary predicate that holds the values of the given lattice predicate
+/// during its recursive computation.
+fn lattice_predicate(predicate: &Identifier) -> Identifier {
+    Identifier(format!("{LATTICE_PREDICATE_PREFIX}{predicate}"))
+}
+
+/// Return the error for an aggregate of the given predicate that is not monotone.
+fn non_monotone(predicate: &Identifier, reason: String) -> RuleAnalysisError {
+    RuleAnalysisError::NonMonotoneAggregate {
+        predicate: predicate.clone(),
+        reason,
+    }
+}
+
+/// Return the index of the strongly connected component of the predicate dependency graph
+/// for each predicate occurring in a rule.
+fn dependency_components(program: &ChaseProgram) -> HashMap<Identifier, usize> {
+    let mut graph = LabeledGraph::<Identifier, (), Directed>::default();
+
+    for rule in program.rules() {
+        for head_atom in rule.head() {
+            graph.add_node(head_atom.predicate());
+
+            for body_atom in rule.all_body() {
+                graph.add_edge(body_atom.predicate(), head_atom.predicate(), ());
+            }
+        }
+    }
+
+    let graph = graph.graph();
+    petgraph::algo::tarjan_scc(graph)
+        .into_iter()
+        .enumerate()
+        .flat_map(|(component, nodes)| {
+            nodes
+                .into_iter()
+                .map(move |node| (graph[node].clone(), component))
+        })
+        .collect()
+}
+
+/// Return the order of the aggregated values the given term depends on, if any.
+///
+/// Returns an error if the value of the term does not change monotonically with these values.
+fn term_order(
+    term: &Term,
+    lattice_variables: &HashMap<Variable, LatticeOrder>,
+    predicate: &Identifier,
+) -> Result<Option<LatticeOrder>, RuleAnalysisError> {
+    let monotone_operands = match term {
+        Term::Primitive(PrimitiveTerm::Variable(variable)) => {
+            return Ok(lattice_variables.get(variable).copied())
+        }
+        Term::Binary {
+            operation: BinaryOperation::NumericAddition,
+            lhs,
+            rhs,
+        } => vec![lhs.as_ref(), rhs.as_ref()],
+        Term::Nary {
+            operation:
+                NaryOperation::NumericSum
+                | NaryOperation::NumericMinimum
+                | NaryOperation::NumericMaximum,
+            parameters,
+        } => parameters.iter().collect(),
+        _ => {
+            if term
+                .variables()
+                .any(|variable| lattice_variables.contains_key(variable))
+            {
+                return Err(non_monotone(
+                    predicate,
+                    format!("aggregated value is used in non-monotone term {term}"),
+                ));
+            }
+
+            return Ok(None);
+        }
+    };
+
+    let mut result = None;
+    for operand in monotone_operands {
+        match (result, term_order(operand, lattice_variables, predicate)?) {
+            (_, None) => {}
+            (None, order) => result = order,
+            (Some(current), Some(order)) if current == order => {}
+            _ => {
+                return Err(non_monotone(
+                    predicate,
+                    format!("aggregated values of different orders are combined in {term}"),
+                ))
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Check that a constraint on the body of a rule only bounds aggregated values
+/// from the side of the worse values.
+fn check_constraint(
+    constraint: &Constraint,
+    lattice_variables: &HashMap<Variable, LatticeOrder>,
+    predicate: &Identifier,
+) -> Result<(), RuleAnalysisError> {
+    let (left, right) = constraint.terms();
+    let left_order = term_order(left, lattice_variables, predicate)?;
+    let right_order = term_order(right, lattice_variables, predicate)?;
+
+    let is_monotone = matches!(
+        (constraint, left_order, right_order),
+        (_, None, None)
+            | (
+                Constraint::LessThan(_, _) | Constraint::LessThanEq(_, _),
+                Some(LatticeOrder::Decreasing),
+                None,
+            )
+            | (
+                Constraint::LessThan(_, _) | Constraint::LessThanEq(_, _),
+                None,
+                Some(LatticeOrder::Increasing),
+            )
+            | (
+                Constraint::GreaterThan(_, _) | Constraint::GreaterThanEq(_, _),
+                Some(LatticeOrder::Increasing),
+                None,
+            )
+            | (
+                Constraint::GreaterThan(_, _) | Constraint::GreaterThanEq(_, _),
+                None,
+                Some(LatticeOrder::Decreasing),
+            )
+    );
+
+    if is_monotone {
+        Ok(())
+    } else {
+        Err(non_monotone(
+            predicate,
+            format!("aggregated value is used in non-monotone constraint {constraint}"),
+        ))
+    }
+}
+
+/// Return `true` if the given constraints ensure that the value of `variable` is not negative.
+fn is_non_negative(variable: &Variable, constraints: &[Constraint]) -> bool {
+    constraints.iter().any(|constraint| {
+        let bound = match constraint {
+            Constraint::GreaterThan(Term::Primitive(PrimitiveTerm::Variable(bounded)), bound)
+            | Constraint::GreaterThanEq(Term::Primitive(PrimitiveTerm::Variable(bounded)), bound)
+            | Constraint::LessThan(bound, Term::Primitive(PrimitiveTerm::Variable(bounded)))
+            | Constraint::LessThanEq(bound, Term::Primitive(PrimitiveTerm::Variable(bounded)))
+                if bounded == variable =>
+            {
+                bound
+            }
+            _ => return false,
+        };
+
+        match bound {
+            Term::Primitive(PrimitiveTerm::GroundTerm(value)) => value
+                .to_i64()
+                .map(|value| value >= 0)
+                .or_else(|| value.to_f64().map(|value| value >= 0.0))
+                .unwrap_or(false),
+            _ => false,
+        }
+    })
+}
+
+/// Return the position of the aggregate in the head of a rule
+/// that derives a lattice predicate.
+fn aggregate_position(
+    rule: &ChaseRule,
+    aggregate: &ChaseAggregate,
+    predicate: &Identifier,
+) -> Result<LatticePosition, RuleAnalysisError> {
+    if rule.head().len() > 1 {
+        return Err(non_monotone(
+            predicate,
+            "rule has more than one head atom".to_string(),
+        ));
+    }
+
+    if !rule.aggregate_constructors().is_empty() || !rule.aggregate_constraints().is_empty() {
+        return Err(non_monotone(
+            predicate,
+            "result of the aggregate is used in a complex term".to_string(),
+        ));
+    }
+
+    let index = rule.head()[0]
+        .terms()
+        .iter()
+        .position(|term| *term == PrimitiveTerm::Variable(aggregate.output_variable.clone()))
+        .expect("aggregate output occurs in the head");
+
+    Ok(LatticePosition {
+        index,
+        order: LatticeOrder::from_operation(aggregate.aggregate_operation),
+    })
+}
+
+/// Check that the aggregated values of the given lattice predicates
+/// are only used in monotone ways in a rule.
+///
+/// The positive body atoms whose predicate is contained in `recursive`
+/// contain the aggregated values.
+fn check_rule(
+    rule: &ChaseRule,
+    lattice_positions: &HashMap<Identifier, LatticePosition>,
+    recursive: &HashSet<Identifier>,
+) -> Result<(), RuleAnalysisError> {
+    let predicate = rule.head()[0].predicate();
+
+    let mut lattice_variables = HashMap::<Variable, LatticeOrder>::new();
+    for atom in rule.positive_body() {
+        if !recursive.contains(&atom.predicate()) {
+            continue;
+        }
+
+        let position = lattice_positions[&atom.predicate()];
+        lattice_variables.insert(atom.terms()[position.index].clone(), position.order);
+    }
+
+    for variable in lattice_variables.keys() {
+        let occurrences = rule
+            .positive_body()
+            .iter()
+            .filter(|atom| atom.terms().contains(variable))
+            .count();
+        if occurrences > 1 {
+            return Err(non_monotone(
+                &predicate,
+                format!("aggregated value {variable} is used in a join"),
+            ));
+        }
+    }
+
+    for constructor in rule.positive_constructors() {
+        if let Some(order) = term_order(constructor.term(), &lattice_variables, &predicate)? {
+            lattice_variables.insert(constructor.variable().clone(), order);
+        }
+    }
+
+    for constraint in rule.positive_constraints() {
+        check_constraint(constraint, &lattice_variables, &predicate)?;
+    }
+
+    let negative_variables = rule
+        .negative_body()
+        .iter()
+        .flat_map(|atom| atom.terms())
+        .chain(
+            rule.negative_constraints()
+                .iter()
+                .flatten()
+                .flat_map(|constraint| {
+                    let (left, right) = constraint.terms();
+                    left.variables().chain(right.variables())
+                }),
+        );
+    for variable in negative_variables {
+        if lattice_variables.contains_key(variable) {
+            return Err(non_monotone(
+                &predicate,
+                format!("aggregated value {variable} is used in a negated atom"),
+            ));
+        }
+    }
+
+    if let Some(aggregate) = rule.aggregate() {
+        let order = LatticeOrder::from_operation(aggregate.aggregate_operation);
+
+        if let Some(input_order) = lattice_variables.get(&aggregate.input_variable) {
+            let is_monotone = *input_order == order
+                && matches!(
+                    aggregate.aggregate_operation,
+                    AggregateOperation::Min | AggregateOperation::Max
+                );
+            if !is_monotone {
+                return Err(non_monotone(
+                    &predicate,
+                    format!(
+                        "aggregated value {} is aggregated in a different order",
+                        aggregate.input_variable
+                    ),
+                ));
+            }
+        }
+
+        if let Some(variable) = aggregate
+            .distinct_variables
+            .iter()
+            .find(|variable| lattice_variables.contains_key(variable))
+        {
+            return Err(non_monotone(
+                &predicate,
+                format!("aggregated value {variable} is used as a distinct variable"),
+            ));
+        }
+
+        if aggregate.aggregate_operation == AggregateOperation::Sum
+            && !is_non_negative(&aggregate.input_variable, rule.positive_constraints())
+        {
+            return Err(non_monotone(
+                &predicate,
+                format!(
+                    "summed variable {} is not restricted to non-negative values",
+                    aggregate.input_variable
+                ),
+            ));
+        }
+
+        lattice_variables.insert(aggregate.output_variable.clone(), order);
+    }
+
+    for atom in rule.head() {
+        let position = lattice_positions.get(&atom.predicate());
+
+        for (index, term) in atom.terms().iter().enumerate() {
+            let PrimitiveTerm::Variable(variable) = term else {
+                continue;
+            };
+            let Some(order) = lattice_variables.get(variable) else {
+                continue;
+            };
+
+            match position {
+                Some(position) if position.index == index && position.order == *order => {}
+                _ => {
+                    return Err(non_monotone(
+                        &predicate,
+                        format!(
+                        "aggregated value {variable} is used in position {} of predicate \"{}\"",
+                        index + 1,
+                        atom.predicate()
+                    ),
+                    ))
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Rewrite the given program such that aggregates in recursive rules are maintained
+/// during the computation of the recursive predicates.
+///
+/// Returns an error if such an aggregate is not known to be monotone.
+/// Programs without aggregates in recursive rules are returned unchanged.
+pub(crate) fn rewrite_recursive_aggregates(
+    mut program: ChaseProgram,
+) -> Result<ChaseProgram, RuleAnalysisError> {
+    let components = dependency_components(&program);
+
+    let mut lattice_predicates = HashSet::<Identifier>::new();
+    for rule in program.rules() {
+        let Some(head_index) = rule.aggregate_head_index() else {
+            continue;
+        };
+        let predicate = rule.head()[head_index].predicate();
+
+        if rule
+            .positive_body()
+            .iter()
+            .any(|atom| components[&atom.predicate()] == components[&predicate])
+        {
+            lattice_predicates.insert(predicate);
+        }
+    }
+
+    if lattice_predicates.is_empty() {
+        return Ok(program);
+    }
+
+    let mut lattice_positions = HashMap::<Identifier, LatticePosition>::new();
+    for rule in program.rules() {
+        let (Some(aggregate), Some(head_index)) = (rule.aggregate(), rule.aggregate_head_index())
+        else {
+            continue;
+        };
+        let predicate = rule.head()[head_index].predicate();
+        if !lattice_predicates.contains(&predicate) {
+            continue;
+        }
+
+        let position = aggregate_position(rule, aggregate, &predicate)?;
+        match lattice_positions.get(&predicate) {
+            Some(known) if *known != position => {
+                return Err(non_monotone(
+                    &predicate,
+                    "aggregates of different positions or orders are used".to_string(),
+                ));
+            }
+            _ => {
+                lattice_positions.insert(predicate, position);
+            }
+        }
+    }
+
+    for rule in program.rules_mut() {
+        let head_components = rule
+            .head()
+            .iter()
+            .map(|atom| components[&atom.predicate()])
+            .collect::<HashSet<_>>();
+        let recursive = lattice_predicates
+            .iter()
+            .filter(|predicate| head_components.contains(&components[predicate]))
+            .cloned()
+            .collect::<HashSet<_>>();
+
+        if rule
+            .head()
+            .iter()
+            .any(|atom| lattice_predicates.contains(&atom.predicate()))
+            && rule.head().iter().any(|atom| {
+                atom.terms()
+                    .iter()
+                    .any(|term| matches!(term, PrimitiveTerm::Variable(variable) if variable.is_existential()))
+            })
+        {
+            return Err(non_monotone(
+                &rule.head()[0].predicate(),
+                "rule has existential variables".to_string(),
+            ));
+        }
+
+        if recursive.is_empty() {
+            continue;
+        }
+
+        check_rule(rule, &lattice_positions, &recursive)?;
+
+        for atom in rule.head_mut() {
+            if lattice_predicates.contains(&atom.predicate()) {
+                *atom =
+                    PrimitiveAtom::new(lattice_predicate(&atom.predicate()), atom.terms().clone());
+            }
+        }
+        for atom in rule.positive_body_mut() {
+            if recursive.contains(&atom.predicate()) {
+                *atom =
+                    VariableAtom::new(lattice_predicate(&atom.predicate()), atom.terms().clone());
+            }
+        }
+    }
+
+    for fact in program.facts_mut() {
+        if lattice_predicates.contains(&fact.predicate()) {
+            let predicate = lattice_predicate(&fact.predicate());
+            *fact = ChaseFact::new(predicate, fact.terms().clone());
+        }
+    }
+
+    for (predicate, _) in program.imports_mut() {
+        if lattice_predicates.contains(predicate) {
+            *predicate = lattice_predicate(predicate);
+        }
+    }
+
+    let lattice_declarations = program
+        .declarations()
+        .iter()
+        .filter(|declaration| lattice_predicates.contains(declaration.predicate()))
+        .map(|declaration| {
+            PredicateDeclaration::new(
+                lattice_predicate(declaration.predicate()),
+                declaration.types().to_vec(),
+            )
+        })
+        .collect::<Vec<_>>();
+    program.declarations_mut().extend(lattice_declarations);
+
+    let mut lattice_positions = lattice_positions.into_iter().collect::<Vec<_>>();
+    lattice_positions.sort_by(|(left, _), (right, _)| left.cmp(right));
+
+    for (predicate, position) in lattice_positions {
+        let arity = program
+            .rules()
+            .iter()
+            .flat_map(|rule| rule.head())
+            .find(|atom| atom.predicate() == lattice_predicate(&predicate))
+            .map(|atom| atom.arity())
+            .expect("lattice predicates occur in the head of a rule");
+
+        let variables = (0..arity)
+            .map(|index| Variable::Universal(format!("{LATTICE_VARIABLE_PREFIX}{index}")))
+            .collect::<Vec<_>>();
+        let output_variable = Variable::Universal(format!("{LATTICE_VARIABLE_PREFIX}{arity}"));
+
+        let aggregate = ChaseAggregate {
+            aggregate_operation: match position.order {
+                LatticeOrder::Decreasing => AggregateOperation::Min,
+                LatticeOrder::Increasing => AggregateOperation::Max,
+            },
+            input_variable: variables[position.index].clone(),
+            distinct_variables: vec![],
+            group_by_variables: variables
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| *index != position.index)
+                .map(|(_, variable)| variable.clone())
+                .collect(),
+            output_variable: output_variable.clone(),
+        };
+
+        let mut head_terms = variables
+            .iter()
+            .cloned()
+            .map(PrimitiveTerm::Variable)
+            .collect::<Vec<_>>();
+        head_terms[position.index] = PrimitiveTerm::Variable(output_variable);
+
+        let head = PrimitiveAtom::new(predicate.clone(), head_terms);
+        let body = VariableAtom::new(lattice_predicate(&predicate), variables);
+        program
+            .rules_mut()
+            .push(ChaseRule::aggregate_rule(head, vec![body], aggregate));
+
+        program
+            .lattice_positions_mut()
+            .insert(lattice_predicate(&predicate), position);
+    }
+
+    Ok(program)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        io::parser::parse_program,
+        model::{
+            chase_model::{ChaseAtom, ChaseProgram, LatticeOrder, LatticePosition},
+            Identifier,
+        },
+        program_analysis::analysis::RuleAnalysisError,
+    };
+
+    use super::rewrite_recursive_aggregates;
+
+    fn rewrite(input: &str) -> Result<ChaseProgram, RuleAnalysisError> {
+        rewrite_recursive_aggregates(parse_program(input).unwrap().try_into().unwrap())
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn recursive_aggregates_rewrite() {
+        let program = rewrite(
+            r#"
+            edge(1, 2, 1) .
+            distance(1, 0) .
+            distance(?y, #min(?d + ?w)) :- distance(?x, ?d), edge(?x, ?y, ?w) .
+            count(#count(?x)) :- distance(?x, ?d) .
+            "#,
+        )
+        .unwrap();
+
+        let lattice = Identifier("RECURSIVE_AGGREGATE_FOR_distance".to_string());
+        assert_eq!(
+            program.lattice_positions().get(&lattice),
+            Some(&LatticePosition {
+                index: 1,
+                order: LatticeOrder::Decreasing
+            })
+        );
+        assert_eq!(program.lattice_positions().len(), 1);
+        assert_eq!(program.rules().len(), 3);
+        assert!(program
+            .facts()
+            .iter()
+            .any(|fact| fact.predicate() == lattice));
+
+        // Aggregates outside of recursive cycles are not changed
+        let program = rewrite(
+            r#"
+            edge(1, 2) .
+            count(?x, #count(?y)) :- edge(?x, ?y) .
+            "#,
+        )
+        .unwrap();
+        assert!(program.lattice_positions().is_empty());
+        assert_eq!(program.rules().len(), 1);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn non_monotone_recursive_aggregates() {
+        let programs = [
+            // Counting aggregated values
+            "distance(?y, #count(?d)) :- distance(?x, ?d), edge(?x, ?y) .",
+            // Bounding aggregated values from the wrong side
+            "distance(?y, #min(?d + 1)) :- distance(?x, ?d), edge(?x, ?y), ?d > 3 .",
+            // Subtracting aggregated values
+            "distance(?y, #max(?d - 1)) :- distance(?x, ?d), edge(?x, ?y) .",
+            // Summing values that may be negative
+            "total(?x, #sum(?w)) :- total(?y, ?v), edge(?x, ?y, ?w) .",
+            // Using aggregated values outside of the aggregated position
+            r#"copy(?y, ?d) :- distance(?y, ?d) .
+            distance(?y, #min(?d)) :- copy(?x, ?d), edge(?x, ?y) ."#,
+            // Joining on aggregated values
+            "distance(?y, #min(?d)) :- distance(?x, ?d), edge(?x, ?y), limit(?d) .",
+            // Negating aggregated values
+            "distance(?y, #min(?d)) :- distance(?x, ?d), edge(?x, ?y), ~blocked(?d) .",
+            // Mixing aggregates of different orders
+            r#"distance(?y, #min(?d)) :- distance(?x, ?d), edge(?x, ?y) .
+            distance(?y, #max(?d)) :- distance(?x, ?d), edge(?y, ?x) ."#,
+        ];
+
+        for program in programs {
+            assert!(
+                matches!(
+                    rewrite(program),
+                    Err(RuleAnalysisError::NonMonotoneAggregate { .. })
+                ),
+                "{program}"
+            );
+        }
+    }
+}
@@ -0,0 +1,427 @@
+//! Checks of the values occurring in a program against the declared types of its predicates.
+
+use std::collections::{hash_map::Entry, HashMap};
+
+use nemo_physical::{datasources::value_validator::ValueValidator, datavalues::AnyDataValue};
+use thiserror::Error;
+
+use crate::model::{
+    Atom, Identifier, PredicateDeclaration, PrimitiveTerm, Program, SourcePosition, Term,
+    ValueType, Variable,
+};
+
+/// Element of a program in which a [TypeError] occurs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeErrorLocation {
+    /// A type declaration
+    Declaration(Option<SourcePosition>),
+    /// A fact, given in its textual representation
+    Fact(String),
+    /// A rule
+    Rule {
+        /// The rule as text, or its index in the program
+        rule: String,
+        /// Position of the rule in the source text, if known
+        position: Option<SourcePosition>,
+    },
+    /// An import directive
+    Import {
+        /// The imported predicate
+        predicate: Identifier,
+        /// Position of the directive in the source text, if known
+        position: Option<SourcePosition>,
+    },
+}
+
+impl TypeErrorLocation {
+    /// Return the position of the element in the source text, if known.
+    pub fn position(&self) -> Option<SourcePosition> {
+        match self {
+            TypeErrorLocation::Declaration(position)
+            | TypeErrorLocation::Rule { position, .. }
+            | TypeErrorLocation::Import { position, .. } => *position,
+            TypeErrorLocation::Fact(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for TypeErrorLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TypeErrorLocation::Declaration(Some(position)) => {
+                write!(f, "declaration at {position}")
+            }
+            TypeErrorLocation::Declaration(None) => f.write_str("declaration"),
+            TypeErrorLocation::Fact(fact) => write!(f, "fact {fact}"),
+            TypeErrorLocation::Rule {
+                position: Some(position),
+                ..
+            } => write!(f, "rule at {position}"),
+            TypeErrorLocation::Rule { rule, .. } => write!(f, "rule {rule}"),
+            TypeErrorLocation::Import {
+                predicate,
+                position: Some(position),
+            } => write!(f, "import of \"{predicate}\" at {position}"),
+            TypeErrorLocation::Import { predicate, .. } => write!(f, "import of \"{predicate}\""),
+        }
+    }
+}
+
+/// Errors caused by values that do not match the declared type of a predicate
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TypeError {
+    /// A predicate is declared more than once with different types
+    #[error("{location}: predicate \"{predicate}\" was already declared with different types")]
+    ConflictingDeclarations {
+        /// The declared predicate
+        predicate: Identifier,
+        /// The conflicting declaration
+        location: TypeErrorLocation,
+    },
+    /// A predicate is used with an arity different from its declaration
+    #[error("{location}: predicate \"{predicate}\" is declared with arity {declared}, but used with arity {used}")]
+    ArityMismatch {
+        /// The declared predicate
+        predicate: Identifier,
+        /// The declared arity
+        declared: usize,
+        /// The arity of the use
+        used: usize,
+        /// Where the predicate is used
+        location: TypeErrorLocation,
+    },
+    /// A value does not belong to the declared type of its position
+    #[error("{location}: value {value} in position {position} of predicate \"{predicate}\" is not of declared type {expected}")]
+    ValueTypeMismatch {
+        /// The declared predicate
+        predicate: Identifier,
+        /// The position of the value, starting at 1
+        position: usize,
+        /// The offending value
+        value: String,
+        /// The declared type of the position
+        expected: ValueType,
+        /// Where the value occurs
+        location: TypeErrorLocation,
+    },
+    /// A variable is bound to values of one type but used in a position of a different type
+    #[error("{location}: variable {variable} in position {position} of predicate \"{predicate}\" is bound to values of type {found}, but declared type is {expected}")]
+    VariableTypeMismatch {
+        /// The declared predicate
+        predicate: Identifier,
+        /// The position of the variable, starting at 1
+        position: usize,
+        /// The offending variable
+        variable: Variable,
+        /// The declared type of the position
+        expected: ValueType,
+        /// The type of the values the variable is bound to
+        found: ValueType,
+        /// Where the variable occurs
+        location: TypeErrorLocation,
+    },
+}
+
+impl TypeError {
+    /// Return the position in the source text at which the error occurs, if known.
+    pub fn position(&self) -> Option<SourcePosition> {
+        match self {
+            TypeError::ConflictingDeclarations { location, .. }
+            | TypeError::ArityMismatch { location, .. }
+            | TypeError::ValueTypeMismatch { location, .. }
+            | TypeError::VariableTypeMismatch { location, .. } => location.position(),
+        }
+    }
+}
+
+/// Return the declared types of all predicates with a type declaration in the given list.
+pub(crate) fn declared_types(
+    declarations: &[PredicateDeclaration],
+) -> Result<HashMap<Identifier, Vec<ValueType>>, TypeError> {
+    let mut result = HashMap::<Identifier, Vec<ValueType>>::new();
+
+    for declaration in declarations {
+        match result.entry(declaration.predicate().clone()) {
+            Entry::Occupied(entry) => {
+                if entry.get() != declaration.types() {
+                    return Err(TypeError::ConflictingDeclarations {
+                        predicate: declaration.predicate().clone(),
+                        location: TypeErrorLocation::Declaration(declaration.position()),
+                    });
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(declaration.types().to_vec());
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Check that the given value belongs to the declared type of position `index` of `predicate`.
+pub(crate) fn check_value(
+    predicate: &Identifier,
+    types: &[ValueType],
+    index: usize,
+    value: &AnyDataValue,
+    location: impl FnOnce() -> TypeErrorLocation,
+) -> Result<(), TypeError> {
+    let expected = types[index];
+
+    if expected.admits(value) {
+        Ok(())
+    } else {
+        Err(TypeError::ValueTypeMismatch {
+            predicate: predicate.clone(),
+            position: index + 1,
+            value: value.to_string(),
+            expected,
+            location: location(),
+        })
+    }
+}
+
+/// Check that the given atom has the declared arity of its predicate.
+fn check_arity(
+    atom: &Atom,
+    types: &[ValueType],
+    location: impl FnOnce() -> TypeErrorLocation,
+) -> Result<(), TypeError> {
+    if atom.terms().len() == types.len() {
+        Ok(())
+    } else {
+        Err(TypeError::ArityMismatch {
+            predicate: atom.predicate(),
+            declared: types.len(),
+            used: atom.terms().len(),
+            location: location(),
+        })
+    }
+}
+
+/// Check the facts and rules of the given program against its type declarations.
+///
+/// Constants in facts and rule heads must belong to the declared type of their position,
+/// and variables in rule heads must not be bound to values of a different declared type.
+/// Values that are only known at runtime are checked during the execution of the program.
+pub(crate) fn check_declared_types(program: &Program) -> Result<(), TypeError> {
+    let declarations = declared_types(program.declarations())?;
+    if declarations.is_empty() {
+        return Ok(());
+    }
+
+    for fact in program.facts() {
+        let Some(types) = declarations.get(&fact.0.predicate()) else {
+            continue;
+        };
+        let location = || TypeErrorLocation::Fact(fact.to_string());
+
+        check_arity(&fact.0, types, location)?;
+
+        for (index, term) in fact.0.terms().iter().enumerate() {
+            if let Term::Primitive(PrimitiveTerm::GroundTerm(value)) = term {
+                check_value(&fact.0.predicate(), types, index, value, location)?;
+            }
+        }
+    }
+
+    for rule in program.rules() {
+        let location = || TypeErrorLocation::Rule {
+            rule: rule.to_string(),
+            position: rule.position(),
+        };
+
+        let mut variable_types = HashMap::<&Variable, ValueType>::new();
+        for literal in rule.body() {
+            let atom = literal.atom();
+            let Some(types) = declarations.get(&atom.predicate()) else {
+                continue;
+            };
+
+            check_arity(atom, types, location)?;
+
+            if literal.is_positive() {
+                for (term, value_type) in atom.terms().iter().zip(types) {
+                    if let (Term::Primitive(PrimitiveTerm::Variable(variable)), false) =
+                        (term, *value_type == ValueType::Any)
+                    {
+                        variable_types.entry(variable).or_insert(*value_type);
+                    }
+                }
+            }
+        }
+
+        for atom in rule.head() {
+            let Some(types) = declarations.get(&atom.predicate()) else {
+                continue;
+            };
+
+            check_arity(atom, types, location)?;
+
+            for (index, term) in atom.terms().iter().enumerate() {
+                match term {
+                    Term::Primitive(PrimitiveTerm::GroundTerm(value)) => {
+                        check_value(&atom.predicate(), types, index, value, location)?;
+                    }
+                    Term::Primitive(PrimitiveTerm::Variable(variable)) => {
+                        let expected = types[index];
+                        let Some(&found) = variable_types.get(variable) else {
+                            continue;
+                        };
+
+                        if expected != ValueType::Any && expected != found {
+                            return Err(TypeError::VariableTypeMismatch {
+                                predicate: atom.predicate(),
+                                position: index + 1,
+                                variable: variable.clone(),
+                                expected,
+                                found,
+                                location: location(),
+                            });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// [ValueValidator] that checks imported values against the declared types of a predicate
+#[derive(Debug)]
+pub(crate) struct DeclaredTypeValidator {
+    /// The imported predicate
+    predicate: Identifier,
+    /// The declared types of the predicate
+    types: Vec<ValueType>,
+    /// Position of the import directive in the source text, if known
+    position: Option<SourcePosition>,
+}
+
+impl DeclaredTypeValidator {
+    /// Create a new [DeclaredTypeValidator].
+    pub(crate) fn new(
+        predicate: Identifier,
+        types: Vec<ValueType>,
+        position: Option<SourcePosition>,
+    ) -> Self {
+        Self {
+            predicate,
+            types,
+            position,
+        }
+    }
+}
+
+impl ValueValidator for DeclaredTypeValidator {
+    fn validate(
+        &self,
+        column: usize,
+        value: &AnyDataValue,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        check_value(&self.predicate, &self.types, column, value, || {
+            TypeErrorLocation::Import {
+                predicate: self.predicate.clone(),
+                position: self.position,
+            }
+        })?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        io::parser::parse_program,
+        model::{Identifier, ValueType},
+    };
+
+    use super::{check_declared_types, TypeError};
+
+    fn check(input: &str) -> Result<(), TypeError> {
+        check_declared_types(&parse_program(input).unwrap())
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn well_typed_program() {
+        assert_eq!(
+            check(
+                r#"@declare p(int, string, any) .
+                @declare q(string, int) .
+                p(1, "a", <b>) .
+                q(?y, ?x) :- p(?x, ?y, ?z) .
+                q("c", ?x + 1) :- p(?x, _, _) ."#
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn ill_typed_program() {
+        let error = check(
+            r#"@declare p(int, string) .
+            p(1, 2) ."#,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            error,
+            TypeError::ValueTypeMismatch {
+                position: 2,
+                expected: ValueType::String,
+                ..
+            }
+        ));
+
+        let error = check(
+            r#"@declare p(int, string) .
+            @declare q(int) .
+            q(?y) :- p(?x, ?y) ."#,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            error,
+            TypeError::VariableTypeMismatch {
+                position: 1,
+                expected: ValueType::Int,
+                found: ValueType::String,
+                ..
+            }
+        ));
+        assert_eq!(error.position().map(|position| position.line), Some(3));
+
+        let error = check(
+            r#"@declare p(int) .
+            q(?x) :- p(?x, ?y) ."#,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            error,
+            TypeError::ArityMismatch {
+                declared: 1,
+                used: 2,
+                ..
+            }
+        ));
+
+        let error = check(
+            r#"@declare p(int) .
+            @declare p(string) ."#,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            TypeError::ConflictingDeclarations {
+                predicate: Identifier::new("p".to_string()),
+                location: super::TypeErrorLocation::Declaration(Some(
+                    crate::model::SourcePosition::new(2, 13)
+                )),
+            }
+        );
+    }
+}
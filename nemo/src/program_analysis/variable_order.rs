@@ -184,7 +184,9 @@ impl IterationOrder {
     }
 }
 
-fn column_order_for(atom: &VariableAtom, var_order: &VariableOrder) -> ColumnOrder {
+/// Return the [ColumnOrder] in which the table of the given atom
+/// has to be available for joining it according to the given [VariableOrder].
+pub(crate) fn column_order_for(atom: &VariableAtom, var_order: &VariableOrder) -> ColumnOrder {
     let mut partial_col_order: Vec<usize> = var_order
         .iter()
         .flat_map(|var| {
@@ -978,6 +980,7 @@ mod test {
             predicate,
             format: FileFormat::CSV,
             attributes,
+            position: None,
         })
     }
 
@@ -12,11 +12,17 @@ use nemo_physical::{
         database::{
             id::{ExecutionId, PermanentTableId},
             sources::TableSource,
-            Dict,
+            Dict, ExecutionStatistics,
         },
         execution_plan::{ColumnOrder, ExecutionNodeRef, ExecutionPlan},
+        plan_explanation::PlanExplanation,
+    },
+    meta::timing::TimedCode,
+    tabular::{
+        operations::{OperationColumnMarker, OperationTable},
+        statistics::TableStatistics,
+        trie::Trie,
     },
-    tabular::{operations::OperationTable, trie::Trie},
     util::mapping::permutation::Permutation,
 };
 
@@ -26,8 +32,12 @@ use std::{
     collections::HashMap,
     hash::Hash,
     ops::Range,
+    time::Duration,
 };
 
+/// Step that is associated with the input facts of each predicate
+const EDB_STEP: usize = 0;
+
 /// Indicates that the table contains the union of successive tables.
 /// For example assume that for predicate p there were tables derived in steps 2, 4, 7, 10, 11.
 /// The range [4, 10] would be represented with SubtableRange { start: 1, len: 3 }.
@@ -61,6 +71,17 @@ impl PartialOrd for SubtableRange {
     }
 }
 
+/// Statistics about the tables of a predicate,
+/// which are used for estimating the cost of rule applications
+#[derive(Debug, Clone)]
+pub(crate) struct PredicateStatistics {
+    /// Statistics about all the rows of the predicate
+    pub(crate) table: TableStatistics,
+    /// Number of rows of each subtable of the predicate
+    /// together with the [ColumnOrder]s in which it is available without reordering
+    pub(crate) subtables: Vec<(usize, Vec<ColumnOrder>)>,
+}
+
 #[derive(Debug, Default)]
 struct SubtableHandler {
     single: Vec<(usize, PermanentTableId)>,
@@ -111,8 +132,8 @@ impl SubtableHandler {
     }
 
     pub fn subtable(&self, step: usize) -> Option<PermanentTableId> {
-        let postion = *self.single_steps().find(|&&s| s == step)?;
-        Some(self.single[postion].1)
+        let position = self.single_steps().position(|&s| s == step)?;
+        Some(self.single[position].1)
     }
 
     /// TODO: Currently only counting of in-memory facts is supported, see <https://github.com/knowsys/nemo/issues/335>
@@ -131,6 +152,26 @@ impl SubtableHandler {
         self.single.push((step, id));
     }
 
+    /// Replace the table associated with the given step,
+    /// or remove it if `id` is `None`.
+    ///
+    /// Since combined tables might contain outdated rows afterwards, they are discarded.
+    pub fn replace_single_table(&mut self, step: usize, id: Option<PermanentTableId>) {
+        self.combined.clear();
+
+        let position = self.single.partition_point(|(s, _)| *s < step);
+        let exists = self.single.get(position).is_some_and(|(s, _)| *s == step);
+
+        match (exists, id) {
+            (true, Some(id)) => self.single[position].1 = id,
+            (true, None) => {
+                self.single.remove(position);
+            }
+            (false, Some(id)) => self.single.insert(position, (step, id)),
+            (false, None) => {}
+        }
+    }
+
     pub fn add_combined_table(&mut self, range: &Range<usize>, id: PermanentTableId) {
         let cover = self.normalize_range(range);
         if cover.len <= 1 {
@@ -223,6 +264,29 @@ impl SubtableIdentifier {
     }
 }
 
+/// Role of a table computed by a [SubtableExecutionPlan] in the application of a rule,
+/// which is used for collecting statistics about the application
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ComputedTableRole {
+    /// Matches of the body of the rule
+    Body,
+    /// Facts for the given predicate before duplicates are eliminated
+    Candidates(Identifier),
+}
+
+/// Statistics about the tables computed by a [SubtableExecutionPlan]
+#[derive(Debug, Default, Clone)]
+pub(crate) struct SubtableExecutionStatistics {
+    /// Statistics of the tables that were assigned a [ComputedTableRole]
+    pub(crate) roles: Vec<(ComputedTableRole, ExecutionStatistics)>,
+    /// Number of rows of the new subtables of each predicate
+    pub(crate) new_rows: HashMap<Identifier, usize>,
+    /// Time spent on computing all tables
+    pub(crate) time: Duration,
+    /// Statistics of every computed table
+    pub(crate) tables: HashMap<ExecutionId, ExecutionStatistics>,
+}
+
 /// A execution plan that will result in the creation of new chase subtables.
 #[derive(Debug, Default)]
 pub struct SubtableExecutionPlan {
@@ -231,9 +295,40 @@ pub struct SubtableExecutionPlan {
     /// Each tree in the plan that will result in a new permanent table
     /// will have an associated [SubtableIdentifier].
     map_subtrees: HashMap<ExecutionId, SubtableIdentifier>,
+    /// Trees in the plan whose statistics are reported
+    roles: HashMap<ExecutionId, ComputedTableRole>,
+    /// Whether facts are stored before duplicates are eliminated, see [SubtableExecutionPlan::add_candidate_table]
+    count_duplicates: bool,
 }
 
 impl SubtableExecutionPlan {
+    /// Create a new [SubtableExecutionPlan],
+    /// which stores the facts of each predicate before duplicates are eliminated
+    /// if `count_duplicates` is true.
+    pub(crate) fn new(count_duplicates: bool) -> Self {
+        Self {
+            count_duplicates,
+            ..Default::default()
+        }
+    }
+
+    /// Mark the tree of the plan with the given id,
+    /// so that its statistics are reported after the plan is executed.
+    pub(crate) fn add_role(&mut self, id: ExecutionId, role: ComputedTableRole) {
+        self.roles.insert(id, role);
+    }
+
+    /// Add a temporary table containing the facts of the given predicate before duplicates are eliminated,
+    /// if duplicates are counted for this plan.
+    ///
+    /// This is not done by default, since the table would otherwise not need to be materialized.
+    pub(crate) fn add_candidate_table(&mut self, node: ExecutionNodeRef, predicate: &Identifier) {
+        if self.count_duplicates {
+            let id = self.add_temporary_table(node, "Duplicate Candidates");
+            self.add_role(id, ComputedTableRole::Candidates(predicate.clone()));
+        }
+    }
+
     /// Add a temporary table to the plan.
     pub fn add_temporary_table(&mut self, node: ExecutionNodeRef, tree_name: &str) -> ExecutionId {
         self.execution_plan.write_temporary(node, tree_name)
@@ -356,7 +451,7 @@ impl TableManager {
 
     /// Return the [PermanentTableId] that is associated with a given subtable.
     /// Returns None if the predicate does not exist.
-    fn table_id(&self, subtable: &SubtableIdentifier) -> Option<PermanentTableId> {
+    pub(crate) fn table_id(&self, subtable: &SubtableIdentifier) -> Option<PermanentTableId> {
         self.predicate_subtables
             .get(&subtable.predicate)?
             .subtable(subtable.step)
@@ -377,6 +472,34 @@ impl TableManager {
             .map(|s| s.count_rows(&self.database))
     }
 
+    /// Return [PredicateStatistics] about all the rows that belong to a predicate,
+    /// combined from the statistics of its subtables.
+    /// Returns None if the predicate does not exist.
+    pub(crate) fn predicate_statistics(
+        &mut self,
+        predicate: &Identifier,
+    ) -> Result<Option<PredicateStatistics>, Error> {
+        let Some(handler) = self.predicate_subtables.get(predicate) else {
+            return Ok(None);
+        };
+        let subtable_ids = handler.single.iter().map(|(_, id)| *id).collect::<Vec<_>>();
+
+        let mut result = PredicateStatistics {
+            table: TableStatistics::empty(self.arity(predicate)),
+            subtables: Vec::new(),
+        };
+        for id in subtable_ids {
+            let statistics = self.database.table_statistics(id)?;
+
+            result.table = result.table.union(statistics);
+            result
+                .subtables
+                .push((statistics.num_rows, self.database.table_orders(id)));
+        }
+
+        Ok(Some(result))
+    }
+
     /// Get a list of column iterators for the full table (i.e. the expanded trie)
     pub(crate) fn table_row_iterator(
         &mut self,
@@ -385,6 +508,20 @@ impl TableManager {
         Ok(self.database.table_row_iterator(id)?)
     }
 
+    /// Convert a table into a [RecordBatch][arrow_array::RecordBatch] with the given column names,
+    /// where values of string columns are represented by the given function.
+    #[cfg(feature = "arrow")]
+    pub(crate) fn table_record_batch(
+        &mut self,
+        id: PermanentTableId,
+        column_names: &[String],
+        serialize: fn(&AnyDataValue) -> String,
+    ) -> Result<arrow_array::RecordBatch, Error> {
+        Ok(self
+            .database
+            .table_record_batch(id, column_names, serialize)?)
+    }
+
     /// Combine all subtables of a predicate into one table
     /// and return the [PermanentTableId] of that new table.
     pub(crate) fn combine_predicate(
@@ -456,6 +593,14 @@ impl TableManager {
             .insert(predicate, SubtableHandler::default());
     }
 
+    /// Return the steps and ids of all single-step subtables of a predicate.
+    pub(crate) fn subtables(&self, predicate: &Identifier) -> Vec<(usize, PermanentTableId)> {
+        self.predicate_subtables
+            .get(predicate)
+            .map(|handler| handler.single.clone())
+            .unwrap_or_default()
+    }
+
     /// Check whether a predicate has been registered.
     #[allow(dead_code)]
     fn predicate_exists(&self, predicate: &Identifier) -> bool {
@@ -474,21 +619,100 @@ impl TableManager {
     /// Add a table that represents the input facts for some predicate for the chase procedure.
     /// Predicate must be registered before calling this function.
     pub(crate) fn add_edb(&mut self, predicate: Identifier, sources: Vec<TableSource>) {
+        self.add_table_sources(predicate, EDB_STEP, sources);
+    }
+
+    /// Add a source of input facts for some predicate to the table that represents them,
+    /// or create this table if the predicate has no input facts yet.
+    /// Predicate must be registered before calling this function
+    /// and its input facts must not have been loaded yet.
+    pub(crate) fn add_edb_source(&mut self, predicate: Identifier, source: TableSource) {
+        match self.table_id(&SubtableIdentifier::new(predicate.clone(), EDB_STEP)) {
+            Some(table_id) => self
+                .database
+                .add_source(table_id, ColumnOrder::default(), source),
+            None => self.add_edb(predicate, vec![source]),
+        }
+    }
+
+    /// Add a table that contains the given sources as a subtable of a predicate
+    /// that is associated with the given step.
+    /// Predicate must be registered before calling this function.
+    pub(crate) fn add_table_sources(
+        &mut self,
+        predicate: Identifier,
+        step: usize,
+        sources: Vec<TableSource>,
+    ) {
         let arity = if let Some(source) = sources.first() {
             source.arity()
         } else {
             return;
         };
 
-        let edb_order = ColumnOrder::default();
-        const EDB_STEP: usize = 0;
+        let order = ColumnOrder::default();
+
+        let name = self.generate_table_name(&predicate, &order, step);
+
+        let table_id = self.database.register_table(&name, arity);
+        self.database.add_sources(table_id, order, sources);
+
+        self.add_subtable(SubtableIdentifier::new(predicate, step), table_id)
+    }
+
+    /// Replace the subtable of a predicate that is associated with the given step
+    /// by a table that contains the given sources.
+    /// If no sources are given, the subtable is removed.
+    /// Predicate must be registered before calling this function.
+    pub(crate) fn replace_subtable(
+        &mut self,
+        predicate: Identifier,
+        step: usize,
+        sources: Vec<TableSource>,
+    ) {
+        let table_id = if sources.is_empty() {
+            None
+        } else {
+            let arity = self.arity(&predicate);
+            let order = ColumnOrder::default();
 
-        let name = self.generate_table_name(&predicate, &edb_order, EDB_STEP);
+            let name = self.generate_table_name(&predicate, &order, step);
+
+            let table_id = self.database.register_table(&name, arity);
+            self.database.add_sources(table_id, order, sources);
+
+            Some(table_id)
+        };
+
+        self.predicate_subtables
+            .get_mut(&predicate)
+            .expect("Predicate should be registered before calling this function")
+            .replace_single_table(step, table_id);
+    }
+
+    /// Replace all subtables of a predicate by a single table
+    /// that contains the given sources and is associated with the given step,
+    /// and return the [PermanentTableId] of that table.
+    /// Predicate must be registered before calling this function.
+    pub(crate) fn replace_predicate(
+        &mut self,
+        predicate: Identifier,
+        step: usize,
+        sources: Vec<TableSource>,
+    ) -> PermanentTableId {
+        let arity = self.arity(&predicate);
+        let order = ColumnOrder::default();
+
+        let name = self.generate_table_name(&predicate, &order, step);
 
         let table_id = self.database.register_table(&name, arity);
-        self.database.add_sources(table_id, edb_order, sources);
+        self.database.add_sources(table_id, order, sources);
 
-        self.add_subtable(SubtableIdentifier::new(predicate, EDB_STEP), table_id)
+        self.predicate_subtables
+            .insert(predicate.clone(), SubtableHandler::default());
+        self.add_subtable(SubtableIdentifier::new(predicate, step), table_id);
+
+        table_id
     }
 
     /// Add a [Trie] as a subtable of a predicate.
@@ -591,21 +815,78 @@ impl TableManager {
     }
 
     /// Execute a plan and add the results as subtables to the manager.
-    pub fn execute_plan(
+    ///
+    /// Returns the predicates which received new tables,
+    /// together with statistics about the computed tables.
+    pub(crate) fn execute_plan(
         &mut self,
-        subtable_plan: SubtableExecutionPlan,
-    ) -> Result<Vec<Identifier>, Error> {
-        let result = self.database.execute_plan(subtable_plan.execution_plan)?;
+        mut subtable_plan: SubtableExecutionPlan,
+    ) -> Result<(Vec<Identifier>, SubtableExecutionStatistics), Error> {
+        let (result, tree_statistics) = self
+            .database
+            .execute_plan_with_statistics(subtable_plan.execution_plan)?;
+
+        let mut statistics = SubtableExecutionStatistics {
+            time: tree_statistics.values().map(|tree| tree.time).sum(),
+            ..Default::default()
+        };
+        for (plan_id, role) in subtable_plan.roles.drain() {
+            if let Some(tree) = tree_statistics.get(&plan_id) {
+                statistics.roles.push((role, *tree));
+            }
+        }
 
         let mut updated_predicates = Vec::new();
         for (plan_id, table_id) in result {
             let subtable = subtable_plan.map_subtrees.get(&plan_id).unwrap();
             updated_predicates.push(subtable.predicate.clone());
 
+            *statistics
+                .new_rows
+                .entry(subtable.predicate.clone())
+                .or_default() += tree_statistics
+                .get(&plan_id)
+                .and_then(|tree| tree.rows)
+                .unwrap_or(0);
+
             self.add_subtable(subtable.clone(), table_id);
         }
+        statistics.tables = tree_statistics;
 
-        Ok(updated_predicates)
+        Ok((updated_predicates, statistics))
+    }
+
+    /// Return a name for the given table, which states the predicate and steps it belongs to.
+    fn subtable_name(&self, id: PermanentTableId) -> String {
+        for (predicate, handler) in &self.predicate_subtables {
+            if let Some((step, _)) = handler.single.iter().find(|(_, table)| *table == id) {
+                return format!("{} (step {step})", predicate.name());
+            }
+
+            if let Some((range, _)) = handler.combined.iter().find(|(_, table)| *table == id) {
+                let (start, end) = range.start_end();
+                return format!(
+                    "{} (steps {}-{})",
+                    predicate.name(),
+                    handler.single[start].0,
+                    handler.single[end].0
+                );
+            }
+        }
+
+        self.database.table_name(id).to_string()
+    }
+
+    /// Describe the tables computed by the given plan,
+    /// where columns are named using `marker_name`.
+    pub(crate) fn explain_plan(
+        &self,
+        subtable_plan: &SubtableExecutionPlan,
+        marker_name: impl Fn(&OperationColumnMarker) -> String,
+    ) -> PlanExplanation {
+        subtable_plan
+            .execution_plan
+            .explain(|id| self.subtable_name(id), marker_name)
     }
 
     /// Returns a reference to the constants dictionary
@@ -619,6 +900,32 @@ impl TableManager {
         self.database.dictionary_mut()
     }
 
+    /// Return the time spent on the operations of the underlying [DatabaseInstance].
+    pub fn timing(&self) -> &TimedCode {
+        self.database.timing()
+    }
+
+    /// Return a mutable reference to the time spent on the operations of the underlying [DatabaseInstance].
+    pub fn timing_mut(&mut self) -> &mut TimedCode {
+        self.database.timing_mut()
+    }
+
+    /// Return the amount of memory consumed by all tables of all predicates.
+    pub fn memory_consumption(&self) -> ByteSize {
+        self.predicate_subtables
+            .values()
+            .flat_map(|handler| {
+                handler
+                    .single
+                    .iter()
+                    .map(|(_, id)| id)
+                    .chain(handler.combined.iter().map(|(_, id)| id))
+            })
+            .fold(ByteSize(0), |memory, id| {
+                memory + self.database.memory_consumption(*id)
+            })
+    }
+
     /// Return the current [MemoryUsage].
     pub fn memory_usage(&self) -> MemoryUsage {
         let mut result = MemoryUsage::new_block("Chase");
@@ -0,0 +1,108 @@
+//! Contains a union-find structure for maintaining equivalence classes.
+
+use std::{collections::HashMap, hash::Hash};
+
+/// Union-find structure over arbitrary elements.
+///
+/// Elements that were never merged with another element
+/// are not stored and are their own representative.
+#[derive(Debug, Clone)]
+pub struct UnionFind<T>
+where
+    T: Clone + Eq + Hash,
+{
+    /// Parent of each stored element; representatives are their own parent
+    parents: HashMap<T, T>,
+}
+
+impl<T> Default for UnionFind<T>
+where
+    T: Clone + Eq + Hash,
+{
+    fn default() -> Self {
+        Self {
+            parents: HashMap::new(),
+        }
+    }
+}
+
+impl<T> UnionFind<T>
+where
+    T: Clone + Eq + Hash,
+{
+    /// Return the representative of the equivalence class of the given element.
+    pub fn find(&mut self, element: &T) -> T {
+        let Some(parent) = self.parents.get(element).cloned() else {
+            return element.clone();
+        };
+        if &parent == element {
+            return parent;
+        }
+
+        let representative = self.find(&parent);
+        self.parents.insert(element.clone(), representative.clone());
+
+        representative
+    }
+
+    /// Merge the equivalence classes of the two given elements.
+    ///
+    /// The representative of the merged class is the representative of `left`,
+    /// unless `prefer_right` returns `true` for the two current representatives.
+    ///
+    /// Returns `false` if both elements were already in the same class.
+    pub fn union(
+        &mut self,
+        left: &T,
+        right: &T,
+        prefer_right: impl FnOnce(&T, &T) -> bool,
+    ) -> bool {
+        let left = self.find(left);
+        let right = self.find(right);
+        if left == right {
+            return false;
+        }
+
+        let (representative, other) = if prefer_right(&left, &right) {
+            (right, left)
+        } else {
+            (left, right)
+        };
+
+        self.parents
+            .insert(representative.clone(), representative.clone());
+        self.parents.insert(other, representative);
+
+        true
+    }
+
+    /// Return `true` if no elements have been merged.
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::UnionFind;
+
+    #[test]
+    fn union_find_classes() {
+        let mut union_find = UnionFind::<usize>::default();
+        assert!(union_find.is_empty());
+        assert_eq!(union_find.find(&1), 1);
+
+        assert!(union_find.union(&1, &2, |_, _| false));
+        assert!(union_find.union(&3, &4, |_, _| true));
+        assert!(!union_find.union(&2, &1, |_, _| false));
+
+        assert_eq!(union_find.find(&2), 1);
+        assert_eq!(union_find.find(&3), 4);
+
+        assert!(union_find.union(&2, &3, |left, right| right < left));
+        for element in 1..=4 {
+            assert_eq!(union_find.find(&element), 1);
+        }
+        assert_eq!(union_find.find(&5), 5);
+    }
+}